@@ -0,0 +1,7 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    tonic_build::compile_protos("proto/solana_gateway.proto")?;
+    // Migrations are embedded by `sqlx::migrate!`; rebuild when they change.
+    println!("cargo:rerun-if-changed=migrations");
+    Ok(())
+}
@@ -0,0 +1,88 @@
+-- Tables created by the gateway before migrations were introduced. Every
+-- statement is idempotent so databases bootstrapped that way upgrade cleanly.
+
+CREATE TABLE IF NOT EXISTS solana_transactions (
+    signature TEXT PRIMARY KEY,
+    slot BIGINT NOT NULL DEFAULT 0,
+    status TEXT NOT NULL,
+    accounts TEXT[] NOT NULL DEFAULT '{}',
+    amount BIGINT,
+    fee BIGINT,
+    block_time TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_solana_transactions_accounts ON solana_transactions USING GIN (accounts);
+CREATE INDEX IF NOT EXISTS idx_solana_transactions_slot ON solana_transactions (slot DESC, signature DESC);
+CREATE INDEX IF NOT EXISTS idx_solana_transactions_unsettled ON solana_transactions (created_at)
+    WHERE status IN ('pending', 'confirmed');
+
+CREATE TABLE IF NOT EXISTS solana_pools (
+    address TEXT PRIMARY KEY,
+    dex TEXT NOT NULL,
+    token_a TEXT NOT NULL,
+    token_b TEXT NOT NULL,
+    vault_a TEXT NOT NULL,
+    vault_b TEXT NOT NULL,
+    reserve_a NUMERIC(39, 0) NOT NULL DEFAULT 0,
+    reserve_b NUMERIC(39, 0) NOT NULL DEFAULT 0,
+    fee_ppm INTEGER NOT NULL,
+    liquidity NUMERIC(39, 0),
+    sqrt_price_x64 NUMERIC(39, 0),
+    tick_current INTEGER,
+    tick_spacing INTEGER,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_solana_pools_token_a ON solana_pools (token_a);
+CREATE INDEX IF NOT EXISTS idx_solana_pools_token_b ON solana_pools (token_b);
+
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    key TEXT NOT NULL,
+    route TEXT NOT NULL,
+    request_hash TEXT NOT NULL,
+    response JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (key, route)
+);
+
+CREATE TABLE IF NOT EXISTS wallets (
+    id UUID PRIMARY KEY,
+    label TEXT,
+    pubkey TEXT NOT NULL UNIQUE,
+    encrypted_key BYTEA NOT NULL,
+    nonce BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    actor TEXT NOT NULL,
+    method TEXT NOT NULL,
+    route TEXT NOT NULL,
+    request_hash TEXT NOT NULL,
+    status_code INTEGER NOT NULL,
+    outcome TEXT NOT NULL,
+    signature TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_actor ON audit_log (actor, id DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_log_created_at ON audit_log (created_at);
+
+CREATE OR REPLACE FUNCTION audit_log_append_only() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_trigger WHERE tgname = 'audit_log_append_only') THEN
+        CREATE TRIGGER audit_log_append_only BEFORE UPDATE OR DELETE ON audit_log
+            FOR EACH ROW EXECUTE FUNCTION audit_log_append_only();
+    END IF;
+END
+$$;
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS pool_candles (
+    pool_address TEXT NOT NULL,
+    -- One of '1m', '5m', '1h', '1d'.
+    interval TEXT NOT NULL,
+    bucket_start TIMESTAMPTZ NOT NULL,
+    -- Token B per token A, decimal adjusted.
+    open DOUBLE PRECISION NOT NULL,
+    high DOUBLE PRECISION NOT NULL,
+    low DOUBLE PRECISION NOT NULL,
+    close DOUBLE PRECISION NOT NULL,
+    -- Decimal-adjusted reserve movement observed between samples.
+    volume_a DOUBLE PRECISION NOT NULL DEFAULT 0,
+    volume_b DOUBLE PRECISION NOT NULL DEFAULT 0,
+    samples INTEGER NOT NULL DEFAULT 1,
+    PRIMARY KEY (pool_address, interval, bucket_start)
+);
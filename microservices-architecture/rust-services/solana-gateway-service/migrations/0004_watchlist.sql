@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS watchlist (
+    address TEXT PRIMARY KEY,
+    label TEXT,
+    -- Last observed state; NULL until the watcher first sees the account.
+    lamports BIGINT,
+    -- Hex SHA-256 of the account data, NULL while the account doesn't exist.
+    data_hash TEXT,
+    observed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS account_changes (
+    id BIGSERIAL PRIMARY KEY,
+    address TEXT NOT NULL REFERENCES watchlist (address) ON DELETE CASCADE,
+    previous_lamports BIGINT NOT NULL,
+    lamports BIGINT NOT NULL,
+    data_changed BOOLEAN NOT NULL,
+    data_hash TEXT,
+    detected_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_changes_address ON account_changes (address, id DESC);
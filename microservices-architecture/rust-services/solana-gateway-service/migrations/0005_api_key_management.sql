@@ -0,0 +1,7 @@
+-- Per-key quota overrides, both set or both NULL for the configured defaults.
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS read_per_minute INTEGER;
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS trading_per_minute INTEGER;
+-- Requests authenticated with the key, flushed from memory periodically.
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS request_count BIGINT NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_api_keys_revoked ON api_keys (id) WHERE revoked_at IS NOT NULL;
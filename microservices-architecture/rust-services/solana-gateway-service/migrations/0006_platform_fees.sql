@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS platform_fees (
+    id BIGSERIAL PRIMARY KEY,
+    signature TEXT NOT NULL,
+    pool_id TEXT NOT NULL,
+    -- Input mint of the swap, in which the fee is paid.
+    mint TEXT NOT NULL,
+    -- Raw units of `mint`.
+    amount BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_platform_fees_created ON platform_fees (created_at);
@@ -0,0 +1 @@
+ALTER TABLE solana_transactions ADD COLUMN IF NOT EXISTS memo TEXT;
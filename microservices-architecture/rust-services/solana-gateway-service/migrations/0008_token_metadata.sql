@@ -0,0 +1,9 @@
+-- Off-chain token metadata from the configured token list.
+CREATE TABLE IF NOT EXISTS token_metadata (
+    mint TEXT PRIMARY KEY,
+    symbol TEXT NOT NULL,
+    name TEXT NOT NULL,
+    logo_uri TEXT,
+    coingecko_id TEXT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,33 @@
+-- Recurring swaps executed by the gateway's own wallet.
+CREATE TABLE IF NOT EXISTS dca_schedules (
+    id UUID PRIMARY KEY,
+    input_mint TEXT NOT NULL,
+    output_mint TEXT NOT NULL,
+    -- Raw units of `input_mint` swapped per execution.
+    amount_per_execution BIGINT NOT NULL,
+    interval_secs BIGINT NOT NULL,
+    -- Raw units of `input_mint` the schedule may spend in total.
+    total_budget BIGINT NOT NULL,
+    spent BIGINT NOT NULL DEFAULT 0,
+    slippage_bps INTEGER,
+    -- active, paused, cancelled or completed.
+    status TEXT NOT NULL DEFAULT 'active',
+    fills INTEGER NOT NULL DEFAULT 0,
+    next_execution_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_dca_schedules_due ON dca_schedules (next_execution_at) WHERE status = 'active';
+
+CREATE TABLE IF NOT EXISTS dca_executions (
+    id BIGSERIAL PRIMARY KEY,
+    schedule_id UUID NOT NULL REFERENCES dca_schedules (id) ON DELETE CASCADE,
+    amount_in BIGINT NOT NULL,
+    -- Set for fills; failed attempts carry `error` instead.
+    signature TEXT,
+    error TEXT,
+    executed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_dca_executions_schedule ON dca_executions (schedule_id, id DESC);
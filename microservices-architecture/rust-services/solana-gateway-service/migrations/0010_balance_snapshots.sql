@@ -0,0 +1,13 @@
+-- Periodic balances of watched accounts, for portfolio history.
+CREATE TABLE IF NOT EXISTS balance_snapshots (
+    id BIGSERIAL PRIMARY KEY,
+    address TEXT NOT NULL REFERENCES watchlist (address) ON DELETE CASCADE,
+    -- NULL for the account's SOL balance.
+    mint TEXT,
+    -- Raw units: lamports, or base units of `mint`.
+    amount BIGINT NOT NULL,
+    decimals SMALLINT NOT NULL,
+    taken_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_balance_snapshots_address ON balance_snapshots (address, taken_at);
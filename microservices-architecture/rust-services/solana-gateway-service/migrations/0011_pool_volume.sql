@@ -0,0 +1,22 @@
+-- Swaps through stored pools, summed per hour by the volume indexer.
+CREATE TABLE IF NOT EXISTS pool_volume_hourly (
+    pool_address TEXT NOT NULL,
+    hour_start TIMESTAMPTZ NOT NULL,
+    -- Decimal-adjusted amounts of each token moved through the vaults.
+    volume_a DOUBLE PRECISION NOT NULL DEFAULT 0,
+    volume_b DOUBLE PRECISION NOT NULL DEFAULT 0,
+    -- Decimal-adjusted fees, charged on the input side of each swap.
+    fees_a DOUBLE PRECISION NOT NULL DEFAULT 0,
+    fees_b DOUBLE PRECISION NOT NULL DEFAULT 0,
+    swaps INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (pool_address, hour_start)
+);
+
+CREATE INDEX IF NOT EXISTS idx_pool_volume_hourly_hour ON pool_volume_hourly (hour_start);
+
+-- Newest transaction folded into `pool_volume_hourly` per pool.
+CREATE TABLE IF NOT EXISTS pool_swap_cursors (
+    pool_address TEXT PRIMARY KEY,
+    last_signature TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
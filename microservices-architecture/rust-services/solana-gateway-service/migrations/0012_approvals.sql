@@ -0,0 +1,22 @@
+-- Large transfers held until enough API keys approve them.
+CREATE TABLE IF NOT EXISTS approvals (
+    id UUID PRIMARY KEY,
+    -- The transfer request as submitted.
+    request JSONB NOT NULL,
+    -- Subject of the requesting principal, or 'anonymous'.
+    requested_by TEXT NOT NULL,
+    -- gateway or squads.
+    backend TEXT NOT NULL,
+    -- pending, executing, executed, proposed or failed.
+    status TEXT NOT NULL DEFAULT 'pending',
+    approved_by UUID[] NOT NULL DEFAULT '{}',
+    -- The sent transfer, or the transaction creating the Squads proposal.
+    signature TEXT,
+    squads_proposal TEXT,
+    error TEXT,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_approvals_pending ON approvals (created_at DESC) WHERE status = 'pending';
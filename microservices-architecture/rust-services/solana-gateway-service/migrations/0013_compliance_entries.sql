@@ -0,0 +1,13 @@
+-- Compliance list entries managed through the admin API, merged with the
+-- lists in the config file.
+CREATE TABLE IF NOT EXISTS compliance_entries (
+    id BIGSERIAL PRIMARY KEY,
+    -- deny or allow.
+    list TEXT NOT NULL,
+    -- address or mint.
+    kind TEXT NOT NULL,
+    value TEXT NOT NULL,
+    reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (list, kind, value)
+);
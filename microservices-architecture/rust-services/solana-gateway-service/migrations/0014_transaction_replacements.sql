@@ -0,0 +1,2 @@
+-- Swaps resent after expiring point from the expired attempt to the next.
+ALTER TABLE solana_transactions ADD COLUMN IF NOT EXISTS replaced_by TEXT;
@@ -0,0 +1,12 @@
+-- Caller-supplied transfer references, unique per caller so a retried or
+-- repeated request can't send the same payment twice. Kept indefinitely.
+CREATE TABLE IF NOT EXISTS client_references (
+    -- Subject of the requesting principal, e.g. 'api-key:<id>', or 'anonymous'.
+    owner TEXT NOT NULL,
+    reference TEXT NOT NULL,
+    -- The sent transfer, or the approval holding it; both null while sending.
+    signature TEXT,
+    approval_id UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (owner, reference)
+);
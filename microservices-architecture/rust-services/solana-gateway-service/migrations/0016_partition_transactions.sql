@@ -0,0 +1,55 @@
+-- Transaction history partitioned by month of `created_at`, so old months
+-- can be dropped (and archived) whole. Partitions are named
+-- solana_transactions_YYYY_MM; the retention task creates upcoming months.
+-- The partition key has to be part of every unique index, so signatures
+-- are kept unique by `record_transaction` rather than by the primary key.
+ALTER TABLE solana_transactions RENAME TO solana_transactions_unpartitioned;
+ALTER INDEX IF EXISTS idx_solana_transactions_accounts RENAME TO idx_solana_transactions_unpartitioned_accounts;
+ALTER INDEX IF EXISTS idx_solana_transactions_slot RENAME TO idx_solana_transactions_unpartitioned_slot;
+ALTER INDEX IF EXISTS idx_solana_transactions_unsettled RENAME TO idx_solana_transactions_unpartitioned_unsettled;
+
+CREATE TABLE solana_transactions (
+    signature TEXT NOT NULL,
+    slot BIGINT NOT NULL DEFAULT 0,
+    status TEXT NOT NULL,
+    accounts TEXT[] NOT NULL DEFAULT '{}',
+    amount BIGINT,
+    fee BIGINT,
+    block_time TIMESTAMPTZ,
+    memo TEXT,
+    replaced_by TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (signature, created_at)
+) PARTITION BY RANGE (created_at);
+
+CREATE INDEX idx_solana_transactions_signature ON solana_transactions (signature);
+CREATE INDEX idx_solana_transactions_accounts ON solana_transactions USING GIN (accounts);
+CREATE INDEX idx_solana_transactions_slot ON solana_transactions (slot DESC, signature DESC);
+CREATE INDEX idx_solana_transactions_unsettled ON solana_transactions (created_at)
+    WHERE status IN ('pending', 'confirmed');
+
+-- A partition for every month with stored transactions through the next
+-- three months.
+DO $$
+DECLARE
+    month DATE := date_trunc('month', COALESCE(
+        (SELECT MIN(created_at) FROM solana_transactions_unpartitioned), NOW()))::DATE;
+BEGIN
+    WHILE month <= date_trunc('month', NOW() + INTERVAL '3 months')::DATE LOOP
+        EXECUTE format(
+            'CREATE TABLE IF NOT EXISTS %I PARTITION OF solana_transactions FOR VALUES FROM (%L) TO (%L)',
+            'solana_transactions_' || to_char(month, 'YYYY_MM'),
+            month,
+            (month + INTERVAL '1 month')::DATE
+        );
+        month := (month + INTERVAL '1 month')::DATE;
+    END LOOP;
+END $$;
+
+INSERT INTO solana_transactions
+    (signature, slot, status, accounts, amount, fee, block_time, memo, replaced_by, created_at, updated_at)
+SELECT signature, slot, status, accounts, amount, fee, block_time, memo, replaced_by, created_at, updated_at
+FROM solana_transactions_unpartitioned;
+
+DROP TABLE solana_transactions_unpartitioned;
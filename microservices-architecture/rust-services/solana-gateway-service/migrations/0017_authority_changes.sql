@@ -0,0 +1,15 @@
+-- Authorities last observed on each watched account, by name
+-- (`program_owner`, `token_owner`, `delegate`, ...); NULL while the account
+-- doesn't exist or until it is next polled.
+ALTER TABLE watchlist ADD COLUMN IF NOT EXISTS authorities JSONB;
+
+CREATE TABLE IF NOT EXISTS authority_changes (
+    id BIGSERIAL PRIMARY KEY,
+    address TEXT NOT NULL REFERENCES watchlist (address) ON DELETE CASCADE,
+    authority TEXT NOT NULL,
+    previous_value TEXT,
+    new_value TEXT,
+    detected_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_authority_changes_address ON authority_changes (address, id DESC);
@@ -0,0 +1,9 @@
+-- Signed swap quotes that were executed, kept until they expire so each
+-- quote is executed at most once.
+CREATE TABLE IF NOT EXISTS executed_quotes (
+    id UUID PRIMARY KEY,
+    expires_at TIMESTAMPTZ NOT NULL,
+    executed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_executed_quotes_expires_at ON executed_quotes (expires_at);
@@ -0,0 +1,20 @@
+-- Hourly usage per API key, flushed from memory periodically: requests and
+-- error responses, and the swaps made with the key with their input per
+-- input mint.
+CREATE TABLE IF NOT EXISTS api_key_usage (
+    api_key_id UUID NOT NULL REFERENCES api_keys(id) ON DELETE CASCADE,
+    hour TIMESTAMPTZ NOT NULL,
+    requests BIGINT NOT NULL DEFAULT 0,
+    client_errors BIGINT NOT NULL DEFAULT 0,
+    server_errors BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (api_key_id, hour)
+);
+
+CREATE TABLE IF NOT EXISTS api_key_volume (
+    api_key_id UUID NOT NULL REFERENCES api_keys(id) ON DELETE CASCADE,
+    hour TIMESTAMPTZ NOT NULL,
+    mint TEXT NOT NULL,
+    swaps BIGINT NOT NULL DEFAULT 0,
+    amount NUMERIC NOT NULL DEFAULT 0,
+    PRIMARY KEY (api_key_id, hour, mint)
+);
@@ -0,0 +1,6 @@
+-- When each pool was first stored, for listing new pools and detecting
+-- them in the new-pool feed. Pools stored before this migration get its
+-- time.
+ALTER TABLE solana_pools ADD COLUMN IF NOT EXISTS created_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+CREATE INDEX IF NOT EXISTS idx_solana_pools_created_at ON solana_pools (created_at DESC, address DESC);
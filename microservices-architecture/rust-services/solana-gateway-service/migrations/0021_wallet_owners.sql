@@ -0,0 +1,7 @@
+-- Subject of the principal that created or imported each wallet, e.g.
+-- 'api-key:<id>', or 'anonymous'. Only it and admins can see the wallet
+-- and sign with it. Wallets stored before this migration are left to
+-- admins and, while authentication is off, anonymous callers.
+ALTER TABLE wallets ADD COLUMN IF NOT EXISTS owner TEXT NOT NULL DEFAULT 'anonymous';
+
+CREATE INDEX IF NOT EXISTS idx_wallets_owner ON wallets (owner, created_at);
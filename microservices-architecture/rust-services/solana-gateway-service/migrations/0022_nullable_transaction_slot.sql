@@ -0,0 +1,7 @@
+-- A transaction has no slot until it is confirmed. Pending rows used to be
+-- stored with slot 0; they now leave it null, and the confirmation tracker
+-- and reconciler fill it in.
+ALTER TABLE solana_transactions ALTER COLUMN slot DROP NOT NULL;
+ALTER TABLE solana_transactions ALTER COLUMN slot DROP DEFAULT;
+
+UPDATE solana_transactions SET slot = NULL WHERE slot = 0;
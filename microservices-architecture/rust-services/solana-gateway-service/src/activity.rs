@@ -0,0 +1,436 @@
+use crate::memo;
+use crate::pools::Dex;
+use crate::solana_client::SolanaClient;
+use crate::tokens::TokenProgram;
+use anyhow::Result;
+use futures::future::try_join_all;
+use serde::Serialize;
+use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
+use solana_sdk::{
+    message::VersionedMessage, pubkey::Pubkey, signature::Signature, stake::instruction::StakeInstruction,
+    system_instruction::SystemInstruction, system_program,
+};
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, UiInstruction, UiLoadedAddresses, UiTransactionStatusMeta,
+};
+use spl_token::instruction::TokenInstruction;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+/// Most transactions fetched for one page of activity.
+pub const MAX_ACTIVITY_PAGE: i64 = 50;
+
+// Decimals of native SOL, reported for the SOL side of a swap.
+const SOL_DECIMALS: u8 = 9;
+
+/// Something that happened to the account in a transaction. Token amounts
+/// are raw integers with the mint's `decimals`; counterparties are wallet
+/// owners where the token account's owner is known.
+#[derive(Clone, Debug, PartialEq, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ActivityEvent {
+    SolReceived {
+        lamports: u64,
+        from: String,
+    },
+    SolSent {
+        lamports: u64,
+        to: String,
+    },
+    TokenReceived {
+        mint: String,
+        amount: u64,
+        decimals: u8,
+        from: String,
+    },
+    TokenSent {
+        mint: String,
+        amount: u64,
+        decimals: u8,
+        to: String,
+    },
+    NftReceived {
+        mint: String,
+        from: String,
+    },
+    NftSent {
+        mint: String,
+        to: String,
+    },
+    /// Net balance change over a transaction that invoked a known DEX. The
+    /// native SOL side includes rent paid for accounts the swap opened.
+    Swapped {
+        dex: Dex,
+        input_mint: String,
+        input_amount: u64,
+        input_decimals: u8,
+        output_mint: String,
+        output_amount: u64,
+        output_decimals: u8,
+    },
+    Staked {
+        stake_account: String,
+        vote_account: String,
+        lamports: u64,
+    },
+    Unstaked {
+        stake_account: String,
+    },
+    StakeWithdrawn {
+        stake_account: String,
+        lamports: u64,
+    },
+}
+
+#[derive(Clone, Serialize, ToSchema)]
+pub struct Activity {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub success: bool,
+    /// Network fee, set when the account paid it.
+    pub fee: Option<u64>,
+    /// Text of the transaction's SPL Memo instructions.
+    pub memo: Option<String>,
+    /// Empty for failed transactions and ones the decoders don't recognize.
+    pub events: Vec<ActivityEvent>,
+}
+
+/// Instruction with its accounts resolved, top-level and inner alike.
+struct ParsedInstruction {
+    program_id: Pubkey,
+    accounts: Vec<Pubkey>,
+    data: Vec<u8>,
+}
+
+pub(crate) struct TokenAccountBalance {
+    pub mint: String,
+    pub owner: Option<String>,
+    pub decimals: u8,
+    pub pre: u64,
+    pub post: u64,
+}
+
+/// What a transaction's decoders need to know about the account whose
+/// activity is being built.
+struct Context<'a> {
+    keys: &'a [Pubkey],
+    meta: &'a UiTransactionStatusMeta,
+    tokens: HashMap<Pubkey, TokenAccountBalance>,
+    /// The account itself and the token accounts it owns.
+    owned: HashSet<Pubkey>,
+}
+
+/// One page of the account's activity, newest first: signatures come from
+/// `getSignaturesForAddress` and each transaction is fetched and decoded.
+pub async fn account_activity(
+    client: &SolanaClient,
+    address: &Pubkey,
+    before: Option<Signature>,
+    limit: i64,
+) -> Result<Vec<Activity>> {
+    let signatures = client
+        .get_signatures_for_address(address, before, limit.clamp(1, MAX_ACTIVITY_PAGE) as usize)
+        .await?;
+    try_join_all(signatures.into_iter().map(|status| async move {
+        let signature = Signature::from_str(&status.signature)?;
+        let transaction = client.get_raw_transaction(&signature).await?;
+        Ok::<_, anyhow::Error>(parse(address, status, &transaction))
+    }))
+    .await
+}
+
+fn parse(
+    address: &Pubkey,
+    status: RpcConfirmedTransactionStatusWithSignature,
+    transaction: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Activity {
+    let mut activity = Activity {
+        signature: status.signature,
+        slot: status.slot,
+        block_time: status.block_time,
+        success: status.err.is_none(),
+        fee: None,
+        memo: None,
+        events: Vec::new(),
+    };
+    let (Some(decoded), Some(meta)) = (
+        transaction.transaction.transaction.decode(),
+        transaction.transaction.meta.as_ref(),
+    ) else {
+        return activity;
+    };
+
+    activity.memo = memo::from_message(&decoded.message);
+    let keys = account_keys(&decoded.message, meta);
+    if keys.first() == Some(address) {
+        activity.fee = Some(meta.fee);
+    }
+    if !activity.success {
+        return activity;
+    }
+
+    let tokens = token_balances(&keys, meta);
+    let owner = address.to_string();
+    let owned = std::iter::once(*address)
+        .chain(
+            tokens
+                .iter()
+                .filter(|(_, balance)| balance.owner.as_ref() == Some(&owner))
+                .map(|(key, _)| *key),
+        )
+        .collect();
+    let context = Context {
+        keys: &keys,
+        meta,
+        tokens,
+        owned,
+    };
+
+    // Transfers inside a swap are its legs, so only the net trade is shown.
+    let swap = context.swap(address);
+    let swapped = swap.is_some();
+    activity.events.extend(swap);
+    for instruction in instructions(&decoded.message, meta, &keys) {
+        let event = if instruction.program_id == system_program::id() {
+            context.decode_system(&instruction).filter(|_| !swapped)
+        } else if TokenProgram::from_owner(&instruction.program_id).is_some() {
+            context.decode_token(&instruction).filter(|_| !swapped)
+        } else if instruction.program_id == solana_sdk::stake::program::id() {
+            context.decode_stake(address, &instruction)
+        } else {
+            None
+        };
+        activity.events.extend(event);
+    }
+    activity
+}
+
+/// Static keys followed by those loaded from lookup tables, which is the
+/// order instruction account indexes refer to.
+pub(crate) fn account_keys(message: &VersionedMessage, meta: &UiTransactionStatusMeta) -> Vec<Pubkey> {
+    let loaded: Option<UiLoadedAddresses> = meta.loaded_addresses.clone().into();
+    let loaded = loaded
+        .map(|loaded| loaded.writable.into_iter().chain(loaded.readonly).collect::<Vec<_>>())
+        .unwrap_or_default();
+    message
+        .static_account_keys()
+        .iter()
+        .copied()
+        .chain(loaded.iter().filter_map(|key| Pubkey::from_str(key).ok()))
+        .collect()
+}
+
+/// Top-level instructions in order, each followed by the instructions it
+/// invoked.
+fn instructions(message: &VersionedMessage, meta: &UiTransactionStatusMeta, keys: &[Pubkey]) -> Vec<ParsedInstruction> {
+    let resolve = |program_id_index: u8, accounts: &[u8], data: Vec<u8>| {
+        Some(ParsedInstruction {
+            program_id: *keys.get(program_id_index as usize)?,
+            accounts: accounts
+                .iter()
+                .map(|index| keys.get(*index as usize).copied())
+                .collect::<Option<_>>()?,
+            data,
+        })
+    };
+    let inner = Option::<Vec<_>>::from(meta.inner_instructions.clone()).unwrap_or_default();
+
+    let mut parsed = Vec::new();
+    for (index, instruction) in message.instructions().iter().enumerate() {
+        parsed.extend(resolve(
+            instruction.program_id_index,
+            &instruction.accounts,
+            instruction.data.clone(),
+        ));
+        for invoked in inner.iter().filter(|inner| inner.index as usize == index) {
+            for instruction in &invoked.instructions {
+                if let UiInstruction::Compiled(instruction) = instruction {
+                    let Ok(data) = bs58::decode(&instruction.data).into_vec() else {
+                        continue;
+                    };
+                    parsed.extend(resolve(instruction.program_id_index, &instruction.accounts, data));
+                }
+            }
+        }
+    }
+    parsed
+}
+
+/// Pre- and post-transaction balances of every token account involved.
+pub(crate) fn token_balances(keys: &[Pubkey], meta: &UiTransactionStatusMeta) -> HashMap<Pubkey, TokenAccountBalance> {
+    let pre = Option::<Vec<_>>::from(meta.pre_token_balances.clone()).unwrap_or_default();
+    let post = Option::<Vec<_>>::from(meta.post_token_balances.clone()).unwrap_or_default();
+
+    let mut balances = HashMap::new();
+    for (balance, is_post) in pre.into_iter().map(|b| (b, false)).chain(post.into_iter().map(|b| (b, true))) {
+        let Some(key) = keys.get(balance.account_index as usize) else {
+            continue;
+        };
+        let amount = balance.ui_token_amount.amount.parse().unwrap_or_default();
+        let entry = balances.entry(*key).or_insert_with(|| TokenAccountBalance {
+            mint: balance.mint.clone(),
+            owner: Option::from(balance.owner.clone()),
+            decimals: balance.ui_token_amount.decimals,
+            pre: 0,
+            post: 0,
+        });
+        if is_post {
+            entry.post = amount;
+        } else {
+            entry.pre = amount;
+        }
+    }
+    balances
+}
+
+impl Context<'_> {
+    fn is_owned(&self, key: &Pubkey) -> bool {
+        self.owned.contains(key)
+    }
+
+    /// The wallet behind a token account when known, else the account.
+    fn counterparty(&self, key: &Pubkey) -> String {
+        self.tokens
+            .get(key)
+            .and_then(|balance| balance.owner.clone())
+            .unwrap_or_else(|| key.to_string())
+    }
+
+    fn post_balance(&self, key: &Pubkey) -> u64 {
+        self.keys
+            .iter()
+            .position(|k| k == key)
+            .and_then(|index| self.meta.post_balances.get(index).copied())
+            .unwrap_or_default()
+    }
+
+    fn decode_system(&self, instruction: &ParsedInstruction) -> Option<ActivityEvent> {
+        let (lamports, from, to) = match bincode::deserialize(&instruction.data).ok()? {
+            SystemInstruction::Transfer { lamports } => {
+                (lamports, instruction.accounts.first()?, instruction.accounts.get(1)?)
+            }
+            SystemInstruction::TransferWithSeed { lamports, .. } => {
+                (lamports, instruction.accounts.first()?, instruction.accounts.get(2)?)
+            }
+            _ => return None,
+        };
+        match (self.is_owned(from), self.is_owned(to)) {
+            (true, false) => Some(ActivityEvent::SolSent {
+                lamports,
+                to: to.to_string(),
+            }),
+            (false, true) => Some(ActivityEvent::SolReceived {
+                lamports,
+                from: from.to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn decode_token(&self, instruction: &ParsedInstruction) -> Option<ActivityEvent> {
+        let (amount, source, destination) = match TokenInstruction::unpack(&instruction.data).ok()? {
+            TokenInstruction::Transfer { amount } => {
+                (amount, instruction.accounts.first()?, instruction.accounts.get(1)?)
+            }
+            TokenInstruction::TransferChecked { amount, .. } => {
+                (amount, instruction.accounts.first()?, instruction.accounts.get(2)?)
+            }
+            _ => return None,
+        };
+        let balance = self.tokens.get(source).or_else(|| self.tokens.get(destination))?;
+        let mint = balance.mint.clone();
+        let decimals = balance.decimals;
+        let nft = decimals == 0 && amount == 1;
+
+        match (self.is_owned(source), self.is_owned(destination)) {
+            (true, false) => {
+                let to = self.counterparty(destination);
+                Some(if nft {
+                    ActivityEvent::NftSent { mint, to }
+                } else {
+                    ActivityEvent::TokenSent {
+                        mint,
+                        amount,
+                        decimals,
+                        to,
+                    }
+                })
+            }
+            (false, true) => {
+                let from = self.counterparty(source);
+                Some(if nft {
+                    ActivityEvent::NftReceived { mint, from }
+                } else {
+                    ActivityEvent::TokenReceived {
+                        mint,
+                        amount,
+                        decimals,
+                        from,
+                    }
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn decode_stake(&self, address: &Pubkey, instruction: &ParsedInstruction) -> Option<ActivityEvent> {
+        if !instruction.accounts.contains(address) {
+            return None;
+        }
+        let stake_account = instruction.accounts.first()?;
+        match bincode::deserialize(&instruction.data).ok()? {
+            StakeInstruction::DelegateStake => Some(ActivityEvent::Staked {
+                stake_account: stake_account.to_string(),
+                vote_account: instruction.accounts.get(1)?.to_string(),
+                lamports: self.post_balance(stake_account),
+            }),
+            StakeInstruction::Deactivate => Some(ActivityEvent::Unstaked {
+                stake_account: stake_account.to_string(),
+            }),
+            StakeInstruction::Withdraw(lamports) => Some(ActivityEvent::StakeWithdrawn {
+                stake_account: stake_account.to_string(),
+                lamports,
+            }),
+            _ => None,
+        }
+    }
+
+    /// A swap is one asset out and one in across the account's token
+    /// accounts, with native SOL standing in when only one side is a token.
+    fn swap(&self, address: &Pubkey) -> Option<ActivityEvent> {
+        let dex = self.keys.iter().find_map(Dex::from_program)?;
+
+        let owner = address.to_string();
+        let mut changes: HashMap<&str, (i128, u8)> = HashMap::new();
+        for balance in self.tokens.values().filter(|b| b.owner.as_ref() == Some(&owner)) {
+            let change = changes.entry(balance.mint.as_str()).or_insert((0, balance.decimals));
+            change.0 += balance.post as i128 - balance.pre as i128;
+        }
+        let sent: Vec<_> = changes.iter().filter(|(_, (change, _))| *change < 0).collect();
+        let received: Vec<_> = changes.iter().filter(|(_, (change, _))| *change > 0).collect();
+
+        let index = self.keys.iter().position(|key| key == address)?;
+        let fee = if index == 0 { self.meta.fee as i128 } else { 0 };
+        let sol_change = *self.meta.post_balances.get(index)? as i128 - *self.meta.pre_balances.get(index)? as i128 + fee;
+        let native = spl_token::native_mint::id().to_string();
+        let sol = (native.as_str(), (sol_change, SOL_DECIMALS));
+
+        let (input, output) = match (sent.as_slice(), received.as_slice()) {
+            ([(input_mint, input)], [(output_mint, output)]) => ((**input_mint, **input), (**output_mint, **output)),
+            ([], [(output_mint, output)]) if sol_change < 0 => (sol, (**output_mint, **output)),
+            ([(input_mint, input)], []) if sol_change > 0 => ((**input_mint, **input), sol),
+            _ => return None,
+        };
+        Some(ActivityEvent::Swapped {
+            dex,
+            input_mint: input.0.to_string(),
+            input_amount: input.1 .0.unsigned_abs() as u64,
+            input_decimals: input.1 .1,
+            output_mint: output.0.to_string(),
+            output_amount: output.1 .0 as u64,
+            output_decimals: output.1 .1,
+        })
+    }
+}
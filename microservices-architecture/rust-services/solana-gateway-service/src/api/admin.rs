@@ -0,0 +1,41 @@
+use crate::{handlers, middleware, AppState};
+use axum::{
+    middleware::from_fn_with_state,
+    routing::{delete, get, post, put},
+    Router,
+};
+
+/// Operational controls, mounted when `admin.enabled` is set. Every call is
+/// audited.
+pub fn routes(state: &AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/admin/api-keys",
+            get(handlers::admin::list_api_keys).post(handlers::admin::create_api_key),
+        )
+        .route("/admin/api-keys/:id", delete(handlers::admin::revoke_api_key))
+        .route("/admin/api-keys/:id/rotate", post(handlers::admin::rotate_api_key))
+        .route("/admin/api-keys/:id/scopes", put(handlers::admin::set_api_key_scopes))
+        .route("/admin/api-keys/:id/rate-limit", put(handlers::admin::set_api_key_rate_limit))
+        .route("/admin/api-keys/:id/usage", get(handlers::admin::get_api_key_usage))
+        .route(
+            "/admin/maintenance",
+            get(handlers::admin::get_maintenance).put(handlers::admin::set_maintenance),
+        )
+        .route(
+            "/admin/log-level",
+            get(handlers::admin::get_log_level).put(handlers::admin::set_log_level),
+        )
+        .route(
+            "/admin/compliance",
+            get(handlers::admin::list_compliance_entries).post(handlers::admin::add_compliance_entry),
+        )
+        .route("/admin/compliance/:id", delete(handlers::admin::remove_compliance_entry))
+        .route("/admin/config/reload", post(handlers::admin::reload_config))
+        .route("/admin/caches/flush", post(handlers::admin::flush_caches))
+        .route("/admin/rpc/endpoints", get(handlers::admin::get_rpc_endpoints))
+        .route("/admin/pools/reindex", post(handlers::admin::reindex_pools))
+        .route_layer(from_fn_with_state(state.clone(), middleware::audit_mutations))
+        .route_layer(from_fn_with_state(state.clone(), middleware::require_admin_token))
+        .route_layer(from_fn_with_state(state.clone(), middleware::limit_body_trading))
+}
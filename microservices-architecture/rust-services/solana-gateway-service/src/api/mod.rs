@@ -0,0 +1,173 @@
+use crate::config::DeprecationConfig;
+use crate::maintenance::MaintenanceGroup;
+use crate::{handlers, middleware, openapi, AppState};
+use axum::{
+    error_handling::HandleErrorLayer,
+    extract::{Request, State},
+    http::{Extensions, HeaderMap, HeaderName, HeaderValue, StatusCode, Version},
+    middleware::{from_fn_with_state, Next},
+    response::Response,
+    routing::{get, post},
+    Extension, Router,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tower::{limit::GlobalConcurrencyLimitLayer, ServiceBuilder};
+use tower_http::{
+    compression::{
+        predicate::{NotForContentType, Predicate, SizeAbove},
+        CompressionLayer,
+    },
+    timeout::TimeoutLayer,
+};
+
+pub mod admin;
+pub mod v1;
+pub mod v2;
+
+/// Every API version plus the unversioned health, metrics and docs routes.
+/// Versions are separate route trees under `/api/<version>`, so a breaking
+/// change ships as a new version while the old one keeps serving.
+pub fn router(state: AppState) -> Router {
+    let mut router = Router::new()
+        .route("/health", get(crate::health_check))
+        .route("/health/live", get(handlers::health::liveness))
+        .route("/health/ready", get(handlers::health::readiness))
+        .route("/metrics", get(handlers::metrics::get_metrics))
+        .route("/api/v1/openapi.json", get(openapi::openapi_json))
+        .merge(versioned(&state, "v1", v1::routes(&state)))
+        .merge(openapi::swagger_ui(&state.config));
+    if state.config.api.v2_enabled {
+        router = router.merge(versioned(&state, "v2", v2::routes(&state)));
+    }
+    if state.config.graphql.enabled {
+        router = router.merge(graphql(&state));
+    }
+    if state.config.admin.enabled {
+        router = router.merge(admin::routes(&state));
+    }
+
+    router
+        .route_layer(from_fn_with_state(state.clone(), middleware::track_metrics))
+        .with_state(state)
+}
+
+/// `POST /graphql` is a read endpoint; GraphiQL, like Swagger UI, is served
+/// without authentication.
+fn graphql(state: &AppState) -> Router<AppState> {
+    let routes = Router::new()
+        .route("/graphql", post(handlers::graphql::graphql))
+        .layer(Extension(crate::graphql::schema(state.clone())));
+    let mut router = read_group(state, routes);
+    if state.config.graphql.graphiql {
+        router = router.route("/graphiql", get(handlers::graphql::graphiql));
+    }
+    router
+}
+
+/// Read endpoints need the viewer role and get the read body and rate
+/// limits, deadline and concurrency cap.
+fn read_group(state: &AppState, routes: Router<AppState>) -> Router<AppState> {
+    let routes = routes
+        .route_layer(from_fn_with_state(state.clone(), middleware::rate_limit_read))
+        .route_layer(from_fn_with_state(state.clone(), middleware::require_viewer))
+        .route_layer(from_fn_with_state(state.clone(), middleware::limit_body_read));
+    let routes = with_limits(state, routes, state.config.timeouts.read(), state.config.concurrency.read);
+    compressed(state, routes, state.config.compression.read)
+}
+
+/// Mutating endpoints need the trader role, are audited and get the trading
+/// body and rate limits and deadline, with their own concurrency cap. They
+/// are rejected while their maintenance group is paused.
+fn trading_group(
+    state: &AppState,
+    routes: Router<AppState>,
+    group: MaintenanceGroup,
+    concurrency: usize,
+) -> Router<AppState> {
+    let routes = routes
+        .route_layer(from_fn_with_state(state.clone(), middleware::audit_mutations))
+        .route_layer(from_fn_with_state(state.clone(), middleware::rate_limit_trading))
+        .route_layer(from_fn_with_state((state.clone(), group), middleware::reject_when_paused))
+        .route_layer(from_fn_with_state(state.clone(), middleware::require_trader))
+        .route_layer(from_fn_with_state(state.clone(), middleware::limit_body_trading));
+    let routes = with_limits(state, routes, state.config.timeouts.trading(), concurrency);
+    compressed(state, routes, state.config.compression.trading)
+}
+
+fn with_limits(state: &AppState, routes: Router<AppState>, timeout: Duration, concurrency: usize) -> Router<AppState> {
+    let retry_after = state.config.concurrency.retry_after_secs;
+    routes.layer(TimeoutLayer::new(timeout)).layer(
+        ServiceBuilder::new()
+            .layer(HandleErrorLayer::new(move |err| async move { middleware::shed_load(err, retry_after) }))
+            .load_shed()
+            // Shared by every route in the group, unlike `concurrency_limit`
+            .layer(GlobalConcurrencyLimitLayer::new(concurrency)),
+    )
+}
+
+fn compressed(state: &AppState, routes: Router<AppState>, enabled: bool) -> Router<AppState> {
+    if !enabled {
+        return routes;
+    }
+    let config = &state.config.compression;
+    let predicate = SizeAbove::new(config.min_bytes)
+        .and(NotForContentType::GRPC)
+        .and(NotForContentType::IMAGES)
+        .and(NotForContentType::SSE)
+        // WebSocket upgrades have no body to compress
+        .and(|status: StatusCode, _: Version, _: &HeaderMap, _: &Extensions| {
+            status != StatusCode::SWITCHING_PROTOCOLS
+        });
+    routes.layer(
+        CompressionLayer::new()
+            .gzip(config.gzip)
+            .br(config.br)
+            .zstd(config.zstd)
+            .no_deflate()
+            .compress_when(predicate),
+    )
+}
+
+/// Adds deprecation headers to a version's responses when it is configured
+/// as deprecated.
+fn versioned(state: &AppState, version: &str, routes: Router<AppState>) -> Router<AppState> {
+    match state.config.api.deprecations.get(version) {
+        Some(policy) => routes.layer(from_fn_with_state(Arc::new(policy.clone()), deprecation_headers)),
+        None => routes,
+    }
+}
+
+/// `Deprecation` (RFC 9745), `Sunset` (RFC 8594) and a `Link` to the
+/// successor version.
+async fn deprecation_headers(
+    State(policy): State<Arc<DeprecationConfig>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+
+    let deprecation = match policy.deprecated_at {
+        Some(at) => format!("@{}", at.timestamp()),
+        None => "true".to_string(),
+    };
+    let sunset = policy
+        .sunset_at
+        .map(|at| at.format("%a, %d %b %Y %H:%M:%S GMT").to_string());
+    let link = policy
+        .successor
+        .as_ref()
+        .map(|successor| format!("<{}>; rel=\"successor-version\"", successor));
+
+    for (name, value) in [
+        ("deprecation", Some(deprecation)),
+        ("sunset", sunset),
+        ("link", link),
+    ] {
+        if let Some(value) = value.and_then(|v| HeaderValue::from_str(&v).ok()) {
+            headers.insert(HeaderName::from_static(name), value);
+        }
+    }
+    response
+}
@@ -0,0 +1,106 @@
+use super::{read_group, trading_group};
+use crate::maintenance::MaintenanceGroup;
+use crate::{
+    create_transaction, execute_swap, get_account_balance, get_account_info, get_pool_info, get_pools,
+    get_token_balances, get_token_info, get_transaction, handlers, middleware, AppState,
+};
+use axum::{
+    middleware::from_fn_with_state,
+    routing::{delete, get, post},
+    Router,
+};
+
+pub fn routes(state: &AppState) -> Router<AppState> {
+    let admin = from_fn_with_state(state.clone(), middleware::require_admin);
+
+    let mut read_routes = Router::new()
+        .route("/api/v1/accounts/batch", post(handlers::accounts::get_accounts_batch))
+        .route("/api/v1/accounts/:address", get(get_account_info))
+        .route("/api/v1/accounts/:address/activity", get(handlers::activity::get_account_activity))
+        .route("/api/v1/accounts/:address/balance", get(get_account_balance))
+        .route("/api/v1/accounts/:address/balance-history", get(handlers::balance_history::get_balance_history))
+        .route("/api/v1/accounts/:address/decoded", get(handlers::accounts::get_decoded_account))
+        .route("/api/v1/accounts/:address/tokens", get(get_token_balances))
+        .route("/api/v1/accounts/:address/transactions", get(handlers::history::get_account_transactions))
+        .route("/api/v1/accounts/:address/nfts", get(handlers::nfts::get_account_nfts))
+        .route("/api/v1/accounts/:address/stakes", get(handlers::stakes::get_stake_accounts))
+        .route("/api/v1/accounts/:address/subscribe", get(handlers::subscriptions::subscribe_account))
+        .route("/api/v1/transactions/statuses", post(handlers::transactions::get_signature_statuses))
+        .route("/api/v1/transactions/:signature", get(get_transaction))
+        .route("/api/v1/transactions/:signature/stream", get(handlers::subscriptions::stream_transaction_status))
+        .route("/api/v1/tokens/:mint", get(get_token_info))
+        .route("/api/v1/tokens/:mint/holders", get(handlers::holders::get_token_holders))
+        .route("/api/v1/nfts/:mint", get(handlers::nfts::get_nft))
+        .route("/api/v1/fees/priority", get(handlers::fees::get_priority_fees))
+        .route("/api/v1/rent", get(handlers::rent::get_rent_exemption))
+        .route("/api/v1/verify-signature", post(handlers::signatures::verify_signature))
+        .route("/api/v1/pda", post(handlers::pda::derive_pda))
+        .route("/api/v1/prices", get(handlers::prices::get_prices))
+        .route("/api/v1/prices/:mint", get(handlers::prices::get_price))
+        .route("/api/v1/wallets", get(handlers::wallets::list_wallets))
+        .route("/api/v1/wallets/:id", get(handlers::wallets::get_wallet))
+        .route("/api/v1/approvals", get(handlers::approvals::list_approvals))
+        .route("/api/v1/approvals/:id", get(handlers::approvals::get_approval))
+        .route("/api/v1/nonces/:address", get(handlers::nonces::get_nonce))
+        .route("/api/v1/audit", get(handlers::audit::get_audit_log).route_layer(admin.clone()))
+        .route("/api/v1/stats/fees", get(handlers::stats::get_fee_stats).route_layer(admin.clone()))
+        .route("/api/v1/me/usage", get(handlers::usage::get_my_usage))
+        .route("/api/v1/programs/:program_id/logs/stream", get(handlers::subscriptions::stream_program_logs))
+        .route("/api/v1/pools", get(get_pools))
+        .route("/api/v1/pools/stream", get(handlers::subscriptions::stream_new_pools))
+        .route("/api/v1/pools/:pool_id", get(get_pool_info))
+        .route("/api/v1/pools/:pool_id/candles", get(handlers::candles::get_pool_candles))
+        .route("/api/v1/swap/routes", get(handlers::swap::get_swap_routes))
+        .route("/api/v1/swap/quotes", get(handlers::swap::get_swap_quotes))
+        .route("/api/v1/swap/quote", post(handlers::swap::quote_swap))
+        .route("/api/v1/watchlist", get(handlers::watchlist::list_watchlist))
+        .route("/api/v1/watchlist/:address/changes", get(handlers::watchlist::get_account_changes))
+        .route("/api/v1/watchlist/:address/authority-changes", get(handlers::watchlist::get_authority_changes))
+        .route("/api/v1/dca", get(handlers::dca::list_dca_schedules))
+        .route("/api/v1/dca/:id", get(handlers::dca::get_dca_schedule))
+        .route("/api/v1/dca/:id/executions", get(handlers::dca::get_dca_executions));
+    if state.config.rpc_passthrough.enabled {
+        read_routes = read_routes.route("/api/v1/rpc", post(handlers::rpc::rpc_passthrough));
+    }
+
+    let trading_routes = Router::new()
+        .route("/api/v1/transactions", post(create_transaction))
+        .route("/api/v1/transactions/submit", post(handlers::transactions::submit_signed_transaction))
+        .route("/api/v1/approvals/:id/approve", post(handlers::approvals::approve))
+        .route("/api/v1/accounts/:address/token-accounts", post(handlers::token_accounts::create_token_account))
+        .route(
+            "/api/v1/accounts/:address/token-accounts/cleanup",
+            post(handlers::token_accounts::cleanup_token_accounts),
+        )
+        .route("/api/v1/wallets", post(handlers::wallets::create_wallet))
+        .route("/api/v1/wallets/import", post(handlers::wallets::import_wallet))
+        .route("/api/v1/wallets/:id/export", post(handlers::wallets::export_wallet).route_layer(admin))
+        .route("/api/v1/nonces", post(handlers::nonces::create_nonce))
+        .route("/api/v1/nonces/:address/advance", post(handlers::nonces::advance_nonce))
+        .route("/api/v1/stakes/delegate", post(handlers::stakes::delegate_stake))
+        .route("/api/v1/stakes/deactivate", post(handlers::stakes::deactivate_stake))
+        .route("/api/v1/stakes/withdraw", post(handlers::stakes::withdraw_stake))
+        .route("/api/v1/watchlist", post(handlers::watchlist::watch_account))
+        .route("/api/v1/watchlist/:address", delete(handlers::watchlist::unwatch_account));
+
+    let swap_routes = Router::new()
+        .route("/api/v1/swap", post(execute_swap))
+        .route("/api/v1/swap/transaction", post(handlers::swap::build_swap_transaction))
+        .route("/api/v1/swap/route/transaction", post(handlers::swap::build_route_transaction))
+        .route("/api/v1/pools/:pool_id/deposit", post(handlers::liquidity::deposit_liquidity))
+        .route("/api/v1/pools/:pool_id/withdraw", post(handlers::liquidity::withdraw_liquidity))
+        .route("/api/v1/dca", post(handlers::dca::create_dca_schedule))
+        .route("/api/v1/dca/:id/pause", post(handlers::dca::pause_dca_schedule))
+        .route("/api/v1/dca/:id/resume", post(handlers::dca::resume_dca_schedule))
+        .route("/api/v1/dca/:id/cancel", post(handlers::dca::cancel_dca_schedule));
+
+    Router::new()
+        .merge(read_group(state, read_routes))
+        .merge(trading_group(
+            state,
+            trading_routes,
+            MaintenanceGroup::Transactions,
+            state.config.concurrency.write,
+        ))
+        .merge(trading_group(state, swap_routes, MaintenanceGroup::Swaps, state.config.concurrency.swap))
+}
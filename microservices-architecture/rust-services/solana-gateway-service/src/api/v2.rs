@@ -0,0 +1,76 @@
+//! Version 2 of the REST API. Routes land here when their response shape
+//! changes incompatibly; everything else stays on v1 until v2 is complete.
+
+use super::read_group;
+use crate::cache::CacheBypass;
+use crate::error::{ApiError, ErrorBody};
+use crate::solana_client::AccountInfo;
+use crate::AppState;
+use axum::{
+    extract::{Path, State},
+    response::Json,
+    routing::get,
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use utoipa::ToSchema;
+
+pub fn routes(state: &AppState) -> Router<AppState> {
+    let read_routes = Router::new().route("/api/v2/accounts/:address", get(get_account));
+
+    Router::new().merge(read_group(state, read_routes))
+}
+
+/// v1 `AccountInfo` with `balance` renamed to `lamports`.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AccountV2 {
+    pub address: String,
+    pub lamports: u64,
+    pub owner: String,
+    pub executable: bool,
+    pub rent_epoch: u64,
+}
+
+impl From<AccountInfo> for AccountV2 {
+    fn from(account: AccountInfo) -> Self {
+        Self {
+            address: account.address,
+            lamports: account.balance,
+            owner: account.owner,
+            executable: account.executable,
+            rent_epoch: account.rent_epoch,
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/accounts/{address}",
+    tag = "accounts",
+    params(("address" = String, Path, description = "Base58 account address")),
+    responses(
+        (status = 200, description = "Account info", body = AccountV2),
+        (status = 400, description = "Invalid address", body = ErrorBody),
+        (status = 404, description = "Account not found", body = ErrorBody),
+        (status = 503, description = "RPC unavailable", body = ErrorBody)
+    )
+)]
+pub async fn get_account(
+    State(state): State<AppState>,
+    CacheBypass(bypass): CacheBypass,
+    Path(address): Path<String>,
+) -> Result<Json<AccountV2>, ApiError> {
+    // Shares the v1 cache entry; only the response shape differs.
+    let key = format!("account_info:{}", address);
+    let ttl = state.config.cache.account_info_ttl();
+    let fetch = || state.solana_client.get_account_info(&address);
+
+    match state.cache.get_or_fetch(&key, ttl, bypass, fetch).await {
+        Ok(account_info) => Ok(Json(AccountV2::from(account_info))),
+        Err(e) => {
+            warn!("Failed to get account info for {}: {}", address, e);
+            Err(e.into())
+        }
+    }
+}
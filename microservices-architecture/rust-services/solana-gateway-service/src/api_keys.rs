@@ -0,0 +1,295 @@
+use crate::audit::hex_sha256;
+use crate::auth::{Principal, Role};
+use crate::config::{ApiKeyConfig, ClientQuota};
+use crate::database::Database;
+use crate::error::ApiError;
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use moka::future::Cache;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tracing::warn;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+const KEY_PREFIX: &str = "sgw_";
+const MAX_CACHED_KEYS: u64 = 10_000;
+
+/// An issued key as stored; the key itself is only known to its holder.
+#[derive(Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub name: String,
+    /// Role names (`viewer`, `trader`, `admin`); the key acts with the
+    /// highest of them.
+    pub scopes: Vec<String>,
+    /// Quota overrides; the configured defaults apply when unset.
+    pub read_per_minute: Option<i32>,
+    pub trading_per_minute: Option<i32>,
+    /// Requests authenticated with the key, lagging by up to the sync
+    /// interval.
+    pub request_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Per-key quotas, both set or both omitted to use the configured defaults.
+#[derive(Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyRateLimit {
+    pub read_per_minute: Option<u32>,
+    pub trading_per_minute: Option<u32>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct NewApiKey {
+    pub name: String,
+    pub scopes: Vec<String>,
+    #[serde(flatten)]
+    pub rate_limit: ApiKeyRateLimit,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ApiKeyScopes {
+    pub scopes: Vec<String>,
+}
+
+/// What a verified key entitles its caller to, attached to the request as
+/// an extension.
+#[derive(Clone, Debug)]
+pub struct ApiKeyGrant {
+    pub id: Uuid,
+    pub role: Role,
+    pub quota: Option<ClientQuota>,
+}
+
+/// Usage counted in memory since the last sync.
+#[derive(Default)]
+pub struct PendingUsage {
+    pub requests: i64,
+    /// Requests answered with a 4xx status.
+    pub client_errors: i64,
+    /// Requests answered with a 5xx status.
+    pub server_errors: i64,
+    pub last_used_at: Option<DateTime<Utc>>,
+    /// Swaps and their summed input, in raw units, per input mint.
+    pub volume: HashMap<String, (i64, u128)>,
+}
+
+/// Swaps made with a key and their summed input in one mint.
+#[derive(Serialize, sqlx::FromRow, ToSchema)]
+pub struct MintVolume {
+    pub mint: String,
+    pub swaps: i64,
+    /// Raw units of `mint`, as a decimal string since it can exceed 64 bits.
+    pub amount: String,
+}
+
+/// A key's usage over one period.
+#[derive(Serialize, ToSchema)]
+pub struct UsagePeriod {
+    pub period_start: DateTime<Utc>,
+    pub requests: i64,
+    pub client_errors: i64,
+    pub server_errors: i64,
+    /// Share of `requests` answered with an error status.
+    pub error_rate: f64,
+    /// Swaps the gateway executed and submitted, per input mint. Swaps only
+    /// built for the client to sign aren't counted.
+    pub volume: Vec<MintVolume>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ApiKeyUsage {
+    pub api_key_id: Uuid,
+    /// Newest period first. Usage is recorded by the hour it was synced
+    /// in, lagging by up to the sync interval.
+    pub periods: Vec<UsagePeriod>,
+}
+
+/// Verifies `X-API-Key` values against the database. Verified keys are
+/// cached, and revoked key IDs are kept in memory so a revocation takes
+/// effect on the next request rather than when the cache entry expires.
+/// Usage is counted in memory and written back by `spawn_sync`.
+pub struct ApiKeyStore {
+    enabled: bool,
+    salt: Option<String>,
+    database: Arc<Database>,
+    verified: Cache<String, ApiKeyGrant>,
+    revoked: RwLock<HashSet<Uuid>>,
+    usage: Mutex<HashMap<Uuid, PendingUsage>>,
+}
+
+impl ApiKey {
+    fn grant(&self) -> ApiKeyGrant {
+        let quota = match (self.read_per_minute, self.trading_per_minute) {
+            (Some(read), Some(trading)) => Some(ClientQuota {
+                read_per_minute: read.max(1) as u32,
+                trading_per_minute: trading.max(1) as u32,
+            }),
+            _ => None,
+        };
+        ApiKeyGrant {
+            id: self.id,
+            // Scopes are validated when set, so unknown names only come from
+            // manual edits and grant nothing.
+            role: self
+                .scopes
+                .iter()
+                .filter_map(|scope| Role::from_name(scope))
+                .max()
+                .unwrap_or(Role::Viewer),
+            quota,
+        }
+    }
+}
+
+impl ApiKeyGrant {
+    pub fn principal(&self) -> Principal {
+        Principal {
+            subject: format!("api-key:{}", self.id),
+            role: self.role,
+        }
+    }
+}
+
+impl ApiKeyStore {
+    pub fn new(database: Arc<Database>, config: &ApiKeyConfig) -> Self {
+        Self {
+            enabled: config.enabled,
+            salt: config.salt.clone(),
+            database,
+            verified: Cache::builder()
+                .max_capacity(MAX_CACHED_KEYS)
+                .time_to_live(Duration::from_secs(config.cache_secs))
+                .build(),
+            revoked: RwLock::new(HashSet::new()),
+            usage: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// A new random API key and the hash stored in place of it.
+    pub fn generate(&self) -> (String, String) {
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let key = format!("{}{}", KEY_PREFIX, bs58::encode(bytes).into_string());
+        let hash = self.hash(&key);
+        (key, hash)
+    }
+
+    fn hash(&self, key: &str) -> String {
+        match &self.salt {
+            Some(salt) => hex_sha256(format!("{}{}", salt, key).as_bytes()),
+            None => hex_sha256(key.as_bytes()),
+        }
+    }
+
+    /// Resolves a presented key and counts the request against it.
+    pub async fn verify(&self, key: &str) -> Result<ApiKeyGrant, ApiError> {
+        let hash = self.hash(key);
+        let grant = match self.verified.get(&hash).await {
+            Some(grant) => grant,
+            None => {
+                let Some(api_key) = self.database.get_api_key_by_hash(&hash).await? else {
+                    return Err(ApiError::Unauthorized("Invalid API key".to_string()));
+                };
+                let grant = api_key.grant();
+                self.verified.insert(hash, grant.clone()).await;
+                grant
+            }
+        };
+        if self.revoked.read().unwrap().contains(&grant.id) {
+            return Err(ApiError::Unauthorized("API key has been revoked".to_string()));
+        }
+
+        let mut usage = self.usage.lock().unwrap();
+        let usage = usage.entry(grant.id).or_default();
+        usage.requests += 1;
+        usage.last_used_at = Some(Utc::now());
+        Ok(grant)
+    }
+
+    /// Counts an error status answered to a request made with the key.
+    pub fn record_response(&self, id: Uuid, status: StatusCode) {
+        if !status.is_client_error() && !status.is_server_error() {
+            return;
+        }
+        let mut usage = self.usage.lock().unwrap();
+        let usage = usage.entry(id).or_default();
+        if status.is_client_error() {
+            usage.client_errors += 1;
+        } else {
+            usage.server_errors += 1;
+        }
+    }
+
+    /// Counts a swap of `amount_in` raw units of `input_mint` submitted with
+    /// the key, if the caller used one.
+    pub fn record_swap(&self, grant: Option<&ApiKeyGrant>, input_mint: &str, amount_in: u64) {
+        let Some(grant) = grant else {
+            return;
+        };
+        let mut usage = self.usage.lock().unwrap();
+        let (swaps, amount) = usage
+            .entry(grant.id)
+            .or_default()
+            .volume
+            .entry(input_mint.to_string())
+            .or_default();
+        *swaps += 1;
+        *amount += u128::from(amount_in);
+    }
+
+    /// Rejects the key from now on, on this replica immediately and on
+    /// others at their next sync.
+    pub async fn revoke(&self, id: Uuid) {
+        self.revoked.write().unwrap().insert(id);
+        self.forget(id).await;
+    }
+
+    /// Drops cached grants for a key whose hash, scopes or quotas changed.
+    pub async fn forget(&self, id: Uuid) {
+        let stale: Vec<Arc<String>> = self
+            .verified
+            .iter()
+            .filter(|(_, grant)| grant.id == id)
+            .map(|(hash, _)| hash)
+            .collect();
+        for hash in stale {
+            self.verified.invalidate(hash.as_str()).await;
+        }
+    }
+
+    /// Writes accumulated usage and reloads the revoked key IDs.
+    async fn sync(&self) -> anyhow::Result<()> {
+        let usage = std::mem::take(&mut *self.usage.lock().unwrap());
+        for (id, usage) in usage {
+            self.database.record_api_key_usage(id, &usage).await?;
+        }
+        let revoked = self.database.list_revoked_api_key_ids().await?;
+        *self.revoked.write().unwrap() = revoked.into_iter().collect();
+        Ok(())
+    }
+}
+
+pub fn spawn_sync(store: Arc<ApiKeyStore>, config: ApiKeyConfig) {
+    if !config.enabled {
+        return;
+    }
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.sync_secs.max(1)));
+        loop {
+            interval.tick().await;
+            if let Err(e) = store.sync().await {
+                warn!("Failed to sync API keys: {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,178 @@
+use crate::api_keys::ApiKeyGrant;
+use crate::auth::{Principal, Role};
+use crate::client_references;
+use crate::config::{ApprovalBackend, ApprovalConfig};
+use crate::error::ApiError;
+use crate::solana_client::SignMode;
+use crate::squads;
+use crate::wallets;
+use crate::{AppState, TransactionRequest};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Values of `approvals.status`.
+pub const PENDING: &str = "pending";
+pub const EXECUTING: &str = "executing";
+pub const EXECUTED: &str = "executed";
+pub const PROPOSED: &str = "proposed";
+pub const FAILED: &str = "failed";
+
+/// A transfer held until enough API keys approve it.
+#[derive(Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct Approval {
+    pub id: Uuid,
+    /// The transfer request as submitted.
+    pub request: serde_json::Value,
+    /// Subject of the caller that requested the transfer.
+    pub requested_by: String,
+    /// `gateway` or `squads`.
+    pub backend: String,
+    /// `pending`, `executing`, `executed`, `proposed` (awaiting the Squads
+    /// members) or `failed`.
+    pub status: String,
+    /// API keys that approved, in order.
+    pub approved_by: Vec<Uuid>,
+    /// The sent transfer, or the transaction creating the Squads proposal.
+    pub signature: Option<String>,
+    pub squads_proposal: Option<String>,
+    /// Why the approved transfer couldn't be sent; it isn't retried.
+    pub error: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Whether `request` is a transfer signed by the gateway at or above its
+/// threshold. Client-signed transfers and dry runs never are.
+pub fn requires_approval(config: &ApprovalConfig, request: &TransactionRequest) -> bool {
+    if !config.enabled || request.sign_mode != SignMode::Gateway || request.dry_run {
+        return false;
+    }
+    match &request.mint {
+        Some(mint) => config
+            .mint_thresholds
+            .get(mint)
+            .map_or(false, |threshold| request.amount >= *threshold),
+        None => request.amount >= config.threshold_lamports,
+    }
+}
+
+/// Holds a validated transfer for approval.
+pub async fn request_approval(
+    state: &AppState,
+    principal: Option<&Principal>,
+    request: &TransactionRequest,
+) -> Result<Approval> {
+    let config = &state.config.approvals;
+    if config.backend == ApprovalBackend::Squads {
+        let multisig = squads::multisig_address(&config.squads)?;
+        let vault = squads::vault_address(&multisig, config.squads.vault_index);
+        request.validate_squads(&vault.to_string())?;
+    }
+    // Checked now, as the approved transfer is signed without the requester.
+    if let Some(wallet_id) = request.wallet_id {
+        state.wallets.get(wallets::scope(principal), wallet_id).await?;
+    }
+    let requested_by = client_references::owner(principal);
+    let reference = client_references::claim(&state.database, requested_by, request.client_reference.as_deref()).await?;
+    let approval = state
+        .database
+        .create_approval(request, requested_by, config.backend.as_str(), config.ttl_secs)
+        .await?;
+    if let Some(reference) = reference {
+        reference.complete(None, Some(approval.id)).await;
+    }
+    info!("Transfer {} from {} is awaiting approval", approval.id, request.from);
+    Ok(approval)
+}
+
+/// Records `grant`'s approval. The approval that completes the required
+/// count also signs and sends the transfer, or proposes it to the multisig.
+pub async fn approve(state: &AppState, id: Uuid, grant: &ApiKeyGrant) -> Result<Approval> {
+    let config = &state.config.approvals;
+    let allowed = if config.approvers.is_empty() {
+        grant.role == Role::Admin
+    } else {
+        config.approvers.contains(&grant.id)
+    };
+    if !allowed {
+        return Err(ApiError::Forbidden("API key may not approve transfers".to_string()).into());
+    }
+
+    let subject = grant.principal().subject;
+    let approval = match state
+        .database
+        .add_approval(id, grant.id, &subject, config.required_approvals)
+        .await?
+    {
+        Some(approval) => approval,
+        None => return Err(rejection(state, id, grant.id, &subject).await.into()),
+    };
+    if approval.status != EXECUTING {
+        return Ok(approval);
+    }
+    execute(state, &approval).await
+}
+
+/// Why an approval wasn't recorded.
+async fn rejection(state: &AppState, id: Uuid, key_id: Uuid, subject: &str) -> ApiError {
+    let approval = match state.database.get_approval(id).await {
+        Ok(Some(approval)) => approval,
+        Ok(None) => return ApiError::NotFound(format!("Approval {} not found", id)),
+        Err(e) => return ApiError::from(e),
+    };
+    if approval.requested_by == subject {
+        ApiError::Forbidden("Transfers can't be approved by their requester".to_string())
+    } else if approval.approved_by.contains(&key_id) {
+        ApiError::Conflict(format!("Approval {} was already approved with this key", id))
+    } else if approval.status != PENDING {
+        ApiError::Conflict(format!("Approval {} is {}", id, approval.status))
+    } else {
+        ApiError::Conflict(format!("Approval {} expired at {}", id, approval.expires_at))
+    }
+}
+
+async fn execute(state: &AppState, approval: &Approval) -> Result<Approval> {
+    let outcome = async {
+        let mut request: TransactionRequest = serde_json::from_value(approval.request.clone())?;
+        if approval.backend == ApprovalBackend::Squads.as_str() {
+            let wallet_id = request
+                .wallet_id
+                .ok_or_else(|| ApiError::BadRequest("wallet_id is required".to_string()))?;
+            let member = state.wallets.keypair(None, wallet_id).await?;
+            let proposal = squads::propose(&state.solana_client, &state.config.approvals.squads, &member, &request).await?;
+            info!(
+                "Proposed approval {} as Squads transaction {}",
+                approval.id, proposal.transaction_index
+            );
+            return Ok::<_, anyhow::Error>((PROPOSED, proposal.signature, Some(proposal.proposal.to_string())));
+        }
+        // Keyed per approval, so a transfer that went out but wasn't
+        // recorded is never sent twice.
+        let key = format!("approval:{}", approval.id);
+        // The reference was claimed for this approval when it was requested.
+        request.client_reference = None;
+        let transaction = crate::send_transfer(state, None, None, Some(&key), &request).await?;
+        Ok((EXECUTED, transaction.signature, None))
+    };
+
+    match outcome.await {
+        Ok((status, signature, proposal)) => {
+            state
+                .database
+                .finish_approval(approval.id, status, Some(&signature), proposal.as_deref(), None)
+                .await
+        }
+        Err(e) => {
+            warn!("Failed to execute approved transfer {}: {}", approval.id, e);
+            state
+                .database
+                .finish_approval(approval.id, FAILED, None, None, Some(&e.to_string()))
+                .await
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::{IntoParams, ToSchema};
+
+/// One mutating request as recorded in the append-only `audit_log` table.
+#[derive(Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct AuditEntry {
+    pub id: i64,
+    /// `key:<fingerprint>` for API-key callers, `ip:<address>` otherwise.
+    pub actor: String,
+    pub method: String,
+    pub route: String,
+    /// Hex SHA-256 of the raw request body.
+    pub request_hash: String,
+    pub status_code: i32,
+    /// `success`, `failure`, or `blocked` by the compliance lists.
+    pub outcome: String,
+    pub signature: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct NewAuditEntry {
+    pub actor: String,
+    pub method: String,
+    pub route: String,
+    pub request_hash: String,
+    pub status_code: u16,
+    pub signature: Option<String>,
+    /// The response carried the `blocked` error code.
+    pub blocked: bool,
+}
+
+impl NewAuditEntry {
+    pub fn outcome(&self) -> &'static str {
+        if self.blocked {
+            "blocked"
+        } else if self.status_code < 400 {
+            "success"
+        } else {
+            "failure"
+        }
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct AuditQuery {
+    pub actor: Option<String>,
+    pub route: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Audit actors never contain raw API keys; a key is reduced to a short
+/// SHA-256 fingerprint. Authenticated callers are recorded by token subject.
+pub fn actor(subject: Option<&str>, api_key: Option<&str>, ip: Option<String>) -> String {
+    match (subject, api_key, ip) {
+        (Some(subject), _, _) => format!("sub:{}", subject),
+        (None, Some(key), _) => format!("key:{}", &hex_sha256(key.as_bytes())[..16]),
+        (None, None, Some(ip)) => format!("ip:{}", ip),
+        (None, None, None) => "anonymous".to_string(),
+    }
+}
+
+pub fn hex_sha256(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
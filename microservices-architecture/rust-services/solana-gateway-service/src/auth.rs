@@ -0,0 +1,216 @@
+use crate::config::AuthConfig;
+use crate::error::ApiError;
+use anyhow::{Context, Result};
+use jsonwebtoken::{
+    decode, decode_header,
+    jwk::{AlgorithmParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm},
+    Algorithm, DecodingKey, Validation,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+// Unknown key ids trigger a JWKS refetch at most this often, so forged
+// tokens can't be used to hammer the identity provider.
+const MIN_REFETCH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Gateway roles, each including the permissions of the ones before it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Viewer,
+    Trader,
+    Admin,
+}
+
+impl Role {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::Viewer => "viewer",
+            Role::Trader => "trader",
+            Role::Admin => "admin",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Role> {
+        match name {
+            "viewer" => Some(Role::Viewer),
+            "trader" => Some(Role::Trader),
+            "admin" => Some(Role::Admin),
+            _ => None,
+        }
+    }
+}
+
+/// Verified caller, attached to the request as an extension.
+#[derive(Clone, Debug)]
+pub struct Principal {
+    pub subject: String,
+    pub role: Role,
+}
+
+struct KeySet {
+    keys: HashMap<String, (Algorithm, DecodingKey)>,
+    fetched_at: Instant,
+}
+
+/// Validates JWT bearer tokens against the identity provider's JWKS.
+pub struct Authenticator {
+    config: AuthConfig,
+    http: reqwest::Client,
+    keys: RwLock<KeySet>,
+}
+
+impl Authenticator {
+    pub fn new(config: &AuthConfig) -> Result<Self> {
+        if config.enabled && config.jwks_url.is_none() {
+            anyhow::bail!("auth.jwks_url is required when authentication is enabled");
+        }
+        Ok(Self {
+            config: config.clone(),
+            http: reqwest::Client::builder().timeout(Duration::from_secs(10)).build()?,
+            keys: RwLock::new(KeySet {
+                keys: HashMap::new(),
+                fetched_at: Instant::now() - MIN_REFETCH_INTERVAL,
+            }),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Verifies signature, expiry, issuer and audience, and maps the roles
+    /// claim to the highest gateway role it grants.
+    pub async fn verify(&self, token: &str) -> Result<Principal, ApiError> {
+        let unauthorized = |message: &str| ApiError::Unauthorized(message.to_string());
+
+        let header = decode_header(token).map_err(|_| unauthorized("Malformed bearer token"))?;
+        let kid = header.kid.ok_or_else(|| unauthorized("Bearer token has no key id"))?;
+        let (algorithm, key) = self.key(&kid).await?;
+        if header.alg != algorithm {
+            return Err(unauthorized("Bearer token algorithm does not match its key"));
+        }
+
+        let mut validation = Validation::new(algorithm);
+        if let Some(issuer) = &self.config.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+        match &self.config.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+
+        let claims = decode::<serde_json::Value>(token, &key, &validation)
+            .map_err(|e| unauthorized(&format!("Invalid bearer token: {}", e)))?
+            .claims;
+        let subject = claims
+            .get("sub")
+            .and_then(|sub| sub.as_str())
+            .ok_or_else(|| unauthorized("Bearer token has no subject"))?
+            .to_string();
+        let role = self
+            .role_names(&claims)
+            .filter_map(|name| self.config.role_mapping.get(name).copied().or_else(|| Role::from_name(name)))
+            .max()
+            .ok_or_else(|| ApiError::Forbidden("Bearer token grants no gateway role".to_string()))?;
+
+        Ok(Principal { subject, role })
+    }
+
+    /// Role names from the configured claim, which may hold a string or an
+    /// array of strings.
+    fn role_names<'a>(&self, claims: &'a serde_json::Value) -> impl Iterator<Item = &'a str> {
+        let claim = claims.get(&self.config.roles_claim);
+        let single = claim.and_then(|value| value.as_str());
+        let many = claim
+            .and_then(|value| value.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|value| value.as_str());
+        single.into_iter().chain(many)
+    }
+
+    /// Looks up a signing key, refreshing the JWKS when it is stale or the
+    /// key id is unknown (the provider may have rotated keys).
+    async fn key(&self, kid: &str) -> Result<(Algorithm, DecodingKey), ApiError> {
+        let refresh = Duration::from_secs(self.config.jwks_refresh_secs);
+        {
+            let keys = self.keys.read().await;
+            let fresh = keys.fetched_at.elapsed() < refresh;
+            match keys.keys.get(kid) {
+                Some(key) if fresh => return Ok(key.clone()),
+                None if keys.fetched_at.elapsed() < MIN_REFETCH_INTERVAL => {
+                    return Err(ApiError::Unauthorized("Unknown signing key".to_string()));
+                }
+                _ => {}
+            }
+        }
+
+        let mut keys = self.keys.write().await;
+        match self.fetch_keys().await {
+            Ok(fetched) => {
+                info!("Loaded {} signing keys from JWKS", fetched.len());
+                *keys = KeySet {
+                    keys: fetched,
+                    fetched_at: Instant::now(),
+                };
+            }
+            // Keep serving cached keys while the provider is unreachable.
+            Err(e) => {
+                warn!("Failed to refresh JWKS: {}", e);
+                keys.fetched_at = Instant::now() - refresh.saturating_sub(MIN_REFETCH_INTERVAL);
+            }
+        }
+        keys.keys
+            .get(kid)
+            .cloned()
+            .ok_or_else(|| ApiError::Unauthorized("Unknown signing key".to_string()))
+    }
+
+    async fn fetch_keys(&self) -> Result<HashMap<String, (Algorithm, DecodingKey)>> {
+        let url = self.config.jwks_url.as_deref().context("No JWKS URL configured")?;
+        let jwks: JwkSet = self.http.get(url).send().await?.error_for_status()?.json().await?;
+
+        Ok(jwks
+            .keys
+            .iter()
+            .filter_map(|jwk| {
+                let kid = jwk.common.key_id.clone()?;
+                let algorithm = jwk_algorithm(jwk)?;
+                let key = DecodingKey::from_jwk(jwk).ok()?;
+                Some((kid, (algorithm, key)))
+            })
+            .collect())
+    }
+}
+
+/// Signing algorithm of a JWKS key: its `alg` when present, otherwise the
+/// default for its key type. Only asymmetric algorithms are accepted; a
+/// shared secret has no place in a JWKS.
+fn jwk_algorithm(jwk: &Jwk) -> Option<Algorithm> {
+    match jwk.common.key_algorithm {
+        Some(KeyAlgorithm::RS256) => Some(Algorithm::RS256),
+        Some(KeyAlgorithm::RS384) => Some(Algorithm::RS384),
+        Some(KeyAlgorithm::RS512) => Some(Algorithm::RS512),
+        Some(KeyAlgorithm::PS256) => Some(Algorithm::PS256),
+        Some(KeyAlgorithm::PS384) => Some(Algorithm::PS384),
+        Some(KeyAlgorithm::PS512) => Some(Algorithm::PS512),
+        Some(KeyAlgorithm::ES256) => Some(Algorithm::ES256),
+        Some(KeyAlgorithm::ES384) => Some(Algorithm::ES384),
+        Some(KeyAlgorithm::EdDSA) => Some(Algorithm::EdDSA),
+        Some(_) => None,
+        None => match &jwk.algorithm {
+            AlgorithmParameters::RSA(_) => Some(Algorithm::RS256),
+            AlgorithmParameters::EllipticCurve(params) => match params.curve {
+                EllipticCurve::P256 => Some(Algorithm::ES256),
+                EllipticCurve::P384 => Some(Algorithm::ES384),
+                _ => None,
+            },
+            AlgorithmParameters::OctetKeyPair(_) => Some(Algorithm::EdDSA),
+            AlgorithmParameters::OctetKey(_) => None,
+        },
+    }
+}
@@ -0,0 +1,181 @@
+//! Calls to AWS JSON 1.1 APIs (KMS, Secrets Manager) and S3 uploads with
+//! SigV4 signed requests. Credentials come from the standard
+//! `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and `AWS_SESSION_TOKEN`
+//! variables.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+struct Credentials {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+}
+
+impl Credentials {
+    fn from_env() -> Result<Self> {
+        let var = |name: &str| std::env::var(name).with_context(|| format!("{} is not set", name));
+        Ok(Self {
+            access_key_id: var("AWS_ACCESS_KEY_ID")?,
+            secret_access_key: var("AWS_SECRET_ACCESS_KEY")?,
+            session_token: std::env::var("AWS_SESSION_TOKEN").ok(),
+        })
+    }
+}
+
+pub struct AwsClient {
+    http: reqwest::Client,
+    endpoint: reqwest::Url,
+    region: String,
+    /// Signing name, e.g. `kms`.
+    service: &'static str,
+    /// `X-Amz-Target` prefix, e.g. `TrentService`; unused for S3.
+    target_prefix: &'static str,
+    credentials: Credentials,
+}
+
+impl AwsClient {
+    /// A client for `service` in `region`, or in `AWS_REGION` when unset.
+    /// The endpoint defaults to the service's public regional endpoint.
+    pub fn new(
+        service: &'static str,
+        target_prefix: &'static str,
+        region: Option<&str>,
+        endpoint: Option<&str>,
+        timeout_ms: u64,
+    ) -> Result<Self> {
+        let region = region
+            .map(str::to_string)
+            .or_else(|| std::env::var("AWS_REGION").ok())
+            .ok_or_else(|| anyhow!("A region or AWS_REGION is required for {}", service))?;
+        let endpoint = match endpoint {
+            Some(endpoint) => endpoint.to_string(),
+            None => format!("https://{}.{}.amazonaws.com/", service, region),
+        };
+        Ok(Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_millis(timeout_ms))
+                .build()?,
+            endpoint: reqwest::Url::parse(&endpoint)?,
+            region,
+            service,
+            target_prefix,
+            credentials: Credentials::from_env()?,
+        })
+    }
+
+    /// Calls `action` of a JSON API.
+    pub async fn call(&self, action: &str, body: Value) -> Result<Value> {
+        let headers = vec![
+            ("content-type", "application/x-amz-json-1.1".to_string()),
+            ("x-amz-target", format!("{}.{}", self.target_prefix, action)),
+        ];
+        let path = self.endpoint.path().to_string();
+        let response = self
+            .send(reqwest::Method::POST, &path, headers, serde_json::to_vec(&body)?)
+            .await?;
+        let status = response.status();
+        let body: Value = response.json().await?;
+        if !status.is_success() {
+            return Err(anyhow!("{} {} failed with {}: {}", self.service, action, status, body));
+        }
+        Ok(body)
+    }
+
+    /// Uploads an S3 object, addressed path-style. `key` is used as given,
+    /// so it may only contain characters that need no URI encoding.
+    pub async fn put_object(&self, bucket: &str, key: &str, content_type: &str, body: Vec<u8>) -> Result<()> {
+        let path = format!("{}/{}/{}", self.endpoint.path().trim_end_matches('/'), bucket, key);
+        let headers = vec![("content-type", content_type.to_string())];
+        let response = self.send(reqwest::Method::PUT, &path, headers, body).await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!("s3 PutObject of {} failed with {}: {}", key, status, body));
+        }
+        Ok(())
+    }
+
+    /// Sends a request to `path` on the endpoint, signed with SigV4 over
+    /// `headers` plus the host, date, payload hash and session token.
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        mut headers: Vec<(&'static str, String)>,
+        body: Vec<u8>,
+    ) -> Result<reqwest::Response> {
+        let now = Utc::now();
+        let timestamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let host = self
+            .endpoint
+            .host_str()
+            .ok_or_else(|| anyhow!("{} endpoint has no host", self.service))?;
+        let host = match self.endpoint.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.to_string(),
+        };
+        let payload_hash = hex::encode(Sha256::digest(&body));
+
+        headers.push(("host", host));
+        headers.push(("x-amz-content-sha256", payload_hash.clone()));
+        headers.push(("x-amz-date", timestamp.clone()));
+        if let Some(token) = &self.credentials.session_token {
+            headers.push(("x-amz-security-token", token.clone()));
+        }
+        // Canonical headers are sorted by name.
+        headers.sort_by_key(|(name, _)| *name);
+        let canonical_headers: String = headers
+            .iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value))
+            .collect();
+        let signed_headers = headers.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+
+        let canonical_request = format!(
+            "{}\n{}\n\n{}\n{}\n{}",
+            method, path, canonical_headers, signed_headers, payload_hash
+        );
+        let scope = format!("{}/{}/{}/aws4_request", date, self.region, self.service);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            timestamp,
+            scope,
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+        let key = [date.as_str(), self.region.as_str(), self.service, "aws4_request"]
+            .iter()
+            .fold(
+                format!("AWS4{}", self.credentials.secret_access_key).into_bytes(),
+                |key, part| hmac_sha256(&key, part.as_bytes()),
+            );
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.credentials.access_key_id,
+            scope,
+            signed_headers,
+            hex::encode(hmac_sha256(&key, string_to_sign.as_bytes()))
+        );
+
+        let mut url = self.endpoint.clone();
+        url.set_path(path);
+        let mut request = self
+            .http
+            .request(method, url)
+            .header("authorization", authorization);
+        for (name, value) in headers.into_iter().filter(|(name, _)| *name != "host") {
+            request = request.header(name, value);
+        }
+        Ok(request.body(body).send().await?)
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
@@ -0,0 +1,115 @@
+use crate::config::BalanceSnapshotConfig;
+use crate::database::Database;
+use crate::solana_client::SolanaClient;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+use utoipa::ToSchema;
+
+// Decimals of a SOL balance in lamports.
+const SOL_DECIMALS: u8 = 9;
+
+/// One balance held by an account at the time of a snapshot.
+#[derive(Clone, Serialize, ToSchema)]
+pub struct SnapshotBalance {
+    /// Absent for the SOL balance.
+    pub mint: Option<String>,
+    pub amount: i64,
+    pub decimals: i16,
+}
+
+/// The last snapshot taken within one bucket of the requested interval.
+#[derive(Serialize, ToSchema)]
+pub struct BalancePoint {
+    pub bucket_start: DateTime<Utc>,
+    pub balances: Vec<SnapshotBalance>,
+}
+
+/// A snapshot row with the start of the bucket it falls in.
+#[derive(sqlx::FromRow)]
+pub struct BucketedBalance {
+    pub bucket_start: DateTime<Utc>,
+    pub mint: Option<String>,
+    pub amount: i64,
+    pub decimals: i16,
+}
+
+/// Groups rows ordered by bucket into one point per bucket.
+pub fn into_points(rows: Vec<BucketedBalance>) -> Vec<BalancePoint> {
+    let mut points: Vec<BalancePoint> = Vec::new();
+    for row in rows {
+        let balance = SnapshotBalance {
+            mint: row.mint,
+            amount: row.amount,
+            decimals: row.decimals,
+        };
+        match points.last_mut() {
+            Some(point) if point.bucket_start == row.bucket_start => point.balances.push(balance),
+            _ => points.push(BalancePoint {
+                bucket_start: row.bucket_start,
+                balances: vec![balance],
+            }),
+        }
+    }
+    points
+}
+
+/// Records the SOL and token balances of every watched account each
+/// interval. Empty token accounts are skipped.
+pub fn spawn_snapshots(
+    client: Arc<SolanaClient>,
+    database: Arc<Database>,
+    config: BalanceSnapshotConfig,
+    max_accounts: i64,
+) {
+    if !config.enabled {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs.max(1)));
+        loop {
+            interval.tick().await;
+            if let Err(e) = snapshot(&client, &database, max_accounts).await {
+                warn!("Failed to snapshot watched balances: {}", e);
+            }
+        }
+    });
+}
+
+async fn snapshot(client: &SolanaClient, database: &Database, max_accounts: i64) -> Result<()> {
+    let taken_at = Utc::now();
+    for watched in database.list_watched_accounts(max_accounts).await? {
+        let balances = async {
+            let (lamports, tokens) = futures::try_join!(
+                client.get_balance(&watched.address),
+                client.get_token_balances(&watched.address),
+            )?;
+            let mut balances = vec![SnapshotBalance {
+                mint: None,
+                amount: lamports as i64,
+                decimals: SOL_DECIMALS as i16,
+            }];
+            balances.extend(tokens.into_iter().filter(|token| token.amount > 0).map(|token| {
+                SnapshotBalance {
+                    mint: Some(token.mint),
+                    amount: token.amount as i64,
+                    decimals: token.decimals as i16,
+                }
+            }));
+            Ok::<_, anyhow::Error>(balances)
+        };
+        // One failing account doesn't hold up the others.
+        match balances.await {
+            Ok(balances) => {
+                database.record_balance_snapshot(&watched.address, &balances, taken_at).await?;
+                debug!("Snapshotted {} balances of {}", balances.len(), watched.address);
+            }
+            Err(e) => warn!("Failed to snapshot balances of {}: {}", watched.address, e),
+        }
+    }
+    Ok(())
+}
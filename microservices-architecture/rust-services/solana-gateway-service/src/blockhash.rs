@@ -0,0 +1,75 @@
+use crate::config::BlockhashConfig;
+use crate::solana_client::SolanaClient;
+use solana_sdk::hash::Hash;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+struct CachedBlockhash {
+    hash: Hash,
+    fetched_at: Instant,
+}
+
+/// Most recent blockhash, shared by every transaction build so submissions
+/// don't each pay a `getLatestBlockhash` round-trip.
+pub struct BlockhashCache {
+    latest: RwLock<Option<CachedBlockhash>>,
+    enabled: bool,
+    max_age: Duration,
+}
+
+impl BlockhashCache {
+    pub fn new(config: &BlockhashConfig) -> Self {
+        Self {
+            latest: RwLock::new(None),
+            enabled: config.enabled,
+            max_age: Duration::from_secs(config.max_age_secs),
+        }
+    }
+
+    /// The cached blockhash, unless it is older than the configured maximum
+    /// age and may expire before a transaction built on it lands.
+    pub fn get(&self) -> Option<Hash> {
+        if !self.enabled {
+            return None;
+        }
+        let latest = self.latest.read().unwrap();
+        latest
+            .as_ref()
+            .filter(|cached| cached.fetched_at.elapsed() < self.max_age)
+            .map(|cached| cached.hash)
+    }
+
+    pub fn store(&self, hash: Hash) {
+        *self.latest.write().unwrap() = Some(CachedBlockhash {
+            hash,
+            fetched_at: Instant::now(),
+        });
+    }
+
+    /// Drops the cached blockhash after the cluster reported it unknown.
+    pub fn invalidate(&self, hash: &Hash) {
+        let mut latest = self.latest.write().unwrap();
+        if latest.as_ref().is_some_and(|cached| cached.hash == *hash) {
+            *latest = None;
+        }
+    }
+}
+
+/// Refreshes the cached blockhash in the background, roughly every 20 slots
+/// by default.
+pub fn spawn_refresh(client: Arc<SolanaClient>, config: BlockhashConfig) {
+    if !config.enabled {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_millis(config.refresh_interval_ms.max(100)));
+        loop {
+            interval.tick().await;
+            if let Err(e) = client.refresh_blockhash().await {
+                warn!("Failed to refresh blockhash: {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,145 @@
+use crate::config::CacheConfig;
+use anyhow::Result;
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts},
+};
+use redis::{aio::ConnectionManager, AsyncCommands};
+use serde::{de::DeserializeOwned, Serialize};
+use std::convert::Infallible;
+use std::future::Future;
+use std::time::Duration;
+use tracing::{info, warn};
+
+const KEY_PREFIX: &str = "solana_gateway";
+const BYPASS_HEADER: &str = "x-cache-bypass";
+// Keys deleted per DEL command when flushing.
+const FLUSH_BATCH_SIZE: usize = 500;
+
+/// Optional Redis cache for read responses. Cache failures are logged and
+/// treated as misses so Redis being down never breaks a read.
+pub struct ResponseCache {
+    connection: Option<ConnectionManager>,
+    prefix: String,
+}
+
+impl ResponseCache {
+    pub async fn connect(config: &CacheConfig) -> Result<Self> {
+        let prefix = config.key_prefix.clone().unwrap_or_else(|| KEY_PREFIX.to_string());
+        let Some(url) = config.redis_url() else {
+            info!("Redis cache disabled");
+            return Ok(Self {
+                connection: None,
+                prefix,
+            });
+        };
+
+        let client = redis::Client::open(url)?;
+        let connection = ConnectionManager::new(client).await?;
+        Ok(Self {
+            connection: Some(connection),
+            prefix,
+        })
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let mut connection = self.connection.clone()?;
+        let key = format!("{}:{}", self.prefix, key);
+        match connection.get::<_, Option<String>>(&key).await {
+            Ok(value) => value.and_then(|v| serde_json::from_str(&v).ok()),
+            Err(e) => {
+                warn!("Cache read failed for {}: {}", key, e);
+                None
+            }
+        }
+    }
+
+    pub async fn set<T: Serialize>(&self, key: &str, value: &T, ttl: Duration) {
+        let Some(mut connection) = self.connection.clone() else {
+            return;
+        };
+        let key = format!("{}:{}", self.prefix, key);
+        let Ok(payload) = serde_json::to_string(value) else {
+            return;
+        };
+        if let Err(e) = connection
+            .set_ex::<_, _, ()>(&key, payload, ttl.as_secs().max(1))
+            .await
+        {
+            warn!("Cache write failed for {}: {}", key, e);
+        }
+    }
+
+    /// Deletes every cached response under this cache's prefix and returns
+    /// how many were removed. The default cluster's prefix also covers the
+    /// other clusters' entries.
+    pub async fn flush(&self) -> Result<u64> {
+        let Some(mut connection) = self.connection.clone() else {
+            return Ok(0);
+        };
+        let pattern = format!("{}:*", self.prefix);
+        let mut keys: Vec<String> = Vec::new();
+        {
+            let mut iter = connection.scan_match::<_, String>(&pattern).await?;
+            while let Some(key) = iter.next_item().await {
+                keys.push(key);
+            }
+        }
+
+        let mut deleted = 0;
+        for chunk in keys.chunks(FLUSH_BATCH_SIZE) {
+            deleted += connection.del::<_, u64>(chunk).await?;
+        }
+        Ok(deleted)
+    }
+
+    /// Serves `key` from cache unless `bypass` is set, otherwise runs `fetch`
+    /// and stores its result for `ttl`.
+    pub async fn get_or_fetch<T, F, Fut>(
+        &self,
+        key: &str,
+        ttl: Duration,
+        bypass: bool,
+        fetch: F,
+    ) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        if !bypass {
+            if let Some(value) = self.get(key).await {
+                return Ok(value);
+            }
+        }
+
+        let value = fetch().await?;
+        self.set(key, &value, ttl).await;
+        Ok(value)
+    }
+}
+
+/// Set when the client sent `Cache-Control: no-cache` or `X-Cache-Bypass: true`.
+pub struct CacheBypass(pub bool);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for CacheBypass {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let header_value = |name: &str| {
+            parts
+                .headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_ascii_lowercase())
+        };
+
+        let no_cache = header_value(header::CACHE_CONTROL.as_str())
+            .map_or(false, |v| v.contains("no-cache") || v.contains("no-store"));
+        let bypass = header_value(BYPASS_HEADER).map_or(false, |v| v == "true" || v == "1");
+
+        Ok(CacheBypass(no_cache || bypass))
+    }
+}
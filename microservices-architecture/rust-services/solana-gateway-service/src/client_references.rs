@@ -0,0 +1,90 @@
+use crate::auth::Principal;
+use crate::database::Database;
+use crate::error::ApiError;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tracing::warn;
+use uuid::Uuid;
+
+/// A stored `client_reference` and what it was used for.
+#[derive(sqlx::FromRow)]
+pub struct ClientReference {
+    pub signature: Option<String>,
+    pub approval_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The scope references are unique in: the caller's subject, so each API
+/// key (or token subject) has its own references.
+pub fn owner(principal: Option<&Principal>) -> &str {
+    principal.map_or("anonymous", |principal| principal.subject.as_str())
+}
+
+/// A reference reserved for one transfer. Dropping it before `complete`,
+/// because sending failed or the client disconnected, releases the
+/// reference so the transfer can be retried.
+pub struct ReferenceClaim {
+    database: Arc<Database>,
+    owner: String,
+    reference: String,
+    armed: bool,
+}
+
+impl ReferenceClaim {
+    /// Ties the reference to the sent transfer or the approval holding it,
+    /// after which it can't be used again.
+    pub async fn complete(mut self, signature: Option<&str>, approval_id: Option<Uuid>) {
+        self.armed = false;
+        if let Err(e) = self
+            .database
+            .complete_client_reference(&self.owner, &self.reference, signature, approval_id)
+            .await
+        {
+            warn!("Failed to complete client reference {}: {}", self.reference, e);
+        }
+    }
+}
+
+impl Drop for ReferenceClaim {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        let database = self.database.clone();
+        let owner = std::mem::take(&mut self.owner);
+        let reference = std::mem::take(&mut self.reference);
+        tokio::spawn(async move {
+            if let Err(e) = database.release_client_reference(&owner, &reference).await {
+                warn!("Failed to release client reference {}: {}", reference, e);
+            }
+        });
+    }
+}
+
+/// Reserves `reference` for `owner`, or explains what already used it.
+pub async fn claim(
+    database: &Arc<Database>,
+    owner: &str,
+    reference: Option<&str>,
+) -> Result<Option<ReferenceClaim>, ApiError> {
+    let Some(reference) = reference else {
+        return Ok(None);
+    };
+    if database.claim_client_reference(owner, reference).await? {
+        return Ok(Some(ReferenceClaim {
+            database: database.clone(),
+            owner: owner.to_string(),
+            reference: reference.to_string(),
+            armed: true,
+        }));
+    }
+
+    let used_by = match database.get_client_reference(owner, reference).await? {
+        Some(ClientReference { signature: Some(signature), .. }) => format!("transaction {}", signature),
+        Some(ClientReference { approval_id: Some(id), .. }) => format!("approval {}", id),
+        Some(ClientReference { created_at, .. }) => format!("a transfer still being sent since {}", created_at),
+        // Released between the two queries.
+        None => "a transfer that just failed; retry".to_string(),
+    };
+    Err(ApiError::Conflict(format!("client_reference {} was already used by {}", reference, used_by)))
+}
@@ -0,0 +1,104 @@
+use crate::error::ApiError;
+use axum::{
+    extract::{Request, State},
+    http::Uri,
+    response::{IntoResponse, Response},
+    Router,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+pub const CLUSTER_HEADER: &str = "x-solana-cluster";
+const API_PREFIX: &str = "/api/";
+
+/// Routes each request to the API of one Solana cluster. The cluster is
+/// taken from a `/api/<version>/<cluster>/...` path prefix, then the
+/// `X-Solana-Cluster` header, and is the default cluster otherwise. Every
+/// cluster has its own state, so RPC clients, databases and caches never mix.
+#[derive(Clone)]
+pub struct ClusterRouter {
+    default_name: String,
+    default: Router,
+    clusters: Arc<HashMap<String, Router>>,
+}
+
+impl ClusterRouter {
+    pub fn new(default_name: String, default: Router, clusters: HashMap<String, Router>) -> Self {
+        Self {
+            default_name,
+            default,
+            clusters: Arc::new(clusters),
+        }
+    }
+
+    pub fn into_router(self) -> Router {
+        Router::new().fallback(dispatch).with_state(self)
+    }
+
+    fn get(&self, name: &str) -> Option<&Router> {
+        if name == self.default_name {
+            Some(&self.default)
+        } else {
+            self.clusters.get(name)
+        }
+    }
+
+    /// Strips a cluster path prefix from the request, returning the cluster
+    /// it named.
+    fn take_prefix(&self, request: &mut Request) -> Result<Option<Router>, ApiError> {
+        let Some((version, path)) = request
+            .uri()
+            .path()
+            .strip_prefix(API_PREFIX)
+            .and_then(|path| path.split_once('/'))
+        else {
+            return Ok(None);
+        };
+        let Some((name, rest)) = path.split_once('/') else {
+            return Ok(None);
+        };
+        let Some(router) = self.get(name).cloned() else {
+            return Ok(None);
+        };
+
+        let path_and_query = match request.uri().query() {
+            Some(query) => format!("{}{}/{}?{}", API_PREFIX, version, rest, query),
+            None => format!("{}{}/{}", API_PREFIX, version, rest),
+        };
+        let mut parts = request.uri().clone().into_parts();
+        parts.path_and_query = Some(
+            path_and_query
+                .parse()
+                .map_err(|_| ApiError::BadRequest("Invalid request path".to_string()))?,
+        );
+        *request.uri_mut() =
+            Uri::from_parts(parts).map_err(|_| ApiError::BadRequest("Invalid request path".to_string()))?;
+        Ok(Some(router))
+    }
+
+    fn select(&self, request: &mut Request) -> Result<Router, ApiError> {
+        if let Some(router) = self.take_prefix(request)? {
+            return Ok(router);
+        }
+        match request.headers().get(CLUSTER_HEADER) {
+            None => Ok(self.default.clone()),
+            Some(value) => {
+                let name = value.to_str().unwrap_or_default().trim().to_ascii_lowercase();
+                self.get(&name)
+                    .cloned()
+                    .ok_or_else(|| ApiError::BadRequest(format!("Unknown Solana cluster '{}'", name)))
+            }
+        }
+    }
+}
+
+async fn dispatch(State(routers): State<ClusterRouter>, mut request: Request) -> Response {
+    match routers.select(&mut request) {
+        Ok(router) => match router.oneshot(request).await {
+            Ok(response) => response,
+            Err(never) => match never {},
+        },
+        Err(e) => e.into_response(),
+    }
+}
@@ -0,0 +1,170 @@
+use crate::config::ComplianceConfig;
+use crate::database::Database;
+use crate::error::ApiError;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::warn;
+use utoipa::ToSchema;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ListName {
+    Deny,
+    Allow,
+}
+
+impl ListName {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ListName::Deny => "deny",
+            ListName::Allow => "allow",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    /// Transfer destination or swap owner.
+    Address,
+    Mint,
+}
+
+impl EntryKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EntryKind::Address => "address",
+            EntryKind::Mint => "mint",
+        }
+    }
+}
+
+/// A list entry added through the admin API.
+#[derive(Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct ComplianceEntry {
+    pub id: i64,
+    /// `deny` or `allow`.
+    pub list: String,
+    /// `address` or `mint`.
+    pub kind: String,
+    pub value: String,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct NewComplianceEntry {
+    pub list: ListName,
+    pub kind: EntryKind,
+    /// Base58 address or mint.
+    pub value: String,
+    /// Kept for reviewers, e.g. the sanctions list the address is from.
+    pub reason: Option<String>,
+}
+
+#[derive(Default)]
+struct Lists {
+    denied_addresses: HashSet<String>,
+    denied_mints: HashSet<String>,
+    allowed_addresses: HashSet<String>,
+    allowed_mints: HashSet<String>,
+}
+
+/// The configured lists merged with the stored entries, held in memory so
+/// screening never waits on the database. Reloaded by `spawn_sync` and
+/// after every change through the admin API.
+pub struct Compliance {
+    database: Arc<Database>,
+    config: ComplianceConfig,
+    lists: RwLock<Lists>,
+}
+
+impl Compliance {
+    pub fn new(database: Arc<Database>, config: &ComplianceConfig) -> Self {
+        let compliance = Self {
+            database,
+            config: config.clone(),
+            lists: RwLock::new(Lists::default()),
+        };
+        *compliance.lists.write().unwrap() = compliance.merged(&[]);
+        compliance
+    }
+
+    pub async fn reload(&self) -> Result<()> {
+        let entries = self.database.list_compliance_entries().await?;
+        *self.lists.write().unwrap() = self.merged(&entries);
+        Ok(())
+    }
+
+    fn merged(&self, entries: &[ComplianceEntry]) -> Lists {
+        let mut lists = Lists {
+            denied_addresses: self.config.denied_addresses.iter().cloned().collect(),
+            denied_mints: self.config.denied_mints.iter().cloned().collect(),
+            allowed_addresses: self.config.allowed_addresses.iter().cloned().collect(),
+            allowed_mints: self.config.allowed_mints.iter().cloned().collect(),
+        };
+        for entry in entries {
+            let set = match (entry.list.as_str(), entry.kind.as_str()) {
+                ("deny", "address") => &mut lists.denied_addresses,
+                ("deny", "mint") => &mut lists.denied_mints,
+                ("allow", "address") => &mut lists.allowed_addresses,
+                ("allow", "mint") => &mut lists.allowed_mints,
+                _ => continue,
+            };
+            set.insert(entry.value.clone());
+        }
+        lists
+    }
+
+    /// Refuses a transfer to a screened-out destination or of a screened-out
+    /// mint; `None` is a SOL transfer.
+    pub fn check_transfer(&self, to: &str, mint: Option<&str>) -> Result<(), ApiError> {
+        let native = spl_token::native_mint::id().to_string();
+        self.check(Some(to), &[mint.unwrap_or(&native)])
+    }
+
+    /// Refuses a swap by a screened-out owner or between screened-out mints.
+    /// Gateway-signed swaps have no owner to check.
+    pub fn check_swap(&self, owner: Option<&str>, input_mint: &str, output_mint: &str) -> Result<(), ApiError> {
+        self.check(owner, &[input_mint, output_mint])
+    }
+
+    fn check(&self, address: Option<&str>, mints: &[&str]) -> Result<(), ApiError> {
+        let lists = self.lists.read().unwrap();
+        let screened = |value: &str, denied: &HashSet<String>, allowed: &HashSet<String>| {
+            denied.contains(value) || (self.config.allowlist_only && !allowed.contains(value))
+        };
+        let blocked = match address {
+            Some(address) if screened(address, &lists.denied_addresses, &lists.allowed_addresses) => {
+                Some(format!("Address {}", address))
+            }
+            _ => mints
+                .iter()
+                .find(|mint| screened(mint, &lists.denied_mints, &lists.allowed_mints))
+                .map(|mint| format!("Mint {}", mint)),
+        };
+        match blocked {
+            Some(blocked) => {
+                warn!("{} blocked by compliance lists", blocked);
+                Err(ApiError::Blocked(format!("{} is not permitted", blocked)))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+pub fn spawn_sync(compliance: Arc<Compliance>, config: ComplianceConfig) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.sync_secs.max(1)));
+        loop {
+            interval.tick().await;
+            if let Err(e) = compliance.reload().await {
+                warn!("Failed to reload compliance lists: {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,2785 @@
+use crate::auth::Role;
+use crate::fees::PriorityFeeStrategy;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Settings that must be given; everything else has a default.
+const REQUIRED_SETTINGS: [&str; 2] = ["database_url", "solana_rpc_url"];
+
+const REDACTED: &str = "<redacted>";
+
+/// Every problem found while loading the configuration.
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid configuration:\n  {}", .0.join("\n  "))]
+pub struct InvalidConfig(pub Vec<String>);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Config {
+    pub database_url: String,
+    /// Read replicas for list and history queries. Writes, and reads that
+    /// must see them, always go to `database_url`.
+    #[serde(default)]
+    pub database_read_urls: Vec<String>,
+    #[serde(default)]
+    pub database_pool: DatabasePoolConfig,
+    pub solana_rpc_url: String,
+    /// PubSub websocket endpoint; derived from `solana_rpc_url` when unset.
+    #[serde(default)]
+    pub solana_ws_url: Option<String>,
+    /// Weighted RPC endpoints used for failover. When empty, `solana_rpc_url`
+    /// is used as the only endpoint.
+    #[serde(default)]
+    pub solana_rpc_endpoints: Vec<RpcEndpointConfig>,
+    #[serde(default)]
+    pub rpc_failover: RpcFailoverConfig,
+    #[serde(default)]
+    pub rpc_retry: RpcRetryConfig,
+    #[serde(default)]
+    pub rpc_http: RpcHttpConfig,
+    #[serde(default)]
+    pub slot_lag: SlotLagConfig,
+    #[serde(default)]
+    pub rpc_passthrough: RpcPassthroughConfig,
+    #[serde(default)]
+    pub graphql: GraphqlConfig,
+    #[serde(default)]
+    pub blockhash: BlockhashConfig,
+    #[serde(default = "default_mint_cache_ttl_secs")]
+    pub mint_cache_ttl_secs: u64,
+    #[serde(default = "default_mint_cache_capacity")]
+    pub mint_cache_capacity: u64,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    #[serde(default)]
+    pub tracing: TracingConfig,
+    #[serde(default)]
+    pub fees: FeeConfig,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    #[serde(default)]
+    pub pool_discovery: PoolDiscoveryConfig,
+    #[serde(default)]
+    pub pool_feed: PoolFeedConfig,
+    #[serde(default)]
+    pub pool_analytics: PoolAnalyticsConfig,
+    #[serde(default)]
+    pub candles: CandleConfig,
+    #[serde(default)]
+    pub pool_volume: PoolVolumeConfig,
+    #[serde(default)]
+    pub transaction_retention: TransactionRetentionConfig,
+    #[serde(default)]
+    pub route_cache: RouteCacheConfig,
+    #[serde(default)]
+    pub watchlist: WatchlistConfig,
+    #[serde(default)]
+    pub webhooks: WebhookConfig,
+    #[serde(default)]
+    pub balance_snapshots: BalanceSnapshotConfig,
+    #[serde(default)]
+    pub dca: DcaConfig,
+    #[serde(default = "default_idempotency_ttl_secs")]
+    pub idempotency_ttl_secs: u64,
+    /// Serves Swagger UI at `/swagger-ui`.
+    #[serde(default)]
+    pub swagger_ui: bool,
+    /// Treats every transfer and swap as a dry run and never broadcasts;
+    /// for staging environments.
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default)]
+    pub price_oracle: PriceOracleConfig,
+    #[serde(default)]
+    pub timeouts: TimeoutConfig,
+    #[serde(default)]
+    pub concurrency: ConcurrencyConfig,
+    #[serde(default)]
+    pub body_limits: BodyLimitConfig,
+    #[serde(default)]
+    pub compression: CompressionConfig,
+    #[serde(default)]
+    pub api: ApiConfig,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    #[serde(default)]
+    pub admin: AdminConfig,
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+    #[serde(default)]
+    pub api_keys: ApiKeyConfig,
+    #[serde(default)]
+    pub secrets: SecretsConfig,
+    #[serde(default)]
+    pub wallets: WalletConfig,
+    #[serde(default)]
+    pub signer: SignerConfig,
+    #[serde(default)]
+    pub approvals: ApprovalConfig,
+    #[serde(default)]
+    pub compliance: ComplianceConfig,
+    #[serde(default)]
+    pub swap: SwapConfig,
+    #[serde(default)]
+    pub tls: TlsConfig,
+    #[serde(default)]
+    pub grpc: GrpcConfig,
+    #[serde(default)]
+    pub confirmations: ConfirmationConfig,
+    #[serde(default)]
+    pub reconciler: ReconcilerConfig,
+    /// Readiness fails when the newest confirmed block is older than this.
+    #[serde(default = "default_max_block_age_secs")]
+    pub max_block_age_secs: i64,
+    /// Most addresses accepted by `POST /api/v1/accounts/batch`.
+    #[serde(default = "default_max_batch_accounts")]
+    pub max_batch_accounts: usize,
+    #[serde(default)]
+    pub nfts: NftConfig,
+    #[serde(default)]
+    pub token_registry: TokenRegistryConfig,
+    #[serde(default)]
+    pub token_safety: TokenSafetyConfig,
+    #[serde(default)]
+    pub kafka: KafkaConfig,
+    #[serde(default)]
+    pub jito: JitoConfig,
+    #[serde(default)]
+    pub jupiter: JupiterConfig,
+    /// Applies pending database migrations at startup. Disable when
+    /// migrations are run as a separate deployment step (`--migrate-only`).
+    #[serde(default = "default_true")]
+    pub run_migrations: bool,
+    /// Name of the cluster served by the settings above.
+    #[serde(default = "default_cluster_name")]
+    pub cluster: String,
+    /// Additional clusters, selected per request with the `X-Solana-Cluster`
+    /// header or a `/api/<version>/<name>/` path prefix.
+    #[serde(default)]
+    pub clusters: HashMap<String, ClusterConfig>,
+}
+
+/// Connection settings for an additional Solana cluster. Everything else is
+/// shared with the default cluster.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClusterConfig {
+    pub solana_rpc_url: String,
+    #[serde(default)]
+    pub solana_ws_url: Option<String>,
+    #[serde(default)]
+    pub solana_rpc_endpoints: Vec<RpcEndpointConfig>,
+    /// Separate database, so transactions, pools and wallets recorded on one
+    /// cluster are never served for another.
+    pub database_url: String,
+    #[serde(default)]
+    pub database_read_urls: Vec<String>,
+}
+
+/// Postgres connection pool settings, applied to every cluster's database.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DatabasePoolConfig {
+    #[serde(default = "default_db_max_connections")]
+    pub max_connections: u32,
+    /// Connections kept open even when idle.
+    #[serde(default)]
+    pub min_connections: u32,
+    /// How long a query waits for a free connection before failing.
+    #[serde(default = "default_db_acquire_timeout_secs")]
+    pub acquire_timeout_secs: u64,
+    /// Idle connections above `min_connections` are closed after this long.
+    #[serde(default = "default_db_idle_timeout_secs")]
+    pub idle_timeout_secs: u64,
+    #[serde(default = "default_db_max_lifetime_secs")]
+    pub max_lifetime_secs: u64,
+    /// Prepared statements cached per connection.
+    #[serde(default = "default_db_statement_cache_capacity")]
+    pub statement_cache_capacity: usize,
+    /// How often pool utilization is sampled into metrics.
+    #[serde(default = "default_db_metrics_interval_secs")]
+    pub metrics_interval_secs: u64,
+    /// How often replicas that failed a query are checked before reads are
+    /// routed back to them.
+    #[serde(default = "default_db_replica_check_interval_secs")]
+    pub replica_check_interval_secs: u64,
+}
+
+impl Default for DatabasePoolConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: default_db_max_connections(),
+            min_connections: 0,
+            acquire_timeout_secs: default_db_acquire_timeout_secs(),
+            idle_timeout_secs: default_db_idle_timeout_secs(),
+            max_lifetime_secs: default_db_max_lifetime_secs(),
+            statement_cache_capacity: default_db_statement_cache_capacity(),
+            metrics_interval_secs: default_db_metrics_interval_secs(),
+            replica_check_interval_secs: default_db_replica_check_interval_secs(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RpcEndpointConfig {
+    pub url: String,
+    #[serde(default = "default_endpoint_weight")]
+    pub weight: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RpcFailoverConfig {
+    /// Consecutive failures before an endpoint is quarantined.
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    /// First quarantine period; doubled on every repeated quarantine.
+    #[serde(default = "default_quarantine_base_secs")]
+    pub quarantine_base_secs: u64,
+    #[serde(default = "default_quarantine_max_secs")]
+    pub quarantine_max_secs: u64,
+}
+
+/// HTTP client settings for every RPC endpoint. Reusing warm connections
+/// matters at high request rates, where a TCP and TLS handshake per call
+/// would dominate latency.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RpcHttpConfig {
+    /// Idle keep-alive connections kept open per endpoint host.
+    #[serde(default = "default_rpc_http_pool_max_idle_per_host")]
+    pub pool_max_idle_per_host: usize,
+    /// How long an idle connection stays in the pool.
+    #[serde(default = "default_rpc_http_pool_idle_timeout_secs")]
+    pub pool_idle_timeout_secs: u64,
+    /// Most requests in flight to one endpoint, and so connections open to
+    /// it over HTTP/1.1. Further calls wait for a free slot. Unlimited
+    /// when unset.
+    #[serde(default)]
+    pub max_connections_per_host: Option<usize>,
+    #[serde(default = "default_rpc_http_connect_timeout_ms")]
+    pub connect_timeout_ms: u64,
+    /// TCP keepalive probe interval; disabled when unset.
+    #[serde(default = "default_rpc_http_tcp_keepalive_secs")]
+    pub tcp_keepalive_secs: Option<u64>,
+    #[serde(default = "default_true")]
+    pub tcp_nodelay: bool,
+    /// Speaks HTTP/2 without negotiation, for plaintext endpoints known to
+    /// support it. HTTPS endpoints negotiate HTTP/2 through ALPN anyway.
+    #[serde(default)]
+    pub http2_prior_knowledge: bool,
+    /// HTTP/2 PING interval keeping multiplexed connections alive.
+    #[serde(default = "default_rpc_http_http2_keep_alive_secs")]
+    pub http2_keep_alive_secs: Option<u64>,
+}
+
+impl Default for RpcHttpConfig {
+    fn default() -> Self {
+        Self {
+            pool_max_idle_per_host: default_rpc_http_pool_max_idle_per_host(),
+            pool_idle_timeout_secs: default_rpc_http_pool_idle_timeout_secs(),
+            max_connections_per_host: None,
+            connect_timeout_ms: default_rpc_http_connect_timeout_ms(),
+            tcp_keepalive_secs: default_rpc_http_tcp_keepalive_secs(),
+            tcp_nodelay: default_true(),
+            http2_prior_knowledge: false,
+            http2_keep_alive_secs: default_rpc_http_http2_keep_alive_secs(),
+        }
+    }
+}
+
+/// Retries for idempotent RPC reads that failed on every endpoint. Delays
+/// grow exponentially from `base_delay_ms` with full jitter.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RpcRetryConfig {
+    /// Total attempts including the first one.
+    #[serde(default = "default_rpc_retry_max_attempts")]
+    pub max_attempts: u32,
+    #[serde(default = "default_rpc_retry_base_delay_ms")]
+    pub base_delay_ms: u64,
+    #[serde(default = "default_rpc_retry_max_delay_ms")]
+    pub max_delay_ms: u64,
+    /// Minimum wait after the provider answered 429 Too Many Requests.
+    #[serde(default = "default_rpc_rate_limit_delay_ms")]
+    pub rate_limit_delay_ms: u64,
+}
+
+impl Default for RpcRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_rpc_retry_max_attempts(),
+            base_delay_ms: default_rpc_retry_base_delay_ms(),
+            max_delay_ms: default_rpc_retry_max_delay_ms(),
+            rate_limit_delay_ms: default_rpc_rate_limit_delay_ms(),
+        }
+    }
+}
+
+/// Compares each RPC endpoint's slot with a reference node. Endpoints more
+/// than `max_lag_slots` behind are marked degraded and only used when no
+/// other endpoint is available. Disabled without a `reference_url`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SlotLagConfig {
+    #[serde(default)]
+    pub reference_url: Option<String>,
+    #[serde(default = "default_max_slot_lag")]
+    pub max_lag_slots: u64,
+    #[serde(default = "default_slot_lag_interval_secs")]
+    pub interval_secs: u64,
+}
+
+impl Default for SlotLagConfig {
+    fn default() -> Self {
+        Self {
+            reference_url: None,
+            max_lag_slots: default_max_slot_lag(),
+            interval_secs: default_slot_lag_interval_secs(),
+        }
+    }
+}
+
+/// `POST /api/v1/rpc`, which forwards allowlisted JSON-RPC methods to the
+/// RPC pool. Callers only need the viewer role, so list read-only methods.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RpcPassthroughConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_rpc_passthrough_methods")]
+    pub methods: Vec<String>,
+}
+
+impl Default for RpcPassthroughConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            methods: default_rpc_passthrough_methods(),
+        }
+    }
+}
+
+/// `POST /graphql`, a query-only schema over accounts, token balances,
+/// transactions and pools. Depth and complexity limits bound the RPC calls
+/// one query can fan out into.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GraphqlConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_graphql_max_depth")]
+    pub max_depth: usize,
+    #[serde(default = "default_graphql_max_complexity")]
+    pub max_complexity: usize,
+    /// Serves GraphiQL on `GET /graphiql`.
+    #[serde(default)]
+    pub graphiql: bool,
+}
+
+impl Default for GraphqlConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_depth: default_graphql_max_depth(),
+            max_complexity: default_graphql_max_complexity(),
+            graphiql: false,
+        }
+    }
+}
+
+/// Background-refreshed blockhash shared by transaction builds.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BlockhashConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// About 20 slots at 400ms per slot.
+    #[serde(default = "default_blockhash_refresh_interval_ms")]
+    pub refresh_interval_ms: u64,
+    /// A blockhash expires after 150 blocks (~60s); older cached hashes are
+    /// refetched before use.
+    #[serde(default = "default_blockhash_max_age_secs")]
+    pub max_age_secs: u64,
+}
+
+impl Default for BlockhashConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            refresh_interval_ms: default_blockhash_refresh_interval_ms(),
+            max_age_secs: default_blockhash_max_age_secs(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    /// Histogram buckets (seconds) for inbound HTTP request latency.
+    #[serde(default = "default_request_latency_buckets")]
+    pub request_latency_buckets: Vec<f64>,
+    /// Histogram buckets (seconds) for outbound Solana RPC call latency.
+    #[serde(default = "default_rpc_latency_buckets")]
+    pub rpc_latency_buckets: Vec<f64>,
+    /// Service level objectives, exported with their error budget burn
+    /// rates.
+    #[serde(default)]
+    pub slos: Vec<SloConfig>,
+    /// Windows over which burn rates are computed, e.g. 300 and 3600 for
+    /// a fast-burn alert requiring both the 5m and 1h rates to be high.
+    #[serde(default = "default_slo_windows_secs")]
+    pub slo_windows_secs: Vec<u64>,
+    /// Attach the trace ID of the request's span to HTTP and RPC latency
+    /// observations. With `tracing.otlp_endpoint` set that is the span the
+    /// gateway exports; without it, only requests whose caller sent a W3C
+    /// `traceparent` get exemplars, pointing at the caller's trace.
+    /// Exemplars are only exposed to scrapers asking for OpenMetrics, which
+    /// Prometheus does with `--enable-feature=exemplar-storage`.
+    #[serde(default)]
+    pub exemplars: bool,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            request_latency_buckets: default_request_latency_buckets(),
+            rpc_latency_buckets: default_rpc_latency_buckets(),
+            slos: Vec::new(),
+            slo_windows_secs: default_slo_windows_secs(),
+            exemplars: false,
+        }
+    }
+}
+
+/// An objective for the requests to a set of routes. A burn rate of 1
+/// spends the error budget exactly as fast as the objective allows; 14.4
+/// sustained for an hour spends 2% of a 30-day budget.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SloConfig {
+    /// The `slo` label, e.g. `swap-execute`.
+    pub name: String,
+    /// Route templates as matched by the router, e.g.
+    /// `/api/v1/accounts/:address`; a trailing `*` matches every route
+    /// starting with the rest.
+    pub routes: Vec<String>,
+    /// Fraction of requests that must not fail with a 5xx status, e.g.
+    /// 0.999.
+    pub availability: Option<f64>,
+    pub latency: Option<LatencyObjective>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LatencyObjective {
+    /// Fraction of requests not failing with a 5xx status that must
+    /// complete within `threshold_ms`, e.g. 0.99.
+    pub target: f64,
+    pub threshold_ms: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// `EnvFilter` directives; adjustable at runtime through
+    /// `PUT /admin/log-level` or a configuration reload.
+    #[serde(default = "default_log_level")]
+    pub level: String,
+    #[serde(default)]
+    pub format: LogFormat,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            level: default_log_level(),
+            format: LogFormat::default(),
+        }
+    }
+}
+
+/// OpenTelemetry tracing of API requests, continuing the caller's W3C
+/// `traceparent` when it sends one.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TracingConfig {
+    /// OTLP/gRPC collector spans are exported to, e.g.
+    /// `http://otel-collector:4317`. Tracing is off without one.
+    pub otlp_endpoint: Option<String>,
+    #[serde(default = "default_tracing_service_name")]
+    pub service_name: String,
+    /// Share of traces started by the gateway that are sampled; continued
+    /// traces follow the caller's decision.
+    #[serde(default = "default_tracing_sample_ratio")]
+    pub sample_ratio: f64,
+}
+
+impl Default for TracingConfig {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: None,
+            service_name: default_tracing_service_name(),
+            sample_ratio: default_tracing_sample_ratio(),
+        }
+    }
+}
+
+/// `text` and `pretty` are for terminals; `json` is one object per line
+/// for log shippers.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Pretty,
+    Json,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FeeConfig {
+    /// Priority fee strategy applied when a request doesn't specify one.
+    #[serde(default)]
+    pub strategy: PriorityFeeStrategy,
+    /// Limit used when simulation is disabled or fails.
+    #[serde(default = "default_compute_unit_limit")]
+    pub compute_unit_limit: u32,
+    /// Simulate transactions before sending and request the compute units
+    /// they consumed plus `compute_unit_margin_pct`.
+    #[serde(default = "default_true")]
+    pub simulate_compute_units: bool,
+    #[serde(default = "default_compute_unit_margin_pct")]
+    pub compute_unit_margin_pct: u32,
+    /// Ceiling (micro-lamports per CU) for estimated priority fees.
+    #[serde(default = "default_max_unit_price")]
+    pub max_unit_price: u64,
+}
+
+impl Default for FeeConfig {
+    fn default() -> Self {
+        Self {
+            strategy: PriorityFeeStrategy::default(),
+            compute_unit_limit: default_compute_unit_limit(),
+            simulate_compute_units: true,
+            compute_unit_margin_pct: default_compute_unit_margin_pct(),
+            max_unit_price: default_max_unit_price(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CacheConfig {
+    /// Full Redis URL; takes precedence over `redis_host`/`redis_port`.
+    pub redis_url: Option<String>,
+    pub redis_host: Option<String>,
+    pub redis_port: Option<u16>,
+    pub account_info_ttl_secs: Option<u64>,
+    pub token_info_ttl_secs: Option<u64>,
+    pub pool_info_ttl_secs: Option<u64>,
+    pub rent_ttl_secs: Option<u64>,
+    pub holders_ttl_secs: Option<u64>,
+    /// Namespace for cache keys; set per cluster.
+    pub key_prefix: Option<String>,
+}
+
+impl CacheConfig {
+    pub fn redis_url(&self) -> Option<String> {
+        self.redis_url.clone().or_else(|| {
+            self.redis_host
+                .as_ref()
+                .map(|host| format!("redis://{}:{}", host, self.redis_port.unwrap_or(6379)))
+        })
+    }
+
+    pub fn account_info_ttl(&self) -> Duration {
+        Duration::from_secs(self.account_info_ttl_secs.unwrap_or(5))
+    }
+
+    pub fn token_info_ttl(&self) -> Duration {
+        Duration::from_secs(self.token_info_ttl_secs.unwrap_or(300))
+    }
+
+    pub fn pool_info_ttl(&self) -> Duration {
+        Duration::from_secs(self.pool_info_ttl_secs.unwrap_or(10))
+    }
+
+    /// Rent parameters only change through a feature activation.
+    pub fn rent_ttl(&self) -> Duration {
+        Duration::from_secs(self.rent_ttl_secs.unwrap_or(3_600))
+    }
+
+    pub fn holders_ttl(&self) -> Duration {
+        Duration::from_secs(self.holders_ttl_secs.unwrap_or(300))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PoolAnalyticsConfig {
+    /// Decimal-adjusted trade sizes quoted for price impact in pool info.
+    #[serde(default = "default_impact_sizes")]
+    pub impact_sizes: Vec<f64>,
+    /// Price moves (bps) at which liquidity depth is reported.
+    #[serde(default = "default_depth_bps")]
+    pub depth_bps: Vec<u32>,
+}
+
+impl Default for PoolAnalyticsConfig {
+    fn default() -> Self {
+        Self {
+            impact_sizes: default_impact_sizes(),
+            depth_bps: default_depth_bps(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PoolDiscoveryConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_pool_discovery_interval_secs")]
+    pub interval_secs: u64,
+    /// Only pools containing one of these mints are scanned. An empty list
+    /// scans every pool account, which is very expensive on mainnet.
+    #[serde(default = "default_tracked_mints")]
+    pub tracked_mints: Vec<String>,
+}
+
+impl Default for PoolDiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            interval_secs: default_pool_discovery_interval_secs(),
+            tracked_mints: default_tracked_mints(),
+        }
+    }
+}
+
+/// Live detection of newly created pools, streamed from
+/// `/api/v1/pools/stream`. Off by default: each supported DEX gets a
+/// `programSubscribe`, which receives every write to any of its pools.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PoolFeedConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// New pools buffered per stream client before it starts lagging.
+    #[serde(default = "default_pool_feed_channel_capacity")]
+    pub channel_capacity: usize,
+}
+
+impl Default for PoolFeedConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            channel_capacity: default_pool_feed_channel_capacity(),
+        }
+    }
+}
+
+/// Background sampling of stored pools into OHLCV candles.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CandleConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_candle_sample_interval_secs")]
+    pub sample_interval_secs: u64,
+    /// Most stored pools sampled per round.
+    #[serde(default = "default_candle_max_pools")]
+    pub max_pools: i64,
+}
+
+impl Default for CandleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            sample_interval_secs: default_candle_sample_interval_secs(),
+            max_pools: default_candle_max_pools(),
+        }
+    }
+}
+
+/// Background indexing of swaps through stored pools into hourly volume
+/// and fee totals.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PoolVolumeConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_pool_volume_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    /// Most stored pools indexed per round.
+    #[serde(default = "default_pool_volume_max_pools")]
+    pub max_pools: i64,
+    /// Most new signatures fetched per pool and round; swaps beyond them
+    /// are skipped rather than backfilled.
+    #[serde(default = "default_pool_volume_max_signatures")]
+    pub max_signatures: usize,
+    /// Hourly buckets older than this are deleted.
+    #[serde(default = "default_pool_volume_retention_days")]
+    pub retention_days: i64,
+}
+
+impl Default for PoolVolumeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            poll_interval_secs: default_pool_volume_poll_interval_secs(),
+            max_pools: default_pool_volume_max_pools(),
+            max_signatures: default_pool_volume_max_signatures(),
+            retention_days: default_pool_volume_retention_days(),
+        }
+    }
+}
+
+/// Maintenance of the monthly `solana_transactions` partitions: upcoming
+/// months are created ahead of time and, with `retention_months` set,
+/// months past it are dropped, after being archived when `archive` names a
+/// bucket.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransactionRetentionConfig {
+    /// Whole months kept before the current one; unset keeps every month.
+    pub retention_months: Option<u32>,
+    /// Months created ahead of the current one.
+    #[serde(default = "default_transaction_premake_months")]
+    pub premake_months: u32,
+    #[serde(default = "default_transaction_retention_interval_secs")]
+    pub interval_secs: u64,
+    #[serde(default)]
+    pub archive: TransactionArchiveConfig,
+}
+
+impl Default for TransactionRetentionConfig {
+    fn default() -> Self {
+        Self {
+            retention_months: None,
+            premake_months: default_transaction_premake_months(),
+            interval_secs: default_transaction_retention_interval_secs(),
+            archive: TransactionArchiveConfig::default(),
+        }
+    }
+}
+
+/// S3 bucket receiving each dropped partition as one Parquet object,
+/// `<prefix>solana_transactions_YYYY_MM.parquet`. Credentials are read from
+/// the same variables as the AWS KMS signer.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransactionArchiveConfig {
+    pub bucket: Option<String>,
+    #[serde(default = "default_transaction_archive_prefix")]
+    pub prefix: String,
+    pub region: Option<String>,
+    /// Overrides the regional endpoint, e.g. for MinIO or a VPC endpoint.
+    /// Objects are addressed path-style.
+    pub endpoint: Option<String>,
+    /// Rows read from the partition per query while writing the archive.
+    #[serde(default = "default_transaction_archive_batch_size")]
+    pub batch_size: i64,
+    #[serde(default = "default_transaction_archive_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+impl Default for TransactionArchiveConfig {
+    fn default() -> Self {
+        Self {
+            bucket: None,
+            prefix: default_transaction_archive_prefix(),
+            region: None,
+            endpoint: None,
+            batch_size: default_transaction_archive_batch_size(),
+            timeout_ms: default_transaction_archive_timeout_ms(),
+        }
+    }
+}
+
+/// In-memory cache of swap route searches per (input mint, output mint,
+/// power-of-two amount bucket). Hits re-quote the cached routes for the
+/// requested amount instead of searching every pool again.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RouteCacheConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_route_cache_ttl_secs")]
+    pub ttl_secs: u64,
+    #[serde(default = "default_route_cache_capacity")]
+    pub capacity: u64,
+    /// Reserve change, in basis points, seen by the candle indexer that
+    /// evicts cached routes through the pool.
+    #[serde(default = "default_route_cache_invalidation_bps")]
+    pub invalidation_bps: u32,
+}
+
+impl Default for RouteCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            ttl_secs: default_route_cache_ttl_secs(),
+            capacity: default_route_cache_capacity(),
+            invalidation_bps: default_route_cache_invalidation_bps(),
+        }
+    }
+}
+
+/// Background change detection for accounts on the watchlist.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WatchlistConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_watchlist_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    /// Most accounts that can be watched at once.
+    #[serde(default = "default_watchlist_max_accounts")]
+    pub max_accounts: i64,
+}
+
+impl Default for WatchlistConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            poll_interval_secs: default_watchlist_poll_interval_secs(),
+            max_accounts: default_watchlist_max_accounts(),
+        }
+    }
+}
+
+/// Periodic balance snapshots of watched accounts.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BalanceSnapshotConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_balance_snapshot_interval_secs")]
+    pub interval_secs: u64,
+}
+
+impl Default for BalanceSnapshotConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            interval_secs: default_balance_snapshot_interval_secs(),
+        }
+    }
+}
+
+/// Recurring swap schedules executed with the gateway's wallet.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DcaConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_dca_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    /// Due schedules executed per poll.
+    #[serde(default = "default_dca_batch_size")]
+    pub batch_size: i64,
+    /// Shortest interval a schedule may use.
+    #[serde(default = "default_dca_min_interval_secs")]
+    pub min_interval_secs: u64,
+    /// Most active or paused schedules at once.
+    #[serde(default = "default_dca_max_schedules")]
+    pub max_schedules: i64,
+}
+
+impl Default for DcaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            poll_interval_secs: default_dca_poll_interval_secs(),
+            batch_size: default_dca_batch_size(),
+            min_interval_secs: default_dca_min_interval_secs(),
+            max_schedules: default_dca_max_schedules(),
+        }
+    }
+}
+
+/// Delivery of queued webhook events.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_webhook_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    #[serde(default = "default_webhook_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Total attempts before a delivery is abandoned.
+    #[serde(default = "default_webhook_max_attempts")]
+    pub max_attempts: u32,
+    /// First retry delay, doubled after every failed attempt.
+    #[serde(default = "default_webhook_retry_base_delay_secs")]
+    pub retry_base_delay_secs: u64,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            poll_interval_secs: default_webhook_poll_interval_secs(),
+            timeout_secs: default_webhook_timeout_secs(),
+            max_attempts: default_webhook_max_attempts(),
+            retry_base_delay_secs: default_webhook_retry_base_delay_secs(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConfirmationConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_confirmation_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    /// Pending transactions the cluster still hasn't seen after this long
+    /// are marked expired; their blockhash is no longer valid.
+    #[serde(default = "default_confirmation_expiry_secs")]
+    pub expiry_secs: i64,
+}
+
+impl Default for ConfirmationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            poll_interval_ms: default_confirmation_poll_interval_ms(),
+            expiry_secs: default_confirmation_expiry_secs(),
+        }
+    }
+}
+
+/// Periodic re-check of stored transactions the confirmation tracker left
+/// unsettled, searching the full ledger history.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReconcilerConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_reconciler_interval_secs")]
+    pub interval_secs: u64,
+    /// Only records at least this old are checked; younger ones are still
+    /// the tracker's. Keep it above `confirmations.expiry_secs`.
+    #[serde(default = "default_reconciler_min_age_secs")]
+    pub min_age_secs: i64,
+    /// Records checked per run.
+    #[serde(default = "default_reconciler_batch_size")]
+    pub batch_size: i64,
+}
+
+impl Default for ReconcilerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            interval_secs: default_reconciler_interval_secs(),
+            min_age_secs: default_reconciler_min_age_secs(),
+            batch_size: default_reconciler_batch_size(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct KafkaConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_kafka_brokers")]
+    pub brokers: String,
+    #[serde(default = "default_kafka_client_id")]
+    pub client_id: String,
+    #[serde(default = "default_kafka_message_timeout_ms")]
+    pub message_timeout_ms: u64,
+    #[serde(default = "default_transactions_topic")]
+    pub transactions_topic: String,
+    #[serde(default = "default_swaps_topic")]
+    pub swaps_topic: String,
+    #[serde(default = "default_accounts_topic")]
+    pub accounts_topic: String,
+    /// Accounts whose changes are published to `accounts_topic`.
+    #[serde(default)]
+    pub tracked_accounts: Vec<String>,
+}
+
+/// Jito block engine used for swaps submitted with `mev_protection`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JitoConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_jito_block_engine_url")]
+    pub block_engine_url: String,
+    /// Tip paid to the validator with every bundle.
+    #[serde(default = "default_jito_tip_lamports")]
+    pub tip_lamports: u64,
+    /// Tip accounts used in rotation.
+    #[serde(default = "default_jito_tip_accounts")]
+    pub tip_accounts: Vec<String>,
+    #[serde(default = "default_jito_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+    #[serde(default = "default_jito_status_poll_interval_ms")]
+    pub status_poll_interval_ms: u64,
+    /// How long to wait for a bundle to land before reporting it pending.
+    #[serde(default = "default_jito_status_timeout_secs")]
+    pub status_timeout_secs: u64,
+}
+
+impl Default for JitoConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            block_engine_url: default_jito_block_engine_url(),
+            tip_lamports: default_jito_tip_lamports(),
+            tip_accounts: default_jito_tip_accounts(),
+            request_timeout_secs: default_jito_request_timeout_secs(),
+            status_poll_interval_ms: default_jito_status_poll_interval_ms(),
+            status_timeout_secs: default_jito_status_timeout_secs(),
+        }
+    }
+}
+
+/// Jupiter's quote API, compared against the gateway's own routes by
+/// `GET /api/v1/swap/quotes`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JupiterConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_jupiter_quote_url")]
+    pub quote_url: String,
+    /// Sent as `x-api-key`; required by the paid endpoints.
+    pub api_key: Option<String>,
+    #[serde(default = "default_jupiter_request_timeout_ms")]
+    pub request_timeout_ms: u64,
+}
+
+impl Default for JupiterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            quote_url: default_jupiter_quote_url(),
+            api_key: None,
+            request_timeout_ms: default_jupiter_request_timeout_ms(),
+        }
+    }
+}
+
+impl Default for KafkaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            brokers: default_kafka_brokers(),
+            client_id: default_kafka_client_id(),
+            message_timeout_ms: default_kafka_message_timeout_ms(),
+            transactions_topic: default_transactions_topic(),
+            swaps_topic: default_swaps_topic(),
+            accounts_topic: default_accounts_topic(),
+            tracked_accounts: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NftConfig {
+    /// Timeout for fetching off-chain metadata JSON from a token's URI.
+    #[serde(default = "default_nft_offchain_timeout_secs")]
+    pub offchain_timeout_secs: u64,
+    /// Off-chain documents larger than this are not parsed.
+    #[serde(default = "default_nft_offchain_max_bytes")]
+    pub offchain_max_bytes: usize,
+}
+
+impl Default for NftConfig {
+    fn default() -> Self {
+        Self {
+            offchain_timeout_secs: default_nft_offchain_timeout_secs(),
+            offchain_max_bytes: default_nft_offchain_max_bytes(),
+        }
+    }
+}
+
+/// Off-chain token metadata merged into mint and balance responses.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TokenRegistryConfig {
+    /// Token list in the Solana token-list format or a bare array of its
+    /// entries (as served by Jupiter). Without one, only metadata already
+    /// in the database is served.
+    #[serde(default)]
+    pub list_url: Option<String>,
+    /// Keeps only entries for this chain (101 is mainnet-beta); entries
+    /// without a chain ID are always kept.
+    #[serde(default)]
+    pub chain_id: Option<u64>,
+    #[serde(default = "default_token_registry_refresh_secs")]
+    pub refresh_secs: u64,
+    #[serde(default = "default_token_registry_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for TokenRegistryConfig {
+    fn default() -> Self {
+        Self {
+            list_url: None,
+            chain_id: None,
+            refresh_secs: default_token_registry_refresh_secs(),
+            timeout_secs: default_token_registry_timeout_secs(),
+        }
+    }
+}
+
+/// Mint risk reports, shown in token info and optionally enforced on swaps.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TokenSafetyConfig {
+    /// Rejects swaps whose output token has a high-risk flag.
+    #[serde(default)]
+    pub strict: bool,
+    /// Never blocked in strict mode. Stablecoins such as USDC keep a
+    /// freeze authority and need listing here.
+    #[serde(default)]
+    pub trusted_mints: Vec<String>,
+    /// Largest accounts summed for the concentration check.
+    #[serde(default = "default_token_safety_top_holders")]
+    pub top_holders: usize,
+    #[serde(default = "default_token_safety_max_top_holders_percent")]
+    pub max_top_holders_percent: f64,
+    #[serde(default = "default_token_safety_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+    #[serde(default = "default_token_safety_cache_capacity")]
+    pub cache_capacity: u64,
+}
+
+impl Default for TokenSafetyConfig {
+    fn default() -> Self {
+        Self {
+            strict: false,
+            trusted_mints: Vec::new(),
+            top_holders: default_token_safety_top_holders(),
+            max_top_holders_percent: default_token_safety_max_top_holders_percent(),
+            cache_ttl_secs: default_token_safety_cache_ttl_secs(),
+            cache_capacity: default_token_safety_cache_capacity(),
+        }
+    }
+}
+
+/// Serves the REST API over HTTPS, for deployments without a TLS
+/// terminating proxy in front of the gateway.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TlsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// PEM certificate chain, leaf first.
+    pub cert_path: Option<String>,
+    /// PEM private key (PKCS#8, PKCS#1 or SEC1).
+    pub key_path: Option<String>,
+    /// How often the files are checked for a rotated certificate. Zero
+    /// disables reloading.
+    #[serde(default = "default_tls_reload_secs")]
+    pub reload_secs: u64,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cert_path: None,
+            key_path: None,
+            reload_secs: default_tls_reload_secs(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GrpcConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_grpc_port")]
+    pub port: u16,
+}
+
+impl Default for GrpcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            port: default_grpc_port(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SwapConfig {
+    /// Slippage applied when a swap request doesn't specify one.
+    #[serde(default = "default_slippage_bps")]
+    pub default_slippage_bps: u16,
+    #[serde(default = "default_max_slippage_bps")]
+    pub max_slippage_bps: u16,
+    /// Share of every swap's input paid to `treasury`, in basis points.
+    #[serde(default)]
+    pub platform_fee_bps: u16,
+    /// Wallet whose associated token accounts receive platform fees;
+    /// required when `platform_fee_bps` is set.
+    pub treasury: Option<String>,
+    /// Times a gateway-signed swap whose blockhash expired before it landed
+    /// is rebuilt and resent.
+    #[serde(default = "default_swap_expiry_retries")]
+    pub expiry_retries: u32,
+    #[serde(default)]
+    pub quotes: SwapQuoteConfig,
+    /// Swaps SOL sides through a temporary wSOL account, funded with SOL
+    /// input and closed after the swap, so SOL pairs trade from and into
+    /// native SOL. When off, they use the owner's own wSOL account.
+    #[serde(default = "default_true")]
+    pub wrap_sol: bool,
+}
+
+impl Default for SwapConfig {
+    fn default() -> Self {
+        Self {
+            default_slippage_bps: default_slippage_bps(),
+            max_slippage_bps: default_max_slippage_bps(),
+            platform_fee_bps: 0,
+            treasury: None,
+            expiry_retries: default_swap_expiry_retries(),
+            quotes: SwapQuoteConfig::default(),
+            wrap_sol: true,
+        }
+    }
+}
+
+/// Signed, expiring quote IDs issued by `POST /api/v1/swap/quote`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SwapQuoteConfig {
+    /// Issues quote IDs and requires client swaps to reference one.
+    #[serde(default)]
+    pub enabled: bool,
+    /// HMAC key quote IDs are signed with; every replica needs the same.
+    pub secret: Option<String>,
+    /// How long a quote can be executed for.
+    #[serde(default = "default_quote_ttl_secs")]
+    pub ttl_secs: u64,
+    /// Execution is refused once the current output is this far below the
+    /// quoted one, in basis points.
+    #[serde(default = "default_quote_max_price_move_bps")]
+    pub max_price_move_bps: u16,
+}
+
+impl Default for SwapQuoteConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            secret: None,
+            ttl_secs: default_quote_ttl_secs(),
+            max_price_move_bps: default_quote_max_price_move_bps(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WalletConfig {
+    /// Hex-encoded 32-byte AES-256-GCM key used to encrypt stored keypairs.
+    /// Wallet endpoints are unavailable without it.
+    pub master_key: Option<String>,
+    /// Allows `POST /api/v1/wallets/:id/export` to return secret keys.
+    #[serde(default)]
+    pub allow_export: bool,
+}
+
+/// The gateway's own signing key, used for transfers from its address that
+/// don't name a wallet. Production deployments should use a KMS backend so
+/// the private key never enters the process.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignerConfig {
+    #[serde(default)]
+    pub backend: SignerBackend,
+    /// `solana-keygen` JSON file; required for the `keypair_file` backend
+    /// unless `keypair` is set.
+    pub keypair_path: Option<String>,
+    /// The contents of a `solana-keygen` JSON file, used in place of
+    /// `keypair_path`; typically supplied through `secrets`.
+    #[serde(default)]
+    pub keypair: Option<String>,
+    #[serde(default)]
+    pub aws_kms: AwsKmsConfig,
+    #[serde(default)]
+    pub gcp_kms: GcpKmsConfig,
+    /// Timeout of each call to the KMS.
+    #[serde(default = "default_kms_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+impl Default for SignerConfig {
+    fn default() -> Self {
+        Self {
+            backend: SignerBackend::default(),
+            keypair_path: None,
+            keypair: None,
+            aws_kms: AwsKmsConfig::default(),
+            gcp_kms: GcpKmsConfig::default(),
+            timeout_ms: default_kms_timeout_ms(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerBackend {
+    /// The gateway signs only with stored wallets.
+    #[default]
+    Disabled,
+    /// A keypair read from disk or configured at startup; for development.
+    KeypairFile,
+    AwsKms,
+    GcpKms,
+}
+
+/// An `ECC_NIST_EDWARDS25519` signing key. Credentials are read from the
+/// standard `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and
+/// `AWS_SESSION_TOKEN` variables.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AwsKmsConfig {
+    /// Key ID, ARN or alias.
+    pub key_id: Option<String>,
+    pub region: Option<String>,
+    /// Overrides the regional endpoint, e.g. for a VPC endpoint.
+    pub endpoint: Option<String>,
+}
+
+/// An `EC_SIGN_ED25519` key version.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GcpKmsConfig {
+    /// `projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/*`.
+    pub key_version: Option<String>,
+    /// OAuth access token; the instance's service account token is fetched
+    /// from the metadata server when unset.
+    pub access_token: Option<String>,
+}
+
+/// Screening of transfer destinations, swap owners and mints. Entries added
+/// through `/admin/compliance` are merged with these lists.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ComplianceConfig {
+    #[serde(default)]
+    pub denied_addresses: Vec<String>,
+    #[serde(default)]
+    pub denied_mints: Vec<String>,
+    /// Accepts only allowlisted addresses and mints, for institutional
+    /// deployments. SOL transfers are checked as the wrapped SOL mint.
+    #[serde(default)]
+    pub allowlist_only: bool,
+    #[serde(default)]
+    pub allowed_addresses: Vec<String>,
+    #[serde(default)]
+    pub allowed_mints: Vec<String>,
+    /// How often entries added by other instances are picked up.
+    #[serde(default = "default_compliance_sync_secs")]
+    pub sync_secs: u64,
+}
+
+impl Default for ComplianceConfig {
+    fn default() -> Self {
+        Self {
+            denied_addresses: Vec::new(),
+            denied_mints: Vec::new(),
+            allowlist_only: false,
+            allowed_addresses: Vec::new(),
+            allowed_mints: Vec::new(),
+            sync_secs: default_compliance_sync_secs(),
+        }
+    }
+}
+
+/// Multi-party approval of large server-signed transfers. A transfer at or
+/// above its threshold is held as a pending approval until enough API keys
+/// other than the requester's approve it, and only then signed and sent.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ApprovalConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// SOL transfers of at least this many lamports need approval.
+    #[serde(default = "default_approval_threshold_lamports")]
+    pub threshold_lamports: u64,
+    /// Thresholds in raw units per mint; transfers of other mints don't
+    /// need approval.
+    #[serde(default)]
+    pub mint_thresholds: HashMap<String, u64>,
+    #[serde(default = "default_required_approvals")]
+    pub required_approvals: usize,
+    /// API key IDs allowed to approve; any key with the admin role may when
+    /// empty.
+    #[serde(default)]
+    pub approvers: Vec<Uuid>,
+    /// Pending approvals can't be approved after this long.
+    #[serde(default = "default_approval_ttl_secs")]
+    pub ttl_secs: u64,
+    #[serde(default)]
+    pub backend: ApprovalBackend,
+    #[serde(default)]
+    pub squads: SquadsConfig,
+}
+
+impl Default for ApprovalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold_lamports: default_approval_threshold_lamports(),
+            mint_thresholds: HashMap::new(),
+            required_approvals: default_required_approvals(),
+            approvers: Vec::new(),
+            ttl_secs: default_approval_ttl_secs(),
+            backend: ApprovalBackend::default(),
+            squads: SquadsConfig::default(),
+        }
+    }
+}
+
+/// What happens to a transfer once it is approved.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalBackend {
+    /// The requesting wallet signs and sends the transfer.
+    #[default]
+    Gateway,
+    /// The transfer is proposed from a Squads v4 vault, with the requesting
+    /// wallet as the proposing member; the multisig's other members approve
+    /// and execute it on-chain.
+    Squads,
+}
+
+impl ApprovalBackend {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ApprovalBackend::Gateway => "gateway",
+            ApprovalBackend::Squads => "squads",
+        }
+    }
+}
+
+/// The requesting wallet must be a member of the multisig allowed to
+/// initiate and vote.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SquadsConfig {
+    /// Multisig account; required for the `squads` backend.
+    pub multisig: Option<String>,
+    #[serde(default)]
+    pub vault_index: u8,
+}
+
+/// JWT bearer authentication against the platform identity provider. When
+/// enabled, reads need the viewer role, mutations the trader role and
+/// administrative endpoints the admin role.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuthConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    pub jwks_url: Option<String>,
+    /// Expected `iss`; unchecked when unset.
+    pub issuer: Option<String>,
+    /// Expected `aud`; unchecked when unset.
+    pub audience: Option<String>,
+    /// Claim holding the caller's role names.
+    #[serde(default = "default_roles_claim")]
+    pub roles_claim: String,
+    /// Identity provider role names mapped to gateway roles. Names that
+    /// aren't mapped are used as is (`viewer`, `trader`, `admin`).
+    #[serde(default)]
+    pub role_mapping: HashMap<String, Role>,
+    #[serde(default = "default_jwks_refresh_secs")]
+    pub jwks_refresh_secs: u64,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            jwks_url: None,
+            issuer: None,
+            audience: None,
+            roles_claim: default_roles_claim(),
+            role_mapping: HashMap::new(),
+            jwks_refresh_secs: default_jwks_refresh_secs(),
+        }
+    }
+}
+
+/// Operator endpoints under `/admin`. They are authenticated with a static
+/// token instead of bearer tokens, so they stay reachable while the
+/// identity provider isn't.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AdminConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Expected in the `X-Admin-Token` header; required when enabled.
+    pub token: Option<String>,
+}
+
+/// Route groups in maintenance at startup; applied again on reload.
+/// Their write endpoints answer 503 while reads keep serving.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MaintenanceConfig {
+    #[serde(default)]
+    pub transactions: bool,
+    #[serde(default)]
+    pub swaps: bool,
+    /// Shown to clients in the 503 responses.
+    pub reason: Option<String>,
+}
+
+/// API keys issued through `/admin/api-keys`. When enabled, an `X-API-Key`
+/// must be an active key and authenticates the caller with the role its
+/// scopes grant; callers without one need a bearer token.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ApiKeyConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// How long a verified key is trusted before it is looked up again.
+    #[serde(default = "default_api_key_cache_secs")]
+    pub cache_secs: u64,
+    /// How often usage counts are written and revocations made by other
+    /// replicas are picked up.
+    #[serde(default = "default_api_key_sync_secs")]
+    pub sync_secs: u64,
+    /// Mixed into the stored hash of every key. Keys issued before it was
+    /// set or changed no longer verify.
+    #[serde(default)]
+    pub salt: Option<String>,
+}
+
+impl Default for ApiKeyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cache_secs: default_api_key_cache_secs(),
+            sync_secs: default_api_key_sync_secs(),
+            salt: None,
+        }
+    }
+}
+
+/// Settings fetched from a secret store at startup, taking precedence over
+/// the config file and environment. Refreshed every `refresh_secs`; a
+/// rotated database password is picked up by new connections, other
+/// changed settings apply on restart.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SecretsConfig {
+    #[serde(default)]
+    pub backend: SecretsBackend,
+    /// Secret references keyed by setting, e.g. `database_url` or
+    /// `wallets.master_key`. A reference is `<path>#<field>`; for AWS the
+    /// field may be omitted to use the whole secret string.
+    #[serde(default)]
+    pub settings: HashMap<String, String>,
+    /// Zero disables refreshing.
+    #[serde(default = "default_secrets_refresh_secs")]
+    pub refresh_secs: u64,
+    /// Timeout of each call to the secret store.
+    #[serde(default = "default_secrets_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default)]
+    pub vault: VaultConfig,
+    #[serde(default)]
+    pub aws_secrets_manager: AwsSecretsManagerConfig,
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self {
+            backend: SecretsBackend::default(),
+            settings: HashMap::new(),
+            refresh_secs: default_secrets_refresh_secs(),
+            timeout_ms: default_secrets_timeout_ms(),
+            vault: VaultConfig::default(),
+            aws_secrets_manager: AwsSecretsManagerConfig::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretsBackend {
+    #[default]
+    Disabled,
+    Vault,
+    AwsSecretsManager,
+}
+
+/// A HashiCorp Vault KV version 2 secrets engine.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VaultConfig {
+    /// e.g. `https://vault.internal:8200`.
+    pub address: Option<String>,
+    /// Read from `VAULT_TOKEN` when unset.
+    pub token: Option<String>,
+    /// Enterprise namespace, sent as `X-Vault-Namespace`.
+    pub namespace: Option<String>,
+    /// Mount path of the KV engine.
+    #[serde(default = "default_vault_mount")]
+    pub mount: String,
+}
+
+impl Default for VaultConfig {
+    fn default() -> Self {
+        Self {
+            address: None,
+            token: None,
+            namespace: None,
+            mount: default_vault_mount(),
+        }
+    }
+}
+
+/// Credentials are read from the same variables as the AWS KMS signer.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AwsSecretsManagerConfig {
+    pub region: Option<String>,
+    /// Overrides the regional endpoint, e.g. for a VPC endpoint.
+    pub endpoint: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ApiConfig {
+    /// Serves `/api/v2` alongside `/api/v1`.
+    #[serde(default)]
+    pub v2_enabled: bool,
+    /// Deprecation notices keyed by version (`v1`), sent as response headers.
+    #[serde(default)]
+    pub deprecations: HashMap<String, DeprecationConfig>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeprecationConfig {
+    pub deprecated_at: Option<DateTime<Utc>>,
+    /// When the version stops being served.
+    pub sunset_at: Option<DateTime<Utc>>,
+    /// URL of the version replacing this one.
+    pub successor: Option<String>,
+}
+
+/// In-flight request caps per route group. Requests above a cap are shed
+/// with 503 instead of queueing, so one group can't starve another.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConcurrencyConfig {
+    #[serde(default = "default_read_concurrency")]
+    pub read: usize,
+    /// Transfers, wallet, nonce and stake operations.
+    #[serde(default = "default_write_concurrency")]
+    pub write: usize,
+    #[serde(default = "default_swap_concurrency")]
+    pub swap: usize,
+    /// `Retry-After` sent with shed requests.
+    #[serde(default = "default_shed_retry_after_secs")]
+    pub retry_after_secs: u64,
+}
+
+impl Default for ConcurrencyConfig {
+    fn default() -> Self {
+        Self {
+            read: default_read_concurrency(),
+            write: default_write_concurrency(),
+            swap: default_swap_concurrency(),
+            retry_after_secs: default_shed_retry_after_secs(),
+        }
+    }
+}
+
+/// Response compression, negotiated with `Accept-Encoding`, per route
+/// group. Streams (SSE) and small bodies are sent as is.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CompressionConfig {
+    #[serde(default = "default_true")]
+    pub read: bool,
+    /// Transfer, wallet, nonce, stake and swap responses, which are small.
+    #[serde(default)]
+    pub trading: bool,
+    #[serde(default = "default_true")]
+    pub gzip: bool,
+    #[serde(default = "default_true")]
+    pub br: bool,
+    #[serde(default = "default_true")]
+    pub zstd: bool,
+    /// Smallest body compressed, in bytes.
+    #[serde(default = "default_compression_min_bytes")]
+    pub min_bytes: u16,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            read: default_true(),
+            trading: false,
+            gzip: default_true(),
+            br: default_true(),
+            zstd: default_true(),
+            min_bytes: default_compression_min_bytes(),
+        }
+    }
+}
+
+/// Largest accepted request body per route group, in bytes. Oversized
+/// bodies are rejected with 413 before they are buffered. Limits above
+/// 2 MiB are capped by the JSON extractor's own limit.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BodyLimitConfig {
+    #[serde(default = "default_read_body_bytes")]
+    pub read_bytes: usize,
+    #[serde(default = "default_trading_body_bytes")]
+    pub trading_bytes: usize,
+    /// Overrides keyed by route template, e.g. `/api/v1/accounts/batch`.
+    #[serde(default = "default_route_body_limits")]
+    pub routes: HashMap<String, usize>,
+}
+
+impl Default for BodyLimitConfig {
+    fn default() -> Self {
+        Self {
+            read_bytes: default_read_body_bytes(),
+            trading_bytes: default_trading_body_bytes(),
+            routes: default_route_body_limits(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TimeoutConfig {
+    /// Deadline for a whole request to a read endpoint.
+    #[serde(default = "default_read_timeout_secs")]
+    pub read_secs: u64,
+    /// Deadline for a whole request to a trading endpoint.
+    #[serde(default = "default_trading_timeout_secs")]
+    pub trading_secs: u64,
+    /// Deadline for a single RPC attempt before failing over.
+    #[serde(default = "default_rpc_timeout_secs")]
+    pub rpc_secs: u64,
+}
+
+impl TimeoutConfig {
+    pub fn read(&self) -> Duration {
+        Duration::from_secs(self.read_secs)
+    }
+
+    pub fn trading(&self) -> Duration {
+        Duration::from_secs(self.trading_secs)
+    }
+
+    pub fn rpc(&self) -> Duration {
+        Duration::from_secs(self.rpc_secs)
+    }
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        Self {
+            read_secs: default_read_timeout_secs(),
+            trading_secs: default_trading_timeout_secs(),
+            rpc_secs: default_rpc_timeout_secs(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Requests per second across all clients.
+    #[serde(default = "default_global_per_second")]
+    pub global_per_second: u32,
+    /// Per-client quotas; the burst size equals the per-minute quota.
+    #[serde(default = "default_read_per_minute")]
+    pub read_per_minute: u32,
+    #[serde(default = "default_trading_per_minute")]
+    pub trading_per_minute: u32,
+    /// Quota overrides keyed by `X-API-Key` value.
+    #[serde(default)]
+    pub api_keys: HashMap<String, ClientQuota>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientQuota {
+    #[serde(default = "default_read_per_minute")]
+    pub read_per_minute: u32,
+    #[serde(default = "default_trading_per_minute")]
+    pub trading_per_minute: u32,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            global_per_second: default_global_per_second(),
+            read_per_minute: default_read_per_minute(),
+            trading_per_minute: default_trading_per_minute(),
+            api_keys: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PriceOracleConfig {
+    /// Pyth price account per token mint.
+    #[serde(default = "default_price_feeds")]
+    pub feeds: HashMap<String, String>,
+    /// Prices published longer ago than this are flagged as stale.
+    #[serde(default = "default_max_price_staleness_slots")]
+    pub max_staleness_slots: u64,
+    /// Largest accepted gap between a quoted rate and the oracle rate.
+    #[serde(default = "default_max_price_deviation_bps")]
+    pub max_deviation_bps: u32,
+}
+
+impl Default for PriceOracleConfig {
+    fn default() -> Self {
+        Self {
+            feeds: default_price_feeds(),
+            max_staleness_slots: default_max_price_staleness_slots(),
+            max_deviation_bps: default_max_price_deviation_bps(),
+        }
+    }
+}
+
+impl Default for RpcFailoverConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: default_failure_threshold(),
+            quarantine_base_secs: default_quarantine_base_secs(),
+            quarantine_max_secs: default_quarantine_max_secs(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_endpoint_weight() -> u32 {
+    1
+}
+
+fn default_rpc_http_pool_max_idle_per_host() -> usize {
+    64
+}
+
+fn default_rpc_http_pool_idle_timeout_secs() -> u64 {
+    90
+}
+
+fn default_rpc_http_connect_timeout_ms() -> u64 {
+    2_000
+}
+
+fn default_rpc_http_tcp_keepalive_secs() -> Option<u64> {
+    Some(60)
+}
+
+fn default_rpc_http_http2_keep_alive_secs() -> Option<u64> {
+    Some(30)
+}
+
+fn default_rpc_retry_max_attempts() -> u32 {
+    3
+}
+
+fn default_rpc_retry_base_delay_ms() -> u64 {
+    100
+}
+
+fn default_rpc_retry_max_delay_ms() -> u64 {
+    2_000
+}
+
+fn default_rpc_rate_limit_delay_ms() -> u64 {
+    1_000
+}
+
+fn default_max_slot_lag() -> u64 {
+    50
+}
+
+fn default_slot_lag_interval_secs() -> u64 {
+    10
+}
+
+fn default_blockhash_refresh_interval_ms() -> u64 {
+    8_000
+}
+
+fn default_blockhash_max_age_secs() -> u64 {
+    30
+}
+
+fn default_failure_threshold() -> u32 {
+    3
+}
+
+fn default_quarantine_base_secs() -> u64 {
+    5
+}
+
+fn default_quarantine_max_secs() -> u64 {
+    300
+}
+
+fn default_roles_claim() -> String {
+    "roles".to_string()
+}
+
+fn default_jwks_refresh_secs() -> u64 {
+    300
+}
+
+fn default_api_key_cache_secs() -> u64 {
+    60
+}
+
+fn default_approval_threshold_lamports() -> u64 {
+    // 100 SOL
+    100_000_000_000
+}
+
+fn default_required_approvals() -> usize {
+    2
+}
+
+fn default_approval_ttl_secs() -> u64 {
+    86_400
+}
+
+fn default_kms_timeout_ms() -> u64 {
+    5_000
+}
+
+fn default_compliance_sync_secs() -> u64 {
+    30
+}
+
+fn default_api_key_sync_secs() -> u64 {
+    10
+}
+
+fn default_tls_reload_secs() -> u64 {
+    60
+}
+
+fn default_secrets_refresh_secs() -> u64 {
+    300
+}
+
+fn default_secrets_timeout_ms() -> u64 {
+    5_000
+}
+
+fn default_vault_mount() -> String {
+    "secret".to_string()
+}
+
+fn default_candle_sample_interval_secs() -> u64 {
+    60
+}
+
+fn default_candle_max_pools() -> i64 {
+    200
+}
+
+fn default_pool_volume_poll_interval_secs() -> u64 {
+    60
+}
+
+fn default_pool_volume_max_pools() -> i64 {
+    50
+}
+
+fn default_pool_volume_max_signatures() -> usize {
+    200
+}
+
+fn default_pool_volume_retention_days() -> i64 {
+    8
+}
+
+fn default_transaction_premake_months() -> u32 {
+    3
+}
+
+fn default_transaction_retention_interval_secs() -> u64 {
+    3600
+}
+
+fn default_transaction_archive_prefix() -> String {
+    "solana_transactions/".to_string()
+}
+
+fn default_transaction_archive_batch_size() -> i64 {
+    10_000
+}
+
+fn default_transaction_archive_timeout_ms() -> u64 {
+    300_000
+}
+
+fn default_route_cache_ttl_secs() -> u64 {
+    10
+}
+
+fn default_route_cache_capacity() -> u64 {
+    10_000
+}
+
+fn default_route_cache_invalidation_bps() -> u32 {
+    50
+}
+
+fn default_watchlist_poll_interval_secs() -> u64 {
+    10
+}
+
+fn default_watchlist_max_accounts() -> i64 {
+    1_000
+}
+
+fn default_balance_snapshot_interval_secs() -> u64 {
+    3_600
+}
+
+fn default_dca_poll_interval_secs() -> u64 {
+    10
+}
+
+fn default_dca_batch_size() -> i64 {
+    20
+}
+
+fn default_dca_min_interval_secs() -> u64 {
+    60
+}
+
+fn default_dca_max_schedules() -> i64 {
+    100
+}
+
+fn default_webhook_poll_interval_secs() -> u64 {
+    5
+}
+
+fn default_webhook_timeout_secs() -> u64 {
+    10
+}
+
+fn default_webhook_max_attempts() -> u32 {
+    8
+}
+
+fn default_webhook_retry_base_delay_secs() -> u64 {
+    10
+}
+
+fn default_read_concurrency() -> usize {
+    512
+}
+
+fn default_write_concurrency() -> usize {
+    128
+}
+
+fn default_swap_concurrency() -> usize {
+    32
+}
+
+fn default_shed_retry_after_secs() -> u64 {
+    1
+}
+
+fn default_compression_min_bytes() -> u16 {
+    1_024
+}
+
+fn default_read_body_bytes() -> usize {
+    16 * 1024
+}
+
+fn default_trading_body_bytes() -> usize {
+    64 * 1024
+}
+
+fn default_route_body_limits() -> HashMap<String, usize> {
+    HashMap::from([("/api/v1/accounts/batch".to_string(), 1024 * 1024)])
+}
+
+fn default_db_max_connections() -> u32 {
+    10
+}
+
+fn default_db_acquire_timeout_secs() -> u64 {
+    30
+}
+
+fn default_db_idle_timeout_secs() -> u64 {
+    600
+}
+
+fn default_db_max_lifetime_secs() -> u64 {
+    1800
+}
+
+fn default_db_statement_cache_capacity() -> usize {
+    100
+}
+
+fn default_db_metrics_interval_secs() -> u64 {
+    15
+}
+
+fn default_db_replica_check_interval_secs() -> u64 {
+    10
+}
+
+fn default_log_level() -> String {
+    "solana_gateway_service=debug,tower_http=debug".to_string()
+}
+
+fn default_tracing_service_name() -> String {
+    "solana-gateway-service".to_string()
+}
+
+fn default_tracing_sample_ratio() -> f64 {
+    1.0
+}
+
+fn default_request_latency_buckets() -> Vec<f64> {
+    vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]
+}
+
+fn default_rpc_latency_buckets() -> Vec<f64> {
+    vec![0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.0, 5.0, 10.0, 30.0]
+}
+
+fn default_slo_windows_secs() -> Vec<u64> {
+    vec![300, 1_800, 3_600, 21_600, 86_400, 259_200]
+}
+
+fn default_compute_unit_limit() -> u32 {
+    200_000
+}
+
+fn default_compute_unit_margin_pct() -> u32 {
+    10
+}
+
+fn default_max_unit_price() -> u64 {
+    1_000_000
+}
+
+fn default_impact_sizes() -> Vec<f64> {
+    vec![1.0, 10.0, 100.0]
+}
+
+fn default_depth_bps() -> Vec<u32> {
+    vec![50, 100, 200, 500]
+}
+
+fn default_pool_discovery_interval_secs() -> u64 {
+    300
+}
+
+fn default_pool_feed_channel_capacity() -> usize {
+    256
+}
+
+fn default_tracked_mints() -> Vec<String> {
+    vec![
+        // Wrapped SOL and USDC
+        "So11111111111111111111111111111111111111112".to_string(),
+        "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+    ]
+}
+
+fn default_idempotency_ttl_secs() -> u64 {
+    24 * 60 * 60
+}
+
+fn default_confirmation_poll_interval_ms() -> u64 {
+    2_000
+}
+
+fn default_confirmation_expiry_secs() -> i64 {
+    150
+}
+
+fn default_reconciler_interval_secs() -> u64 {
+    300
+}
+
+fn default_reconciler_min_age_secs() -> i64 {
+    600
+}
+
+fn default_reconciler_batch_size() -> i64 {
+    1_000
+}
+
+fn default_jito_block_engine_url() -> String {
+    "https://mainnet.block-engine.jito.wtf".to_string()
+}
+
+fn default_jupiter_quote_url() -> String {
+    "https://quote-api.jup.ag/v6/quote".to_string()
+}
+
+fn default_jupiter_request_timeout_ms() -> u64 {
+    2000
+}
+
+fn default_jito_tip_lamports() -> u64 {
+    10_000
+}
+
+fn default_jito_tip_accounts() -> Vec<String> {
+    // Mainnet tip accounts published by Jito Labs
+    [
+        "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+        "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
+        "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
+        "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
+        "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
+        "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
+        "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+        "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
+    ]
+    .iter()
+    .map(|account| account.to_string())
+    .collect()
+}
+
+fn default_jito_request_timeout_secs() -> u64 {
+    10
+}
+
+fn default_jito_status_poll_interval_ms() -> u64 {
+    1000
+}
+
+fn default_jito_status_timeout_secs() -> u64 {
+    30
+}
+
+fn default_cluster_name() -> String {
+    "mainnet".to_string()
+}
+
+fn default_kafka_brokers() -> String {
+    "localhost:9092".to_string()
+}
+
+fn default_kafka_client_id() -> String {
+    "solana-gateway-service".to_string()
+}
+
+fn default_kafka_message_timeout_ms() -> u64 {
+    5_000
+}
+
+fn default_transactions_topic() -> String {
+    "solana.transactions".to_string()
+}
+
+fn default_swaps_topic() -> String {
+    "solana.swaps".to_string()
+}
+
+fn default_accounts_topic() -> String {
+    "solana.accounts".to_string()
+}
+
+fn default_nft_offchain_timeout_secs() -> u64 {
+    5
+}
+
+fn default_nft_offchain_max_bytes() -> usize {
+    256 * 1024
+}
+
+// Cheap reads the typed API doesn't cover. Scans such as getProgramAccounts
+// and getLargestAccounts are left out.
+fn default_rpc_passthrough_methods() -> Vec<String> {
+    [
+        "getBlock",
+        "getBlockHeight",
+        "getBlockProduction",
+        "getBlockTime",
+        "getClusterNodes",
+        "getEpochInfo",
+        "getEpochSchedule",
+        "getGenesisHash",
+        "getInflationGovernor",
+        "getInflationRate",
+        "getInflationReward",
+        "getLeaderSchedule",
+        "getMinimumBalanceForRentExemption",
+        "getRecentPerformanceSamples",
+        "getRecentPrioritizationFees",
+        "getSlotLeaders",
+        "getStakeMinimumDelegation",
+        "getSupply",
+        "getTokenLargestAccounts",
+        "getTokenSupply",
+        "getVersion",
+        "getVoteAccounts",
+    ]
+    .map(String::from)
+    .to_vec()
+}
+
+fn default_graphql_max_depth() -> usize {
+    8
+}
+
+fn default_graphql_max_complexity() -> usize {
+    500
+}
+
+fn default_token_registry_refresh_secs() -> u64 {
+    3_600
+}
+
+fn default_token_registry_timeout_secs() -> u64 {
+    30
+}
+
+fn default_token_safety_top_holders() -> usize {
+    10
+}
+
+fn default_token_safety_max_top_holders_percent() -> f64 {
+    50.0
+}
+
+fn default_token_safety_cache_ttl_secs() -> u64 {
+    300
+}
+
+fn default_token_safety_cache_capacity() -> u64 {
+    10_000
+}
+
+fn default_grpc_port() -> u16 {
+    50051
+}
+
+fn default_slippage_bps() -> u16 {
+    50
+}
+
+fn default_max_slippage_bps() -> u16 {
+    1_000
+}
+
+fn default_swap_expiry_retries() -> u32 {
+    2
+}
+
+fn default_quote_ttl_secs() -> u64 {
+    30
+}
+
+fn default_quote_max_price_move_bps() -> u16 {
+    50
+}
+
+fn default_read_timeout_secs() -> u64 {
+    15
+}
+
+fn default_trading_timeout_secs() -> u64 {
+    30
+}
+
+fn default_rpc_timeout_secs() -> u64 {
+    10
+}
+
+fn default_global_per_second() -> u32 {
+    500
+}
+
+fn default_read_per_minute() -> u32 {
+    600
+}
+
+fn default_trading_per_minute() -> u32 {
+    30
+}
+
+fn default_price_feeds() -> HashMap<String, String> {
+    HashMap::from([
+        // SOL/USD and USDC/USD on mainnet-beta
+        (
+            "So11111111111111111111111111111111111111112".to_string(),
+            "H6ARHf6YXhGYeQfUzQNGk6rDNnLBQKrenN712K4AQJEG".to_string(),
+        ),
+        (
+            "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+            "Gnt27xtC473ZT2Mw5u8wZ68Z3gULkSTb5DuxJy7eJotD".to_string(),
+        ),
+    ])
+}
+
+fn default_max_price_staleness_slots() -> u64 {
+    25
+}
+
+fn default_max_price_deviation_bps() -> u32 {
+    500
+}
+
+fn default_max_block_age_secs() -> i64 {
+    60
+}
+
+fn default_max_batch_accounts() -> usize {
+    100
+}
+
+fn default_mint_cache_ttl_secs() -> u64 {
+    3600
+}
+
+fn default_mint_cache_capacity() -> u64 {
+    10_000
+}
+
+impl Config {
+    /// Layers, lowest precedence first: the defaults in this module, the
+    /// config `file` (any extension config-rs reads), `SECTION__KEY`
+    /// environment variables, settings fetched from the configured secret
+    /// store and `KEY=VALUE` command-line overrides. Every missing or
+    /// invalid setting is reported at once.
+    pub async fn load(file: &str, overrides: &[String]) -> Result<Self> {
+        let mut settings = layered(file, &HashMap::new(), overrides)?;
+        let secrets = match settings.get::<SecretsConfig>("secrets") {
+            Ok(secrets) => secrets,
+            Err(config::ConfigError::NotFound(_)) => SecretsConfig::default(),
+            Err(e) => return Err(e.into()),
+        };
+        if let Some(provider) = crate::secrets::from_config(&secrets)? {
+            let values = crate::secrets::resolve(provider.as_ref(), &secrets).await?;
+            settings = layered(file, &values, overrides)?;
+        }
+
+        let missing = missing_settings(&settings);
+        if !missing.is_empty() {
+            return Err(InvalidConfig(missing).into());
+        }
+        let config: Config = settings.try_deserialize()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks settings that deserialize but can't work, collecting every
+    /// problem rather than stopping at the first.
+    pub fn validate(&self) -> Result<(), InvalidConfig> {
+        let mut problems = Vec::new();
+        let mut check = |ok: bool, problem: String| {
+            if !ok {
+                problems.push(problem);
+            }
+        };
+
+        check_url(&mut check, "database_url", &self.database_url, &["postgres", "postgresql"]);
+        for (i, url) in self.database_read_urls.iter().enumerate() {
+            check_url(&mut check, &format!("database_read_urls[{}]", i), url, &["postgres", "postgresql"]);
+        }
+        check_url(&mut check, "solana_rpc_url", &self.solana_rpc_url, &["http", "https"]);
+        if let Some(url) = &self.solana_ws_url {
+            check_url(&mut check, "solana_ws_url", url, &["ws", "wss"]);
+        }
+        for (i, endpoint) in self.solana_rpc_endpoints.iter().enumerate() {
+            check_url(&mut check, &format!("solana_rpc_endpoints[{}].url", i), &endpoint.url, &["http", "https"]);
+        }
+        for (name, cluster) in &self.clusters {
+            check(*name != self.cluster, format!("clusters.{} has the default cluster's name", name));
+            check_url(&mut check, &format!("clusters.{}.database_url", name), &cluster.database_url, &["postgres", "postgresql"]);
+            for (i, url) in cluster.database_read_urls.iter().enumerate() {
+                check_url(&mut check, &format!("clusters.{}.database_read_urls[{}]", name, i), url, &["postgres", "postgresql"]);
+            }
+            check_url(&mut check, &format!("clusters.{}.solana_rpc_url", name), &cluster.solana_rpc_url, &["http", "https"]);
+        }
+
+        check(
+            tracing_subscriber::EnvFilter::try_new(&self.logging.level).is_ok(),
+            format!("logging.level {} is not a valid filter", self.logging.level),
+        );
+        if let Some(endpoint) = &self.tracing.otlp_endpoint {
+            check_url(&mut check, "tracing.otlp_endpoint", endpoint, &["http", "https"]);
+        }
+        check(
+            (0.0..=1.0).contains(&self.tracing.sample_ratio),
+            "tracing.sample_ratio must be between 0 and 1".to_string(),
+        );
+        let limits = &self.rate_limit;
+        check(
+            limits.global_per_second > 0
+                && limits.read_per_minute > 0
+                && limits.trading_per_minute > 0
+                && limits.api_keys.values().all(|quota| quota.read_per_minute > 0 && quota.trading_per_minute > 0),
+            "rate_limit limits must be greater than zero".to_string(),
+        );
+
+        for (i, slo) in self.metrics.slos.iter().enumerate() {
+            let name = format!("metrics.slos[{}]", i);
+            check(
+                !self.metrics.slos[..i].iter().any(|other| other.name == slo.name),
+                format!("{} repeats the name {}", name, slo.name),
+            );
+            check(!slo.routes.is_empty(), format!("{} has no routes", name));
+            check(
+                slo.availability.is_some() || slo.latency.is_some(),
+                format!("{} sets no availability or latency objective", name),
+            );
+            let targets = slo.availability.into_iter().chain(slo.latency.as_ref().map(|latency| latency.target));
+            for target in targets {
+                check(
+                    target > 0.0 && target < 1.0,
+                    format!("{} objectives must be between 0 and 1", name),
+                );
+            }
+        }
+        check(
+            !self.metrics.slo_windows_secs.is_empty() && !self.metrics.slo_windows_secs.contains(&0),
+            "metrics.slo_windows_secs must be positive".to_string(),
+        );
+
+        let pool = &self.database_pool;
+        check(pool.max_connections > 0, "database_pool.max_connections must be positive".to_string());
+        check(
+            pool.min_connections <= pool.max_connections,
+            "database_pool.min_connections may not exceed max_connections".to_string(),
+        );
+        check(
+            !self.admin.enabled || self.admin.token.is_some(),
+            "admin.token is required when the admin API is enabled".to_string(),
+        );
+
+        let swap = &self.swap;
+        check(swap.max_slippage_bps <= 10_000, "swap.max_slippage_bps may not exceed 10000".to_string());
+        check(
+            swap.default_slippage_bps <= swap.max_slippage_bps,
+            "swap.default_slippage_bps may not exceed swap.max_slippage_bps".to_string(),
+        );
+        if swap.platform_fee_bps > 0 {
+            check(swap.platform_fee_bps < 10_000, "swap.platform_fee_bps must be below 10000".to_string());
+            check(
+                swap.treasury.as_deref().map_or(false, is_pubkey),
+                "swap.treasury must be a public key when a platform fee is set".to_string(),
+            );
+        }
+        if swap.quotes.enabled {
+            check(
+                swap.quotes.secret.as_ref().map_or(false, |secret| secret.len() >= 32),
+                "swap.quotes.secret of at least 32 characters is required when signed quotes are enabled".to_string(),
+            );
+            check(swap.quotes.ttl_secs > 0, "swap.quotes.ttl_secs must be positive".to_string());
+            check(
+                swap.quotes.max_price_move_bps <= 10_000,
+                "swap.quotes.max_price_move_bps may not exceed 10000".to_string(),
+            );
+        }
+
+        let signer = &self.signer;
+        match signer.backend {
+            SignerBackend::Disabled => {}
+            SignerBackend::KeypairFile => check(
+                signer.keypair_path.is_some() || signer.keypair.is_some(),
+                "signer.keypair_path or signer.keypair is required for the keypair_file backend".to_string(),
+            ),
+            SignerBackend::AwsKms => check(
+                signer.aws_kms.key_id.is_some(),
+                "signer.aws_kms.key_id is required for the aws_kms backend".to_string(),
+            ),
+            SignerBackend::GcpKms => check(
+                signer.gcp_kms.key_version.is_some(),
+                "signer.gcp_kms.key_version is required for the gcp_kms backend".to_string(),
+            ),
+        }
+        if self.graphql.enabled {
+            check(
+                self.graphql.max_depth > 0 && self.graphql.max_complexity > 0,
+                "graphql.max_depth and graphql.max_complexity must be positive".to_string(),
+            );
+        }
+        let retention = &self.transaction_retention;
+        check(
+            retention.premake_months > 0,
+            "transaction_retention.premake_months must be positive".to_string(),
+        );
+        check(
+            retention.archive.batch_size > 0,
+            "transaction_retention.archive.batch_size must be positive".to_string(),
+        );
+        if let Some(endpoint) = &retention.archive.endpoint {
+            check_url(&mut check, "transaction_retention.archive.endpoint", endpoint, &["http", "https"]);
+        }
+        if self.jupiter.enabled {
+            check_url(&mut check, "jupiter.quote_url", &self.jupiter.quote_url, &["http", "https"]);
+        }
+        if self.tls.enabled {
+            check(self.tls.cert_path.is_some(), "tls.cert_path is required when TLS is enabled".to_string());
+            check(self.tls.key_path.is_some(), "tls.key_path is required when TLS is enabled".to_string());
+        }
+        if self.secrets.backend == SecretsBackend::Vault {
+            match &self.secrets.vault.address {
+                Some(address) => check_url(&mut check, "secrets.vault.address", address, &["http", "https"]),
+                None => check(false, "secrets.vault.address is required for the vault backend".to_string()),
+            }
+        }
+        check(
+            !self.approvals.enabled || self.approvals.required_approvals > 0,
+            "approvals.required_approvals must be positive when approvals are enabled".to_string(),
+        );
+
+        let compliance = &self.compliance;
+        for (list, values) in [
+            ("denied_addresses", &compliance.denied_addresses),
+            ("denied_mints", &compliance.denied_mints),
+            ("allowed_addresses", &compliance.allowed_addresses),
+            ("allowed_mints", &compliance.allowed_mints),
+        ] {
+            for value in values.iter().filter(|value| !is_pubkey(value)) {
+                check(false, format!("compliance.{} has an invalid public key {}", list, value));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(InvalidConfig(problems))
+        }
+    }
+
+    /// The effective configuration as JSON for `--print-config`, with
+    /// credentials, API keys, and URL passwords and query strings replaced.
+    pub fn redacted(&self) -> serde_json::Value {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        // Quota overrides are keyed by the API keys themselves.
+        if let Some(serde_json::Value::Object(quotas)) = value.pointer_mut("/rate_limit/api_keys") {
+            *quotas = std::mem::take(quotas)
+                .into_iter()
+                .enumerate()
+                .map(|(i, (_, quota))| (format!("{} {}", REDACTED, i + 1), quota))
+                .collect();
+        }
+        redact(&mut value);
+        value
+    }
+
+    pub fn ws_url(&self) -> String {
+        self.solana_ws_url.clone().unwrap_or_else(|| {
+            self.solana_rpc_url
+                .replacen("https://", "wss://", 1)
+                .replacen("http://", "ws://", 1)
+        })
+    }
+
+    pub fn idempotency_ttl(&self) -> Duration {
+        Duration::from_secs(self.idempotency_ttl_secs)
+    }
+
+    /// Configuration of every served cluster, the default one first.
+    pub fn cluster_configs(&self) -> Vec<Config> {
+        let mut configs = vec![self.clone()];
+        let mut names: Vec<_> = self.clusters.keys().collect();
+        names.sort();
+        for name in names {
+            configs.push(self.for_cluster(name, &self.clusters[name]));
+        }
+        configs
+    }
+
+    fn for_cluster(&self, name: &str, cluster: &ClusterConfig) -> Config {
+        let mut config = self.clone();
+        config.cluster = name.to_string();
+        config.clusters.clear();
+        config.solana_rpc_url = cluster.solana_rpc_url.clone();
+        config.solana_ws_url = cluster.solana_ws_url.clone();
+        config.solana_rpc_endpoints = cluster.solana_rpc_endpoints.clone();
+        config.database_url = cluster.database_url.clone();
+        config.database_read_urls = cluster.database_read_urls.clone();
+        config.cache.key_prefix = Some(format!("solana_gateway:{}", name));
+        // Downstream consumers only expect events from the default cluster,
+        // and the configured block engine only serves one cluster.
+        config.kafka.enabled = false;
+        config.jito.enabled = false;
+        config
+    }
+
+    pub fn rpc_endpoints(&self) -> Vec<RpcEndpointConfig> {
+        if self.solana_rpc_endpoints.is_empty() {
+            vec![RpcEndpointConfig {
+                url: self.solana_rpc_url.clone(),
+                weight: default_endpoint_weight(),
+            }]
+        } else {
+            self.solana_rpc_endpoints.clone()
+        }
+    }
+}
+
+fn layered(file: &str, secrets: &HashMap<String, String>, overrides: &[String]) -> Result<config::Config> {
+    let mut builder = config::Config::builder()
+        .add_source(config::File::with_name(file).required(false))
+        .add_source(config::Environment::default().separator("__"));
+    for (key, value) in secrets {
+        builder = builder.set_override(key.as_str(), value.as_str())?;
+    }
+    for entry in overrides {
+        let (key, value) = entry
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Override {} is not KEY=VALUE", entry))?;
+        builder = builder.set_override(key.trim(), value)?;
+    }
+    Ok(builder.build()?)
+}
+
+/// Required settings absent from every layer, including those of each
+/// additional cluster.
+fn missing_settings(settings: &config::Config) -> Vec<String> {
+    let mut missing: Vec<String> = REQUIRED_SETTINGS
+        .iter()
+        .filter(|key| settings.get_string(key).is_err())
+        .map(|key| format!("{} is required", key))
+        .collect();
+    let mut clusters: Vec<String> = settings
+        .get_table("clusters")
+        .map(|clusters| clusters.into_keys().collect())
+        .unwrap_or_default();
+    clusters.sort();
+    for name in clusters {
+        for key in ["database_url", "solana_rpc_url"] {
+            let key = format!("clusters.{}.{}", name, key);
+            if settings.get_string(&key).is_err() {
+                missing.push(format!("{} is required", key));
+            }
+        }
+    }
+    missing
+}
+
+fn check_url(check: &mut impl FnMut(bool, String), name: &str, value: &str, schemes: &[&str]) {
+    match reqwest::Url::parse(value) {
+        Ok(url) => check(
+            schemes.contains(&url.scheme()),
+            format!("{} must be a {} URL", name, schemes.join(" or ")),
+        ),
+        Err(e) => check(false, format!("{} is not a valid URL: {}", name, e)),
+    }
+}
+
+fn is_pubkey(value: &str) -> bool {
+    solana_sdk::pubkey::Pubkey::from_str(value).is_ok()
+}
+
+fn is_secret(key: &str) -> bool {
+    const SECRET_KEYS: [&str; 5] = ["token", "master_key", "salt", "keypair", "api_key"];
+    SECRET_KEYS.contains(&key) || key.ends_with("_token") || key.ends_with("secret") || key.contains("password")
+}
+
+fn redact(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(fields) => {
+            for (key, value) in fields.iter_mut() {
+                if is_secret(key) && !value.is_null() {
+                    *value = serde_json::Value::String(REDACTED.to_string());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact),
+        serde_json::Value::String(text) => {
+            // RPC providers commonly put API keys in the query string.
+            if let Ok(mut url) = reqwest::Url::parse(text) {
+                if url.password().is_some() {
+                    let _ = url.set_password(Some(REDACTED));
+                }
+                if url.query().is_some() {
+                    url.set_query(Some(REDACTED));
+                }
+                *text = url.to_string();
+            }
+        }
+        _ => {}
+    }
+}
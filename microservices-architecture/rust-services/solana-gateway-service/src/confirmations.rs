@@ -0,0 +1,111 @@
+use crate::config::ConfirmationConfig;
+use crate::database::{Database, UnsettledTransaction};
+use crate::events::EventPublisher;
+use crate::solana_client::SolanaClient;
+use anyhow::Result;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::{TransactionConfirmationStatus, TransactionStatus};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+// Unsettled transactions checked per poll.
+const MAX_TRACKED: i64 = 1_000;
+
+/// Polls signature statuses for submitted transactions and moves their
+/// stored status through pending → confirmed → finalized, or to failed or
+/// expired.
+pub fn spawn_tracker(
+    client: Arc<SolanaClient>,
+    database: Arc<Database>,
+    events: Arc<EventPublisher>,
+    config: ConfirmationConfig,
+) {
+    if !config.enabled {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_millis(config.poll_interval_ms));
+        loop {
+            interval.tick().await;
+            if let Err(e) = poll(&client, &database, &events, &config).await {
+                warn!("Transaction confirmation tracking failed: {}", e);
+            }
+        }
+    });
+}
+
+async fn poll(
+    client: &SolanaClient,
+    database: &Database,
+    events: &EventPublisher,
+    config: &ConfirmationConfig,
+) -> Result<()> {
+    let tracked: Vec<(UnsettledTransaction, Signature)> = database
+        .unsettled_transactions(MAX_TRACKED)
+        .await?
+        .into_iter()
+        .filter_map(|tx| {
+            let signature = Signature::from_str(&tx.signature).ok()?;
+            Some((tx, signature))
+        })
+        .collect();
+    if tracked.is_empty() {
+        return Ok(());
+    }
+
+    let signatures: Vec<Signature> = tracked.iter().map(|(_, signature)| *signature).collect();
+    let statuses = client.get_signature_statuses(&signatures).await?;
+    let expires_before = chrono::Utc::now() - chrono::Duration::seconds(config.expiry_secs);
+
+    for ((tx, _), status) in tracked.iter().zip(statuses) {
+        let (next, slot) = match &status {
+            Some(status) => (status_name(status), Some(status.slot)),
+            None if tx.created_at < expires_before => ("expired", None),
+            None => continue,
+        };
+        if next == tx.status {
+            continue;
+        }
+
+        debug!("Transaction {} is now {}", tx.signature, next);
+        database.update_transaction_status(&tx.signature, next, slot).await?;
+        events.transaction_status(&tx.signature, next, slot);
+
+        record_settled(client, database, &tx.signature, next).await?;
+    }
+    Ok(())
+}
+
+/// Settled transactions get their fee and block time filled in once.
+pub(crate) async fn record_settled(
+    client: &SolanaClient,
+    database: &Database,
+    signature: &str,
+    status: &str,
+) -> Result<()> {
+    if !matches!(status, "finalized" | "failed") {
+        return Ok(());
+    }
+    match client.get_transaction(signature).await {
+        Ok(mut info) => {
+            info.status = status.to_string();
+            database.record_transaction(&info.to_record(None)).await?;
+        }
+        Err(e) => warn!("Failed to fetch settled transaction {}: {}", signature, e),
+    }
+    Ok(())
+}
+
+pub(crate) fn status_name(status: &TransactionStatus) -> &'static str {
+    if status.err.is_some() {
+        return "failed";
+    }
+    match status.confirmation_status {
+        Some(TransactionConfirmationStatus::Finalized) => "finalized",
+        Some(TransactionConfirmationStatus::Confirmed) => "confirmed",
+        _ => "pending",
+    }
+}
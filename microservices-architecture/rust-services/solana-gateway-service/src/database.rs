@@ -0,0 +1,2203 @@
+use crate::api_keys::{ApiKey, ApiKeyRateLimit, ApiKeyUsage, MintVolume, NewApiKey, PendingUsage, UsagePeriod};
+use crate::approvals::{self, Approval};
+use crate::audit::{AuditEntry, AuditQuery, NewAuditEntry};
+use crate::balance_history::{BucketedBalance, SnapshotBalance};
+use crate::client_references::ClientReference;
+use crate::compliance::{ComplianceEntry, NewComplianceEntry};
+use crate::config::DatabasePoolConfig;
+use crate::dca::{self, DcaExecution, DcaRequest, DcaSchedule};
+use crate::error::ApiError;
+use crate::metrics::Metrics;
+use crate::pagination::{Pagination, SortOrder};
+use crate::pools::candles::{Candle, CandleInterval, PoolSample};
+use crate::pools::volume::{HourlyVolume, PoolVolume};
+use crate::pools::{ClmmState, Dex, Pool, PoolFilter};
+use crate::swap::{FeePeriod, FeeSummary, SwapQuote};
+use crate::token_registry::TokenMetadataRow;
+use crate::wallets::{EncryptedKey, Wallet};
+use crate::watchlist::{AccountChange, Authorities, AuthorityChange, NewAuthorityChange, ObservedState, WatchedAccount};
+use crate::webhooks::{self, PendingDelivery};
+use crate::TransactionRequest;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::{
+    migrate::Migrator,
+    postgres::{PgConnectOptions, PgPoolOptions},
+    PgConnection, PgPool,
+};
+use std::collections::HashMap;
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// Embedded from `migrations/`; applied in order and recorded in
+// `_sqlx_migrations`.
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+const DCA_SCHEDULE_COLUMNS: &str = "id, input_mint, output_mint, amount_per_execution, interval_secs, \
+    total_budget, spent, slippage_bps, status, fills, next_execution_at, created_at, updated_at";
+
+const APPROVAL_COLUMNS: &str = "id, request, requested_by, backend, status, approved_by, signature, squads_proposal, \
+    error, expires_at, created_at, updated_at";
+
+// Every `api_keys` column but the hash, which is never read back.
+const API_KEY_COLUMNS: &str = "id, name, scopes, read_per_minute, trading_per_minute, request_count, \
+    created_at, last_used_at, revoked_at";
+
+pub struct Database {
+    pool: PgPool,
+    /// Read replicas, taken in turn by `read`.
+    replicas: Vec<Replica>,
+    next_replica: AtomicUsize,
+    statement_cache_capacity: usize,
+}
+
+struct Replica {
+    pool: PgPool,
+    /// Cleared when a query can't reach the replica, and set again once
+    /// `spawn_replica_checks` can.
+    healthy: AtomicBool,
+}
+
+#[derive(Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct TransactionRecord {
+    pub signature: String,
+    /// Null until the transaction is confirmed.
+    pub slot: Option<i64>,
+    pub status: String,
+    pub accounts: Vec<String>,
+    pub amount: Option<i64>,
+    pub fee: Option<i64>,
+    pub block_time: Option<DateTime<Utc>>,
+    pub memo: Option<String>,
+    /// The transaction resent after this one expired unlanded.
+    pub replaced_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A submitted transaction that hasn't reached a final status yet.
+#[derive(sqlx::FromRow)]
+pub struct UnsettledTransaction {
+    pub signature: String,
+    pub status: String,
+    pub slot: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+pub enum IdempotencyClaim {
+    /// The key was unused (or expired) and now belongs to this request.
+    Claimed,
+    Completed(serde_json::Value),
+    InProgress,
+    PayloadMismatch,
+}
+
+#[derive(sqlx::FromRow)]
+struct PoolRow {
+    address: String,
+    dex: String,
+    token_a: String,
+    token_b: String,
+    vault_a: String,
+    vault_b: String,
+    reserve_a: String,
+    reserve_b: String,
+    fee_ppm: i32,
+    liquidity: Option<String>,
+    sqrt_price_x64: Option<String>,
+    tick_current: Option<i32>,
+    tick_spacing: Option<i32>,
+    #[sqlx(default)]
+    sort_key: String,
+}
+
+impl TryFrom<PoolRow> for Pool {
+    type Error = anyhow::Error;
+
+    fn try_from(row: PoolRow) -> Result<Self> {
+        let clmm = match (row.liquidity, row.sqrt_price_x64, row.tick_current, row.tick_spacing) {
+            (Some(liquidity), Some(sqrt_price), Some(tick_current), Some(tick_spacing)) => Some(ClmmState {
+                liquidity: liquidity.parse()?,
+                sqrt_price_x64: sqrt_price.parse()?,
+                tick_current,
+                tick_spacing: tick_spacing as u16,
+                amm_config: None,
+            }),
+            _ => None,
+        };
+
+        Ok(Pool {
+            id: row.address,
+            dex: row.dex.parse()?,
+            token_a: row.token_a,
+            token_b: row.token_b,
+            vault_a: row.vault_a,
+            vault_b: row.vault_b,
+            reserve_a: row.reserve_a.parse()?,
+            reserve_b: row.reserve_b.parse()?,
+            fee_ppm: row.fee_ppm as u32,
+            clmm,
+        })
+    }
+}
+
+impl Database {
+    /// Connects to the primary. Replicas are connected lazily and only take
+    /// reads once `spawn_replica_checks` has reached them.
+    pub async fn new(database_url: &str, read_urls: &[String], config: &DatabasePoolConfig) -> Result<Self> {
+        let options = PgConnectOptions::from_str(database_url)?
+            .statement_cache_capacity(config.statement_cache_capacity);
+        let pool = pool_options(config).connect_with(options).await?;
+        let replicas = read_urls
+            .iter()
+            .map(|url| {
+                let options =
+                    PgConnectOptions::from_str(url)?.statement_cache_capacity(config.statement_cache_capacity);
+                Ok(Replica {
+                    pool: pool_options(config).connect_lazy_with(options),
+                    healthy: AtomicBool::new(false),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            pool,
+            replicas,
+            next_replica: AtomicUsize::new(0),
+            statement_cache_capacity: config.statement_cache_capacity,
+        })
+    }
+
+    /// Opens new connections with `database_url`, e.g. after its password
+    /// was rotated. Open connections are kept until they are retired.
+    pub fn set_url(&self, database_url: &str) -> Result<()> {
+        let options =
+            PgConnectOptions::from_str(database_url)?.statement_cache_capacity(self.statement_cache_capacity);
+        self.pool.set_connect_options(options);
+        Ok(())
+    }
+
+    /// Applies pending migrations. Safe to run from several replicas at once;
+    /// sqlx serializes runners with an advisory lock.
+    pub async fn migrate(&self) -> Result<()> {
+        MIGRATOR.run(&self.pool).await?;
+        Ok(())
+    }
+
+    pub async fn ping(&self) -> Result<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// Runs a read-only query on the next healthy replica, or on the primary
+    /// when there is none. Replicas lag the primary, so reads that must see
+    /// the caller's own writes don't go through here. A replica that can't
+    /// be reached is taken out of rotation and the query retried on the
+    /// primary.
+    async fn read<T, F, Fut>(&self, query: F) -> Result<T>
+    where
+        F: Fn(PgPool) -> Fut,
+        Fut: Future<Output = Result<T, sqlx::Error>>,
+    {
+        if let Some(replica) = self.healthy_replica() {
+            match query(replica.pool.clone()).await {
+                Ok(value) => return Ok(value),
+                Err(e) if is_unavailable(&e) => {
+                    warn!("Database replica unavailable, reading from the primary: {}", e);
+                    replica.healthy.store(false, Ordering::Relaxed);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(query(self.pool.clone()).await?)
+    }
+
+    fn healthy_replica(&self) -> Option<&Replica> {
+        let count = self.replicas.len();
+        let start = self.next_replica.fetch_add(1, Ordering::Relaxed);
+        (0..count)
+            .map(|i| &self.replicas[(start + i) % count])
+            .find(|replica| replica.healthy.load(Ordering::Relaxed))
+    }
+
+    /// Inserts a transaction or refreshes the stored copy. Fields that are
+    /// unknown in the new record keep their previously stored values.
+    /// `solana_transactions` is partitioned by `created_at`, which leaves no
+    /// unique index on the signature alone, so concurrent writers of one
+    /// signature are serialized with an advisory lock instead.
+    pub async fn record_transaction(&self, record: &TransactionRecord) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+            .bind(&record.signature)
+            .execute(&mut *tx)
+            .await?;
+        let updated = sqlx::query(
+            r#"
+            UPDATE solana_transactions SET
+                slot = GREATEST(slot, $2),
+                status = $3,
+                accounts = CASE WHEN cardinality($4::TEXT[]) > 0 THEN $4 ELSE accounts END,
+                amount = COALESCE($5, amount),
+                fee = COALESCE($6, fee),
+                block_time = COALESCE($7, block_time),
+                memo = COALESCE($8, memo),
+                updated_at = NOW()
+            WHERE signature = $1
+            "#,
+        )
+        .bind(&record.signature)
+        .bind(record.slot)
+        .bind(&record.status)
+        .bind(&record.accounts)
+        .bind(record.amount)
+        .bind(record.fee)
+        .bind(record.block_time)
+        .bind(&record.memo)
+        .execute(&mut *tx)
+        .await?;
+        if updated.rows_affected() == 0 {
+            sqlx::query(
+                r#"
+                INSERT INTO solana_transactions (signature, slot, status, accounts, amount, fee, block_time, memo)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                "#,
+            )
+            .bind(&record.signature)
+            .bind(record.slot)
+            .bind(&record.status)
+            .bind(&record.accounts)
+            .bind(record.amount)
+            .bind(record.fee)
+            .bind(record.block_time)
+            .bind(&record.memo)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Names of the monthly `solana_transactions` partitions, oldest first.
+    pub async fn transaction_partitions(&self) -> Result<Vec<String>> {
+        let names = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT child.relname::TEXT
+            FROM pg_inherits
+            JOIN pg_class child ON child.oid = pg_inherits.inhrelid
+            WHERE pg_inherits.inhparent = 'solana_transactions'::regclass
+            ORDER BY child.relname
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(names)
+    }
+
+    /// Creates partition `name` for `created_at` in [`from`, `to`) unless it
+    /// exists.
+    pub async fn create_transaction_partition(&self, name: &str, from: NaiveDate, to: NaiveDate) -> Result<()> {
+        // Names and bounds are generated by the retention task, never taken
+        // from a request, so formatting them into the DDL is safe.
+        sqlx::query(&format!(
+            "CREATE TABLE IF NOT EXISTS {} PARTITION OF solana_transactions FOR VALUES FROM ('{}') TO ('{}')",
+            name, from, to
+        ))
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// One page of a partition's transactions in (created_at, signature)
+    /// order, resuming after `after`.
+    pub async fn transaction_partition_page(
+        &self,
+        partition: &str,
+        after: Option<(DateTime<Utc>, String)>,
+        limit: i64,
+    ) -> Result<Vec<TransactionRecord>> {
+        let (after_created_at, after_signature) = after.unzip();
+        let records = sqlx::query_as::<_, TransactionRecord>(&format!(
+            r#"
+            SELECT signature, slot, status, accounts, amount, fee, block_time, memo, replaced_by, created_at
+            FROM {}
+            WHERE $1::TIMESTAMPTZ IS NULL OR (created_at, signature) > ($1, $2)
+            ORDER BY created_at, signature
+            LIMIT $3
+            "#,
+            partition
+        ))
+        .bind(after_created_at)
+        .bind(after_signature)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(records)
+    }
+
+    pub async fn drop_transaction_partition(&self, partition: &str) -> Result<()> {
+        sqlx::query(&format!("DROP TABLE IF EXISTS {}", partition))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_transaction(&self, signature: &str) -> Result<Option<TransactionRecord>> {
+        let record = sqlx::query_as::<_, TransactionRecord>(
+            r#"
+            SELECT signature, slot, status, accounts, amount, fee, block_time, memo, replaced_by, created_at
+            FROM solana_transactions
+            WHERE signature = $1
+            "#,
+        )
+        .bind(signature)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(record)
+    }
+
+    /// Pending and confirmed transactions, oldest first.
+    pub async fn unsettled_transactions(&self, limit: i64) -> Result<Vec<UnsettledTransaction>> {
+        let transactions = sqlx::query_as::<_, UnsettledTransaction>(
+            r#"
+            SELECT signature, status, slot, created_at
+            FROM solana_transactions
+            WHERE status IN ('pending', 'confirmed')
+            ORDER BY created_at
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(transactions)
+    }
+
+    /// Pending and confirmed transactions submitted before `created_before`,
+    /// oldest first.
+    pub async fn stale_transactions(
+        &self,
+        created_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<UnsettledTransaction>> {
+        let transactions = sqlx::query_as::<_, UnsettledTransaction>(
+            r#"
+            SELECT signature, status, slot, created_at
+            FROM solana_transactions
+            WHERE status IN ('pending', 'confirmed') AND created_at < $1
+            ORDER BY created_at
+            LIMIT $2
+            "#,
+        )
+        .bind(created_before)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(transactions)
+    }
+
+    pub async fn update_transaction_status(&self, signature: &str, status: &str, slot: Option<u64>) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE solana_transactions
+            SET status = $2, slot = COALESCE($3, slot), updated_at = NOW()
+            WHERE signature = $1
+            "#,
+        )
+        .bind(signature)
+        .bind(status)
+        .bind(slot.map(|s| s as i64))
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns transactions touching `address`, newest first, with those not
+    /// yet confirmed, and so without a slot, ahead of the rest. The cursor is
+    /// the signature of the last row from the previous page.
+    pub async fn get_account_transactions(
+        &self,
+        address: &str,
+        status: Option<&str>,
+        page: &Pagination,
+    ) -> Result<Vec<TransactionRecord>> {
+        let records = self
+            .read(|pool| async move {
+                sqlx::query_as::<_, TransactionRecord>(
+                    r#"
+                    SELECT signature, slot, status, accounts, amount, fee, block_time, memo, replaced_by, created_at
+                    FROM solana_transactions
+                    WHERE $1 = ANY(accounts)
+                      AND ($2::TEXT IS NULL OR status = $2)
+                      AND ($3::TEXT IS NULL OR (COALESCE(slot, 9223372036854775807), signature) <
+                          (SELECT COALESCE(slot, 9223372036854775807), signature
+                           FROM solana_transactions WHERE signature = $3))
+                    ORDER BY slot DESC, signature DESC
+                    LIMIT $4 OFFSET $5
+                    "#,
+                )
+                .bind(address)
+                .bind(status)
+                .bind(page.cursor.as_deref())
+                .bind(page.limit)
+                .bind(page.offset.unwrap_or(0))
+                .fetch_all(&pool)
+                .await
+            })
+            .await?;
+
+        Ok(records)
+    }
+
+    pub async fn upsert_pools(&self, pools: &[Pool]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for pool in pools {
+            let clmm = pool.clmm.as_ref();
+            sqlx::query(
+                r#"
+                INSERT INTO solana_pools (address, dex, token_a, token_b, vault_a, vault_b,
+                    reserve_a, reserve_b, fee_ppm, liquidity, sqrt_price_x64, tick_current, tick_spacing)
+                VALUES ($1, $2, $3, $4, $5, $6, $7::NUMERIC, $8::NUMERIC, $9,
+                    $10::NUMERIC, $11::NUMERIC, $12, $13)
+                ON CONFLICT (address) DO UPDATE SET
+                    reserve_a = EXCLUDED.reserve_a,
+                    reserve_b = EXCLUDED.reserve_b,
+                    fee_ppm = EXCLUDED.fee_ppm,
+                    liquidity = EXCLUDED.liquidity,
+                    sqrt_price_x64 = EXCLUDED.sqrt_price_x64,
+                    tick_current = EXCLUDED.tick_current,
+                    tick_spacing = EXCLUDED.tick_spacing,
+                    updated_at = NOW()
+                "#,
+            )
+            .bind(&pool.id)
+            .bind(pool.dex.as_str())
+            .bind(&pool.token_a)
+            .bind(&pool.token_b)
+            .bind(&pool.vault_a)
+            .bind(&pool.vault_b)
+            .bind(pool.reserve_a.to_string())
+            .bind(pool.reserve_b.to_string())
+            .bind(pool.fee_ppm as i32)
+            .bind(clmm.map(|c| c.liquidity.to_string()))
+            .bind(clmm.map(|c| c.sqrt_price_x64.to_string()))
+            .bind(clmm.map(|c| c.tick_current))
+            .bind(clmm.map(|c| c.tick_spacing as i32))
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Stores a pool seen for the first time. Returns when it was stored, or
+    /// None when it already was.
+    pub async fn insert_new_pool(&self, pool: &Pool) -> Result<Option<DateTime<Utc>>> {
+        let clmm = pool.clmm.as_ref();
+        let created_at = sqlx::query_scalar(
+            r#"
+            INSERT INTO solana_pools (address, dex, token_a, token_b, vault_a, vault_b,
+                reserve_a, reserve_b, fee_ppm, liquidity, sqrt_price_x64, tick_current, tick_spacing)
+            VALUES ($1, $2, $3, $4, $5, $6, $7::NUMERIC, $8::NUMERIC, $9,
+                $10::NUMERIC, $11::NUMERIC, $12, $13)
+            ON CONFLICT (address) DO NOTHING
+            RETURNING created_at
+            "#,
+        )
+        .bind(&pool.id)
+        .bind(pool.dex.as_str())
+        .bind(&pool.token_a)
+        .bind(&pool.token_b)
+        .bind(&pool.vault_a)
+        .bind(&pool.vault_b)
+        .bind(pool.reserve_a.to_string())
+        .bind(pool.reserve_b.to_string())
+        .bind(pool.fee_ppm as i32)
+        .bind(clmm.map(|c| c.liquidity.to_string()))
+        .bind(clmm.map(|c| c.sqrt_price_x64.to_string()))
+        .bind(clmm.map(|c| c.tick_current))
+        .bind(clmm.map(|c| c.tick_spacing as i32))
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(created_at)
+    }
+
+    /// Pools matching `filter`, ordered by `sort_column` with the address as
+    /// tie-breaker. Each pool comes with its sort value as text, from which
+    /// the caller builds keyset cursors; `cursor` is (sort value, address) of
+    /// the last row of the previous page.
+    /// Stored pools trading `mint_x` against `mint_y`, on any DEX.
+    pub async fn list_pair_pool_addresses(&self, mint_x: &str, mint_y: &str, limit: i64) -> Result<Vec<String>> {
+        let addresses = sqlx::query_scalar(
+            r#"
+            SELECT address FROM solana_pools
+            WHERE (token_a = $1 AND token_b = $2) OR (token_a = $2 AND token_b = $1)
+            ORDER BY updated_at DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(mint_x)
+        .bind(mint_y)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(addresses)
+    }
+
+    /// Pool pairs joining `input_mint` to `output_mint` through one other
+    /// mint, as (first pool, intermediate mint, second pool).
+    pub async fn list_two_hop_pool_addresses(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+        limit: i64,
+    ) -> Result<Vec<(String, String, String)>> {
+        let pairs = sqlx::query_as(
+            r#"
+            WITH first_leg AS (
+                SELECT address, updated_at,
+                    CASE WHEN token_a = $1 THEN token_b ELSE token_a END AS via
+                FROM solana_pools WHERE token_a = $1 OR token_b = $1
+            ), second_leg AS (
+                SELECT address, updated_at,
+                    CASE WHEN token_a = $2 THEN token_b ELSE token_a END AS via
+                FROM solana_pools WHERE token_a = $2 OR token_b = $2
+            )
+            SELECT first_leg.address, first_leg.via, second_leg.address
+            FROM first_leg JOIN second_leg ON first_leg.via = second_leg.via
+            WHERE first_leg.via <> $1 AND first_leg.via <> $2
+            ORDER BY LEAST(first_leg.updated_at, second_leg.updated_at) DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(input_mint)
+        .bind(output_mint)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(pairs)
+    }
+
+    pub async fn list_pool_addresses(&self, limit: i64) -> Result<Vec<String>> {
+        let addresses = sqlx::query_scalar("SELECT address FROM solana_pools ORDER BY updated_at DESC LIMIT $1")
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(addresses)
+    }
+
+    /// Folds each sample into the candle of every interval containing `at`.
+    pub async fn record_pool_samples(&self, samples: &[PoolSample], at: DateTime<Utc>) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for sample in samples {
+            for interval in CandleInterval::ALL {
+                sqlx::query(
+                    r#"
+                    INSERT INTO pool_candles (pool_address, interval, bucket_start,
+                        open, high, low, close, volume_a, volume_b)
+                    VALUES ($1, $2, $3, $4, $4, $4, $4, $5, $6)
+                    ON CONFLICT (pool_address, interval, bucket_start) DO UPDATE SET
+                        high = GREATEST(pool_candles.high, EXCLUDED.close),
+                        low = LEAST(pool_candles.low, EXCLUDED.close),
+                        close = EXCLUDED.close,
+                        volume_a = pool_candles.volume_a + EXCLUDED.volume_a,
+                        volume_b = pool_candles.volume_b + EXCLUDED.volume_b,
+                        samples = pool_candles.samples + 1
+                    "#,
+                )
+                .bind(&sample.pool_address)
+                .bind(interval.as_str())
+                .bind(interval.bucket_start(at))
+                .bind(sample.price)
+                .bind(sample.volume_a)
+                .bind(sample.volume_b)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Last indexed signature per pool.
+    pub async fn get_pool_swap_cursors(&self) -> Result<HashMap<String, String>> {
+        let cursors: Vec<(String, String)> =
+            sqlx::query_as("SELECT pool_address, last_signature FROM pool_swap_cursors")
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(cursors.into_iter().collect())
+    }
+
+    /// Adds `hours` to the pool's hourly buckets and moves its cursor to
+    /// `last_signature` in one transaction, so a retried round can't count
+    /// swaps twice.
+    pub async fn record_pool_volume(
+        &self,
+        pool_address: &str,
+        hours: &[HourlyVolume],
+        last_signature: &str,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for hour in hours {
+            sqlx::query(
+                r#"
+                INSERT INTO pool_volume_hourly (pool_address, hour_start, volume_a, volume_b, fees_a, fees_b, swaps)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT (pool_address, hour_start) DO UPDATE SET
+                    volume_a = pool_volume_hourly.volume_a + EXCLUDED.volume_a,
+                    volume_b = pool_volume_hourly.volume_b + EXCLUDED.volume_b,
+                    fees_a = pool_volume_hourly.fees_a + EXCLUDED.fees_a,
+                    fees_b = pool_volume_hourly.fees_b + EXCLUDED.fees_b,
+                    swaps = pool_volume_hourly.swaps + EXCLUDED.swaps
+                "#,
+            )
+            .bind(pool_address)
+            .bind(hour.hour_start)
+            .bind(hour.volume_a)
+            .bind(hour.volume_b)
+            .bind(hour.fees_a)
+            .bind(hour.fees_b)
+            .bind(hour.swaps)
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO pool_swap_cursors (pool_address, last_signature)
+            VALUES ($1, $2)
+            ON CONFLICT (pool_address) DO UPDATE SET last_signature = EXCLUDED.last_signature, updated_at = NOW()
+            "#,
+        )
+        .bind(pool_address)
+        .bind(last_signature)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Totals of the pool's buckets starting within the last 24 hours.
+    pub async fn get_pool_volume_24h(&self, pool_address: &str) -> Result<PoolVolume> {
+        let volume = self
+            .read(|pool| async move {
+                sqlx::query_as::<_, PoolVolume>(
+                    r#"
+                    SELECT COALESCE(SUM(volume_a), 0) AS volume_a, COALESCE(SUM(volume_b), 0) AS volume_b,
+                           COALESCE(SUM(fees_a), 0) AS fees_a, COALESCE(SUM(fees_b), 0) AS fees_b,
+                           COALESCE(SUM(swaps), 0)::BIGINT AS swaps
+                    FROM pool_volume_hourly
+                    WHERE pool_address = $1 AND hour_start > NOW() - INTERVAL '24 hours'
+                    "#,
+                )
+                .bind(pool_address)
+                .fetch_one(&pool)
+                .await
+            })
+            .await?;
+        Ok(volume)
+    }
+
+    pub async fn prune_pool_volume(&self, retention_days: i64) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM pool_volume_hourly WHERE hour_start < NOW() - make_interval(days => $1)")
+            .bind(retention_days as i32)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Most recent candles in `[from, to)`, oldest first.
+    pub async fn get_candles(
+        &self,
+        pool_address: &str,
+        interval: CandleInterval,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        limit: i64,
+    ) -> Result<Vec<Candle>> {
+        let mut candles = self
+            .read(|pool| async move {
+                sqlx::query_as::<_, Candle>(
+                    r#"
+                    SELECT bucket_start, open, high, low, close, volume_a, volume_b
+                    FROM pool_candles
+                    WHERE pool_address = $1 AND interval = $2
+                      AND ($3::TIMESTAMPTZ IS NULL OR bucket_start >= $3)
+                      AND ($4::TIMESTAMPTZ IS NULL OR bucket_start < $4)
+                    ORDER BY bucket_start DESC
+                    LIMIT $5
+                    "#,
+                )
+                .bind(pool_address)
+                .bind(interval.as_str())
+                .bind(from)
+                .bind(to)
+                .bind(limit)
+                .fetch_all(&pool)
+                .await
+            })
+            .await?;
+        candles.reverse();
+        Ok(candles)
+    }
+
+    /// Marks an expired transaction as resent as `replacement`.
+    pub async fn replace_transaction(&self, expired: &str, replacement: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE solana_transactions
+            SET status = 'expired', replaced_by = $2, updated_at = NOW()
+            WHERE signature = $1
+            "#,
+        )
+        .bind(expired)
+        .bind(replacement)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn record_platform_fee(&self, signature: &str, quote: &SwapQuote) -> Result<()> {
+        sqlx::query("INSERT INTO platform_fees (signature, pool_id, mint, amount) VALUES ($1, $2, $3, $4)")
+            .bind(signature)
+            .bind(&quote.pool_id)
+            .bind(&quote.input_mint)
+            .bind(quote.platform_fee as i64)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Inserts or replaces token list entries, in batches.
+    pub async fn upsert_token_metadata(&self, tokens: &[TokenMetadataRow]) -> Result<()> {
+        for chunk in tokens.chunks(1_000) {
+            let mints: Vec<&str> = chunk.iter().map(|row| row.mint.as_str()).collect();
+            let symbols: Vec<&str> = chunk.iter().map(|row| row.metadata.symbol.as_str()).collect();
+            let names: Vec<&str> = chunk.iter().map(|row| row.metadata.name.as_str()).collect();
+            let logo_uris: Vec<Option<&str>> = chunk.iter().map(|row| row.metadata.logo_uri.as_deref()).collect();
+            let coingecko_ids: Vec<Option<&str>> =
+                chunk.iter().map(|row| row.metadata.coingecko_id.as_deref()).collect();
+            sqlx::query(
+                r#"
+                INSERT INTO token_metadata (mint, symbol, name, logo_uri, coingecko_id)
+                SELECT DISTINCT ON (mint) *
+                FROM UNNEST($1::TEXT[], $2::TEXT[], $3::TEXT[], $4::TEXT[], $5::TEXT[])
+                    AS t (mint, symbol, name, logo_uri, coingecko_id)
+                ON CONFLICT (mint) DO UPDATE SET
+                    symbol = EXCLUDED.symbol,
+                    name = EXCLUDED.name,
+                    logo_uri = EXCLUDED.logo_uri,
+                    coingecko_id = EXCLUDED.coingecko_id,
+                    updated_at = NOW()
+                "#,
+            )
+            .bind(&mints)
+            .bind(&symbols)
+            .bind(&names)
+            .bind(&logo_uris)
+            .bind(&coingecko_ids)
+            .execute(&self.pool)
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn list_token_metadata(&self) -> Result<Vec<TokenMetadataRow>> {
+        let tokens = sqlx::query_as::<_, TokenMetadataRow>(
+            "SELECT mint, symbol, name, logo_uri, coingecko_id FROM token_metadata",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(tokens)
+    }
+
+    /// Collected platform fees per token and period, newest period first.
+    pub async fn platform_fee_summary(
+        &self,
+        period: FeePeriod,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<FeeSummary>> {
+        let summary = self
+            .read(|pool| async move {
+                sqlx::query_as::<_, FeeSummary>(
+                    r#"
+                    SELECT date_trunc($1, created_at) AS period_start, mint,
+                           COUNT(*) AS swaps, SUM(amount)::BIGINT AS amount
+                    FROM platform_fees
+                    WHERE ($2::TIMESTAMPTZ IS NULL OR created_at >= $2)
+                      AND ($3::TIMESTAMPTZ IS NULL OR created_at < $3)
+                    GROUP BY period_start, mint
+                    ORDER BY period_start DESC, amount DESC
+                    "#,
+                )
+                .bind(period.as_str())
+                .bind(from)
+                .bind(to)
+                .fetch_all(&pool)
+                .await
+            })
+            .await?;
+        Ok(summary)
+    }
+
+    pub async fn list_pools(
+        &self,
+        filter: &PoolFilter,
+        sort_column: &str,
+        order: SortOrder,
+        cursor: Option<(String, String)>,
+        page: &Pagination,
+    ) -> Result<Vec<(Pool, String)>> {
+        let (direction, comparison) = match order {
+            SortOrder::Asc => ("ASC", ">"),
+            SortOrder::Desc => ("DESC", "<"),
+        };
+        let sort_type = match sort_column {
+            "fee_ppm" => "INTEGER",
+            "created_at" | "updated_at" => "TIMESTAMPTZ",
+            _ => "TEXT",
+        };
+        // The column and direction come from a fixed allow-list, never from
+        // the request, so formatting them into the SQL is safe.
+        let sql = format!(
+            r#"
+            SELECT address, dex, token_a, token_b, vault_a, vault_b,
+                reserve_a::TEXT AS reserve_a, reserve_b::TEXT AS reserve_b, fee_ppm,
+                liquidity::TEXT AS liquidity, sqrt_price_x64::TEXT AS sqrt_price_x64,
+                tick_current, tick_spacing, {column}::TEXT AS sort_key
+            FROM solana_pools
+            WHERE ($1::TEXT IS NULL OR dex = $1)
+              AND ($2::TEXT IS NULL OR token_a = $2 OR token_b = $2)
+              AND ($3::TEXT IS NULL OR ({column}, address) {comparison} ($3::{sort_type}, $4))
+            ORDER BY {column} {direction}, address {direction}
+            LIMIT $5 OFFSET $6
+            "#,
+            column = sort_column,
+        );
+        let sql = sql.as_str();
+        let (cursor_key, cursor_address) = cursor.unzip();
+        let (cursor_key, cursor_address) = (cursor_key.as_deref(), cursor_address.as_deref());
+
+        let rows = self
+            .read(|pool| async move {
+                sqlx::query_as::<_, PoolRow>(sql)
+                    .bind(filter.dex.map(Dex::as_str))
+                    .bind(&filter.token)
+                    .bind(cursor_key)
+                    .bind(cursor_address)
+                    .bind(page.limit)
+                    .bind(page.offset.unwrap_or(0))
+                    .fetch_all(&pool)
+                    .await
+            })
+            .await?;
+
+        rows.into_iter()
+            .map(|mut row| {
+                let sort_key = std::mem::take(&mut row.sort_key);
+                Ok((Pool::try_from(row)?, sort_key))
+            })
+            .collect()
+    }
+
+    pub async fn claim_idempotency_key(
+        &self,
+        key: &str,
+        route: &str,
+        request_hash: &str,
+        ttl: Duration,
+    ) -> Result<IdempotencyClaim> {
+        let claimed = sqlx::query(
+            r#"
+            INSERT INTO idempotency_keys (key, route, request_hash, expires_at)
+            VALUES ($1, $2, $3, NOW() + make_interval(secs => $4))
+            ON CONFLICT (key, route) DO UPDATE SET
+                request_hash = EXCLUDED.request_hash,
+                response = NULL,
+                created_at = NOW(),
+                expires_at = EXCLUDED.expires_at
+            WHERE idempotency_keys.expires_at < NOW()
+            "#,
+        )
+        .bind(key)
+        .bind(route)
+        .bind(request_hash)
+        .bind(ttl.as_secs_f64())
+        .execute(&self.pool)
+        .await?
+        .rows_affected()
+            > 0;
+
+        if claimed {
+            return Ok(IdempotencyClaim::Claimed);
+        }
+
+        let (stored_hash, response): (String, Option<serde_json::Value>) = sqlx::query_as(
+            "SELECT request_hash, response FROM idempotency_keys WHERE key = $1 AND route = $2",
+        )
+        .bind(key)
+        .bind(route)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(match response {
+            _ if stored_hash != request_hash => IdempotencyClaim::PayloadMismatch,
+            Some(response) => IdempotencyClaim::Completed(response),
+            None => IdempotencyClaim::InProgress,
+        })
+    }
+
+    pub async fn complete_idempotency_key(
+        &self,
+        key: &str,
+        route: &str,
+        response: &serde_json::Value,
+    ) -> Result<()> {
+        sqlx::query("UPDATE idempotency_keys SET response = $3 WHERE key = $1 AND route = $2")
+            .bind(key)
+            .bind(route)
+            .bind(response)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn release_idempotency_key(&self, key: &str, route: &str) -> Result<()> {
+        sqlx::query("DELETE FROM idempotency_keys WHERE key = $1 AND route = $2 AND response IS NULL")
+            .bind(key)
+            .bind(route)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Records a signed quote as executed. Returns false when it already
+    /// was.
+    pub async fn claim_quote(&self, id: Uuid, expires_at: DateTime<Utc>) -> Result<bool> {
+        let result = sqlx::query("INSERT INTO executed_quotes (id, expires_at) VALUES ($1, $2) ON CONFLICT DO NOTHING")
+            .bind(id)
+            .bind(expires_at)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Forgets executed quotes that have expired, and so can't be executed
+    /// again anyway.
+    pub async fn purge_expired_quotes(&self) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM executed_quotes WHERE expires_at < NOW()")
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn purge_expired_idempotency_keys(&self) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM idempotency_keys WHERE expires_at < NOW()")
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Stores a new wallet. Returns false when the public key is already
+    /// present.
+    pub async fn insert_wallet(&self, wallet: &Wallet, key: &EncryptedKey) -> Result<bool> {
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO wallets (id, label, pubkey, owner, encrypted_key, nonce, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (pubkey) DO NOTHING
+            "#,
+        )
+        .bind(wallet.id)
+        .bind(&wallet.label)
+        .bind(&wallet.pubkey)
+        .bind(&wallet.owner)
+        .bind(&key.ciphertext)
+        .bind(&key.nonce)
+        .bind(wallet.created_at)
+        .execute(&self.pool)
+        .await?
+        .rows_affected()
+            > 0;
+        Ok(inserted)
+    }
+
+    /// Wallets of `owner`, or all of them when None.
+    pub async fn list_wallets(&self, owner: Option<&str>) -> Result<Vec<Wallet>> {
+        let wallets = sqlx::query_as::<_, Wallet>(
+            r#"
+            SELECT id, label, pubkey, owner, created_at FROM wallets
+            WHERE $1::TEXT IS NULL OR owner = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(owner)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(wallets)
+    }
+
+    /// The wallet, unless `owner` is set and it belongs to someone else.
+    pub async fn get_wallet(&self, id: Uuid, owner: Option<&str>) -> Result<Option<Wallet>> {
+        let wallet = sqlx::query_as::<_, Wallet>(
+            r#"
+            SELECT id, label, pubkey, owner, created_at FROM wallets
+            WHERE id = $1 AND ($2::TEXT IS NULL OR owner = $2)
+            "#,
+        )
+        .bind(id)
+        .bind(owner)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(wallet)
+    }
+
+    /// The wallet's key, unless `owner` is set and it belongs to someone
+    /// else.
+    pub async fn get_wallet_key(&self, id: Uuid, owner: Option<&str>) -> Result<Option<EncryptedKey>> {
+        let key = sqlx::query_as::<_, EncryptedKey>(
+            r#"
+            SELECT encrypted_key AS ciphertext, nonce FROM wallets
+            WHERE id = $1 AND ($2::TEXT IS NULL OR owner = $2)
+            "#,
+        )
+        .bind(id)
+        .bind(owner)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(key)
+    }
+
+    pub async fn create_api_key(&self, key: &NewApiKey, key_hash: &str) -> Result<ApiKey> {
+        let api_key = sqlx::query_as::<_, ApiKey>(&format!(
+            r#"
+            INSERT INTO api_keys (id, name, key_hash, scopes, read_per_minute, trading_per_minute)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING {}
+            "#,
+            API_KEY_COLUMNS
+        ))
+        .bind(Uuid::new_v4())
+        .bind(&key.name)
+        .bind(key_hash)
+        .bind(&key.scopes)
+        .bind(key.rate_limit.read_per_minute.map(|quota| quota as i32))
+        .bind(key.rate_limit.trading_per_minute.map(|quota| quota as i32))
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(api_key)
+    }
+
+    /// Every key, revoked ones included, oldest first.
+    pub async fn list_api_keys(&self) -> Result<Vec<ApiKey>> {
+        let api_keys = sqlx::query_as::<_, ApiKey>(&format!(
+            "SELECT {} FROM api_keys ORDER BY created_at",
+            API_KEY_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(api_keys)
+    }
+
+    /// The unrevoked key with this hash, if any.
+    pub async fn get_api_key_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>> {
+        let api_key = sqlx::query_as::<_, ApiKey>(&format!(
+            "SELECT {} FROM api_keys WHERE key_hash = $1 AND revoked_at IS NULL",
+            API_KEY_COLUMNS
+        ))
+        .bind(key_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(api_key)
+    }
+
+    pub async fn set_api_key_scopes(&self, id: Uuid, scopes: &[String]) -> Result<Option<ApiKey>> {
+        let api_key = sqlx::query_as::<_, ApiKey>(&format!(
+            "UPDATE api_keys SET scopes = $2 WHERE id = $1 AND revoked_at IS NULL RETURNING {}",
+            API_KEY_COLUMNS
+        ))
+        .bind(id)
+        .bind(scopes)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(api_key)
+    }
+
+    pub async fn set_api_key_rate_limit(&self, id: Uuid, rate_limit: &ApiKeyRateLimit) -> Result<Option<ApiKey>> {
+        let api_key = sqlx::query_as::<_, ApiKey>(&format!(
+            r#"
+            UPDATE api_keys SET read_per_minute = $2, trading_per_minute = $3
+            WHERE id = $1 AND revoked_at IS NULL
+            RETURNING {}
+            "#,
+            API_KEY_COLUMNS
+        ))
+        .bind(id)
+        .bind(rate_limit.read_per_minute.map(|quota| quota as i32))
+        .bind(rate_limit.trading_per_minute.map(|quota| quota as i32))
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(api_key)
+    }
+
+    /// Returns false if the key doesn't exist or was already revoked.
+    pub async fn revoke_api_key(&self, id: Uuid) -> Result<bool> {
+        let result = sqlx::query("UPDATE api_keys SET revoked_at = NOW() WHERE id = $1 AND revoked_at IS NULL")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn list_revoked_api_key_ids(&self) -> Result<Vec<Uuid>> {
+        let ids = sqlx::query_scalar::<_, Uuid>("SELECT id FROM api_keys WHERE revoked_at IS NOT NULL")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(ids)
+    }
+
+    /// Adds usage counted since the last sync to the key's totals and to
+    /// the current hour.
+    pub async fn record_api_key_usage(&self, id: Uuid, usage: &PendingUsage) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            UPDATE api_keys
+            SET request_count = request_count + $2,
+                last_used_at = GREATEST(last_used_at, $3)
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(usage.requests)
+        .bind(usage.last_used_at)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO api_key_usage (api_key_id, hour, requests, client_errors, server_errors)
+            SELECT id, date_trunc('hour', NOW()), $2, $3, $4 FROM api_keys WHERE id = $1
+            ON CONFLICT (api_key_id, hour) DO UPDATE
+            SET requests = api_key_usage.requests + EXCLUDED.requests,
+                client_errors = api_key_usage.client_errors + EXCLUDED.client_errors,
+                server_errors = api_key_usage.server_errors + EXCLUDED.server_errors
+            "#,
+        )
+        .bind(id)
+        .bind(usage.requests)
+        .bind(usage.client_errors)
+        .bind(usage.server_errors)
+        .execute(&mut *tx)
+        .await?;
+
+        for (mint, (swaps, amount)) in &usage.volume {
+            sqlx::query(
+                r#"
+                INSERT INTO api_key_volume (api_key_id, hour, mint, swaps, amount)
+                SELECT id, date_trunc('hour', NOW()), $2, $3, $4::NUMERIC FROM api_keys WHERE id = $1
+                ON CONFLICT (api_key_id, hour, mint) DO UPDATE
+                SET swaps = api_key_volume.swaps + EXCLUDED.swaps,
+                    amount = api_key_volume.amount + EXCLUDED.amount
+                "#,
+            )
+            .bind(id)
+            .bind(mint)
+            .bind(swaps)
+            .bind(amount.to_string())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// A key's usage per period, newest period first; None when the key
+    /// doesn't exist.
+    pub async fn api_key_usage(
+        &self,
+        id: Uuid,
+        period: FeePeriod,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Option<ApiKeyUsage>> {
+        #[derive(sqlx::FromRow)]
+        struct RequestRow {
+            period_start: DateTime<Utc>,
+            requests: i64,
+            client_errors: i64,
+            server_errors: i64,
+        }
+        #[derive(sqlx::FromRow)]
+        struct VolumeRow {
+            period_start: DateTime<Utc>,
+            #[sqlx(flatten)]
+            volume: MintVolume,
+        }
+
+        let usage = self
+            .read(|pool| async move {
+                let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM api_keys WHERE id = $1)")
+                    .bind(id)
+                    .fetch_one(&pool)
+                    .await?;
+                if !exists {
+                    return Ok(None);
+                }
+                let requests = sqlx::query_as::<_, RequestRow>(
+                    r#"
+                    SELECT date_trunc($2, hour) AS period_start, SUM(requests)::BIGINT AS requests,
+                           SUM(client_errors)::BIGINT AS client_errors, SUM(server_errors)::BIGINT AS server_errors
+                    FROM api_key_usage
+                    WHERE api_key_id = $1
+                      AND ($3::TIMESTAMPTZ IS NULL OR hour >= $3)
+                      AND ($4::TIMESTAMPTZ IS NULL OR hour < $4)
+                    GROUP BY period_start
+                    ORDER BY period_start DESC
+                    "#,
+                )
+                .bind(id)
+                .bind(period.as_str())
+                .bind(from)
+                .bind(to)
+                .fetch_all(&pool)
+                .await?;
+                let volume = sqlx::query_as::<_, VolumeRow>(
+                    r#"
+                    SELECT date_trunc($2, hour) AS period_start, mint,
+                           SUM(swaps)::BIGINT AS swaps, SUM(amount)::TEXT AS amount
+                    FROM api_key_volume
+                    WHERE api_key_id = $1
+                      AND ($3::TIMESTAMPTZ IS NULL OR hour >= $3)
+                      AND ($4::TIMESTAMPTZ IS NULL OR hour < $4)
+                    GROUP BY period_start, mint
+                    ORDER BY period_start DESC, SUM(amount) DESC
+                    "#,
+                )
+                .bind(id)
+                .bind(period.as_str())
+                .bind(from)
+                .bind(to)
+                .fetch_all(&pool)
+                .await?;
+                Ok(Some((requests, volume)))
+            })
+            .await?;
+        let Some((requests, volume)) = usage else {
+            return Ok(None);
+        };
+
+        let mut volume_by_period: HashMap<DateTime<Utc>, Vec<MintVolume>> = HashMap::new();
+        for row in volume {
+            volume_by_period.entry(row.period_start).or_default().push(row.volume);
+        }
+        let periods = requests
+            .into_iter()
+            .map(|row| UsagePeriod {
+                period_start: row.period_start,
+                requests: row.requests,
+                client_errors: row.client_errors,
+                server_errors: row.server_errors,
+                error_rate: match row.requests {
+                    0 => 0.0,
+                    requests => (row.client_errors + row.server_errors) as f64 / requests as f64,
+                },
+                volume: volume_by_period.remove(&row.period_start).unwrap_or_default(),
+            })
+            .collect();
+        Ok(Some(ApiKeyUsage { api_key_id: id, periods }))
+    }
+
+    pub async fn rotate_api_key(&self, id: Uuid, key_hash: &str) -> Result<bool> {
+        let result = sqlx::query("UPDATE api_keys SET key_hash = $2 WHERE id = $1 AND revoked_at IS NULL")
+            .bind(id)
+            .bind(key_hash)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn insert_audit_entry(&self, entry: &NewAuditEntry) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO audit_log (actor, method, route, request_hash, status_code, outcome, signature)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(&entry.actor)
+        .bind(&entry.method)
+        .bind(&entry.route)
+        .bind(&entry.request_hash)
+        .bind(entry.status_code as i32)
+        .bind(entry.outcome())
+        .bind(&entry.signature)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Audit entries matching `query`, newest first. The cursor is the `id`
+    /// of the last entry from the previous page.
+    pub async fn list_audit_entries(&self, query: &AuditQuery, page: &Pagination) -> Result<Vec<AuditEntry>> {
+        let before = page
+            .cursor
+            .as_deref()
+            .map(str::parse::<i64>)
+            .transpose()
+            .map_err(|_| ApiError::BadRequest("Invalid cursor".to_string()))?;
+
+        let entries = self
+            .read(|pool| async move {
+                sqlx::query_as::<_, AuditEntry>(
+                    r#"
+                    SELECT id, actor, method, route, request_hash, status_code, outcome, signature, created_at
+                    FROM audit_log
+                    WHERE ($1::TEXT IS NULL OR actor = $1)
+                      AND ($2::TEXT IS NULL OR route = $2)
+                      AND ($3::TIMESTAMPTZ IS NULL OR created_at >= $3)
+                      AND ($4::TIMESTAMPTZ IS NULL OR created_at < $4)
+                      AND ($5::BIGINT IS NULL OR id < $5)
+                    ORDER BY id DESC
+                    LIMIT $6 OFFSET $7
+                    "#,
+                )
+                .bind(&query.actor)
+                .bind(&query.route)
+                .bind(query.since)
+                .bind(query.until)
+                .bind(before)
+                .bind(page.limit)
+                .bind(page.offset.unwrap_or(0))
+                .fetch_all(&pool)
+                .await
+            })
+            .await?;
+        Ok(entries)
+    }
+
+    /// Adds an account to the watchlist. Returns None when it is already
+    /// watched.
+    pub async fn insert_watched_account(&self, address: &str, label: Option<&str>) -> Result<Option<WatchedAccount>> {
+        let account = sqlx::query_as::<_, WatchedAccount>(
+            r#"
+            INSERT INTO watchlist (address, label)
+            VALUES ($1, $2)
+            ON CONFLICT (address) DO NOTHING
+            RETURNING address, label, lamports, data_hash, authorities, observed_at, created_at
+            "#,
+        )
+        .bind(address)
+        .bind(label)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(account)
+    }
+
+    pub async fn count_watched_accounts(&self) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM watchlist")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count)
+    }
+
+    pub async fn list_watched_accounts(&self, limit: i64) -> Result<Vec<WatchedAccount>> {
+        let accounts = sqlx::query_as::<_, WatchedAccount>(
+            r#"
+            SELECT address, label, lamports, data_hash, authorities, observed_at, created_at
+            FROM watchlist
+            ORDER BY created_at, address
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(accounts)
+    }
+
+    /// Removes an account and its recorded changes. Returns false when it
+    /// wasn't watched.
+    pub async fn delete_watched_account(&self, address: &str) -> Result<bool> {
+        let deleted = sqlx::query("DELETE FROM watchlist WHERE address = $1")
+            .bind(address)
+            .execute(&self.pool)
+            .await?
+            .rows_affected()
+            > 0;
+        Ok(deleted)
+    }
+
+    pub async fn set_watch_baseline(&self, address: &str, state: &ObservedState) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE watchlist SET lamports = $2, data_hash = $3, authorities = $4, observed_at = NOW()
+            WHERE address = $1
+            "#,
+        )
+        .bind(address)
+        .bind(state.lamports)
+        .bind(&state.data_hash)
+        .bind(state.authorities.as_ref().map(serde_json::to_value).transpose()?)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Records a change, moves the watched account to its new state and
+    /// queues the change for `account.changed` webhooks, atomically.
+    pub async fn record_account_change(&self, watched: &WatchedAccount, state: &ObservedState) -> Result<AccountChange> {
+        let mut tx = self.pool.begin().await?;
+        let change = sqlx::query_as::<_, AccountChange>(
+            r#"
+            INSERT INTO account_changes (address, previous_lamports, lamports, data_changed, data_hash)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, address, previous_lamports, lamports, data_changed, data_hash, detected_at
+            "#,
+        )
+        .bind(&watched.address)
+        .bind(watched.lamports.unwrap_or(0))
+        .bind(state.lamports)
+        .bind(watched.data_hash != state.data_hash)
+        .bind(&state.data_hash)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE watchlist SET lamports = $2, data_hash = $3, authorities = COALESCE($4, authorities), observed_at = $5
+            WHERE address = $1
+            "#,
+        )
+        .bind(&watched.address)
+        .bind(state.lamports)
+        .bind(&state.data_hash)
+        .bind(state.authorities.as_ref().map(serde_json::to_value).transpose()?)
+        .bind(change.detected_at)
+        .execute(&mut *tx)
+        .await?;
+
+        queue_webhook_event(&mut tx, webhooks::ACCOUNT_CHANGED, serde_json::to_value(&change)?).await?;
+
+        tx.commit().await?;
+        Ok(change)
+    }
+
+    /// Records authority changes, moves the watched account to its new
+    /// authorities and queues each change for `account.authority_changed`
+    /// webhooks, atomically.
+    pub async fn record_authority_changes(
+        &self,
+        address: &str,
+        changes: &[NewAuthorityChange],
+        authorities: &Authorities,
+    ) -> Result<Vec<AuthorityChange>> {
+        let mut tx = self.pool.begin().await?;
+        let mut recorded = Vec::with_capacity(changes.len());
+        for change in changes {
+            let change = sqlx::query_as::<_, AuthorityChange>(
+                r#"
+                INSERT INTO authority_changes (address, authority, previous_value, new_value)
+                VALUES ($1, $2, $3, $4)
+                RETURNING id, address, authority, previous_value, new_value, detected_at
+                "#,
+            )
+            .bind(address)
+            .bind(&change.authority)
+            .bind(&change.previous_value)
+            .bind(&change.new_value)
+            .fetch_one(&mut *tx)
+            .await?;
+            queue_webhook_event(&mut tx, webhooks::AUTHORITY_CHANGED, serde_json::to_value(&change)?).await?;
+            recorded.push(change);
+        }
+
+        sqlx::query("UPDATE watchlist SET authorities = $2 WHERE address = $1")
+            .bind(address)
+            .bind(serde_json::to_value(authorities)?)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(recorded)
+    }
+
+    /// Changes recorded for a watched account, newest first. The cursor is
+    /// the `id` of the last change from the previous page.
+    pub async fn list_account_changes(&self, address: &str, page: &Pagination) -> Result<Vec<AccountChange>> {
+        let before = page
+            .cursor
+            .as_deref()
+            .map(str::parse::<i64>)
+            .transpose()
+            .map_err(|_| ApiError::BadRequest("Invalid cursor".to_string()))?;
+
+        let changes = self
+            .read(|pool| async move {
+                sqlx::query_as::<_, AccountChange>(
+                    r#"
+                    SELECT id, address, previous_lamports, lamports, data_changed, data_hash, detected_at
+                    FROM account_changes
+                    WHERE address = $1
+                      AND ($2::BIGINT IS NULL OR id < $2)
+                    ORDER BY id DESC
+                    LIMIT $3 OFFSET $4
+                    "#,
+                )
+                .bind(address)
+                .bind(before)
+                .bind(page.limit)
+                .bind(page.offset.unwrap_or(0))
+                .fetch_all(&pool)
+                .await
+            })
+            .await?;
+        Ok(changes)
+    }
+
+    /// Authority changes recorded for a watched account, newest first. The
+    /// cursor is the `id` of the last change from the previous page.
+    pub async fn list_authority_changes(&self, address: &str, page: &Pagination) -> Result<Vec<AuthorityChange>> {
+        let before = page
+            .cursor
+            .as_deref()
+            .map(str::parse::<i64>)
+            .transpose()
+            .map_err(|_| ApiError::BadRequest("Invalid cursor".to_string()))?;
+
+        let changes = self
+            .read(|pool| async move {
+                sqlx::query_as::<_, AuthorityChange>(
+                    r#"
+                    SELECT id, address, authority, previous_value, new_value, detected_at
+                    FROM authority_changes
+                    WHERE address = $1
+                      AND ($2::BIGINT IS NULL OR id < $2)
+                    ORDER BY id DESC
+                    LIMIT $3 OFFSET $4
+                    "#,
+                )
+                .bind(address)
+                .bind(before)
+                .bind(page.limit)
+                .bind(page.offset.unwrap_or(0))
+                .fetch_all(&pool)
+                .await
+            })
+            .await?;
+        Ok(changes)
+    }
+
+    pub async fn record_balance_snapshot(
+        &self,
+        address: &str,
+        balances: &[SnapshotBalance],
+        taken_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let mints: Vec<Option<String>> = balances.iter().map(|balance| balance.mint.clone()).collect();
+        let amounts: Vec<i64> = balances.iter().map(|balance| balance.amount).collect();
+        let decimals: Vec<i16> = balances.iter().map(|balance| balance.decimals).collect();
+        sqlx::query(
+            r#"
+            INSERT INTO balance_snapshots (address, mint, amount, decimals, taken_at)
+            SELECT $1, mint, amount, decimals, $5
+            FROM UNNEST($2::TEXT[], $3::BIGINT[], $4::SMALLINT[]) AS b (mint, amount, decimals)
+            "#,
+        )
+        .bind(address)
+        .bind(&mints)
+        .bind(&amounts)
+        .bind(&decimals)
+        .bind(taken_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Balances from the last snapshot in every `interval` bucket between
+    /// `from` (inclusive) and `to` (exclusive), ordered by bucket.
+    pub async fn get_balance_history(
+        &self,
+        address: &str,
+        interval: CandleInterval,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<BucketedBalance>> {
+        let rows = self
+            .read(|pool| async move {
+                sqlx::query_as::<_, BucketedBalance>(
+                    r#"
+                    WITH snapshots AS (
+                        SELECT to_timestamp(floor(extract(epoch FROM taken_at) / $2) * $2) AS bucket_start,
+                               mint, amount, decimals, taken_at
+                        FROM balance_snapshots
+                        WHERE address = $1 AND taken_at >= $3 AND taken_at < $4
+                    )
+                    SELECT bucket_start, mint, amount, decimals
+                    FROM snapshots s
+                    WHERE taken_at = (SELECT MAX(taken_at) FROM snapshots l WHERE l.bucket_start = s.bucket_start)
+                    ORDER BY bucket_start, mint NULLS FIRST
+                    "#,
+                )
+                .bind(address)
+                .bind(interval.seconds() as f64)
+                .bind(from)
+                .bind(to)
+                .fetch_all(&pool)
+                .await
+            })
+            .await?;
+        Ok(rows)
+    }
+
+    /// Creates a schedule whose first execution is due immediately.
+    pub async fn create_dca_schedule(&self, request: &DcaRequest) -> Result<DcaSchedule> {
+        let schedule = sqlx::query_as::<_, DcaSchedule>(&format!(
+            r#"
+            INSERT INTO dca_schedules
+                (id, input_mint, output_mint, amount_per_execution, interval_secs, total_budget, slippage_bps,
+                 next_execution_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, NOW())
+            RETURNING {}
+            "#,
+            DCA_SCHEDULE_COLUMNS
+        ))
+        .bind(Uuid::new_v4())
+        .bind(&request.input_mint)
+        .bind(&request.output_mint)
+        .bind(request.amount_per_execution as i64)
+        .bind(request.interval_secs as i64)
+        .bind(request.total_budget as i64)
+        .bind(request.slippage_bps.map(|bps| bps as i32))
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(schedule)
+    }
+
+    /// Schedules that may still execute, active or paused.
+    pub async fn count_open_dca_schedules(&self) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM dca_schedules WHERE status IN ($1, $2)")
+            .bind(dca::ACTIVE)
+            .bind(dca::PAUSED)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count)
+    }
+
+    /// Schedules, newest first.
+    pub async fn list_dca_schedules(&self, limit: i64) -> Result<Vec<DcaSchedule>> {
+        let schedules = sqlx::query_as::<_, DcaSchedule>(&format!(
+            "SELECT {} FROM dca_schedules ORDER BY created_at DESC, id LIMIT $1",
+            DCA_SCHEDULE_COLUMNS
+        ))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(schedules)
+    }
+
+    pub async fn get_dca_schedule(&self, id: Uuid) -> Result<Option<DcaSchedule>> {
+        let schedule = sqlx::query_as::<_, DcaSchedule>(&format!(
+            "SELECT {} FROM dca_schedules WHERE id = $1",
+            DCA_SCHEDULE_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(schedule)
+    }
+
+    /// Moves a schedule to `status` if it is currently in one of `from`.
+    /// Returns None when it doesn't exist or is in another state.
+    pub async fn set_dca_status(&self, id: Uuid, from: &[&str], status: &str) -> Result<Option<DcaSchedule>> {
+        let from: Vec<String> = from.iter().map(|status| status.to_string()).collect();
+        let schedule = sqlx::query_as::<_, DcaSchedule>(&format!(
+            r#"
+            UPDATE dca_schedules SET status = $3, updated_at = NOW()
+            WHERE id = $1 AND status = ANY($2)
+            RETURNING {}
+            "#,
+            DCA_SCHEDULE_COLUMNS
+        ))
+        .bind(id)
+        .bind(&from)
+        .bind(status)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(schedule)
+    }
+
+    /// Claims due active schedules by moving each to its next interval.
+    /// Rows locked by another instance are skipped.
+    pub async fn claim_due_dca_schedules(&self, limit: i64) -> Result<Vec<DcaSchedule>> {
+        let schedules = sqlx::query_as::<_, DcaSchedule>(&format!(
+            r#"
+            UPDATE dca_schedules
+            SET next_execution_at = NOW() + interval_secs * INTERVAL '1 second', updated_at = NOW()
+            WHERE id IN (
+                SELECT id FROM dca_schedules
+                WHERE status = $1 AND next_execution_at <= NOW()
+                ORDER BY next_execution_at
+                LIMIT $2
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING {}
+            "#,
+            DCA_SCHEDULE_COLUMNS
+        ))
+        .bind(dca::ACTIVE)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(schedules)
+    }
+
+    /// Records an execution. A fill also advances the schedule's spend,
+    /// completing it once the budget is used up, and is queued for
+    /// `dca.filled` webhooks, atomically.
+    pub async fn record_dca_execution(
+        &self,
+        schedule: &DcaSchedule,
+        amount_in: u64,
+        signature: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<DcaExecution> {
+        let mut tx = self.pool.begin().await?;
+        let execution = sqlx::query_as::<_, DcaExecution>(
+            r#"
+            INSERT INTO dca_executions (schedule_id, amount_in, signature, error)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, schedule_id, amount_in, signature, error, executed_at
+            "#,
+        )
+        .bind(schedule.id)
+        .bind(amount_in as i64)
+        .bind(signature)
+        .bind(error)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if signature.is_some() {
+            sqlx::query(
+                r#"
+                UPDATE dca_schedules
+                SET spent = spent + $2,
+                    fills = fills + 1,
+                    status = CASE WHEN spent + $2 >= total_budget THEN $3 ELSE status END,
+                    updated_at = NOW()
+                WHERE id = $1
+                "#,
+            )
+            .bind(schedule.id)
+            .bind(amount_in as i64)
+            .bind(dca::COMPLETED)
+            .execute(&mut *tx)
+            .await?;
+            queue_webhook_event(&mut tx, webhooks::DCA_FILLED, serde_json::to_value(&execution)?).await?;
+        }
+
+        tx.commit().await?;
+        Ok(execution)
+    }
+
+    /// Executions of a schedule, newest first. The cursor is the `id` of
+    /// the last execution from the previous page.
+    pub async fn list_dca_executions(&self, schedule_id: Uuid, page: &Pagination) -> Result<Vec<DcaExecution>> {
+        let before = page
+            .cursor
+            .as_deref()
+            .map(str::parse::<i64>)
+            .transpose()
+            .map_err(|_| ApiError::BadRequest("Invalid cursor".to_string()))?;
+
+        let executions = self
+            .read(|pool| async move {
+                sqlx::query_as::<_, DcaExecution>(
+                    r#"
+                    SELECT id, schedule_id, amount_in, signature, error, executed_at
+                    FROM dca_executions
+                    WHERE schedule_id = $1
+                      AND ($2::BIGINT IS NULL OR id < $2)
+                    ORDER BY id DESC
+                    LIMIT $3 OFFSET $4
+                    "#,
+                )
+                .bind(schedule_id)
+                .bind(before)
+                .bind(page.limit)
+                .bind(page.offset.unwrap_or(0))
+                .fetch_all(&pool)
+                .await
+            })
+            .await?;
+        Ok(executions)
+    }
+
+    /// Deliveries that are due, oldest first, for webhooks still active.
+    pub async fn create_approval(
+        &self,
+        request: &TransactionRequest,
+        requested_by: &str,
+        backend: &str,
+        ttl_secs: u64,
+    ) -> Result<Approval> {
+        let approval = sqlx::query_as::<_, Approval>(&format!(
+            r#"
+            INSERT INTO approvals (id, request, requested_by, backend, expires_at)
+            VALUES ($1, $2, $3, $4, NOW() + make_interval(secs => $5))
+            RETURNING {}
+            "#,
+            APPROVAL_COLUMNS
+        ))
+        .bind(Uuid::new_v4())
+        .bind(serde_json::to_value(request)?)
+        .bind(requested_by)
+        .bind(backend)
+        .bind(ttl_secs as f64)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(approval)
+    }
+
+    pub async fn get_approval(&self, id: Uuid) -> Result<Option<Approval>> {
+        let approval = sqlx::query_as::<_, Approval>(&format!(
+            "SELECT {} FROM approvals WHERE id = $1",
+            APPROVAL_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(approval)
+    }
+
+    /// Unexpired approvals still waiting for approvers, newest first.
+    pub async fn list_pending_approvals(&self, limit: i64) -> Result<Vec<Approval>> {
+        let approvals = sqlx::query_as::<_, Approval>(&format!(
+            r#"
+            SELECT {} FROM approvals
+            WHERE status = $1 AND expires_at > NOW()
+            ORDER BY created_at DESC, id
+            LIMIT $2
+            "#,
+            APPROVAL_COLUMNS
+        ))
+        .bind(approvals::PENDING)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(approvals)
+    }
+
+    /// Adds `key_id` to a pending, unexpired approval it hasn't approved and
+    /// wasn't requested by. The approval reaching `required` moves it to
+    /// `executing` in the same update, so exactly one approver executes it.
+    /// Returns None when the approval wasn't recorded.
+    pub async fn add_approval(
+        &self,
+        id: Uuid,
+        key_id: Uuid,
+        subject: &str,
+        required: usize,
+    ) -> Result<Option<Approval>> {
+        let approval = sqlx::query_as::<_, Approval>(&format!(
+            r#"
+            UPDATE approvals
+            SET approved_by = array_append(approved_by, $2),
+                status = CASE WHEN cardinality(approved_by) + 1 >= $4 THEN $6 ELSE status END,
+                updated_at = NOW()
+            WHERE id = $1 AND status = $5 AND expires_at > NOW()
+              AND NOT ($2 = ANY(approved_by)) AND requested_by <> $3
+            RETURNING {}
+            "#,
+            APPROVAL_COLUMNS
+        ))
+        .bind(id)
+        .bind(key_id)
+        .bind(subject)
+        .bind(required as i32)
+        .bind(approvals::PENDING)
+        .bind(approvals::EXECUTING)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(approval)
+    }
+
+    /// Records the outcome of executing an approved transfer.
+    pub async fn finish_approval(
+        &self,
+        id: Uuid,
+        status: &str,
+        signature: Option<&str>,
+        squads_proposal: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<Approval> {
+        let approval = sqlx::query_as::<_, Approval>(&format!(
+            r#"
+            UPDATE approvals
+            SET status = $2, signature = $3, squads_proposal = $4, error = $5, updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            APPROVAL_COLUMNS
+        ))
+        .bind(id)
+        .bind(status)
+        .bind(signature)
+        .bind(squads_proposal)
+        .bind(error)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(approval)
+    }
+
+    pub async fn list_compliance_entries(&self) -> Result<Vec<ComplianceEntry>> {
+        let entries = sqlx::query_as::<_, ComplianceEntry>(
+            "SELECT id, list, kind, value, reason, created_at FROM compliance_entries ORDER BY id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(entries)
+    }
+
+    /// Returns None when the value is already on the list.
+    pub async fn insert_compliance_entry(&self, entry: &NewComplianceEntry) -> Result<Option<ComplianceEntry>> {
+        let entry = sqlx::query_as::<_, ComplianceEntry>(
+            r#"
+            INSERT INTO compliance_entries (list, kind, value, reason)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (list, kind, value) DO NOTHING
+            RETURNING id, list, kind, value, reason, created_at
+            "#,
+        )
+        .bind(entry.list.as_str())
+        .bind(entry.kind.as_str())
+        .bind(&entry.value)
+        .bind(&entry.reason)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(entry)
+    }
+
+    pub async fn delete_compliance_entry(&self, id: i64) -> Result<bool> {
+        let deleted = sqlx::query("DELETE FROM compliance_entries WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?
+            .rows_affected()
+            > 0;
+        Ok(deleted)
+    }
+
+    /// Returns false when the owner already used the reference.
+    pub async fn claim_client_reference(&self, owner: &str, reference: &str) -> Result<bool> {
+        let claimed = sqlx::query(
+            r#"
+            INSERT INTO client_references (owner, reference)
+            VALUES ($1, $2)
+            ON CONFLICT (owner, reference) DO NOTHING
+            "#,
+        )
+        .bind(owner)
+        .bind(reference)
+        .execute(&self.pool)
+        .await?
+        .rows_affected()
+            > 0;
+        Ok(claimed)
+    }
+
+    pub async fn get_client_reference(&self, owner: &str, reference: &str) -> Result<Option<ClientReference>> {
+        let reference = sqlx::query_as::<_, ClientReference>(
+            "SELECT signature, approval_id, created_at FROM client_references WHERE owner = $1 AND reference = $2",
+        )
+        .bind(owner)
+        .bind(reference)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(reference)
+    }
+
+    pub async fn complete_client_reference(
+        &self,
+        owner: &str,
+        reference: &str,
+        signature: Option<&str>,
+        approval_id: Option<Uuid>,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE client_references SET signature = $3, approval_id = $4 WHERE owner = $1 AND reference = $2",
+        )
+        .bind(owner)
+        .bind(reference)
+        .bind(signature)
+        .bind(approval_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Frees a reference whose transfer was never sent.
+    pub async fn release_client_reference(&self, owner: &str, reference: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM client_references
+            WHERE owner = $1 AND reference = $2 AND signature IS NULL AND approval_id IS NULL
+            "#,
+        )
+        .bind(owner)
+        .bind(reference)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn pending_webhook_deliveries(&self, limit: i64) -> Result<Vec<PendingDelivery>> {
+        let deliveries = sqlx::query_as::<_, PendingDelivery>(
+            r#"
+            SELECT d.id, d.event, d.payload, d.attempts, w.url, w.secret
+            FROM webhook_deliveries d
+            JOIN webhooks w ON w.id = d.webhook_id
+            WHERE d.delivered_at IS NULL
+              AND d.next_attempt_at <= NOW()
+              AND w.active
+            ORDER BY d.next_attempt_at
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(deliveries)
+    }
+
+    /// Records one delivery attempt. A failed delivery without a next
+    /// attempt time has been abandoned.
+    pub async fn record_webhook_attempt(
+        &self,
+        id: i64,
+        status_code: Option<i32>,
+        delivered: bool,
+        next_attempt_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET attempts = attempts + 1,
+                status_code = $2,
+                delivered_at = CASE WHEN $3 THEN NOW() END,
+                next_attempt_at = $4
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(status_code)
+        .bind(delivered)
+        .bind(next_attempt_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+/// Queues `payload` for every active webhook subscribed to `event`, as part
+/// of the caller's transaction.
+async fn queue_webhook_event(conn: &mut PgConnection, event: &str, payload: serde_json::Value) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO webhook_deliveries (webhook_id, event, payload, next_attempt_at)
+        SELECT id, $1, $2, NOW()
+        FROM webhooks
+        WHERE active AND (cardinality(events) = 0 OR $1 = ANY(events))
+        "#,
+    )
+    .bind(event)
+    .bind(payload)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+fn pool_options(config: &DatabasePoolConfig) -> PgPoolOptions {
+    PgPoolOptions::new()
+        .max_connections(config.max_connections)
+        .min_connections(config.min_connections)
+        .acquire_timeout(Duration::from_secs(config.acquire_timeout_secs))
+        .idle_timeout(Duration::from_secs(config.idle_timeout_secs))
+        .max_lifetime(Duration::from_secs(config.max_lifetime_secs))
+}
+
+/// Whether a query failed because the server couldn't serve it rather than
+/// because of the query itself: lost connections, shutdowns (SQLSTATE 08
+/// and 57P) and replica recovery conflicts (40001).
+fn is_unavailable(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Io(_)
+        | sqlx::Error::Tls(_)
+        | sqlx::Error::PoolTimedOut
+        | sqlx::Error::PoolClosed
+        | sqlx::Error::WorkerCrashed => true,
+        sqlx::Error::Database(e) => e
+            .code()
+            .map_or(false, |code| code.starts_with("08") || code.starts_with("57P") || code == "40001"),
+        _ => false,
+    }
+}
+
+/// Samples pool utilization into metrics. Wait time is measured by acquiring
+/// a connection the way a query would, so it reflects queueing under load.
+pub fn spawn_pool_metrics(database: Arc<Database>, metrics: Arc<Metrics>, cluster: String, config: &DatabasePoolConfig) {
+    let period = Duration::from_secs(config.metrics_interval_secs.max(1));
+    let max_connections = config.max_connections;
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            let started = Instant::now();
+            let wait = match database.pool.acquire().await {
+                Ok(_connection) => Some(started.elapsed()),
+                Err(e) => {
+                    warn!("Failed to acquire database connection for {}: {}", cluster, e);
+                    None
+                }
+            };
+            let size = database.pool.size();
+            let idle = database.pool.num_idle() as u32;
+            metrics.observe_db_pool(&cluster, size.saturating_sub(idle), idle, max_connections, wait);
+        }
+    });
+}
+
+/// Pings every replica, returning those that answer to the read rotation and
+/// taking out those that don't.
+pub fn spawn_replica_checks(database: Arc<Database>, cluster: String, config: &DatabasePoolConfig) {
+    if database.replicas.is_empty() {
+        return;
+    }
+    let period = Duration::from_secs(config.replica_check_interval_secs.max(1));
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            for (i, replica) in database.replicas.iter().enumerate() {
+                let healthy = match sqlx::query("SELECT 1").execute(&replica.pool).await {
+                    Ok(_) => true,
+                    Err(e) => {
+                        warn!("Database replica {} of {} unavailable: {}", i, cluster, e);
+                        false
+                    }
+                };
+                if healthy && !replica.healthy.swap(true, Ordering::Relaxed) {
+                    info!("Database replica {} of {} is serving reads", i, cluster);
+                } else if !healthy {
+                    replica.healthy.store(false, Ordering::Relaxed);
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,133 @@
+use crate::swap::SwapRequest;
+use crate::AppState;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::{debug, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Values of `dca_schedules.status`.
+pub const ACTIVE: &str = "active";
+pub const PAUSED: &str = "paused";
+pub const CANCELLED: &str = "cancelled";
+pub const COMPLETED: &str = "completed";
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct DcaRequest {
+    pub input_mint: String,
+    pub output_mint: String,
+    /// Raw units of `input_mint` swapped per execution.
+    pub amount_per_execution: u64,
+    pub interval_secs: u64,
+    /// Raw units of `input_mint` spent in total; the last execution swaps
+    /// whatever remains.
+    pub total_budget: u64,
+    pub slippage_bps: Option<u16>,
+}
+
+/// A recurring swap and its progress through the budget.
+#[derive(Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct DcaSchedule {
+    pub id: Uuid,
+    pub input_mint: String,
+    pub output_mint: String,
+    pub amount_per_execution: i64,
+    pub interval_secs: i64,
+    pub total_budget: i64,
+    pub spent: i64,
+    pub slippage_bps: Option<i32>,
+    /// `active`, `paused`, `cancelled` or `completed`.
+    pub status: String,
+    /// Successful executions so far.
+    pub fills: i32,
+    pub next_execution_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One execution of a schedule. Fills are delivered to `dca.filled`
+/// webhooks.
+#[derive(Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct DcaExecution {
+    pub id: i64,
+    pub schedule_id: Uuid,
+    pub amount_in: i64,
+    /// Swap signature of a fill.
+    pub signature: Option<String>,
+    /// Why a failed execution didn't swap; the schedule retries at its
+    /// next interval.
+    pub error: Option<String>,
+    pub executed_at: DateTime<Utc>,
+}
+
+impl DcaSchedule {
+    /// Input swapped by the next execution.
+    pub fn next_amount(&self) -> u64 {
+        self.amount_per_execution.min(self.total_budget - self.spent).max(0) as u64
+    }
+}
+
+/// Executes due schedules through the gateway's swap path. Due schedules
+/// are claimed by moving them to their next interval first, so several
+/// instances can share the database without executing one twice.
+pub fn spawn_scheduler(state: AppState) {
+    let config = state.config.dca.clone();
+    if !config.enabled {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.poll_interval_secs.max(1)));
+        loop {
+            interval.tick().await;
+            let schedules = match state.database.claim_due_dca_schedules(config.batch_size).await {
+                Ok(schedules) => schedules,
+                Err(e) => {
+                    warn!("Failed to claim due DCA schedules: {}", e);
+                    continue;
+                }
+            };
+            for schedule in schedules {
+                execute(&state, &schedule).await;
+            }
+        }
+    });
+}
+
+async fn execute(state: &AppState, schedule: &DcaSchedule) {
+    let amount_in = schedule.next_amount();
+    let request = SwapRequest {
+        pool_id: None,
+        input_mint: schedule.input_mint.clone(),
+        output_mint: Some(schedule.output_mint.clone()),
+        amount_in,
+        min_amount_out: None,
+        slippage_bps: schedule.slippage_bps.map(|bps| bps as u16),
+        mev_protection: false,
+        quote_id: None,
+    };
+    // Keyed per fill: a swap that went out but wasn't recorded is replayed
+    // from the stored response instead of being sent again.
+    let key = format!("dca:{}:{}", schedule.id, schedule.fills);
+    let result = crate::submit_swap(state, Some(&key), &request).await;
+
+    let (signature, error) = match &result {
+        Ok(transaction) => (Some(transaction.signature.as_str()), None),
+        Err(e) => {
+            warn!("DCA schedule {} failed to swap: {}", schedule.id, e);
+            (None, Some(e.to_string()))
+        }
+    };
+    match state
+        .database
+        .record_dca_execution(schedule, amount_in, signature, error.as_deref())
+        .await
+    {
+        Ok(execution) => debug!(
+            "DCA schedule {} executed {} of {}",
+            schedule.id, execution.amount_in, schedule.input_mint
+        ),
+        Err(e) => warn!("Failed to record execution of DCA schedule {}: {}", schedule.id, e),
+    }
+}
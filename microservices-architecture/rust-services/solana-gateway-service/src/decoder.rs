@@ -0,0 +1,78 @@
+use crate::nfts::{self, METADATA_PROGRAM_ID};
+use crate::pools::Dex;
+use crate::solana_client::SolanaClient;
+use crate::tokens::{parse_token_account, TokenProgram};
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::Serialize;
+use solana_account_decoder::parse_account_data::{parse_account_data, AccountAdditionalData};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct DecodedAccount {
+    pub address: String,
+    pub owner: String,
+    pub lamports: u64,
+    pub executable: bool,
+    pub data_len: usize,
+    /// Decoder that recognized the data, e.g. `spl-token`, `stake`, `vote`,
+    /// `mpl-token-metadata` or `raydium-amm`. Absent when none did.
+    pub program: Option<String>,
+    #[schema(value_type = Option<Object>)]
+    pub parsed: Option<serde_json::Value>,
+    /// Base64 account data, only returned when it couldn't be decoded.
+    pub data: Option<String>,
+}
+
+/// Fetches an account and decodes its data by owning program. Token accounts
+/// need their mint's decimals, which are resolved through the mint cache.
+pub async fn decode_account(client: &SolanaClient, address: &str) -> Result<DecodedAccount> {
+    let pubkey = Pubkey::from_str(address)?;
+    let account = client.get_raw_account(&pubkey).await?;
+
+    let mint = TokenProgram::from_owner(&account.owner)
+        .and_then(|program| parse_token_account(program, &account.data))
+        .map(|token_account| token_account.mint);
+    let decimals = match mint {
+        Some(mint) => client.resolve_mints(&[mint]).await?.get(&mint).map(|m| m.decimals),
+        None => None,
+    };
+
+    Ok(decode(&pubkey, &account, decimals))
+}
+
+fn decode(address: &Pubkey, account: &Account, token_decimals: Option<u8>) -> DecodedAccount {
+    let parsed = if account.owner == METADATA_PROGRAM_ID {
+        nfts::parse_metadata(address, &account.data)
+            .and_then(|metadata| serde_json::to_value(metadata).ok())
+            .map(|parsed| ("mpl-token-metadata".to_string(), parsed))
+    } else if let Some(dex) = Dex::from_program(&account.owner) {
+        dex.decode(address, &account.data)
+            .and_then(|pool| serde_json::to_value(pool).ok())
+            .map(|parsed| (dex.as_str().replace('_', "-"), parsed))
+    } else {
+        let additional = AccountAdditionalData {
+            spl_token_decimals: token_decimals,
+        };
+        parse_account_data(address, &account.owner, &account.data, Some(additional))
+            .ok()
+            .map(|parsed| (parsed.program, parsed.parsed))
+    };
+
+    let (program, parsed, data) = match parsed {
+        Some((program, parsed)) => (Some(program), Some(parsed), None),
+        None => (None, None, Some(BASE64.encode(&account.data))),
+    };
+    DecodedAccount {
+        address: address.to_string(),
+        owner: account.owner.to_string(),
+        lamports: account.lamports,
+        executable: account.executable,
+        data_len: account.data.len(),
+        program,
+        parsed,
+        data,
+    }
+}
@@ -0,0 +1,179 @@
+use crate::maintenance::MaintenanceNotice;
+use crate::request_id;
+use crate::rpc_pool::{is_endpoint_failure, is_rate_limited};
+use crate::validation::FieldError;
+use axum::{
+    http::{
+        header::{RETRY_AFTER, WWW_AUTHENTICATE},
+        HeaderValue, StatusCode,
+    },
+    response::{IntoResponse, Json, Response},
+};
+use serde::Serialize;
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    rpc_request::RpcError,
+};
+use solana_sdk::{pubkey::ParsePubkeyError, signature::ParseSignatureError};
+use tracing::error;
+use utoipa::ToSchema;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("{0}")]
+    Unauthorized(String),
+    #[error("{0}")]
+    Forbidden(String),
+    /// Refused by the compliance lists; audited as `blocked`.
+    #[error("{0}")]
+    Blocked(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    Conflict(String),
+    #[error("Request validation failed")]
+    Validation(Vec<FieldError>),
+    #[error("Request body exceeds the {0}-byte limit")]
+    PayloadTooLarge(usize),
+    #[error("Rate limit exceeded, retry in {0} seconds")]
+    TooManyRequests(u64),
+    #[error("{0}")]
+    ServiceUnavailable(String),
+    /// A write route group an operator has put into maintenance.
+    #[error("{}", .0.message())]
+    Maintenance(MaintenanceNotice),
+    #[error("{0}")]
+    Internal(String),
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ErrorBody {
+    pub code: &'static str,
+    pub message: String,
+    /// Same as the `X-Request-Id` response header.
+    pub request_id: String,
+    /// Per-field problems for validation failures.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<FieldError>,
+    /// Body size limit of the route, for 413 responses.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit_bytes: Option<usize>,
+    /// The paused route group, for 503 responses during maintenance.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maintenance: Option<MaintenanceNotice>,
+}
+
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) | ApiError::Blocked(_) => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::ServiceUnavailable(_) | ApiError::Maintenance(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::Forbidden(_) => "forbidden",
+            ApiError::Blocked(_) => "blocked",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::Conflict(_) => "conflict",
+            ApiError::Validation(_) => "validation_failed",
+            ApiError::PayloadTooLarge(_) => "payload_too_large",
+            ApiError::TooManyRequests(_) => "rate_limited",
+            ApiError::ServiceUnavailable(_) => "service_unavailable",
+            ApiError::Maintenance(_) => "maintenance",
+            ApiError::Internal(_) => "internal_error",
+        }
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(err: anyhow::Error) -> Self {
+        let err = match err.downcast::<ApiError>() {
+            Ok(api_error) => return api_error,
+            Err(err) => err,
+        };
+
+        if err.is::<ParsePubkeyError>() {
+            return ApiError::BadRequest("Invalid public key".to_string());
+        }
+        if err.is::<ParseSignatureError>() {
+            return ApiError::BadRequest("Invalid transaction signature".to_string());
+        }
+        if let Some(client_error) = err.downcast_ref::<ClientError>() {
+            if is_rate_limited(client_error) {
+                return ApiError::ServiceUnavailable("Solana RPC provider is rate limiting requests".to_string());
+            }
+            if is_endpoint_failure(client_error) {
+                return ApiError::ServiceUnavailable("Solana RPC node unavailable".to_string());
+            }
+            if let ClientErrorKind::RpcError(RpcError::ForUser(message)) = client_error.kind() {
+                if message.starts_with("AccountNotFound") {
+                    return ApiError::NotFound("Account not found".to_string());
+                }
+            }
+        }
+
+        ApiError::Internal(err.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let request_id = request_id::current().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let message = match &self {
+            // Internal details stay in the logs rather than the response.
+            ApiError::Internal(details) => {
+                error!("Internal error (request_id={}): {}", request_id, details);
+                "Internal server error".to_string()
+            }
+            other => other.to_string(),
+        };
+
+        let details = match &self {
+            ApiError::Validation(errors) => errors.clone(),
+            _ => Vec::new(),
+        };
+        let limit_bytes = match &self {
+            ApiError::PayloadTooLarge(limit) => Some(*limit),
+            _ => None,
+        };
+        let maintenance = match &self {
+            ApiError::Maintenance(notice) => Some(notice.clone()),
+            _ => None,
+        };
+        let body = ErrorBody {
+            code: self.code(),
+            message,
+            request_id,
+            details,
+            limit_bytes,
+            maintenance,
+        };
+        let mut response = (self.status(), Json(body)).into_response();
+        match self {
+            ApiError::TooManyRequests(retry_after) => {
+                response.headers_mut().insert(RETRY_AFTER, HeaderValue::from(retry_after));
+            }
+            ApiError::Unauthorized(_) => {
+                response
+                    .headers_mut()
+                    .insert(WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            }
+            _ => {}
+        }
+        response
+    }
+}
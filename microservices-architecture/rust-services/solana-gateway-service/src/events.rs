@@ -0,0 +1,172 @@
+use crate::config::KafkaConfig;
+use crate::pubsub::{AccountUpdate, PubsubManager};
+use crate::swap::SwapQuote;
+use crate::watchlist::{AccountChange, AuthorityChange};
+use anyhow::{Context, Result};
+use rdkafka::config::ClientConfig;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+// Wait before re-opening an account subscription that ended.
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(5);
+
+/// Messages published for other services. Each carries a `type` tag so one
+/// consumer can handle every topic with the same schema.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GatewayEvent {
+    TransactionStatus {
+        signature: String,
+        status: String,
+        slot: Option<u64>,
+    },
+    SwapExecuted {
+        signature: String,
+        pool_id: String,
+        input_mint: String,
+        output_mint: String,
+        amount_in: u64,
+        platform_fee: u64,
+        expected_amount_out: u64,
+        min_amount_out: u64,
+    },
+    AccountChanged(AccountUpdate),
+    WatchedAccountChanged(AccountChange),
+    WatchedAuthorityChanged(AuthorityChange),
+}
+
+/// Publishes gateway events to Kafka. When disabled every publish is a
+/// no-op, so callers don't need to check.
+pub struct EventPublisher {
+    producer: Option<FutureProducer>,
+    config: KafkaConfig,
+}
+
+impl EventPublisher {
+    pub fn new(config: &KafkaConfig) -> Result<Self> {
+        let producer = if config.enabled {
+            let producer = ClientConfig::new()
+                .set("bootstrap.servers", &config.brokers)
+                .set("client.id", &config.client_id)
+                .set("message.timeout.ms", config.message_timeout_ms.to_string())
+                .create()
+                .context("Failed to create Kafka producer")?;
+            info!("Publishing events to Kafka at {}", config.brokers);
+            Some(producer)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            producer,
+            config: config.clone(),
+        })
+    }
+
+    pub fn transaction_status(&self, signature: &str, status: &str, slot: Option<u64>) {
+        let event = GatewayEvent::TransactionStatus {
+            signature: signature.to_string(),
+            status: status.to_string(),
+            slot,
+        };
+        self.publish(&self.config.transactions_topic, signature, &event);
+    }
+
+    pub fn swap_executed(&self, signature: &str, quote: &SwapQuote) {
+        let event = GatewayEvent::SwapExecuted {
+            signature: signature.to_string(),
+            pool_id: quote.pool_id.clone(),
+            input_mint: quote.input_mint.clone(),
+            output_mint: quote.output_mint.clone(),
+            amount_in: quote.amount_in,
+            platform_fee: quote.platform_fee,
+            expected_amount_out: quote.expected_amount_out,
+            min_amount_out: quote.min_amount_out,
+        };
+        self.publish(&self.config.swaps_topic, signature, &event);
+    }
+
+    pub fn account_changed(&self, update: AccountUpdate) {
+        let key = update.address.clone();
+        self.publish(&self.config.accounts_topic, &key, &GatewayEvent::AccountChanged(update));
+    }
+
+    pub fn watched_account_changed(&self, change: &AccountChange) {
+        let event = GatewayEvent::WatchedAccountChanged(change.clone());
+        self.publish(&self.config.accounts_topic, &change.address, &event);
+    }
+
+    pub fn watched_authority_changed(&self, change: &AuthorityChange) {
+        let event = GatewayEvent::WatchedAuthorityChanged(change.clone());
+        self.publish(&self.config.accounts_topic, &change.address, &event);
+    }
+
+    /// Enqueues the event and logs delivery failures in the background; a
+    /// slow or unavailable broker never blocks the caller.
+    fn publish(&self, topic: &str, key: &str, event: &GatewayEvent) {
+        let Some(producer) = &self.producer else {
+            return;
+        };
+        let payload = match serde_json::to_vec(event) {
+            Ok(payload) => payload,
+            Err(e) => {
+                warn!("Failed to serialize event for {}: {}", topic, e);
+                return;
+            }
+        };
+
+        let delivery = match producer.send_result(FutureRecord::to(topic).key(key).payload(&payload)) {
+            Ok(delivery) => delivery,
+            Err((e, _)) => {
+                warn!("Failed to enqueue event for {}: {}", topic, e);
+                return;
+            }
+        };
+        let topic = topic.to_string();
+        tokio::spawn(async move {
+            match delivery.await {
+                Ok(Ok(_)) => {}
+                Ok(Err((e, _))) => warn!("Failed to deliver event to {}: {}", topic, e),
+                Err(_) => warn!("Event delivery to {} was cancelled", topic),
+            }
+        });
+    }
+}
+
+/// Keeps an account subscription open for every configured tracked account
+/// and publishes each change.
+pub fn spawn_account_forwarder(publisher: Arc<EventPublisher>, pubsub: Arc<PubsubManager>) {
+    if publisher.producer.is_none() {
+        return;
+    }
+
+    for address in &publisher.config.tracked_accounts {
+        let Ok(pubkey) = Pubkey::from_str(address) else {
+            warn!("Ignoring invalid tracked account {}", address);
+            continue;
+        };
+        let publisher = publisher.clone();
+        let pubsub = pubsub.clone();
+        tokio::spawn(async move {
+            loop {
+                let mut updates = pubsub.subscribe_account(pubkey);
+                loop {
+                    match updates.recv().await {
+                        Ok(update) => publisher.account_changed(update),
+                        Err(RecvError::Lagged(skipped)) => {
+                            warn!("Event forwarder for {} lagged, skipped {} updates", pubkey, skipped)
+                        }
+                        Err(RecvError::Closed) => break,
+                    }
+                }
+                tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+            }
+        });
+    }
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::{compute_budget::ComputeBudgetInstruction, instruction::Instruction};
+use utoipa::ToSchema;
+
+/// Most compute units a transaction may request.
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// How aggressively to bid for block space, expressed as a percentile of
+/// recently paid prioritization fees or as a fixed micro-lamport price.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PriorityFeeStrategy {
+    Low,
+    #[default]
+    Medium,
+    High,
+    Custom(u64),
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PriorityFeeEstimate {
+    /// Number of recent slots the estimate is based on.
+    pub samples: usize,
+    pub min: u64,
+    pub low: u64,
+    pub medium: u64,
+    pub high: u64,
+    pub very_high: u64,
+    pub max: u64,
+}
+
+impl PriorityFeeEstimate {
+    /// Builds percentile recommendations (micro-lamports per compute unit)
+    /// from the fees paid in recent slots.
+    pub fn from_samples(mut fees: Vec<u64>) -> Self {
+        fees.sort_unstable();
+        Self {
+            samples: fees.len(),
+            min: percentile(&fees, 0),
+            low: percentile(&fees, 25),
+            medium: percentile(&fees, 50),
+            high: percentile(&fees, 75),
+            very_high: percentile(&fees, 95),
+            max: percentile(&fees, 100),
+        }
+    }
+
+    pub fn price_for(&self, strategy: PriorityFeeStrategy) -> u64 {
+        match strategy {
+            PriorityFeeStrategy::Low => self.low,
+            PriorityFeeStrategy::Medium => self.medium,
+            PriorityFeeStrategy::High => self.high,
+            PriorityFeeStrategy::Custom(price) => price,
+        }
+    }
+}
+
+fn percentile(sorted: &[u64], pct: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let index = (sorted.len() - 1) * pct / 100;
+    sorted[index]
+}
+
+/// Limit covering `units_consumed` in simulation plus `margin_pct` percent,
+/// within what a transaction may request.
+pub fn padded_unit_limit(units_consumed: u64, margin_pct: u32) -> u32 {
+    let padded = units_consumed.saturating_mul(100 + margin_pct as u64) / 100;
+    padded.min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32
+}
+
+pub fn compute_budget_instructions(unit_limit: u32, unit_price: u64) -> Vec<Instruction> {
+    let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(unit_limit)];
+    if unit_price > 0 {
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_price(unit_price));
+    }
+    instructions
+}
@@ -0,0 +1,365 @@
+//! Read-only GraphQL schema served on `/graphql`. Nested fields resolve
+//! lazily, so a query for an account's token balances and their metadata
+//! costs one RPC call instead of a REST round trip per level.
+
+use crate::error::ApiError;
+use crate::pagination::{Pagination, SortOrder, DEFAULT_PAGE_LIMIT, MAX_PAGE_LIMIT};
+use crate::pools::{self, Dex, PoolFilter};
+use crate::solana_client::{AccountInfo, TokenBalance as TokenBalanceInfo, TransactionInfo};
+use crate::token_registry::TokenMetadata;
+use crate::tokens::TokenProgram;
+use crate::AppState;
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Result, Schema, SimpleObject};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use tracing::warn;
+
+pub type GatewaySchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+pub fn schema(state: AppState) -> GatewaySchema {
+    let config = state.config.graphql.clone();
+    Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(state)
+        .limit_depth(config.max_depth)
+        .limit_complexity(config.max_complexity)
+        .finish()
+}
+
+fn state<'a>(ctx: &Context<'a>) -> &'a AppState {
+    ctx.data_unchecked::<AppState>()
+}
+
+fn check_pubkey(value: &str) -> Result<(), ApiError> {
+    Pubkey::from_str(value)
+        .map(|_| ())
+        .map_err(|_| ApiError::BadRequest(format!("{} is not a valid public key", value)))
+}
+
+/// A page of at most `first` items after the `after` cursor.
+fn page(first: Option<i64>, after: Option<String>) -> Pagination {
+    Pagination {
+        limit: first.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT),
+        cursor: after,
+        offset: None,
+    }
+}
+
+pub struct Query;
+
+#[Object]
+impl Query {
+    async fn account(&self, ctx: &Context<'_>, address: String) -> Result<Account> {
+        check_pubkey(&address)?;
+        let state = state(ctx);
+        let key = format!("account_info:{}", address);
+        let ttl = state.config.cache.account_info_ttl();
+        let fetch = || state.solana_client.get_account_info(&address);
+
+        match state.cache.get_or_fetch(&key, ttl, false, fetch).await {
+            Ok(account) => Ok(Account(account)),
+            Err(e) => {
+                warn!("Failed to get account info for {}: {}", address, e);
+                Err(ApiError::from(e).into())
+            }
+        }
+    }
+
+    async fn transaction(&self, ctx: &Context<'_>, signature: String) -> Result<Transaction> {
+        let transaction = crate::lookup_transaction(state(ctx), &signature).await?;
+        Ok(Transaction(transaction))
+    }
+
+    async fn token(&self, ctx: &Context<'_>, mint: String) -> Result<Token> {
+        check_pubkey(&mint)?;
+        let metadata = state(ctx).token_registry.get(&mint);
+        Ok(Token { mint, metadata })
+    }
+
+    /// A pool with live reserves.
+    async fn pool(&self, ctx: &Context<'_>, id: String) -> Result<Pool> {
+        let state = state(ctx);
+        let key = format!("pool_info:{}", id);
+        let ttl = state.config.cache.pool_info_ttl();
+        let fetch = || pools::fetch_pool(&state.solana_client, &id);
+
+        match state.cache.get_or_fetch(&key, ttl, false, fetch).await {
+            Ok(pool) => Ok(Pool(pool)),
+            Err(e) => {
+                warn!("Failed to get pool {}: {}", id, e);
+                Err(ApiError::from(e).into())
+            }
+        }
+    }
+
+    /// Discovered pools ordered by address, with the reserves last indexed.
+    #[graphql(complexity = "first.unwrap_or(DEFAULT_PAGE_LIMIT) as usize * child_complexity")]
+    async fn pools(
+        &self,
+        ctx: &Context<'_>,
+        dex: Option<Dex>,
+        token: Option<String>,
+        first: Option<i64>,
+        after: Option<String>,
+    ) -> Result<PoolConnection> {
+        let state = state(ctx);
+        let page = page(first, after);
+        let filter = PoolFilter { dex, token };
+        let cursor = page.cursor.clone().map(|address| (address.clone(), address));
+
+        match state
+            .database
+            .list_pools(&filter, "address", SortOrder::Asc, cursor, &page)
+            .await
+        {
+            Ok(rows) => {
+                let page = page.page(rows, |(pool, _)| pool.id.clone());
+                Ok(PoolConnection {
+                    nodes: page.items.into_iter().map(|(pool, _)| Pool(pool)).collect(),
+                    next_cursor: page.next_cursor,
+                })
+            }
+            Err(e) => {
+                warn!("Failed to get pools: {}", e);
+                Err(ApiError::from(e).into())
+            }
+        }
+    }
+}
+
+pub struct Account(AccountInfo);
+
+#[Object]
+impl Account {
+    async fn address(&self) -> &str {
+        &self.0.address
+    }
+
+    async fn lamports(&self) -> u64 {
+        self.0.balance
+    }
+
+    async fn owner(&self) -> &str {
+        &self.0.owner
+    }
+
+    async fn executable(&self) -> bool {
+        self.0.executable
+    }
+
+    async fn rent_epoch(&self) -> u64 {
+        self.0.rent_epoch
+    }
+
+    /// SPL Token and Token-2022 balances owned by the account.
+    async fn token_balances(&self, ctx: &Context<'_>) -> Result<Vec<TokenBalance>> {
+        let state = state(ctx);
+        match state.solana_client.get_token_balances(&self.0.address).await {
+            Ok(mut balances) => {
+                state.token_registry.enrich(&mut balances);
+                Ok(balances.into_iter().map(TokenBalance).collect())
+            }
+            Err(e) => {
+                warn!("Failed to get token balances for {}: {}", self.0.address, e);
+                Err(ApiError::from(e).into())
+            }
+        }
+    }
+
+    /// Stored transactions involving the account, newest first.
+    #[graphql(complexity = "first.unwrap_or(DEFAULT_PAGE_LIMIT) as usize * child_complexity")]
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        status: Option<String>,
+        first: Option<i64>,
+        after: Option<String>,
+    ) -> Result<TransactionConnection> {
+        let page = page(first, after);
+        match state(ctx)
+            .database
+            .get_account_transactions(&self.0.address, status.as_deref(), &page)
+            .await
+        {
+            Ok(records) => {
+                let page = page.page(records, |record| record.signature.clone());
+                Ok(TransactionConnection {
+                    nodes: page
+                        .items
+                        .into_iter()
+                        .map(|record| Transaction(record.into()))
+                        .collect(),
+                    next_cursor: page.next_cursor,
+                })
+            }
+            Err(e) => {
+                warn!("Failed to get transaction history for {}: {}", self.0.address, e);
+                Err(ApiError::from(e).into())
+            }
+        }
+    }
+}
+
+pub struct TokenBalance(TokenBalanceInfo);
+
+#[Object]
+impl TokenBalance {
+    /// Raw amount, not decimal-adjusted.
+    async fn amount(&self) -> u64 {
+        self.0.amount
+    }
+
+    async fn decimals(&self) -> u8 {
+        self.0.decimals
+    }
+
+    async fn ui_amount(&self) -> f64 {
+        self.0.ui_amount
+    }
+
+    async fn program(&self) -> TokenProgram {
+        self.0.program
+    }
+
+    async fn token(&self) -> Token {
+        Token {
+            mint: self.0.mint.clone(),
+            metadata: self.0.metadata.clone(),
+        }
+    }
+}
+
+/// A mint with its token registry metadata, which is null when unlisted.
+pub struct Token {
+    mint: String,
+    metadata: Option<TokenMetadata>,
+}
+
+#[Object]
+impl Token {
+    async fn mint(&self) -> &str {
+        &self.mint
+    }
+
+    async fn symbol(&self) -> Option<&str> {
+        self.metadata.as_ref().map(|metadata| metadata.symbol.as_str())
+    }
+
+    async fn name(&self) -> Option<&str> {
+        self.metadata.as_ref().map(|metadata| metadata.name.as_str())
+    }
+
+    async fn logo_uri(&self) -> Option<&str> {
+        self.metadata.as_ref().and_then(|metadata| metadata.logo_uri.as_deref())
+    }
+
+    async fn coingecko_id(&self) -> Option<&str> {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.coingecko_id.as_deref())
+    }
+}
+
+pub struct Transaction(TransactionInfo);
+
+#[Object]
+impl Transaction {
+    async fn signature(&self) -> &str {
+        &self.0.signature
+    }
+
+    async fn status(&self) -> &str {
+        &self.0.status
+    }
+
+    async fn slot(&self) -> Option<u64> {
+        self.0.slot
+    }
+
+    async fn accounts(&self) -> &[String] {
+        &self.0.accounts
+    }
+
+    async fn fee(&self) -> Option<u64> {
+        self.0.fee
+    }
+
+    /// Unix timestamp of the block.
+    async fn block_time(&self) -> Option<i64> {
+        self.0.block_time
+    }
+
+    async fn memo(&self) -> Option<&str> {
+        self.0.memo.as_deref()
+    }
+
+    /// The transaction resent after this one expired unlanded.
+    async fn replaced_by(&self) -> Option<&str> {
+        self.0.replaced_by.as_deref()
+    }
+}
+
+pub struct Pool(pools::Pool);
+
+#[Object]
+impl Pool {
+    async fn id(&self) -> &str {
+        &self.0.id
+    }
+
+    async fn dex(&self) -> Dex {
+        self.0.dex
+    }
+
+    async fn token_a(&self, ctx: &Context<'_>) -> Token {
+        let metadata = state(ctx).token_registry.get(&self.0.token_a);
+        Token {
+            mint: self.0.token_a.clone(),
+            metadata,
+        }
+    }
+
+    async fn token_b(&self, ctx: &Context<'_>) -> Token {
+        let metadata = state(ctx).token_registry.get(&self.0.token_b);
+        Token {
+            mint: self.0.token_b.clone(),
+            metadata,
+        }
+    }
+
+    async fn vault_a(&self) -> &str {
+        &self.0.vault_a
+    }
+
+    async fn vault_b(&self) -> &str {
+        &self.0.vault_b
+    }
+
+    /// Vault balance of token A, in raw units.
+    async fn reserve_a(&self) -> u64 {
+        self.0.reserve_a
+    }
+
+    /// Vault balance of token B, in raw units.
+    async fn reserve_b(&self) -> u64 {
+        self.0.reserve_b
+    }
+
+    /// Swap fee in parts per million (3000 = 0.3%).
+    async fn fee_ppm(&self) -> u32 {
+        self.0.fee_ppm
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct PoolConnection {
+    pub nodes: Vec<Pool>,
+    /// Pass as `after` for the next page; null on the last page.
+    pub next_cursor: Option<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct TransactionConnection {
+    pub nodes: Vec<Transaction>,
+    /// Pass as `after` for the next page; null on the last page.
+    pub next_cursor: Option<String>,
+}
@@ -0,0 +1,236 @@
+use crate::error::ApiError;
+use crate::fees::PriorityFeeStrategy;
+use crate::maintenance::MaintenanceGroup;
+use crate::quotes;
+use crate::solana_client::{AccountInfo, SignMode, TokenBalance, TransactionInfo};
+use crate::swap::SwapRequest;
+use crate::{AppState, TransactionRequest};
+use std::net::SocketAddr;
+use tonic::{transport::Server, Code, Request, Response, Status};
+use tracing::{error, info};
+use uuid::Uuid;
+
+pub mod proto {
+    tonic::include_proto!("solana_gateway.v1");
+}
+
+use proto::solana_gateway_server::{SolanaGateway, SolanaGatewayServer};
+
+/// gRPC counterpart of the account, transaction and swap REST endpoints.
+/// Requests go through the same client, persistence and idempotency paths.
+pub struct GatewayService {
+    state: AppState,
+}
+
+pub fn spawn_server(state: AppState, port: u16) {
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    tokio::spawn(async move {
+        info!("gRPC server listening on {}", addr);
+        let service = SolanaGatewayServer::new(GatewayService { state });
+        if let Err(e) = Server::builder().add_service(service).serve(addr).await {
+            error!("gRPC server failed: {}", e);
+        }
+    });
+}
+
+#[tonic::async_trait]
+impl SolanaGateway for GatewayService {
+    async fn get_account(
+        &self,
+        request: Request<proto::GetAccountRequest>,
+    ) -> Result<Response<proto::Account>, Status> {
+        let address = request.into_inner().address;
+        let account = self
+            .state
+            .solana_client
+            .get_account_info(&address)
+            .await
+            .map_err(ApiError::from)?;
+        Ok(Response::new(account.into()))
+    }
+
+    async fn get_balance(
+        &self,
+        request: Request<proto::GetAccountRequest>,
+    ) -> Result<Response<proto::Balance>, Status> {
+        let address = request.into_inner().address;
+        let lamports = self
+            .state
+            .solana_client
+            .get_balance(&address)
+            .await
+            .map_err(ApiError::from)?;
+        Ok(Response::new(proto::Balance { lamports }))
+    }
+
+    async fn get_token_balances(
+        &self,
+        request: Request<proto::GetAccountRequest>,
+    ) -> Result<Response<proto::TokenBalances>, Status> {
+        let address = request.into_inner().address;
+        let mut balances = self
+            .state
+            .solana_client
+            .get_token_balances(&address)
+            .await
+            .map_err(ApiError::from)?;
+        self.state.token_registry.enrich(&mut balances);
+        Ok(Response::new(proto::TokenBalances {
+            balances: balances.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn submit_transaction(
+        &self,
+        request: Request<proto::SubmitTransactionRequest>,
+    ) -> Result<Response<proto::Transaction>, Status> {
+        self.state.maintenance.check(MaintenanceGroup::Transactions)?;
+        let request = request.into_inner();
+        let idempotency_key = request.idempotency_key.clone();
+        let request = TransactionRequest::try_from(request)?;
+        let transaction = crate::submit_transaction(&self.state, None, idempotency_key.as_deref(), &request).await?;
+        Ok(Response::new(transaction.into()))
+    }
+
+    async fn get_transaction(
+        &self,
+        request: Request<proto::GetTransactionRequest>,
+    ) -> Result<Response<proto::Transaction>, Status> {
+        let signature = request.into_inner().signature;
+        let transaction = crate::lookup_transaction(&self.state, &signature).await?;
+        Ok(Response::new(transaction.into()))
+    }
+
+    async fn execute_swap(
+        &self,
+        request: Request<proto::ExecuteSwapRequest>,
+    ) -> Result<Response<proto::Transaction>, Status> {
+        self.state.maintenance.check(MaintenanceGroup::Swaps)?;
+        let request = request.into_inner();
+        let slippage_bps = request
+            .slippage_bps
+            .map(u16::try_from)
+            .transpose()
+            .map_err(|_| Status::invalid_argument("slippage_bps is out of range"))?;
+        let swap = SwapRequest {
+            pool_id: Some(request.pool_id).filter(|pool_id| !pool_id.is_empty()),
+            input_mint: request.input_mint,
+            output_mint: request.output_mint,
+            amount_in: request.amount_in,
+            min_amount_out: request.min_amount_out,
+            slippage_bps,
+            mev_protection: request.mev_protection,
+            quote_id: request.quote_id,
+        };
+        quotes::require(&self.state.config.swap.quotes, &swap)?;
+        let transaction = crate::submit_swap(&self.state, request.idempotency_key.as_deref(), &swap).await?;
+        Ok(Response::new(transaction.into()))
+    }
+}
+
+impl From<ApiError> for Status {
+    fn from(err: ApiError) -> Self {
+        let code = match &err {
+            ApiError::BadRequest(_) => Code::InvalidArgument,
+            ApiError::Unauthorized(_) => Code::Unauthenticated,
+            ApiError::Forbidden(_) | ApiError::Blocked(_) => Code::PermissionDenied,
+            ApiError::NotFound(_) => Code::NotFound,
+            ApiError::Conflict(_) => Code::AlreadyExists,
+            ApiError::Validation(errors) => {
+                let fields: Vec<String> = errors
+                    .iter()
+                    .map(|e| format!("{} {}", e.field, e.message))
+                    .collect();
+                return Status::invalid_argument(fields.join("; "));
+            }
+            ApiError::PayloadTooLarge(_) | ApiError::TooManyRequests(_) => Code::ResourceExhausted,
+            ApiError::ServiceUnavailable(_) | ApiError::Maintenance(_) => Code::Unavailable,
+            ApiError::Internal(details) => {
+                error!("Internal gRPC error: {}", details);
+                return Status::internal("Internal server error");
+            }
+        };
+        Status::new(code, err.to_string())
+    }
+}
+
+impl TryFrom<proto::SubmitTransactionRequest> for TransactionRequest {
+    type Error = Status;
+
+    fn try_from(request: proto::SubmitTransactionRequest) -> Result<Self, Status> {
+        let priority_fee = match request.priority_fee.as_deref() {
+            None => None,
+            Some("low") => Some(PriorityFeeStrategy::Low),
+            Some("medium") => Some(PriorityFeeStrategy::Medium),
+            Some("high") => Some(PriorityFeeStrategy::High),
+            Some(_) => return Err(Status::invalid_argument("priority_fee must be low, medium or high")),
+        };
+        let wallet_id = request
+            .wallet_id
+            .as_deref()
+            .map(Uuid::parse_str)
+            .transpose()
+            .map_err(|_| Status::invalid_argument("wallet_id is not a valid UUID"))?;
+
+        Ok(TransactionRequest {
+            from: request.from,
+            to: request.to,
+            amount: request.amount,
+            memo: request.memo,
+            mint: request.mint,
+            create_recipient_ata: request.create_recipient_ata,
+            wrap_sol: request.wrap_sol,
+            priority_fee,
+            wallet_id,
+            nonce_account: request.nonce_account,
+            sign_mode: SignMode::Gateway,
+            dry_run: false,
+            client_reference: request.client_reference,
+        })
+    }
+}
+
+impl From<AccountInfo> for proto::Account {
+    fn from(account: AccountInfo) -> Self {
+        Self {
+            address: account.address,
+            balance: account.balance,
+            owner: account.owner,
+            executable: account.executable,
+            rent_epoch: account.rent_epoch,
+        }
+    }
+}
+
+impl From<TokenBalance> for proto::TokenBalance {
+    fn from(balance: TokenBalance) -> Self {
+        let program = match balance.program {
+            crate::tokens::TokenProgram::Token => "spl-token",
+            crate::tokens::TokenProgram::Token2022 => "spl-token-2022",
+        };
+        Self {
+            mint: balance.mint,
+            amount: balance.amount,
+            decimals: balance.decimals as u32,
+            ui_amount: balance.ui_amount,
+            program: program.to_string(),
+            symbol: balance.metadata.as_ref().map(|metadata| metadata.symbol.clone()),
+            name: balance.metadata.as_ref().map(|metadata| metadata.name.clone()),
+            logo_uri: balance.metadata.and_then(|metadata| metadata.logo_uri),
+        }
+    }
+}
+
+impl From<TransactionInfo> for proto::Transaction {
+    fn from(transaction: TransactionInfo) -> Self {
+        Self {
+            signature: transaction.signature,
+            status: transaction.status,
+            slot: transaction.slot,
+            accounts: transaction.accounts,
+            fee: transaction.fee,
+            block_time: transaction.block_time,
+            memo: transaction.memo,
+        }
+    }
+}
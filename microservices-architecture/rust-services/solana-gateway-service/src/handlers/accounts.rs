@@ -0,0 +1,80 @@
+use crate::decoder::{self, DecodedAccount};
+use crate::error::{ApiError, ErrorBody};
+use crate::solana_client::AccountInfo;
+use crate::AppState;
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct BatchAccountsRequest {
+    pub addresses: Vec<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BatchAccountsResponse {
+    /// One entry per requested address, in request order; `null` when the
+    /// account doesn't exist.
+    pub accounts: Vec<Option<AccountInfo>>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/accounts/batch",
+    tag = "accounts",
+    request_body = BatchAccountsRequest,
+    responses(
+        (status = 200, description = "Account info for every address", body = BatchAccountsResponse),
+        (status = 400, description = "Invalid address or too many addresses", body = ErrorBody),
+        (status = 503, description = "RPC unavailable", body = ErrorBody)
+    )
+)]
+pub async fn get_accounts_batch(
+    State(state): State<AppState>,
+    Json(request): Json<BatchAccountsRequest>,
+) -> Result<Json<BatchAccountsResponse>, ApiError> {
+    let max = state.config.max_batch_accounts;
+    if request.addresses.len() > max {
+        return Err(ApiError::BadRequest(format!(
+            "At most {} addresses can be requested per batch",
+            max
+        )));
+    }
+
+    match state.solana_client.get_account_infos(&request.addresses).await {
+        Ok(accounts) => Ok(Json(BatchAccountsResponse { accounts })),
+        Err(e) => {
+            warn!("Failed to get batch account info: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/accounts/{address}/decoded",
+    tag = "accounts",
+    params(("address" = String, Path, description = "Base58 account address")),
+    responses(
+        (status = 200, description = "Account with data decoded by its owning program", body = DecodedAccount),
+        (status = 400, description = "Invalid address", body = ErrorBody),
+        (status = 404, description = "Account not found", body = ErrorBody),
+        (status = 503, description = "RPC unavailable", body = ErrorBody)
+    )
+)]
+pub async fn get_decoded_account(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+) -> Result<Json<DecodedAccount>, ApiError> {
+    match decoder::decode_account(&state.solana_client, &address).await {
+        Ok(account) => Ok(Json(account)),
+        Err(e) => {
+            warn!("Failed to decode account {}: {}", address, e);
+            Err(e.into())
+        }
+    }
+}
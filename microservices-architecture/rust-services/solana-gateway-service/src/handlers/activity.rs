@@ -0,0 +1,63 @@
+use crate::activity::{self, Activity, MAX_ACTIVITY_PAGE};
+use crate::error::{ApiError, ErrorBody};
+use crate::pagination::{PageQuery, Pagination};
+use crate::AppState;
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use serde::Serialize;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::str::FromStr;
+use tracing::warn;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct ActivityFeed {
+    pub activity: Vec<Activity>,
+    pub next_cursor: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/accounts/{address}/activity",
+    tag = "accounts",
+    params(("address" = String, Path, description = "Base58 account address"), PageQuery),
+    responses(
+        (status = 200, description = "On-chain transactions decoded into events, newest first; pages hold at most 50", body = ActivityFeed),
+        (status = 400, description = "Invalid address or cursor", body = ErrorBody)
+    )
+)]
+pub async fn get_account_activity(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    mut pagination: Pagination,
+) -> Result<Json<ActivityFeed>, ApiError> {
+    let Ok(pubkey) = Pubkey::from_str(&address) else {
+        return Err(ApiError::BadRequest("Invalid public key".to_string()));
+    };
+    if pagination.offset.is_some() {
+        return Err(ApiError::BadRequest("Activity is paged by cursor only".to_string()));
+    }
+    let before = pagination
+        .cursor
+        .as_deref()
+        .map(Signature::from_str)
+        .transpose()
+        .map_err(|_| ApiError::BadRequest("Invalid cursor".to_string()))?;
+    pagination.limit = pagination.limit.min(MAX_ACTIVITY_PAGE);
+
+    match activity::account_activity(&state.solana_client, &pubkey, before, pagination.limit).await {
+        Ok(activity) => {
+            let page = pagination.page(activity, |activity| activity.signature.clone());
+            Ok(Json(ActivityFeed {
+                activity: page.items,
+                next_cursor: page.next_cursor,
+            }))
+        }
+        Err(e) => {
+            warn!("Failed to get activity for {}: {}", address, e);
+            Err(e.into())
+        }
+    }
+}
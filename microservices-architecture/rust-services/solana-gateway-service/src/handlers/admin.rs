@@ -0,0 +1,521 @@
+use crate::api_keys::{ApiKey, ApiKeyRateLimit, ApiKeyScopes, ApiKeyUsage, NewApiKey};
+use crate::compliance::{ComplianceEntry, NewComplianceEntry};
+use crate::error::{ApiError, ErrorBody};
+use crate::handlers::usage::{self, UsageQuery};
+use crate::maintenance::{Maintenance, MaintenanceGroup};
+use crate::reload::ReloadReport;
+use crate::rpc_pool::EndpointStatus;
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Serialize, ToSchema)]
+pub struct RotatedApiKey {
+    pub id: Uuid,
+    /// The new key. It is only shown here; the previous key stops working
+    /// immediately.
+    pub key: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CreatedApiKey {
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+    /// The key itself. It is only shown here.
+    pub key: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct MaintenanceStatus {
+    /// Transaction endpoints answer 503 while set; reads keep serving.
+    #[serde(default)]
+    pub transactions_paused: bool,
+    /// Swap endpoints answer 503 while set; reads keep serving.
+    #[serde(default)]
+    pub swaps_paused: bool,
+    /// Shown to clients in the 503 responses.
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl MaintenanceStatus {
+    pub fn current(maintenance: &Maintenance) -> Self {
+        Self {
+            transactions_paused: maintenance.is_paused(MaintenanceGroup::Transactions),
+            swaps_paused: maintenance.is_paused(MaintenanceGroup::Swaps),
+            reason: maintenance.reason(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct LogLevel {
+    /// `EnvFilter` directives, e.g. `solana_gateway_service=trace,tower_http=info`.
+    pub filter: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CacheFlush {
+    /// Redis response cache entries removed.
+    pub response_entries: u64,
+    /// In-memory mint metadata entries dropped.
+    pub mint_entries: u64,
+    /// In-memory swap route searches dropped.
+    pub route_entries: u64,
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/api-keys",
+    tag = "admin",
+    request_body = NewApiKey,
+    responses(
+        (status = 201, description = "Key issued", body = CreatedApiKey),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorBody),
+        (status = 422, description = "Invalid name, scopes or rate limit", body = ErrorBody)
+    )
+)]
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    Json(request): Json<NewApiKey>,
+) -> Result<(StatusCode, Json<CreatedApiKey>), ApiError> {
+    request.validate()?;
+
+    let (key, key_hash) = state.api_keys.generate();
+    match state.database.create_api_key(&request, &key_hash).await {
+        Ok(api_key) => {
+            info!("Issued API key {} ({})", api_key.id, api_key.name);
+            Ok((StatusCode::CREATED, Json(CreatedApiKey { api_key, key })))
+        }
+        Err(e) => {
+            warn!("Failed to issue API key: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/api-keys",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Issued keys with usage, revoked ones included", body = [ApiKey]),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorBody)
+    )
+)]
+pub async fn list_api_keys(State(state): State<AppState>) -> Result<Json<Vec<ApiKey>>, ApiError> {
+    match state.database.list_api_keys().await {
+        Ok(api_keys) => Ok(Json(api_keys)),
+        Err(e) => {
+            warn!("Failed to list API keys: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/admin/api-keys/{id}/scopes",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "API key ID")),
+    request_body = ApiKeyScopes,
+    responses(
+        (status = 200, description = "Scopes replaced", body = ApiKey),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorBody),
+        (status = 404, description = "No active key with this ID", body = ErrorBody),
+        (status = 422, description = "Unknown scope", body = ErrorBody)
+    )
+)]
+pub async fn set_api_key_scopes(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<ApiKeyScopes>,
+) -> Result<Json<ApiKey>, ApiError> {
+    request.validate()?;
+    let updated = state.database.set_api_key_scopes(id, &request.scopes).await;
+    updated_api_key(&state, id, updated).await
+}
+
+#[utoipa::path(
+    put,
+    path = "/admin/api-keys/{id}/rate-limit",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "API key ID")),
+    request_body = ApiKeyRateLimit,
+    responses(
+        (status = 200, description = "Quotas replaced; omitting both restores the defaults", body = ApiKey),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorBody),
+        (status = 404, description = "No active key with this ID", body = ErrorBody),
+        (status = 422, description = "Invalid quota", body = ErrorBody)
+    )
+)]
+pub async fn set_api_key_rate_limit(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<ApiKeyRateLimit>,
+) -> Result<Json<ApiKey>, ApiError> {
+    request.validate()?;
+    let updated = state.database.set_api_key_rate_limit(id, &request).await;
+    updated_api_key(&state, id, updated).await
+}
+
+/// Answers an update, dropping the cached grant so it applies to the next
+/// request.
+async fn updated_api_key(
+    state: &AppState,
+    id: Uuid,
+    updated: anyhow::Result<Option<ApiKey>>,
+) -> Result<Json<ApiKey>, ApiError> {
+    match updated {
+        Ok(Some(api_key)) => {
+            state.api_keys.forget(id).await;
+            info!("Updated API key {}", id);
+            Ok(Json(api_key))
+        }
+        Ok(None) => Err(ApiError::NotFound(format!("API key {} not found", id))),
+        Err(e) => {
+            warn!("Failed to update API key {}: {}", id, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/api-keys/{id}",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "API key ID")),
+    responses(
+        (status = 204, description = "Key revoked; it is rejected from the next request on"),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorBody),
+        (status = 404, description = "No active key with this ID", body = ErrorBody)
+    )
+)]
+pub async fn revoke_api_key(State(state): State<AppState>, Path(id): Path<Uuid>) -> Result<StatusCode, ApiError> {
+    match state.database.revoke_api_key(id).await {
+        Ok(true) => {
+            state.api_keys.revoke(id).await;
+            info!("Revoked API key {}", id);
+            Ok(StatusCode::NO_CONTENT)
+        }
+        Ok(false) => Err(ApiError::NotFound(format!("API key {} not found", id))),
+        Err(e) => {
+            warn!("Failed to revoke API key {}: {}", id, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/api-keys/{id}/rotate",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "API key ID")),
+    responses(
+        (status = 200, description = "Key replaced", body = RotatedApiKey),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorBody),
+        (status = 404, description = "No active key with this ID", body = ErrorBody)
+    )
+)]
+pub async fn rotate_api_key(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RotatedApiKey>, ApiError> {
+    let (key, key_hash) = state.api_keys.generate();
+    match state.database.rotate_api_key(id, &key_hash).await {
+        Ok(true) => {
+            state.api_keys.forget(id).await;
+            info!("Rotated API key {}", id);
+            Ok(Json(RotatedApiKey { id, key }))
+        }
+        Ok(false) => Err(ApiError::NotFound(format!("API key {} not found", id))),
+        Err(e) => {
+            warn!("Failed to rotate API key {}: {}", id, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/api-keys/{id}/usage",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "API key ID"), UsageQuery),
+    responses(
+        (status = 200, description = "Usage of the key per period, newest first", body = ApiKeyUsage),
+        (status = 400, description = "Invalid period or time range", body = ErrorBody),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorBody),
+        (status = 404, description = "API key not found", body = ErrorBody)
+    )
+)]
+pub async fn get_api_key_usage(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<UsageQuery>,
+) -> Result<Json<ApiKeyUsage>, ApiError> {
+    usage::usage(&state, id, &query).await.map(Json)
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/maintenance",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Current maintenance switches", body = MaintenanceStatus),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorBody)
+    )
+)]
+pub async fn get_maintenance(State(state): State<AppState>) -> Json<MaintenanceStatus> {
+    Json(MaintenanceStatus::current(&state.maintenance))
+}
+
+#[utoipa::path(
+    put,
+    path = "/admin/maintenance",
+    tag = "admin",
+    request_body = MaintenanceStatus,
+    responses(
+        (status = 200, description = "Switches applied", body = MaintenanceStatus),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorBody)
+    )
+)]
+pub async fn set_maintenance(
+    State(state): State<AppState>,
+    Json(request): Json<MaintenanceStatus>,
+) -> Json<MaintenanceStatus> {
+    for (group, paused) in [
+        (MaintenanceGroup::Transactions, request.transactions_paused),
+        (MaintenanceGroup::Swaps, request.swaps_paused),
+    ] {
+        if paused != state.maintenance.is_paused(group) {
+            info!(
+                "{} route group {} by operator",
+                group.as_str(),
+                if paused { "paused" } else { "resumed" }
+            );
+        }
+        state.maintenance.set(group, paused, request.reason.clone());
+    }
+    Json(MaintenanceStatus::current(&state.maintenance))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/log-level",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Active log filter", body = LogLevel),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorBody)
+    )
+)]
+pub async fn get_log_level(State(state): State<AppState>) -> Json<LogLevel> {
+    Json(LogLevel {
+        filter: state.log_filter.current(),
+    })
+}
+
+#[utoipa::path(
+    put,
+    path = "/admin/log-level",
+    tag = "admin",
+    request_body = LogLevel,
+    responses(
+        (status = 200, description = "Filter applied to every cluster until restart", body = LogLevel),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorBody),
+        (status = 422, description = "Invalid filter directives", body = ErrorBody)
+    )
+)]
+pub async fn set_log_level(
+    State(state): State<AppState>,
+    Json(request): Json<LogLevel>,
+) -> Result<Json<LogLevel>, ApiError> {
+    request.validate()?;
+    state.log_filter.set(&request.filter)?;
+    info!("Log filter set to {} by operator", request.filter);
+    Ok(Json(request))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/config/reload",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Reloadable settings applied to every cluster; same as sending SIGHUP", body = ReloadReport),
+        (status = 400, description = "Configuration is invalid; nothing was applied", body = ErrorBody),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorBody)
+    )
+)]
+pub async fn reload_config(State(state): State<AppState>) -> Result<Json<ReloadReport>, ApiError> {
+    match state.reloader.reload().await {
+        Ok(report) => Ok(Json(report)),
+        Err(e) => {
+            warn!("Failed to reload configuration: {:#}", e);
+            Err(ApiError::BadRequest(format!("{:#}", e)))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/caches/flush",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Caches emptied", body = CacheFlush),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorBody)
+    )
+)]
+pub async fn flush_caches(State(state): State<AppState>) -> Result<Json<CacheFlush>, ApiError> {
+    let mint_entries = state.solana_client.flush_mint_cache();
+    let route_entries = state.route_cache.flush();
+    match state.cache.flush().await {
+        Ok(response_entries) => {
+            info!(
+                "Flushed {} cached responses, {} mints and {} routes",
+                response_entries, mint_entries, route_entries
+            );
+            Ok(Json(CacheFlush {
+                response_entries,
+                mint_entries,
+                route_entries,
+            }))
+        }
+        Err(e) => {
+            warn!("Failed to flush response cache: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/compliance",
+    tag = "admin",
+    responses(
+        (status = 200, description = "List entries added through the API; configured ones aren't included", body = [ComplianceEntry]),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorBody)
+    )
+)]
+pub async fn list_compliance_entries(State(state): State<AppState>) -> Result<Json<Vec<ComplianceEntry>>, ApiError> {
+    match state.database.list_compliance_entries().await {
+        Ok(entries) => Ok(Json(entries)),
+        Err(e) => {
+            warn!("Failed to list compliance entries: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/compliance",
+    tag = "admin",
+    request_body = NewComplianceEntry,
+    responses(
+        (status = 201, description = "Entry added; other instances apply it within `compliance.sync_secs`", body = ComplianceEntry),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorBody),
+        (status = 409, description = "Already on the list", body = ErrorBody),
+        (status = 422, description = "Invalid value or reason", body = ErrorBody)
+    )
+)]
+pub async fn add_compliance_entry(
+    State(state): State<AppState>,
+    Json(request): Json<NewComplianceEntry>,
+) -> Result<(StatusCode, Json<ComplianceEntry>), ApiError> {
+    request.validate()?;
+
+    let added = async {
+        let entry = state.database.insert_compliance_entry(&request).await?;
+        if entry.is_some() {
+            state.compliance.reload().await?;
+        }
+        Ok::<_, anyhow::Error>(entry)
+    };
+    match added.await {
+        Ok(Some(entry)) => {
+            info!("Added {} to the {} list ({})", entry.value, entry.list, entry.kind);
+            Ok((StatusCode::CREATED, Json(entry)))
+        }
+        Ok(None) => Err(ApiError::Conflict(format!(
+            "{} is already on the {} list",
+            request.value,
+            request.list.as_str()
+        ))),
+        Err(e) => {
+            warn!("Failed to add compliance entry for {}: {}", request.value, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/compliance/{id}",
+    tag = "admin",
+    params(("id" = i64, Path, description = "Entry ID")),
+    responses(
+        (status = 204, description = "Entry removed"),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorBody),
+        (status = 404, description = "Entry not found", body = ErrorBody)
+    )
+)]
+pub async fn remove_compliance_entry(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    let removed = async {
+        let removed = state.database.delete_compliance_entry(id).await?;
+        if removed {
+            state.compliance.reload().await?;
+        }
+        Ok::<_, anyhow::Error>(removed)
+    };
+    match removed.await {
+        Ok(true) => {
+            info!("Removed compliance entry {}", id);
+            Ok(StatusCode::NO_CONTENT)
+        }
+        Ok(false) => Err(ApiError::NotFound(format!("Compliance entry {} not found", id))),
+        Err(e) => {
+            warn!("Failed to remove compliance entry {}: {}", id, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/rpc/endpoints",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Failover state of each RPC endpoint", body = [EndpointStatus]),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorBody)
+    )
+)]
+pub async fn get_rpc_endpoints(State(state): State<AppState>) -> Json<Vec<EndpointStatus>> {
+    Json(state.solana_client.endpoint_status())
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/pools/reindex",
+    tag = "admin",
+    responses(
+        (status = 202, description = "Pool discovery scan started"),
+        (status = 400, description = "Pool discovery is disabled", body = ErrorBody),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorBody)
+    )
+)]
+pub async fn reindex_pools(State(state): State<AppState>) -> Result<StatusCode, ApiError> {
+    if !state.config.pool_discovery.enabled {
+        return Err(ApiError::BadRequest("Pool discovery is disabled".to_string()));
+    }
+    state.pool_reindex.notify_one();
+    Ok(StatusCode::ACCEPTED)
+}
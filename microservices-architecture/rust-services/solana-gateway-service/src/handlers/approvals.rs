@@ -0,0 +1,81 @@
+use crate::api_keys::ApiKeyGrant;
+use crate::approvals::{self, Approval};
+use crate::error::{ApiError, ErrorBody};
+use crate::AppState;
+use axum::{
+    extract::{Path, State},
+    response::Json,
+    Extension,
+};
+use tracing::warn;
+use uuid::Uuid;
+
+// Most pending approvals listed at once.
+const MAX_LISTED_APPROVALS: i64 = 100;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/approvals",
+    tag = "approvals",
+    responses((status = 200, description = "Unexpired transfers awaiting approval, newest first", body = [Approval]))
+)]
+pub async fn list_approvals(State(state): State<AppState>) -> Result<Json<Vec<Approval>>, ApiError> {
+    match state.database.list_pending_approvals(MAX_LISTED_APPROVALS).await {
+        Ok(approvals) => Ok(Json(approvals)),
+        Err(e) => {
+            warn!("Failed to list approvals: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/approvals/{id}",
+    tag = "approvals",
+    params(("id" = Uuid, Path, description = "Approval ID")),
+    responses(
+        (status = 200, description = "Approval", body = Approval),
+        (status = 404, description = "Approval not found", body = ErrorBody)
+    )
+)]
+pub async fn get_approval(State(state): State<AppState>, Path(id): Path<Uuid>) -> Result<Json<Approval>, ApiError> {
+    match state.database.get_approval(id).await {
+        Ok(Some(approval)) => Ok(Json(approval)),
+        Ok(None) => Err(ApiError::NotFound(format!("Approval {} not found", id))),
+        Err(e) => {
+            warn!("Failed to get approval {}: {}", id, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/approvals/{id}/approve",
+    tag = "approvals",
+    params(("id" = Uuid, Path, description = "Approval ID")),
+    responses(
+        (status = 200, description = "Approval; the one completing the required count is executed, and its outcome returned", body = Approval),
+        (status = 403, description = "Caller has no approving API key, or requested the transfer", body = ErrorBody),
+        (status = 404, description = "Approval not found", body = ErrorBody),
+        (status = 409, description = "Already approved with this key, no longer pending, or expired", body = ErrorBody)
+    )
+)]
+pub async fn approve(
+    State(state): State<AppState>,
+    grant: Option<Extension<ApiKeyGrant>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Approval>, ApiError> {
+    let Some(Extension(grant)) = grant else {
+        return Err(ApiError::Forbidden("Approving requires an API key".to_string()));
+    };
+
+    match approvals::approve(&state, id, &grant).await {
+        Ok(approval) => Ok(Json(approval)),
+        Err(e) => {
+            warn!("Failed to approve {} with API key {}: {}", id, grant.id, e);
+            Err(e.into())
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use crate::audit::{AuditEntry, AuditQuery};
+use crate::error::{ApiError, ErrorBody};
+use crate::pagination::{PageQuery, Pagination};
+use crate::AppState;
+use axum::{
+    extract::{Query, State},
+    response::Json,
+};
+use serde::Serialize;
+use tracing::warn;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct AuditLog {
+    pub entries: Vec<AuditEntry>,
+    pub next_cursor: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit",
+    tag = "audit",
+    params(AuditQuery, PageQuery),
+    responses(
+        (status = 200, description = "Audit entries, newest first", body = AuditLog),
+        (status = 400, description = "Invalid paging parameters", body = ErrorBody)
+    )
+)]
+pub async fn get_audit_log(
+    State(state): State<AppState>,
+    pagination: Pagination,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<AuditLog>, ApiError> {
+    match state.database.list_audit_entries(&query, &pagination).await {
+        Ok(entries) => {
+            let page = pagination.page(entries, |entry| entry.id.to_string());
+            Ok(Json(AuditLog {
+                entries: page.items,
+                next_cursor: page.next_cursor,
+            }))
+        }
+        Err(e) => {
+            warn!("Failed to get audit log: {}", e);
+            Err(e.into())
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use crate::balance_history::{self, BalancePoint};
+use crate::error::{ApiError, ErrorBody};
+use crate::pools::candles::CandleInterval;
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use utoipa::{IntoParams, ToSchema};
+
+// Buckets covered when `from` is omitted.
+const DEFAULT_POINTS: i64 = 168;
+pub const MAX_POINTS: i64 = 1_000;
+
+#[derive(Deserialize, IntoParams)]
+pub struct BalanceHistoryQuery {
+    /// Bucket size: `1m`, `5m`, `1h` or `1d` (default `1h`).
+    #[param(value_type = Option<String>)]
+    pub interval: Option<CandleInterval>,
+    /// Start of the range, inclusive (RFC 3339). Defaults to 168 buckets
+    /// before `to`.
+    pub from: Option<DateTime<Utc>>,
+    /// End of the range, exclusive (RFC 3339). Defaults to now.
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl BalanceHistoryQuery {
+    pub fn interval(&self) -> CandleInterval {
+        self.interval.unwrap_or(CandleInterval::OneHour)
+    }
+
+    /// The requested range with defaults applied.
+    pub fn range(&self) -> (DateTime<Utc>, DateTime<Utc>) {
+        let to = self.to.unwrap_or_else(Utc::now);
+        let from = self
+            .from
+            .unwrap_or_else(|| to - Duration::seconds(self.interval().seconds() * DEFAULT_POINTS));
+        (from, to)
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BalanceHistory {
+    pub address: String,
+    pub interval: CandleInterval,
+    /// Buckets with at least one snapshot, oldest first.
+    pub points: Vec<BalancePoint>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/accounts/{address}/balance-history",
+    tag = "accounts",
+    params(("address" = String, Path, description = "Watched account address"), BalanceHistoryQuery),
+    responses(
+        (status = 200, description = "SOL and token balances over time; empty until the account is watched and snapshotted", body = BalanceHistory),
+        (status = 422, description = "Invalid range", body = ErrorBody)
+    )
+)]
+pub async fn get_balance_history(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    Query(query): Query<BalanceHistoryQuery>,
+) -> Result<Json<BalanceHistory>, ApiError> {
+    query.validate()?;
+    let interval = query.interval();
+    let (from, to) = query.range();
+
+    match state.database.get_balance_history(&address, interval, from, to).await {
+        Ok(rows) => Ok(Json(BalanceHistory {
+            address,
+            interval,
+            points: balance_history::into_points(rows),
+        })),
+        Err(e) => {
+            warn!("Failed to get balance history of {}: {}", address, e);
+            Err(e.into())
+        }
+    }
+}
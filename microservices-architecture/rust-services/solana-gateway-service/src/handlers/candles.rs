@@ -0,0 +1,60 @@
+use crate::error::{ApiError, ErrorBody};
+use crate::pools::candles::{Candle, CandleInterval};
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use tracing::warn;
+use utoipa::IntoParams;
+
+const DEFAULT_CANDLES: i64 = 500;
+const MAX_CANDLES: i64 = 1_000;
+
+#[derive(Deserialize, IntoParams)]
+pub struct CandleQuery {
+    /// `1m`, `5m`, `1h` or `1d`.
+    #[param(value_type = String)]
+    pub interval: CandleInterval,
+    /// Earliest bucket start, inclusive (RFC 3339).
+    pub from: Option<DateTime<Utc>>,
+    /// Latest bucket start, exclusive (RFC 3339).
+    pub to: Option<DateTime<Utc>>,
+    /// Most recent candles returned, at most 1000 (default 500).
+    pub limit: Option<i64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/pools/{pool_id}/candles",
+    tag = "pools",
+    params(("pool_id" = String, Path, description = "Base58 pool address"), CandleQuery),
+    responses(
+        (status = 200, description = "OHLCV candles, oldest first", body = [Candle]),
+        (status = 400, description = "Invalid pool address or interval", body = ErrorBody)
+    )
+)]
+pub async fn get_pool_candles(
+    State(state): State<AppState>,
+    Path(pool_id): Path<String>,
+    Query(query): Query<CandleQuery>,
+) -> Result<Json<Vec<Candle>>, ApiError> {
+    Pubkey::from_str(&pool_id).map_err(|_| ApiError::BadRequest("Invalid pool address".to_string()))?;
+    let limit = query.limit.unwrap_or(DEFAULT_CANDLES).clamp(1, MAX_CANDLES);
+
+    match state
+        .database
+        .get_candles(&pool_id, query.interval, query.from, query.to, limit)
+        .await
+    {
+        Ok(candles) => Ok(Json(candles)),
+        Err(e) => {
+            warn!("Failed to get candles for {}: {}", pool_id, e);
+            Err(e.into())
+        }
+    }
+}
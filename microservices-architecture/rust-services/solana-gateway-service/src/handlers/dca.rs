@@ -0,0 +1,199 @@
+use crate::dca::{self, DcaExecution, DcaRequest, DcaSchedule};
+use crate::error::{ApiError, ErrorBody};
+use crate::pagination::{PageQuery, Pagination};
+use crate::AppState;
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use serde::Serialize;
+use tracing::warn;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Serialize, ToSchema)]
+pub struct DcaExecutionLog {
+    pub executions: Vec<DcaExecution>,
+    pub next_cursor: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/dca",
+    tag = "dca",
+    request_body = DcaRequest,
+    responses(
+        (status = 200, description = "Schedule; its first execution is due immediately", body = DcaSchedule),
+        (status = 400, description = "Too many open schedules", body = ErrorBody),
+        (status = 422, description = "Invalid request fields", body = ErrorBody)
+    )
+)]
+pub async fn create_dca_schedule(
+    State(state): State<AppState>,
+    Json(request): Json<DcaRequest>,
+) -> Result<Json<DcaSchedule>, ApiError> {
+    request.validate(&state.config.dca, &state.config.swap)?;
+    let max_schedules = state.config.dca.max_schedules;
+    if state.database.count_open_dca_schedules().await? >= max_schedules {
+        return Err(ApiError::BadRequest(format!(
+            "Too many open DCA schedules ({})",
+            max_schedules
+        )));
+    }
+
+    match state.database.create_dca_schedule(&request).await {
+        Ok(schedule) => Ok(Json(schedule)),
+        Err(e) => {
+            warn!("Failed to create DCA schedule: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/dca",
+    tag = "dca",
+    responses((status = 200, description = "Schedules, newest first", body = [DcaSchedule]))
+)]
+pub async fn list_dca_schedules(State(state): State<AppState>) -> Result<Json<Vec<DcaSchedule>>, ApiError> {
+    match state.database.list_dca_schedules(state.config.dca.max_schedules).await {
+        Ok(schedules) => Ok(Json(schedules)),
+        Err(e) => {
+            warn!("Failed to list DCA schedules: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/dca/{id}",
+    tag = "dca",
+    params(("id" = Uuid, Path, description = "Schedule ID")),
+    responses(
+        (status = 200, description = "Schedule", body = DcaSchedule),
+        (status = 404, description = "Schedule not found", body = ErrorBody)
+    )
+)]
+pub async fn get_dca_schedule(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<DcaSchedule>, ApiError> {
+    match state.database.get_dca_schedule(id).await {
+        Ok(Some(schedule)) => Ok(Json(schedule)),
+        Ok(None) => Err(ApiError::NotFound(format!("DCA schedule {} not found", id))),
+        Err(e) => {
+            warn!("Failed to get DCA schedule {}: {}", id, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/dca/{id}/executions",
+    tag = "dca",
+    params(("id" = Uuid, Path, description = "Schedule ID"), PageQuery),
+    responses(
+        (status = 200, description = "Fills and failed attempts, newest first", body = DcaExecutionLog),
+        (status = 400, description = "Invalid paging parameters", body = ErrorBody)
+    )
+)]
+pub async fn get_dca_executions(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    pagination: Pagination,
+) -> Result<Json<DcaExecutionLog>, ApiError> {
+    match state.database.list_dca_executions(id, &pagination).await {
+        Ok(executions) => {
+            let page = pagination.page(executions, |execution| execution.id.to_string());
+            Ok(Json(DcaExecutionLog {
+                executions: page.items,
+                next_cursor: page.next_cursor,
+            }))
+        }
+        Err(e) => {
+            warn!("Failed to get executions of DCA schedule {}: {}", id, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/dca/{id}/pause",
+    tag = "dca",
+    params(("id" = Uuid, Path, description = "Schedule ID")),
+    responses(
+        (status = 200, description = "Paused schedule", body = DcaSchedule),
+        (status = 404, description = "Schedule not found", body = ErrorBody),
+        (status = 409, description = "Schedule is not active", body = ErrorBody)
+    )
+)]
+pub async fn pause_dca_schedule(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<DcaSchedule>, ApiError> {
+    transition(&state, id, &[dca::ACTIVE], dca::PAUSED).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/dca/{id}/resume",
+    tag = "dca",
+    params(("id" = Uuid, Path, description = "Schedule ID")),
+    responses(
+        (status = 200, description = "Active schedule; an execution missed while paused runs at the next poll", body = DcaSchedule),
+        (status = 404, description = "Schedule not found", body = ErrorBody),
+        (status = 409, description = "Schedule is not paused", body = ErrorBody)
+    )
+)]
+pub async fn resume_dca_schedule(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<DcaSchedule>, ApiError> {
+    transition(&state, id, &[dca::PAUSED], dca::ACTIVE).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/dca/{id}/cancel",
+    tag = "dca",
+    params(("id" = Uuid, Path, description = "Schedule ID")),
+    responses(
+        (status = 200, description = "Cancelled schedule; its executions are kept", body = DcaSchedule),
+        (status = 404, description = "Schedule not found", body = ErrorBody),
+        (status = 409, description = "Schedule already ended", body = ErrorBody)
+    )
+)]
+pub async fn cancel_dca_schedule(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<DcaSchedule>, ApiError> {
+    transition(&state, id, &[dca::ACTIVE, dca::PAUSED], dca::CANCELLED).await
+}
+
+async fn transition(state: &AppState, id: Uuid, from: &[&str], status: &str) -> Result<Json<DcaSchedule>, ApiError> {
+    let updated = async {
+        if let Some(schedule) = state.database.set_dca_status(id, from, status).await? {
+            return Ok::<_, anyhow::Error>(schedule);
+        }
+        match state.database.get_dca_schedule(id).await? {
+            Some(schedule) => Err(ApiError::Conflict(format!(
+                "DCA schedule {} is {}",
+                id, schedule.status
+            ))
+            .into()),
+            None => Err(ApiError::NotFound(format!("DCA schedule {} not found", id)).into()),
+        }
+    };
+
+    match updated.await {
+        Ok(schedule) => Ok(Json(schedule)),
+        Err(e) => {
+            warn!("Failed to set DCA schedule {} {}: {}", id, status, e);
+            Err(e.into())
+        }
+    }
+}
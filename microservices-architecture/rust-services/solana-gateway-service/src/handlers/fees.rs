@@ -0,0 +1,52 @@
+use crate::error::{ApiError, ErrorBody};
+use crate::fees::PriorityFeeEstimate;
+use crate::AppState;
+use axum::{
+    extract::{Query, State},
+    response::Json,
+};
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use tracing::warn;
+use utoipa::IntoParams;
+
+#[derive(Deserialize, IntoParams)]
+pub struct PriorityFeeQuery {
+    /// Comma-separated writable accounts to scope the estimate to their
+    /// local fee markets.
+    pub accounts: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/fees/priority",
+    tag = "fees",
+    params(PriorityFeeQuery),
+    responses(
+        (status = 200, description = "Percentile fee recommendations in micro-lamports per CU", body = PriorityFeeEstimate),
+        (status = 400, description = "Invalid account", body = ErrorBody)
+    )
+)]
+pub async fn get_priority_fees(
+    State(state): State<AppState>,
+    Query(query): Query<PriorityFeeQuery>,
+) -> Result<Json<PriorityFeeEstimate>, ApiError> {
+    let accounts = query
+        .accounts
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .filter(|s| !s.is_empty())
+        .map(Pubkey::from_str)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| ApiError::BadRequest("Invalid public key in accounts".to_string()))?;
+
+    match state.solana_client.estimate_priority_fees(&accounts).await {
+        Ok(estimate) => Ok(Json(estimate)),
+        Err(e) => {
+            warn!("Failed to estimate priority fees: {}", e);
+            Err(e.into())
+        }
+    }
+}
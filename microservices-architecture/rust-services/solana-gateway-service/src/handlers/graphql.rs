@@ -0,0 +1,25 @@
+use crate::error::ErrorBody;
+use crate::graphql::GatewaySchema;
+use async_graphql::http::GraphiQLSource;
+use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use axum::{response::Html, Extension};
+
+#[utoipa::path(
+    post,
+    path = "/graphql",
+    tag = "graphql",
+    request_body(content = Object, description = "GraphQL request: `query`, optional `variables` and `operationName`"),
+    responses(
+        (status = 200, description = "Query result; resolver, depth and complexity errors are listed in `errors`", body = Object),
+        (status = 400, description = "Malformed GraphQL request", body = ErrorBody),
+        (status = 429, description = "Rate limit exceeded", body = ErrorBody)
+    )
+)]
+pub async fn graphql(Extension(schema): Extension<GatewaySchema>, request: GraphQLRequest) -> GraphQLResponse {
+    schema.execute(request.into_inner()).await.into()
+}
+
+/// GraphiQL, mounted only when enabled.
+pub async fn graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint("/graphql").finish())
+}
@@ -0,0 +1,139 @@
+use crate::maintenance::{MaintenanceGroup, MaintenanceNotice};
+use crate::AppState;
+use anyhow::Result;
+use axum::{extract::State, http::StatusCode, response::Json};
+use serde::Serialize;
+use std::future::Future;
+use std::time::Instant;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct DependencyStatus {
+    pub name: &'static str,
+    pub healthy: bool,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ProbeResponse {
+    pub status: &'static str,
+    pub timestamp: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub checks: Vec<DependencyStatus>,
+    /// Write route groups in maintenance. They don't affect readiness, as
+    /// reads keep serving.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub maintenance: Vec<MaintenanceNotice>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    tag = "health",
+    responses((status = 200, description = "Process is running", body = ProbeResponse))
+)]
+pub async fn liveness() -> Json<ProbeResponse> {
+    Json(ProbeResponse {
+        status: "alive",
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        checks: Vec::new(),
+        maintenance: Vec::new(),
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "health",
+    responses(
+        (status = 200, description = "All dependencies healthy", body = ProbeResponse),
+        (status = 503, description = "At least one dependency unhealthy", body = ProbeResponse)
+    )
+)]
+pub async fn readiness(State(state): State<AppState>) -> (StatusCode, Json<ProbeResponse>) {
+    let (database, rpc) = tokio::join!(
+        check("database", async { state.database.ping().await.map(|_| None) }),
+        check("solana_rpc", rpc_freshness(&state)),
+    );
+    let metrics = check("metrics", async { state.metrics.render().map(|_| None) }).await;
+
+    let mut checks = vec![database, rpc, metrics];
+    if state.config.slot_lag.reference_url.is_some() {
+        checks.push(check("rpc_slot_lag", async { slot_lag(&state) }).await);
+    }
+    let ready = checks.iter().all(|check| check.healthy);
+    let maintenance = MaintenanceGroup::ALL
+        .into_iter()
+        .filter_map(|group| state.maintenance.notice(group))
+        .collect();
+    let status = if ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+
+    (
+        status,
+        Json(ProbeResponse {
+            status: if ready { "ready" } else { "not_ready" },
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            checks,
+            maintenance,
+        }),
+    )
+}
+
+/// Healthy when the newest confirmed block is recent enough; reports the
+/// slot and block age either way.
+async fn rpc_freshness(state: &AppState) -> Result<Option<String>> {
+    let slot = state.solana_client.get_slot().await?;
+    let block_time = state.solana_client.get_block_time(slot).await?;
+    let age = chrono::Utc::now().timestamp() - block_time;
+
+    let detail = format!("slot {} is {}s old", slot, age);
+    if age > state.config.max_block_age_secs {
+        anyhow::bail!(detail);
+    }
+    Ok(Some(detail))
+}
+
+/// Healthy while at least one RPC endpoint keeps up with the reference
+/// node; reports every endpoint's last measured lag.
+fn slot_lag(state: &AppState) -> Result<Option<String>> {
+    let endpoints = state.solana_client.endpoint_status();
+    let detail = endpoints
+        .iter()
+        .map(|endpoint| match endpoint.slot_lag {
+            Some(lag) if endpoint.degraded => format!("{} is {} slots behind (degraded)", endpoint.url, lag),
+            Some(lag) => format!("{} is {} slots behind", endpoint.url, lag),
+            None => format!("{} not measured yet", endpoint.url),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    if endpoints.iter().all(|endpoint| endpoint.degraded) {
+        anyhow::bail!(detail);
+    }
+    Ok(Some(detail))
+}
+
+async fn check<F>(name: &'static str, probe: F) -> DependencyStatus
+where
+    F: Future<Output = Result<Option<String>>>,
+{
+    let started = Instant::now();
+    let result = probe.await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    match result {
+        Ok(detail) => DependencyStatus {
+            name,
+            healthy: true,
+            latency_ms,
+            detail,
+        },
+        Err(e) => DependencyStatus {
+            name,
+            healthy: false,
+            latency_ms,
+            detail: Some(e.to_string()),
+        },
+    }
+}
@@ -0,0 +1,64 @@
+use crate::database::TransactionRecord;
+use crate::error::{ApiError, ErrorBody};
+use crate::pagination::{PageQuery, Pagination};
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use tracing::warn;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Deserialize, IntoParams)]
+pub struct HistoryFilter {
+    /// Only transactions in this status (pending, confirmed, finalized, failed, expired, dropped).
+    pub status: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TransactionHistory {
+    pub transactions: Vec<TransactionRecord>,
+    pub next_cursor: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/accounts/{address}/transactions",
+    tag = "accounts",
+    params(("address" = String, Path, description = "Base58 account address"), PageQuery, HistoryFilter),
+    responses(
+        (status = 200, description = "Stored transactions, newest first", body = TransactionHistory),
+        (status = 400, description = "Invalid address", body = ErrorBody)
+    )
+)]
+pub async fn get_account_transactions(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    pagination: Pagination,
+    Query(filter): Query<HistoryFilter>,
+) -> Result<Json<TransactionHistory>, ApiError> {
+    if Pubkey::from_str(&address).is_err() {
+        return Err(ApiError::BadRequest("Invalid public key".to_string()));
+    }
+
+    match state
+        .database
+        .get_account_transactions(&address, filter.status.as_deref(), &pagination)
+        .await
+    {
+        Ok(transactions) => {
+            let page = pagination.page(transactions, |tx| tx.signature.clone());
+            Ok(Json(TransactionHistory {
+                transactions: page.items,
+                next_cursor: page.next_cursor,
+            }))
+        }
+        Err(e) => {
+            warn!("Failed to get transaction history for {}: {}", address, e);
+            Err(e.into())
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use crate::cache::CacheBypass;
+use crate::error::{ApiError, ErrorBody};
+use crate::holders::{HolderDistribution, MAX_LARGEST_ACCOUNTS};
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+};
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use tracing::warn;
+use utoipa::IntoParams;
+
+#[derive(Deserialize, IntoParams)]
+pub struct HoldersQuery {
+    /// Largest accounts to return, at most 20 (the default).
+    pub limit: Option<usize>,
+    /// Also counts funded token accounts. This scans every account of the
+    /// mint and can be slow for widely held tokens.
+    #[serde(default)]
+    pub include_count: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tokens/{mint}/holders",
+    tag = "tokens",
+    params(("mint" = String, Path, description = "Base58 mint address"), HoldersQuery),
+    responses(
+        (status = 200, description = "Largest holders and supply concentration", body = HolderDistribution),
+        (status = 400, description = "Invalid mint", body = ErrorBody),
+        (status = 404, description = "Mint not found", body = ErrorBody),
+        (status = 422, description = "Invalid query fields", body = ErrorBody)
+    )
+)]
+pub async fn get_token_holders(
+    State(state): State<AppState>,
+    CacheBypass(bypass): CacheBypass,
+    Path(mint): Path<String>,
+    Query(query): Query<HoldersQuery>,
+) -> Result<Json<HolderDistribution>, ApiError> {
+    query.validate()?;
+    let limit = query.limit.unwrap_or(MAX_LARGEST_ACCOUNTS);
+    let key = format!("holders:{}:{}:{}", mint, limit, query.include_count);
+    let ttl = state.config.cache.holders_ttl();
+    let fetch = || async {
+        let pubkey = Pubkey::from_str(&mint)?;
+        state
+            .solana_client
+            .get_token_holders(&pubkey, limit, query.include_count)
+            .await
+    };
+
+    match state.cache.get_or_fetch(&key, ttl, bypass, fetch).await {
+        Ok(distribution) => Ok(Json(distribution)),
+        Err(e) => {
+            warn!("Failed to get holders of {}: {}", mint, e);
+            Err(e.into())
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use crate::error::{ApiError, ErrorBody};
+use crate::pools::{
+    self,
+    liquidity::{DepositParams, LiquidityPlan, LiquidityQuote, WithdrawParams},
+};
+use crate::solana_client::UnsignedTransaction;
+use crate::AppState;
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use tracing::warn;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, ToSchema)]
+pub struct DepositRequest {
+    /// Deposits from and receives LP tokens into its associated token
+    /// accounts; signs and pays for the transaction.
+    pub owner: String,
+    /// The side of the pool `amount` is given in; the other side is added
+    /// in proportion to the reserves.
+    pub mint: String,
+    /// Raw units of `mint`.
+    pub amount: u64,
+    /// Tolerated increase of the other side over the quote, in basis points.
+    pub slippage_bps: Option<u16>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct WithdrawRequest {
+    /// Burns LP tokens from and receives both sides into its associated
+    /// token accounts; signs and pays for the transaction.
+    pub owner: String,
+    /// Raw units of the pool's LP mint.
+    pub lp_amount: u64,
+    /// Tolerated shortfall of each side from the quote, in basis points.
+    pub slippage_bps: Option<u16>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct LiquidityTransaction {
+    pub quote: LiquidityQuote,
+    pub transaction: UnsignedTransaction,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/pools/{pool_id}/deposit",
+    tag = "pools",
+    params(("pool_id" = String, Path, description = "Pool account address")),
+    request_body = DepositRequest,
+    responses(
+        (status = 200, description = "Expected LP tokens and the unsigned deposit transaction for the owner to sign", body = LiquidityTransaction),
+        (status = 400, description = "Invalid request, or the pool's DEX doesn't issue LP tokens", body = ErrorBody),
+        (status = 403, description = "Owner or mint refused by the compliance lists", body = ErrorBody),
+        (status = 404, description = "Not a supported pool", body = ErrorBody),
+        (status = 422, description = "Invalid request fields", body = ErrorBody)
+    )
+)]
+pub async fn deposit_liquidity(
+    State(state): State<AppState>,
+    Path(pool_id): Path<String>,
+    Json(request): Json<DepositRequest>,
+) -> Result<Json<LiquidityTransaction>, ApiError> {
+    request.validate(&state.config.swap)?;
+    let params = DepositParams {
+        owner: parse_pubkey("owner", &request.owner)?,
+        mint: parse_pubkey("mint", &request.mint)?,
+        amount: request.amount,
+        slippage_bps: request.slippage_bps.unwrap_or(state.config.swap.default_slippage_bps),
+    };
+
+    let built = async {
+        let pool = pools::fetch_pool(&state.solana_client, &pool_id).await?;
+        state
+            .compliance
+            .check_swap(Some(&request.owner), &pool.token_a, &pool.token_b)?;
+        let plan = pool
+            .dex
+            .adapter()
+            .build_deposit(&state.solana_client, &pool, &params)
+            .await?;
+        liquidity_transaction(&state, plan, &params.owner).await
+    };
+
+    match built.await {
+        Ok(transaction) => Ok(Json(transaction)),
+        Err(e) => {
+            warn!("Failed to build deposit into {} for {}: {}", pool_id, request.owner, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/pools/{pool_id}/withdraw",
+    tag = "pools",
+    params(("pool_id" = String, Path, description = "Pool account address")),
+    request_body = WithdrawRequest,
+    responses(
+        (status = 200, description = "Expected amounts of both sides and the unsigned withdrawal transaction for the owner to sign", body = LiquidityTransaction),
+        (status = 400, description = "Invalid request, or the pool's DEX doesn't issue LP tokens", body = ErrorBody),
+        (status = 403, description = "Owner or mint refused by the compliance lists", body = ErrorBody),
+        (status = 404, description = "Not a supported pool", body = ErrorBody),
+        (status = 422, description = "Invalid request fields", body = ErrorBody)
+    )
+)]
+pub async fn withdraw_liquidity(
+    State(state): State<AppState>,
+    Path(pool_id): Path<String>,
+    Json(request): Json<WithdrawRequest>,
+) -> Result<Json<LiquidityTransaction>, ApiError> {
+    request.validate(&state.config.swap)?;
+    let params = WithdrawParams {
+        owner: parse_pubkey("owner", &request.owner)?,
+        lp_amount: request.lp_amount,
+        slippage_bps: request.slippage_bps.unwrap_or(state.config.swap.default_slippage_bps),
+    };
+
+    let built = async {
+        let pool = pools::fetch_pool(&state.solana_client, &pool_id).await?;
+        state
+            .compliance
+            .check_swap(Some(&request.owner), &pool.token_a, &pool.token_b)?;
+        let plan = pool
+            .dex
+            .adapter()
+            .build_withdraw(&state.solana_client, &pool, &params)
+            .await?;
+        liquidity_transaction(&state, plan, &params.owner).await
+    };
+
+    match built.await {
+        Ok(transaction) => Ok(Json(transaction)),
+        Err(e) => {
+            warn!(
+                "Failed to build withdrawal from {} for {}: {}",
+                pool_id, request.owner, e
+            );
+            Err(e.into())
+        }
+    }
+}
+
+async fn liquidity_transaction(
+    state: &AppState,
+    plan: LiquidityPlan,
+    owner: &Pubkey,
+) -> anyhow::Result<LiquidityTransaction> {
+    let transaction = state
+        .solana_client
+        .build_unsigned_transaction(&plan.instructions, owner)
+        .await?;
+    Ok(LiquidityTransaction {
+        quote: plan.quote,
+        transaction,
+    })
+}
+
+fn parse_pubkey(field: &str, value: &str) -> Result<Pubkey, ApiError> {
+    Pubkey::from_str(value).map_err(|_| ApiError::BadRequest(format!("{} is not a valid public key", field)))
+}
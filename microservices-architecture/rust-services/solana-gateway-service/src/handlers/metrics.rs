@@ -0,0 +1,40 @@
+use crate::error::ApiError;
+use crate::metrics::OPENMETRICS_FORMAT;
+use crate::AppState;
+use axum::{
+    extract::State,
+    http::{header, HeaderMap},
+    response::IntoResponse,
+};
+use tracing::warn;
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "health",
+    responses((
+        status = 200,
+        description = "Prometheus text exposition, or OpenMetrics with latency exemplars when exemplars are enabled and the scraper accepts it",
+        body = String,
+        content_type = "text/plain"
+    ))
+)]
+pub async fn get_metrics(State(state): State<AppState>, headers: HeaderMap) -> Result<impl IntoResponse, ApiError> {
+    let openmetrics = state.metrics.exemplars_enabled()
+        && headers
+            .get(header::ACCEPT)
+            .and_then(|accept| accept.to_str().ok())
+            .map_or(false, |accept| accept.contains("application/openmetrics-text"));
+    let rendered = if openmetrics {
+        state.metrics.render_openmetrics().map(|body| (OPENMETRICS_FORMAT, body))
+    } else {
+        state.metrics.render().map(|body| (prometheus::TEXT_FORMAT, body))
+    };
+    match rendered {
+        Ok((content_type, body)) => Ok(([(header::CONTENT_TYPE, content_type)], body)),
+        Err(e) => {
+            warn!("Failed to render metrics: {}", e);
+            Err(e.into())
+        }
+    }
+}
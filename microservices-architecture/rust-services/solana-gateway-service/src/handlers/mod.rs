@@ -0,0 +1,31 @@
+pub mod accounts;
+pub mod activity;
+pub mod admin;
+pub mod approvals;
+pub mod audit;
+pub mod balance_history;
+pub mod candles;
+pub mod dca;
+pub mod fees;
+pub mod graphql;
+pub mod health;
+pub mod history;
+pub mod holders;
+pub mod liquidity;
+pub mod metrics;
+pub mod nfts;
+pub mod nonces;
+pub mod pda;
+pub mod prices;
+pub mod rent;
+pub mod rpc;
+pub mod signatures;
+pub mod stakes;
+pub mod stats;
+pub mod subscriptions;
+pub mod swap;
+pub mod token_accounts;
+pub mod transactions;
+pub mod usage;
+pub mod wallets;
+pub mod watchlist;
@@ -0,0 +1,66 @@
+use crate::error::{ApiError, ErrorBody};
+use crate::nfts::NftMetadata;
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+};
+use serde::Deserialize;
+use tracing::warn;
+use utoipa::IntoParams;
+
+#[derive(Deserialize, IntoParams)]
+pub struct NftQuery {
+    /// Also fetch the off-chain JSON each metadata URI points to.
+    #[serde(default)]
+    pub resolve: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/nfts/{mint}",
+    tag = "nfts",
+    params(("mint" = String, Path, description = "Base58 NFT mint"), NftQuery),
+    responses(
+        (status = 200, description = "Decoded Token Metadata", body = NftMetadata),
+        (status = 400, description = "Invalid mint", body = ErrorBody),
+        (status = 404, description = "Mint has no metadata account", body = ErrorBody)
+    )
+)]
+pub async fn get_nft(
+    State(state): State<AppState>,
+    Path(mint): Path<String>,
+    Query(query): Query<NftQuery>,
+) -> Result<Json<NftMetadata>, ApiError> {
+    match state.nfts.get_nft(&mint, query.resolve).await {
+        Ok(nft) => Ok(Json(nft)),
+        Err(e) => {
+            warn!("Failed to get NFT metadata for {}: {}", mint, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/accounts/{address}/nfts",
+    tag = "nfts",
+    params(("address" = String, Path, description = "Base58 wallet address"), NftQuery),
+    responses(
+        (status = 200, description = "NFTs held by the wallet", body = [NftMetadata]),
+        (status = 400, description = "Invalid address", body = ErrorBody)
+    )
+)]
+pub async fn get_account_nfts(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    Query(query): Query<NftQuery>,
+) -> Result<Json<Vec<NftMetadata>>, ApiError> {
+    match state.nfts.get_account_nfts(&address, query.resolve).await {
+        Ok(nfts) => Ok(Json(nfts)),
+        Err(e) => {
+            warn!("Failed to get NFTs for {}: {}", address, e);
+            Err(e.into())
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use crate::auth::Principal;
+use crate::error::{ApiError, ErrorBody};
+use crate::solana_client::{NonceInfo, NonceOperation};
+use crate::wallets;
+use crate::AppState;
+use axum::{
+    extract::{Path, State},
+    response::Json,
+    Extension,
+};
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use tracing::warn;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Deserialize, ToSchema)]
+pub struct NonceWalletRequest {
+    /// Stored wallet that pays for and is the authority of the nonce account.
+    pub wallet_id: Uuid,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/nonces/{address}",
+    tag = "nonces",
+    params(("address" = String, Path, description = "Nonce account address")),
+    responses(
+        (status = 200, description = "Current durable nonce", body = NonceInfo),
+        (status = 400, description = "Not a nonce account", body = ErrorBody),
+        (status = 404, description = "Account not found", body = ErrorBody)
+    )
+)]
+pub async fn get_nonce(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+) -> Result<Json<NonceInfo>, ApiError> {
+    let nonce = async {
+        let pubkey = Pubkey::from_str(&address)?;
+        state.solana_client.get_nonce(&pubkey).await
+    };
+
+    match nonce.await {
+        Ok(nonce) => Ok(Json(nonce)),
+        Err(e) => {
+            warn!("Failed to get nonce account {}: {}", address, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/nonces",
+    tag = "nonces",
+    request_body = NonceWalletRequest,
+    responses(
+        (status = 200, description = "Nonce account created", body = NonceOperation),
+        (status = 404, description = "Wallet not found", body = ErrorBody)
+    )
+)]
+pub async fn create_nonce(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Json(request): Json<NonceWalletRequest>,
+) -> Result<Json<NonceOperation>, ApiError> {
+    let principal = principal.as_ref().map(|Extension(principal)| principal);
+    let created = async {
+        let payer = state.wallets.keypair(wallets::scope(principal), request.wallet_id).await?;
+        state.solana_client.create_nonce_account(&payer).await
+    };
+
+    match created.await {
+        Ok(operation) => Ok(Json(operation)),
+        Err(e) => {
+            warn!("Failed to create nonce account: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/nonces/{address}/advance",
+    tag = "nonces",
+    request_body = NonceWalletRequest,
+    params(("address" = String, Path, description = "Nonce account address")),
+    responses(
+        (status = 200, description = "Nonce advanced", body = NonceOperation),
+        (status = 400, description = "Wallet is not the nonce authority", body = ErrorBody)
+    )
+)]
+pub async fn advance_nonce(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(address): Path<String>,
+    Json(request): Json<NonceWalletRequest>,
+) -> Result<Json<NonceOperation>, ApiError> {
+    let principal = principal.as_ref().map(|Extension(principal)| principal);
+    let advanced = async {
+        let pubkey = Pubkey::from_str(&address)?;
+        let nonce = state.solana_client.get_nonce(&pubkey).await?;
+        let authority = state
+            .wallets
+            .signer_for(wallets::scope(principal), request.wallet_id, &nonce.authority)
+            .await?;
+        state.solana_client.advance_nonce_account(&pubkey, &authority).await
+    };
+
+    match advanced.await {
+        Ok(operation) => Ok(Json(operation)),
+        Err(e) => {
+            warn!("Failed to advance nonce account {}: {}", address, e);
+            Err(e.into())
+        }
+    }
+}
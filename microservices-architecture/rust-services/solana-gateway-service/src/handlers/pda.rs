@@ -0,0 +1,79 @@
+use crate::error::{ApiError, ErrorBody};
+use axum::response::Json;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SeedType {
+    /// Base58-decoded bytes, typically a public key.
+    Base58,
+    /// The text's UTF-8 bytes, e.g. `metadata`.
+    Utf8,
+    /// A decimal integer as 8 little-endian bytes, as Anchor's
+    /// `to_le_bytes()` seeds.
+    U64,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PdaSeed {
+    #[serde(rename = "type")]
+    pub seed_type: SeedType,
+    pub value: String,
+}
+
+impl PdaSeed {
+    pub fn bytes(&self) -> Option<Vec<u8>> {
+        match self.seed_type {
+            SeedType::Base58 => bs58::decode(&self.value).into_vec().ok(),
+            SeedType::Utf8 => Some(self.value.as_bytes().to_vec()),
+            SeedType::U64 => self.value.parse::<u64>().ok().map(|n| n.to_le_bytes().to_vec()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PdaRequest {
+    pub program_id: String,
+    /// Seeds in derivation order, without the bump.
+    pub seeds: Vec<PdaSeed>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ProgramDerivedAddress {
+    pub address: String,
+    /// The canonical (highest valid) bump seed.
+    pub bump: u8,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/pda",
+    tag = "accounts",
+    request_body = PdaRequest,
+    responses(
+        (status = 200, description = "Program-derived address and canonical bump", body = ProgramDerivedAddress),
+        (status = 400, description = "No bump yields an off-curve address", body = ErrorBody),
+        (status = 422, description = "Invalid program ID or seeds", body = ErrorBody)
+    )
+)]
+pub async fn derive_pda(Json(request): Json<PdaRequest>) -> Result<Json<ProgramDerivedAddress>, ApiError> {
+    request.validate()?;
+    let program_id = Pubkey::from_str(&request.program_id).map_err(anyhow::Error::from)?;
+    let seeds = request
+        .seeds
+        .iter()
+        .map(PdaSeed::bytes)
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| ApiError::BadRequest("seeds must match their types".to_string()))?;
+    let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+
+    let (address, bump) = Pubkey::try_find_program_address(&seeds, &program_id)
+        .ok_or_else(|| ApiError::BadRequest("No bump seed yields a valid program address".to_string()))?;
+    Ok(Json(ProgramDerivedAddress {
+        address: address.to_string(),
+        bump,
+    }))
+}
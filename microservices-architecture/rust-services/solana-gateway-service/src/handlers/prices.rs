@@ -0,0 +1,77 @@
+use crate::error::{ApiError, ErrorBody};
+use crate::price_oracle::PriceQuote;
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+};
+use serde::Deserialize;
+use tracing::warn;
+use utoipa::IntoParams;
+
+const MAX_PRICE_MINTS: usize = 100;
+
+#[derive(Deserialize, IntoParams)]
+pub struct PricesQuery {
+    /// Comma-separated token mints.
+    pub mints: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/prices/{mint}",
+    tag = "prices",
+    params(("mint" = String, Path, description = "Base58 token mint")),
+    responses(
+        (status = 200, description = "Oracle USD price", body = PriceQuote),
+        (status = 404, description = "No price feed for this mint", body = ErrorBody)
+    )
+)]
+pub async fn get_price(
+    State(state): State<AppState>,
+    Path(mint): Path<String>,
+) -> Result<Json<PriceQuote>, ApiError> {
+    match state.price_oracle.get_price(&mint).await {
+        Ok(price) => Ok(Json(price)),
+        Err(e) => {
+            warn!("Failed to get price for {}: {}", mint, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/prices",
+    tag = "prices",
+    params(PricesQuery),
+    responses(
+        (status = 200, description = "Oracle USD prices for mints with a configured feed", body = [PriceQuote]),
+        (status = 400, description = "Too many mints", body = ErrorBody)
+    )
+)]
+pub async fn get_prices(
+    State(state): State<AppState>,
+    Query(query): Query<PricesQuery>,
+) -> Result<Json<Vec<PriceQuote>>, ApiError> {
+    let mints: Vec<String> = query
+        .mints
+        .split(',')
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect();
+    if mints.len() > MAX_PRICE_MINTS {
+        return Err(ApiError::BadRequest(format!(
+            "At most {} mints can be priced per request",
+            MAX_PRICE_MINTS
+        )));
+    }
+
+    match state.price_oracle.get_prices(&mints).await {
+        Ok(prices) => Ok(Json(prices)),
+        Err(e) => {
+            warn!("Failed to get prices: {}", e);
+            Err(e.into())
+        }
+    }
+}
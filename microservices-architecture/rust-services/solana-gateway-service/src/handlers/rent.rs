@@ -0,0 +1,61 @@
+use crate::cache::CacheBypass;
+use crate::error::{ApiError, ErrorBody};
+use crate::AppState;
+use axum::{
+    extract::{Query, State},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Deserialize, IntoParams)]
+pub struct RentQuery {
+    /// Account data size in bytes.
+    pub data_len: u64,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct RentExemption {
+    pub data_len: u64,
+    /// Smallest balance that keeps an account of this size rent exempt.
+    pub lamports: u64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/rent",
+    tag = "accounts",
+    params(RentQuery),
+    responses(
+        (status = 200, description = "Rent-exempt minimum balance for the account size", body = RentExemption),
+        (status = 422, description = "Invalid query fields", body = ErrorBody)
+    )
+)]
+pub async fn get_rent_exemption(
+    State(state): State<AppState>,
+    CacheBypass(bypass): CacheBypass,
+    Query(query): Query<RentQuery>,
+) -> Result<Json<RentExemption>, ApiError> {
+    query.validate()?;
+    let key = format!("rent:{}", query.data_len);
+    let ttl = state.config.cache.rent_ttl();
+    let fetch = || async {
+        let lamports = state
+            .solana_client
+            .minimum_balance_for_rent_exemption(query.data_len as usize)
+            .await?;
+        Ok(RentExemption {
+            data_len: query.data_len,
+            lamports,
+        })
+    };
+
+    match state.cache.get_or_fetch(&key, ttl, bypass, fetch).await {
+        Ok(rent) => Ok(Json(rent)),
+        Err(e) => {
+            warn!("Failed to get rent exemption for {} bytes: {}", query.data_len, e);
+            Err(e.into())
+        }
+    }
+}
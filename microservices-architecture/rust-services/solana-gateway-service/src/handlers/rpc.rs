@@ -0,0 +1,95 @@
+use crate::error::{ApiError, ErrorBody};
+use crate::rpc_pool::{is_endpoint_failure, is_rate_limited};
+use crate::AppState;
+use axum::{extract::State, response::Json};
+use serde::{Deserialize, Serialize};
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    rpc_request::RpcError,
+};
+use tracing::warn;
+use utoipa::ToSchema;
+
+/// A JSON-RPC 2.0 request for a single method.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct RpcPassthroughRequest {
+    /// Echoed back in the response.
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    pub id: serde_json::Value,
+    pub method: String,
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    pub params: serde_json::Value,
+}
+
+/// A JSON-RPC 2.0 response carrying either the node's result or the error
+/// it returned.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct RpcPassthroughResponse {
+    pub jsonrpc: String,
+    #[schema(value_type = Option<Object>)]
+    pub id: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub result: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcErrorObject>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct RpcErrorObject {
+    pub code: i64,
+    pub message: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/rpc",
+    tag = "rpc",
+    request_body = RpcPassthroughRequest,
+    responses(
+        (status = 200, description = "Node result, or the JSON-RPC error the node returned", body = RpcPassthroughResponse),
+        (status = 403, description = "Method not in the allowlist", body = ErrorBody),
+        (status = 429, description = "Rate limit exceeded", body = ErrorBody),
+        (status = 503, description = "RPC unavailable", body = ErrorBody)
+    )
+)]
+pub async fn rpc_passthrough(
+    State(state): State<AppState>,
+    Json(request): Json<RpcPassthroughRequest>,
+) -> Result<Json<RpcPassthroughResponse>, ApiError> {
+    let (result, error) = match state.solana_client.passthrough(&request.method, request.params).await {
+        Ok(result) => (Some(result), None),
+        // Errors about the request itself go back to the caller as the node
+        // reported them.
+        Err(e) => match node_error(&e) {
+            Some(error) => (None, Some(error)),
+            None => {
+                warn!("RPC passthrough of {} failed: {}", request.method, e);
+                return Err(e.into());
+            }
+        },
+    };
+    Ok(Json(RpcPassthroughResponse {
+        jsonrpc: "2.0".to_string(),
+        id: request.id,
+        result,
+        error,
+    }))
+}
+
+fn node_error(err: &anyhow::Error) -> Option<RpcErrorObject> {
+    let err = err.downcast_ref::<ClientError>()?;
+    // Unhealthy or throttling nodes are the gateway's problem, not the caller's.
+    if is_endpoint_failure(err) || is_rate_limited(err) {
+        return None;
+    }
+    match err.kind() {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, message, .. }) => Some(RpcErrorObject {
+            code: *code,
+            message: message.clone(),
+        }),
+        _ => None,
+    }
+}
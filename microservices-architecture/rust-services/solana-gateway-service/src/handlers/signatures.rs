@@ -0,0 +1,66 @@
+use crate::error::{ApiError, ErrorBody};
+use axum::response::Json;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageEncoding {
+    /// The message is the text as given, such as a sign-in statement.
+    #[default]
+    Utf8,
+    Base64,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct VerifySignatureRequest {
+    /// Signer's base58 public key.
+    pub pubkey: String,
+    pub message: String,
+    #[serde(default)]
+    pub encoding: MessageEncoding,
+    /// Base58 ed25519 signature, as returned by wallet `signMessage`
+    /// implementations after encoding.
+    pub signature: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct SignatureVerification {
+    pub valid: bool,
+}
+
+impl VerifySignatureRequest {
+    pub fn message_bytes(&self) -> Option<Vec<u8>> {
+        match self.encoding {
+            MessageEncoding::Utf8 => Some(self.message.as_bytes().to_vec()),
+            MessageEncoding::Base64 => BASE64.decode(&self.message).ok(),
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/verify-signature",
+    tag = "signatures",
+    request_body = VerifySignatureRequest,
+    responses(
+        (status = 200, description = "Whether the signature is the pubkey's over the message", body = SignatureVerification),
+        (status = 422, description = "Invalid request fields", body = ErrorBody)
+    )
+)]
+pub async fn verify_signature(
+    Json(request): Json<VerifySignatureRequest>,
+) -> Result<Json<SignatureVerification>, ApiError> {
+    request.validate()?;
+    let pubkey = Pubkey::from_str(&request.pubkey).map_err(anyhow::Error::from)?;
+    let signature = Signature::from_str(&request.signature).map_err(anyhow::Error::from)?;
+    let message = request
+        .message_bytes()
+        .ok_or_else(|| ApiError::BadRequest("message is not valid base64".to_string()))?;
+    Ok(Json(SignatureVerification {
+        valid: signature.verify(pubkey.as_ref(), &message),
+    }))
+}
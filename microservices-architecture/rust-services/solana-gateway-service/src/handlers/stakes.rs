@@ -0,0 +1,176 @@
+use crate::error::{ApiError, ErrorBody};
+use crate::solana_client::UnsignedTransaction;
+use crate::stake::StakeAccount;
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+};
+use serde::Deserialize;
+use solana_sdk::{pubkey::Pubkey, stake::instruction as stake_instruction};
+use std::str::FromStr;
+use tracing::warn;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Deserialize, IntoParams)]
+pub struct StakesQuery {
+    /// Include each account's inflation reward from the previous epoch.
+    #[serde(default)]
+    pub rewards: bool,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct DelegateStakeRequest {
+    pub stake_account: String,
+    /// Staker authority; signs and pays for the transaction.
+    pub authority: String,
+    /// Validator vote account to delegate to.
+    pub vote_account: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct DeactivateStakeRequest {
+    pub stake_account: String,
+    /// Staker authority; signs and pays for the transaction.
+    pub authority: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct WithdrawStakeRequest {
+    pub stake_account: String,
+    /// Withdrawer authority; signs and pays for the transaction.
+    pub authority: String,
+    pub to: String,
+    pub lamports: u64,
+}
+
+fn parse_pubkey(value: &str, field: &str) -> Result<Pubkey, ApiError> {
+    Pubkey::from_str(value).map_err(|_| ApiError::BadRequest(format!("{} is not a valid public key", field)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/accounts/{address}/stakes",
+    tag = "stakes",
+    params(("address" = String, Path, description = "Staker or withdrawer authority"), StakesQuery),
+    responses(
+        (status = 200, description = "Stake accounts controlled by the address", body = [StakeAccount]),
+        (status = 400, description = "Invalid address", body = ErrorBody)
+    )
+)]
+pub async fn get_stake_accounts(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    Query(query): Query<StakesQuery>,
+) -> Result<Json<Vec<StakeAccount>>, ApiError> {
+    let stakes = async {
+        let owner = Pubkey::from_str(&address)?;
+        state.solana_client.get_stake_accounts(&owner, query.rewards).await
+    };
+
+    match stakes.await {
+        Ok(stakes) => Ok(Json(stakes)),
+        Err(e) => {
+            warn!("Failed to get stake accounts for {}: {}", address, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/stakes/delegate",
+    tag = "stakes",
+    request_body = DelegateStakeRequest,
+    responses(
+        (status = 200, description = "Unsigned delegate transaction", body = UnsignedTransaction),
+        (status = 400, description = "Invalid account", body = ErrorBody)
+    )
+)]
+pub async fn delegate_stake(
+    State(state): State<AppState>,
+    Json(request): Json<DelegateStakeRequest>,
+) -> Result<Json<UnsignedTransaction>, ApiError> {
+    let stake = parse_pubkey(&request.stake_account, "stake_account")?;
+    let authority = parse_pubkey(&request.authority, "authority")?;
+    let vote = parse_pubkey(&request.vote_account, "vote_account")?;
+    let instruction = stake_instruction::delegate_stake(&stake, &authority, &vote);
+
+    match state
+        .solana_client
+        .build_unsigned_transaction(&[instruction], &authority)
+        .await
+    {
+        Ok(transaction) => Ok(Json(transaction)),
+        Err(e) => {
+            warn!("Failed to build delegate transaction for {}: {}", stake, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/stakes/deactivate",
+    tag = "stakes",
+    request_body = DeactivateStakeRequest,
+    responses(
+        (status = 200, description = "Unsigned deactivate transaction", body = UnsignedTransaction),
+        (status = 400, description = "Invalid account", body = ErrorBody)
+    )
+)]
+pub async fn deactivate_stake(
+    State(state): State<AppState>,
+    Json(request): Json<DeactivateStakeRequest>,
+) -> Result<Json<UnsignedTransaction>, ApiError> {
+    let stake = parse_pubkey(&request.stake_account, "stake_account")?;
+    let authority = parse_pubkey(&request.authority, "authority")?;
+    let instruction = stake_instruction::deactivate_stake(&stake, &authority);
+
+    match state
+        .solana_client
+        .build_unsigned_transaction(&[instruction], &authority)
+        .await
+    {
+        Ok(transaction) => Ok(Json(transaction)),
+        Err(e) => {
+            warn!("Failed to build deactivate transaction for {}: {}", stake, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/stakes/withdraw",
+    tag = "stakes",
+    request_body = WithdrawStakeRequest,
+    responses(
+        (status = 200, description = "Unsigned withdraw transaction", body = UnsignedTransaction),
+        (status = 400, description = "Invalid account or amount", body = ErrorBody)
+    )
+)]
+pub async fn withdraw_stake(
+    State(state): State<AppState>,
+    Json(request): Json<WithdrawStakeRequest>,
+) -> Result<Json<UnsignedTransaction>, ApiError> {
+    let stake = parse_pubkey(&request.stake_account, "stake_account")?;
+    let authority = parse_pubkey(&request.authority, "authority")?;
+    let to = parse_pubkey(&request.to, "to")?;
+    if request.lamports == 0 {
+        return Err(ApiError::BadRequest("lamports must be greater than zero".to_string()));
+    }
+    let instruction = stake_instruction::withdraw(&stake, &authority, &to, request.lamports, None);
+
+    match state
+        .solana_client
+        .build_unsigned_transaction(&[instruction], &authority)
+        .await
+    {
+        Ok(transaction) => Ok(Json(transaction)),
+        Err(e) => {
+            warn!("Failed to build withdraw transaction for {}: {}", stake, e);
+            Err(e.into())
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use crate::error::{ApiError, ErrorBody};
+use crate::swap::{FeePeriod, FeeSummary};
+use crate::AppState;
+use axum::{
+    extract::{Query, State},
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tracing::warn;
+use utoipa::IntoParams;
+
+#[derive(Deserialize, IntoParams)]
+pub struct FeeStatsQuery {
+    /// `hour`, `day` (default), `week` or `month`.
+    #[param(value_type = Option<String>)]
+    pub period: Option<FeePeriod>,
+    /// Earliest swap, inclusive (RFC 3339).
+    pub from: Option<DateTime<Utc>>,
+    /// Latest swap, exclusive (RFC 3339).
+    pub to: Option<DateTime<Utc>>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/stats/fees",
+    tag = "stats",
+    params(FeeStatsQuery),
+    responses(
+        (status = 200, description = "Platform fees of swaps the gateway submitted, per token and period, newest first", body = [FeeSummary]),
+        (status = 400, description = "Invalid period or time range", body = ErrorBody),
+        (status = 403, description = "Requires the admin role", body = ErrorBody)
+    )
+)]
+pub async fn get_fee_stats(
+    State(state): State<AppState>,
+    Query(query): Query<FeeStatsQuery>,
+) -> Result<Json<Vec<FeeSummary>>, ApiError> {
+    let period = query.period.unwrap_or(FeePeriod::Day);
+    match state.database.platform_fee_summary(period, query.from, query.to).await {
+        Ok(summary) => Ok(Json(summary)),
+        Err(e) => {
+            warn!("Failed to summarize platform fees: {}", e);
+            Err(e.into())
+        }
+    }
+}
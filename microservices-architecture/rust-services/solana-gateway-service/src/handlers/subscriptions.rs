@@ -0,0 +1,326 @@
+use crate::confirmations::status_name;
+use crate::error::{ApiError, ErrorBody};
+use crate::pools::feed::NewPool;
+use crate::pools::PoolFilter;
+use crate::pubsub::LogUpdate;
+use crate::AppState;
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, Query, State,
+    },
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+};
+use futures::stream::{self, Stream};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::{TransactionConfirmationStatus, TransactionStatus};
+use std::convert::Infallible;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::warn;
+use utoipa::{IntoParams, ToSchema};
+
+/// Payload of the `status` events sent by the transaction status stream.
+#[derive(Serialize, ToSchema)]
+pub struct TransactionStatusEvent {
+    pub signature: String,
+    /// processed, confirmed, finalized, failed or expired.
+    pub status: String,
+    pub slot: Option<u64>,
+    pub error: Option<String>,
+}
+
+pub async fn subscribe_account(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+) -> Result<Response, ApiError> {
+    let pubkey = Pubkey::from_str(&address)
+        .map_err(|_| ApiError::BadRequest("Invalid public key".to_string()))?;
+    Ok(ws.on_upgrade(move |socket| stream_account_updates(socket, state, pubkey)))
+}
+
+async fn stream_account_updates(mut socket: WebSocket, state: AppState, pubkey: Pubkey) {
+    let mut updates = state.pubsub.subscribe_account(pubkey);
+
+    loop {
+        tokio::select! {
+            update = updates.recv() => match update {
+                Ok(update) => {
+                    let Ok(text) = serde_json::to_string(&update) else { continue };
+                    if socket.send(Message::Text(text)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Subscriber for {} lagged, skipped {} updates", pubkey, skipped);
+                }
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                _ => {}
+            },
+        }
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ProgramLogsQuery {
+    /// Comma-separated keywords; only transactions with a log line
+    /// containing one of them are sent. All transactions when omitted.
+    pub keywords: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/programs/{program_id}/logs/stream",
+    tag = "programs",
+    params(("program_id" = String, Path, description = "Base58 program ID"), ProgramLogsQuery),
+    responses(
+        (status = 200, description = "Server-Sent Events stream of `logs` events, one per confirmed transaction mentioning the program; the same payloads are sent as text messages when the request is a WebSocket upgrade",
+            content_type = "text/event-stream", body = LogUpdate),
+        (status = 400, description = "Invalid program ID", body = ErrorBody)
+    )
+)]
+pub async fn stream_program_logs(
+    ws: Option<WebSocketUpgrade>,
+    State(state): State<AppState>,
+    Path(program_id): Path<String>,
+    Query(query): Query<ProgramLogsQuery>,
+) -> Result<Response, ApiError> {
+    let program = Pubkey::from_str(&program_id)
+        .map_err(|_| ApiError::BadRequest("Invalid program ID".to_string()))?;
+    let filter = LogFilter::new(query.keywords.as_deref());
+    let updates = state.pubsub.subscribe_logs(program);
+
+    if let Some(ws) = ws {
+        return Ok(ws.on_upgrade(move |socket| send_program_logs(socket, updates, filter, program)));
+    }
+
+    let events = stream::unfold(updates, move |mut updates| {
+        let filter = filter.clone();
+        async move {
+            let update = next_log_update(&mut updates, &filter, &program).await?;
+            let sse = Event::default().event("logs").json_data(&update).unwrap_or_default();
+            Some((Ok::<_, Infallible>(sse), updates))
+        }
+    });
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()).into_response())
+}
+
+async fn send_program_logs(
+    mut socket: WebSocket,
+    mut updates: broadcast::Receiver<LogUpdate>,
+    filter: LogFilter,
+    program: Pubkey,
+) {
+    loop {
+        tokio::select! {
+            update = next_log_update(&mut updates, &filter, &program) => {
+                let Some(update) = update else { break };
+                let Ok(text) = serde_json::to_string(&update) else { continue };
+                if socket.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                _ => {}
+            },
+        }
+    }
+}
+
+/// The next update passing `filter`, or `None` once the upstream
+/// subscription has closed.
+async fn next_log_update(
+    updates: &mut broadcast::Receiver<LogUpdate>,
+    filter: &LogFilter,
+    program: &Pubkey,
+) -> Option<LogUpdate> {
+    loop {
+        match updates.recv().await {
+            Ok(update) if filter.matches(&update) => return Some(update),
+            Ok(_) => {}
+            Err(RecvError::Lagged(skipped)) => {
+                warn!("Log subscriber for {} lagged, skipped {} updates", program, skipped);
+            }
+            Err(RecvError::Closed) => return None,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct LogFilter {
+    keywords: Vec<String>,
+}
+
+impl LogFilter {
+    fn new(keywords: Option<&str>) -> Self {
+        let keywords = keywords
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(ToString::to_string)
+            .collect();
+        Self { keywords }
+    }
+
+    fn matches(&self, update: &LogUpdate) -> bool {
+        self.keywords.is_empty()
+            || update
+                .logs
+                .iter()
+                .any(|line| self.keywords.iter().any(|keyword| line.contains(keyword.as_str())))
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/pools/stream",
+    tag = "pools",
+    params(PoolFilter),
+    responses(
+        (status = 101, description = "WebSocket of text messages, one per newly created pool matching the filter", body = NewPool),
+        (status = 503, description = "The new pool feed is disabled", body = ErrorBody)
+    )
+)]
+pub async fn stream_new_pools(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    Query(filter): Query<PoolFilter>,
+) -> Result<Response, ApiError> {
+    if !state.pool_feed.is_enabled() {
+        return Err(ApiError::ServiceUnavailable("New pool feed is disabled".to_string()));
+    }
+    let pools = state.pool_feed.subscribe();
+    Ok(ws.on_upgrade(move |socket| send_new_pools(socket, pools, filter)))
+}
+
+async fn send_new_pools(mut socket: WebSocket, mut pools: broadcast::Receiver<NewPool>, filter: PoolFilter) {
+    loop {
+        tokio::select! {
+            pool = pools.recv() => match pool {
+                Ok(pool) if pool.matches(&filter) => {
+                    let Ok(text) = serde_json::to_string(&pool) else { continue };
+                    if socket.send(Message::Text(text)).await.is_err() {
+                        break;
+                    }
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("New pool subscriber lagged, skipped {} pools", skipped);
+                }
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                _ => {}
+            },
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/transactions/{signature}/stream",
+    tag = "transactions",
+    params(("signature" = String, Path, description = "Base58 transaction signature")),
+    responses(
+        (status = 200, description = "Server-Sent Events stream of `status` events, closed once the transaction is finalized, failed or expired",
+            content_type = "text/event-stream", body = TransactionStatusEvent),
+        (status = 400, description = "Invalid signature", body = ErrorBody)
+    )
+)]
+pub async fn stream_transaction_status(
+    State(state): State<AppState>,
+    Path(signature): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let signature = Signature::from_str(&signature)
+        .map_err(|_| ApiError::BadRequest("Invalid transaction signature".to_string()))?;
+    let config = &state.config.confirmations;
+    let watch = StatusWatch {
+        state: state.clone(),
+        signature,
+        poll_interval: Duration::from_millis(config.poll_interval_ms),
+        expires_at: Instant::now() + Duration::from_secs(config.expiry_secs.max(0) as u64),
+        last: None,
+        first_poll: true,
+    };
+
+    let events = stream::unfold(Some(watch), |watch| async move {
+        let mut watch = watch?;
+        let event = watch.next_transition().await;
+        let done = matches!(event.status.as_str(), "finalized" | "failed" | "expired" | "dropped");
+        let sse = Event::default().event("status").json_data(&event).unwrap_or_default();
+        Some((Ok(sse), (!done).then_some(watch)))
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Polls one signature and yields each status change; the stream ends after
+/// a final status.
+struct StatusWatch {
+    state: AppState,
+    signature: Signature,
+    poll_interval: Duration,
+    expires_at: Instant,
+    last: Option<&'static str>,
+    first_poll: bool,
+}
+
+impl StatusWatch {
+    async fn next_transition(&mut self) -> TransactionStatusEvent {
+        loop {
+            if !std::mem::take(&mut self.first_poll) {
+                tokio::time::sleep(self.poll_interval).await;
+            }
+
+            let status = match self.state.solana_client.get_signature_statuses(&[self.signature]).await {
+                Ok(mut statuses) => statuses.pop().flatten(),
+                Err(e) => {
+                    warn!("Failed to poll status of {}: {}", self.signature, e);
+                    continue;
+                }
+            };
+            let (name, slot, error) = match &status {
+                Some(status) => (
+                    stream_status_name(status),
+                    Some(status.slot),
+                    status.err.as_ref().map(ToString::to_string),
+                ),
+                // Only transactions the cluster has never seen can expire.
+                None if self.last.is_none() && Instant::now() >= self.expires_at => ("expired", None, None),
+                None => continue,
+            };
+            if self.last == Some(name) {
+                continue;
+            }
+
+            self.last = Some(name);
+            return TransactionStatusEvent {
+                signature: self.signature.to_string(),
+                status: name.to_string(),
+                slot,
+                error,
+            };
+        }
+    }
+}
+
+/// Like the stored status, but distinguishes `processed` from transactions
+/// the cluster hasn't seen yet.
+fn stream_status_name(status: &TransactionStatus) -> &'static str {
+    match (&status.err, &status.confirmation_status) {
+        (None, Some(TransactionConfirmationStatus::Processed)) => "processed",
+        _ => status_name(status),
+    }
+}
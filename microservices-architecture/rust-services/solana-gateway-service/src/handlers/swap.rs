@@ -0,0 +1,339 @@
+use crate::error::{ApiError, ErrorBody};
+use crate::pools::routing::{self, Route};
+use crate::quotes::{self, SignedQuote};
+use crate::solana_client::{SignMode, Simulation, TransactionInfo, UnsignedTransaction};
+use crate::swap::{self, QuoteComparison, RouteSwapRequest, SwapQuote, SwapRequest};
+use crate::AppState;
+use axum::{
+    extract::{Query, State},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use tracing::warn;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Deserialize, ToSchema)]
+pub struct SwapTransactionRequest {
+    /// Trades from and into its associated token accounts; signs and pays
+    /// for the transaction.
+    pub owner: String,
+    #[serde(flatten)]
+    pub swap: SwapRequest,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SwapTransaction {
+    pub quote: SwapQuote,
+    pub transaction: UnsignedTransaction,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ExecuteSwapRequest {
+    #[serde(flatten)]
+    pub swap: SwapRequest,
+    /// `client` returns the quote and the transaction for `owner` to sign
+    /// instead of executing the swap. Otherwise the gateway's own signer
+    /// executes it from its token accounts.
+    #[serde(default)]
+    pub sign_mode: SignMode,
+    /// Required when the client signs or for a dry run.
+    pub owner: Option<String>,
+    /// Quotes, builds and simulates the swap for `owner` without sending it.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// A swap that was built and simulated but not sent.
+#[derive(Serialize, ToSchema)]
+pub struct SimulatedSwap {
+    pub quote: SwapQuote,
+    pub simulation: Simulation,
+    pub transaction: UnsignedTransaction,
+}
+
+/// An executed swap, with `sign_mode: "client"` the quote and the
+/// transaction for the caller to sign, or for a dry run its simulation.
+#[derive(Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum SwapResponse {
+    Submitted(TransactionInfo),
+    Unsigned(SwapTransaction),
+    Simulated(SimulatedSwap),
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct RouteQuery {
+    pub input_mint: String,
+    pub output_mint: String,
+    /// Raw units of `input_mint`.
+    pub amount_in: u64,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RouteTransactionRequest {
+    /// Trades from and into its associated token accounts; signs and pays
+    /// for the transaction.
+    pub owner: String,
+    #[serde(flatten)]
+    pub swap: RouteSwapRequest,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RouteTransaction {
+    pub route: Route,
+    /// Enforced by the route's last hop.
+    pub min_amount_out: u64,
+    /// Paid to the platform treasury out of `amount_in`, on top of the
+    /// route's input.
+    pub platform_fee: u64,
+    pub transaction: UnsignedTransaction,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/swap/transaction",
+    tag = "swap",
+    request_body = SwapTransactionRequest,
+    responses(
+        (status = 200, description = "Quote and the unsigned swap transaction for the owner to sign", body = SwapTransaction),
+        (status = 400, description = "Invalid request or output below the slippage limit", body = ErrorBody),
+        (status = 403, description = "Owner or mint refused by the compliance lists", body = ErrorBody),
+        (status = 404, description = "No known pool trades the pair", body = ErrorBody),
+        (status = 422, description = "Invalid request fields", body = ErrorBody)
+    )
+)]
+pub async fn build_swap_transaction(
+    State(state): State<AppState>,
+    Json(request): Json<SwapTransactionRequest>,
+) -> Result<Json<SwapTransaction>, ApiError> {
+    quotes::require(&state.config.swap.quotes, &request.swap)?;
+    swap_transaction(&state, &request, false).await.map(Json)
+}
+
+/// Quotes a swap and builds its unsigned transaction, platform fee
+/// included. Shared with client-signed `POST /api/v1/swap`. A referenced
+/// quote is used up unless the transaction is only built to be simulated.
+pub async fn swap_transaction(
+    state: &AppState,
+    request: &SwapTransactionRequest,
+    dry_run: bool,
+) -> Result<SwapTransaction, ApiError> {
+    request.swap.validate(&state.config.swap)?;
+    let owner = Pubkey::from_str(&request.owner)
+        .map_err(|_| ApiError::BadRequest("owner is not a valid public key".to_string()))?;
+    let (swap_request, quoted) = quotes::resolve(&state.config.swap.quotes, &request.swap)?;
+
+    let built = async {
+        let (pool, quote) = swap::prepare(
+            &state.solana_client,
+            &state.database,
+            &state.price_oracle,
+            &state.config.swap,
+            &swap_request,
+        )
+        .await?;
+        state
+            .compliance
+            .check_swap(Some(&request.owner), &quote.input_mint, &quote.output_mint)?;
+        state.token_safety.check_swap_output(&quote.output_mint).await?;
+        // Building the transaction uses the quote up: the client can sign
+        // and send it without the gateway.
+        if let Some(claims) = quoted.as_ref().filter(|_| !dry_run) {
+            claims.redeem(&state.config.swap.quotes, &state.database, &quote).await?;
+        }
+        let instructions =
+            swap::transaction_ixs(&state.solana_client, &state.config.swap, &pool, &quote, &owner).await?;
+        let transaction = state
+            .solana_client
+            .build_unsigned_transaction(&instructions, &owner)
+            .await?;
+        Ok::<_, anyhow::Error>(SwapTransaction { quote, transaction })
+    };
+
+    match built.await {
+        Ok(swap) => Ok(swap),
+        Err(e) => {
+            warn!("Failed to build swap transaction for {}: {}", owner, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/swap/quote",
+    tag = "swap",
+    request_body = SwapRequest,
+    responses(
+        (status = 200, description = "Quote, with the quote ID to execute it by when signed quotes are enabled", body = SignedQuote),
+        (status = 400, description = "Invalid request or output below the slippage limit", body = ErrorBody),
+        (status = 404, description = "No known pool trades the pair", body = ErrorBody),
+        (status = 422, description = "Invalid request fields", body = ErrorBody)
+    )
+)]
+pub async fn quote_swap(
+    State(state): State<AppState>,
+    Json(request): Json<SwapRequest>,
+) -> Result<Json<SignedQuote>, ApiError> {
+    request.validate(&state.config.swap)?;
+    let quoted = async {
+        let (_, quote) = swap::prepare(
+            &state.solana_client,
+            &state.database,
+            &state.price_oracle,
+            &state.config.swap,
+            &request,
+        )
+        .await?;
+        quotes::sign(&state.config.swap.quotes, quote)
+    };
+    match quoted.await {
+        Ok(quote) => Ok(Json(quote)),
+        Err(e) => {
+            warn!("Failed to quote swap of {}: {}", request.input_mint, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/swap/routes",
+    tag = "swap",
+    params(RouteQuery),
+    responses(
+        (status = 200, description = "Direct and two-hop routes between the mints, best output first", body = [Route]),
+        (status = 422, description = "Invalid query fields", body = ErrorBody)
+    )
+)]
+pub async fn get_swap_routes(
+    State(state): State<AppState>,
+    Query(query): Query<RouteQuery>,
+) -> Result<Json<Vec<Route>>, ApiError> {
+    query.validate()?;
+    let routes = state
+        .route_cache
+        .find_routes(
+            &state.solana_client,
+            &state.database,
+            &query.input_mint,
+            &query.output_mint,
+            query.amount_in,
+        )
+        .await;
+    match routes {
+        Ok(routes) => Ok(Json(routes.into_iter().map(|(route, _)| route).collect())),
+        Err(e) => {
+            warn!("Failed to find routes from {} to {}: {}", query.input_mint, query.output_mint, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/swap/quotes",
+    tag = "swap",
+    params(RouteQuery),
+    responses(
+        (status = 200, description = "Quotes from the router, each DEX's best direct pool and Jupiter, best output first", body = QuoteComparison),
+        (status = 422, description = "Invalid query fields", body = ErrorBody)
+    )
+)]
+pub async fn get_swap_quotes(
+    State(state): State<AppState>,
+    Query(query): Query<RouteQuery>,
+) -> Result<Json<QuoteComparison>, ApiError> {
+    query.validate()?;
+    let comparison = swap::compare_quotes(&state, &query.input_mint, &query.output_mint, query.amount_in).await;
+    Ok(Json(comparison))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/swap/route/transaction",
+    tag = "swap",
+    request_body = RouteTransactionRequest,
+    responses(
+        (status = 200, description = "Best route and the unsigned transaction executing all of its hops", body = RouteTransaction),
+        (status = 400, description = "Invalid request or output below the slippage limit", body = ErrorBody),
+        (status = 403, description = "Owner or mint refused by the compliance lists", body = ErrorBody),
+        (status = 404, description = "No route trades the pair", body = ErrorBody),
+        (status = 422, description = "Invalid request fields", body = ErrorBody)
+    )
+)]
+pub async fn build_route_transaction(
+    State(state): State<AppState>,
+    Json(request): Json<RouteTransactionRequest>,
+) -> Result<Json<RouteTransaction>, ApiError> {
+    request.swap.validate(&state.config.swap)?;
+    let owner = Pubkey::from_str(&request.owner)
+        .map_err(|_| ApiError::BadRequest("owner is not a valid public key".to_string()))?;
+    let slippage_bps = request.swap.slippage_bps.unwrap_or(state.config.swap.default_slippage_bps);
+
+    let built = async {
+        let (route, pools, min_amount_out, platform_fee) = swap::prepare_route(
+            &state.solana_client,
+            &state.database,
+            &state.route_cache,
+            &state.price_oracle,
+            &state.config.swap,
+            &request.swap,
+        )
+        .await?;
+        state
+            .compliance
+            .check_swap(Some(&request.owner), &route.input_mint, &route.output_mint)?;
+        state.token_safety.check_swap_output(&route.output_mint).await?;
+        let input_mint = Pubkey::from_str(&route.input_mint)?;
+        let output_mint = Pubkey::from_str(&route.output_mint)?;
+        let wrapping =
+            swap::native_sol_ixs(&state.config.swap, &owner, &input_mint, &output_mint, request.swap.amount_in)?;
+
+        let mut instructions = wrapping.before;
+        instructions.extend(
+            swap::platform_fee_ixs(
+                &state.solana_client,
+                &state.config.swap,
+                &owner,
+                &input_mint,
+                platform_fee,
+                wrapping.account,
+            )
+            .await?,
+        );
+        instructions.extend(
+            routing::build_route_ixs(
+                &state.solana_client,
+                &route,
+                &pools,
+                &owner,
+                min_amount_out,
+                slippage_bps,
+                wrapping.account,
+            )
+            .await?,
+        );
+        instructions.extend(wrapping.after);
+        let transaction = state
+            .solana_client
+            .build_unsigned_transaction(&instructions, &owner)
+            .await?;
+        Ok::<_, anyhow::Error>(RouteTransaction {
+            route,
+            min_amount_out,
+            platform_fee,
+            transaction,
+        })
+    };
+
+    match built.await {
+        Ok(route) => Ok(Json(route)),
+        Err(e) => {
+            warn!("Failed to build route transaction for {}: {}", owner, e);
+            Err(e.into())
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use crate::auth::Principal;
+use crate::error::{ApiError, ErrorBody};
+use crate::solana_client::{OwnedTokenAccount, UnsignedTransaction};
+use crate::tokens::TokenProgram;
+use crate::wallets;
+use crate::AppState;
+use axum::{
+    extract::{Path, State},
+    response::Json,
+    Extension,
+};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use std::str::FromStr;
+use tracing::warn;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Close instructions per cleanup transaction, well within the packet size.
+const CLOSES_PER_TRANSACTION: usize = 20;
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateTokenAccountRequest {
+    pub mint: String,
+    /// Stored wallet that pays for and submits the creation. Takes precedence
+    /// over `payer`.
+    pub payer_wallet_id: Option<Uuid>,
+    /// Fee payer of the unsigned transaction returned when no wallet is
+    /// given; defaults to the owner.
+    pub payer: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TokenAccountCreation {
+    /// Associated token account address.
+    pub address: String,
+    pub owner: String,
+    pub mint: String,
+    pub program: TokenProgram,
+    /// True when the account already existed and nothing was built.
+    pub exists: bool,
+    /// Set when a stored wallet submitted the creation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// Set when the caller has to sign and submit the creation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<UnsignedTransaction>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct TokenAccountCleanupRequest {
+    /// Stored wallet of the owner that signs and submits the closes. Without
+    /// one, unsigned transactions are returned.
+    pub wallet_id: Option<Uuid>,
+    /// Receives the reclaimed rent; defaults to the owner.
+    pub destination: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct EmptyTokenAccount {
+    pub address: String,
+    pub mint: String,
+    pub program: TokenProgram,
+    /// Returned to the destination when the account is closed.
+    pub lamports: u64,
+    /// Why the owner can't close the account, e.g. `frozen`; None for the
+    /// accounts being closed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<&'static str>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TokenAccountCleanup {
+    pub owner: String,
+    pub destination: String,
+    /// Empty token accounts closed, or to close.
+    pub closed: Vec<EmptyTokenAccount>,
+    /// Empty token accounts the owner can't close.
+    pub skipped: Vec<EmptyTokenAccount>,
+    /// Rent reclaimed from `closed`, before transaction fees.
+    pub reclaimed_lamports: u64,
+    /// Set when a stored wallet submitted the closes, one per transaction.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub signatures: Vec<String>,
+    /// Set when the owner has to sign and submit the closes.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub transactions: Vec<UnsignedTransaction>,
+}
+
+impl EmptyTokenAccount {
+    /// Describes `owned` when it holds no tokens, with the reason it can't
+    /// be closed by its owner, if any.
+    fn new(owned: OwnedTokenAccount) -> Option<Self> {
+        let account = &owned.account;
+        if account.amount != 0 {
+            return None;
+        }
+        let skipped = if account.frozen {
+            Some("frozen")
+        } else if account.withheld_amount.map_or(false, |withheld| withheld > 0) {
+            Some("withheld_transfer_fees")
+        } else if account.close_authority.map_or(false, |authority| authority != account.owner) {
+            Some("close_authority")
+        } else {
+            None
+        };
+        Some(Self {
+            address: owned.address.to_string(),
+            mint: account.mint.to_string(),
+            program: account.program,
+            lamports: owned.lamports,
+            skipped,
+        })
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/accounts/{address}/token-accounts",
+    tag = "accounts",
+    params(("address" = String, Path, description = "Base58 owner address")),
+    request_body = CreateTokenAccountRequest,
+    responses(
+        (status = 200, description = "Existing account, submitted creation or unsigned creation transaction", body = TokenAccountCreation),
+        (status = 400, description = "Invalid owner, mint or payer", body = ErrorBody),
+        (status = 404, description = "Mint or payer wallet not found", body = ErrorBody)
+    )
+)]
+pub async fn create_token_account(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(address): Path<String>,
+    Json(request): Json<CreateTokenAccountRequest>,
+) -> Result<Json<TokenAccountCreation>, ApiError> {
+    let principal = principal.as_ref().map(|Extension(principal)| principal);
+    let created = async {
+        let owner = Pubkey::from_str(&address)?;
+        let mint = Pubkey::from_str(&request.mint)?;
+        let ata = state.solana_client.associated_token_account(&owner, &mint).await?;
+
+        let mut creation = TokenAccountCreation {
+            address: ata.address.to_string(),
+            owner: owner.to_string(),
+            mint: mint.to_string(),
+            program: ata.program,
+            exists: ata.exists,
+            signature: None,
+            transaction: None,
+        };
+        if ata.exists {
+            return Ok(creation);
+        }
+
+        match request.payer_wallet_id {
+            Some(wallet_id) => {
+                let payer = state.wallets.keypair(wallets::scope(principal), wallet_id).await?;
+                let instruction =
+                    create_associated_token_account_idempotent(&payer.pubkey(), &owner, &mint, &ata.program.id());
+                let signature = state.solana_client.sign_and_send(&[instruction], &[&payer]).await?;
+                creation.signature = Some(signature.to_string());
+            }
+            None => {
+                let payer = match &request.payer {
+                    Some(payer) => Pubkey::from_str(payer)?,
+                    None => owner,
+                };
+                let instruction =
+                    create_associated_token_account_idempotent(&payer, &owner, &mint, &ata.program.id());
+                creation.transaction = Some(
+                    state
+                        .solana_client
+                        .build_unsigned_transaction(&[instruction], &payer)
+                        .await?,
+                );
+            }
+        }
+        Ok::<_, anyhow::Error>(creation)
+    };
+
+    match created.await {
+        Ok(creation) => Ok(Json(creation)),
+        Err(e) => {
+            warn!("Failed to create token account for {}: {}", address, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/accounts/{address}/token-accounts/cleanup",
+    tag = "accounts",
+    params(("address" = String, Path, description = "Base58 owner address")),
+    request_body = TokenAccountCleanupRequest,
+    responses(
+        (status = 200, description = "Empty token accounts with submitted or unsigned close transactions", body = TokenAccountCleanup),
+        (status = 400, description = "Invalid owner or destination, or wallet is not the owner", body = ErrorBody),
+        (status = 404, description = "Wallet not found", body = ErrorBody)
+    )
+)]
+pub async fn cleanup_token_accounts(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(address): Path<String>,
+    Json(request): Json<TokenAccountCleanupRequest>,
+) -> Result<Json<TokenAccountCleanup>, ApiError> {
+    let principal = principal.as_ref().map(|Extension(principal)| principal);
+    let cleaned = async {
+        let owner = Pubkey::from_str(&address)?;
+        let destination = match &request.destination {
+            Some(destination) => Pubkey::from_str(destination)?,
+            None => owner,
+        };
+        let (closed, skipped): (Vec<_>, Vec<_>) = state
+            .solana_client
+            .owned_token_accounts(&owner)
+            .await?
+            .into_iter()
+            .filter_map(EmptyTokenAccount::new)
+            .partition(|account| account.skipped.is_none());
+
+        let mut cleanup = TokenAccountCleanup {
+            owner: owner.to_string(),
+            destination: destination.to_string(),
+            reclaimed_lamports: closed.iter().map(|account| account.lamports).sum(),
+            closed,
+            skipped,
+            signatures: Vec::new(),
+            transactions: Vec::new(),
+        };
+        let signer = match request.wallet_id {
+            Some(wallet_id) => Some(
+                state
+                    .wallets
+                    .signer_for(wallets::scope(principal), wallet_id, &address)
+                    .await?,
+            ),
+            None => None,
+        };
+        for batch in cleanup.closed.chunks(CLOSES_PER_TRANSACTION) {
+            let instructions = batch
+                .iter()
+                .map(|account| {
+                    let pubkey = Pubkey::from_str(&account.address)?;
+                    spl_token_2022::instruction::close_account(
+                        &account.program.id(),
+                        &pubkey,
+                        &destination,
+                        &owner,
+                        &[],
+                    )
+                    .map_err(anyhow::Error::from)
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            match &signer {
+                Some(signer) => {
+                    let signature = state.solana_client.sign_and_send(&instructions, &[signer]).await?;
+                    cleanup.signatures.push(signature.to_string());
+                }
+                None => {
+                    let transaction = state
+                        .solana_client
+                        .build_unsigned_transaction(&instructions, &owner)
+                        .await?;
+                    cleanup.transactions.push(transaction);
+                }
+            }
+        }
+        Ok::<_, anyhow::Error>(cleanup)
+    };
+
+    match cleaned.await {
+        Ok(cleanup) => Ok(Json(cleanup)),
+        Err(e) => {
+            warn!("Failed to clean up token accounts of {}: {}", address, e);
+            Err(e.into())
+        }
+    }
+}
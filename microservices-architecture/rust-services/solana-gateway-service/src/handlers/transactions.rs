@@ -0,0 +1,166 @@
+use crate::confirmations::status_name;
+use crate::error::{ApiError, ErrorBody};
+use crate::idempotency::{run_idempotent, IdempotencyKey};
+use crate::memo;
+use crate::solana_client::TransactionInfo;
+use crate::AppState;
+use axum::{extract::State, response::Json};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{packet::PACKET_DATA_SIZE, signature::Signature, transaction::VersionedTransaction};
+use std::str::FromStr;
+use tracing::warn;
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct SignedTransactionRequest {
+    /// Base64 bincode-serialized transaction, legacy or versioned, carrying
+    /// every required signature.
+    pub transaction: String,
+}
+
+/// Most signatures accepted by `POST /api/v1/transactions/statuses`, the
+/// limit of a single `getSignatureStatuses` call.
+pub const MAX_STATUS_SIGNATURES: usize = 256;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct SignatureStatusesRequest {
+    pub signatures: Vec<String>,
+    /// Also searches the ledger beyond the recent status cache, which
+    /// finds older transactions but is slower.
+    #[serde(default)]
+    pub search_history: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SignatureStatus {
+    pub signature: String,
+    /// `pending`, `confirmed`, `finalized` or `failed`.
+    pub status: String,
+    pub slot: u64,
+    /// Blocks confirming the transaction; absent once it is finalized.
+    pub confirmations: Option<usize>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SignatureStatuses {
+    /// One entry per requested signature, in request order; `null` when
+    /// the cluster doesn't know the signature.
+    pub statuses: Vec<Option<SignatureStatus>>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/transactions/statuses",
+    tag = "transactions",
+    request_body = SignatureStatusesRequest,
+    responses(
+        (status = 200, description = "Statuses of every signature", body = SignatureStatuses),
+        (status = 422, description = "Invalid or too many signatures", body = ErrorBody),
+        (status = 503, description = "RPC unavailable", body = ErrorBody)
+    )
+)]
+pub async fn get_signature_statuses(
+    State(state): State<AppState>,
+    Json(request): Json<SignatureStatusesRequest>,
+) -> Result<Json<SignatureStatuses>, ApiError> {
+    request.validate()?;
+    let signatures = request
+        .signatures
+        .iter()
+        .map(|signature| Signature::from_str(signature))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(anyhow::Error::from)?;
+
+    let statuses = if request.search_history {
+        state.solana_client.get_signature_statuses_with_history(&signatures).await
+    } else {
+        state.solana_client.get_signature_statuses(&signatures).await
+    };
+    match statuses {
+        Ok(statuses) => Ok(Json(SignatureStatuses {
+            statuses: signatures
+                .iter()
+                .zip(statuses)
+                .map(|(signature, status)| {
+                    status.map(|status| SignatureStatus {
+                        signature: signature.to_string(),
+                        status: status_name(&status).to_string(),
+                        slot: status.slot,
+                        confirmations: status.confirmations,
+                        error: status.err.as_ref().map(|err| err.to_string()),
+                    })
+                })
+                .collect(),
+        })),
+        Err(e) => {
+            warn!("Failed to get statuses of {} signatures: {}", signatures.len(), e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/transactions/submit",
+    tag = "transactions",
+    request_body = SignedTransactionRequest,
+    params(("Idempotency-Key" = Option<String>, Header, description = "Deduplicates retried submissions")),
+    responses(
+        (status = 200, description = "Transaction submitted", body = TransactionInfo),
+        (status = 400, description = "Malformed transaction, missing signatures or dry-run mode", body = ErrorBody),
+        (status = 409, description = "Idempotency key conflict", body = ErrorBody),
+        (status = 429, description = "Rate limit exceeded", body = ErrorBody)
+    )
+)]
+pub async fn submit_signed_transaction(
+    State(state): State<AppState>,
+    IdempotencyKey(key): IdempotencyKey,
+    Json(request): Json<SignedTransactionRequest>,
+) -> Result<Json<TransactionInfo>, ApiError> {
+    if state.config.dry_run {
+        return Err(ApiError::BadRequest("transactions are not sent in dry-run mode".to_string()));
+    }
+    let transaction = BASE64
+        .decode(&request.transaction)
+        .ok()
+        .filter(|bytes| bytes.len() <= PACKET_DATA_SIZE)
+        .and_then(|bytes| bincode::deserialize::<VersionedTransaction>(&bytes).ok())
+        .ok_or_else(|| ApiError::BadRequest("transaction is not a base64-encoded Solana transaction".to_string()))?;
+
+    let submit = || async {
+        match state.solana_client.send_signed_transaction(&transaction).await {
+            Ok(signature) => {
+                let response = TransactionInfo {
+                    signature: signature.to_string(),
+                    status: "pending".to_string(),
+                    slot: None,
+                    accounts: transaction
+                        .message
+                        .static_account_keys()
+                        .iter()
+                        .map(|key| key.to_string())
+                        .collect(),
+                    fee: None,
+                    block_time: None,
+                    memo: memo::from_message(&transaction.message),
+                    replaced_by: None,
+                };
+                if let Err(e) = state.database.record_transaction(&response.to_record(None)).await {
+                    warn!("Failed to persist transaction {}: {}", response.signature, e);
+                }
+                Ok(response)
+            }
+            Err(e) => {
+                warn!("Failed to submit signed transaction: {}", e);
+                Err(ApiError::from(e))
+            }
+        }
+    };
+
+    let ttl = state.config.idempotency_ttl();
+    run_idempotent(&state.database, key.as_deref(), "transactions/submit", &request, ttl, submit)
+        .await
+        .map(Json)
+}
@@ -0,0 +1,61 @@
+use crate::api_keys::{ApiKeyGrant, ApiKeyUsage};
+use crate::error::{ApiError, ErrorBody};
+use crate::swap::FeePeriod;
+use crate::AppState;
+use axum::{
+    extract::{Query, State},
+    response::Json,
+    Extension,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tracing::warn;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+#[derive(Deserialize, IntoParams)]
+pub struct UsageQuery {
+    /// `hour`, `day` (default), `week` or `month`.
+    #[param(value_type = Option<String>)]
+    pub period: Option<FeePeriod>,
+    /// Earliest hour, inclusive (RFC 3339).
+    pub from: Option<DateTime<Utc>>,
+    /// Latest hour, exclusive (RFC 3339).
+    pub to: Option<DateTime<Utc>>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/me/usage",
+    tag = "usage",
+    params(UsageQuery),
+    responses(
+        (status = 200, description = "Usage of the calling API key per period, newest first", body = ApiKeyUsage),
+        (status = 400, description = "Invalid period or time range", body = ErrorBody),
+        (status = 403, description = "Caller did not authenticate with an API key", body = ErrorBody)
+    )
+)]
+pub async fn get_my_usage(
+    State(state): State<AppState>,
+    grant: Option<Extension<ApiKeyGrant>>,
+    Query(query): Query<UsageQuery>,
+) -> Result<Json<ApiKeyUsage>, ApiError> {
+    let Some(Extension(grant)) = grant else {
+        return Err(ApiError::Forbidden("Usage is tracked per API key".to_string()));
+    };
+    usage(&state, grant.id, &query).await.map(Json)
+}
+
+/// Usage of a key per the query's period. Shared with
+/// `GET /admin/api-keys/:id/usage`.
+pub async fn usage(state: &AppState, id: Uuid, query: &UsageQuery) -> Result<ApiKeyUsage, ApiError> {
+    let period = query.period.unwrap_or(FeePeriod::Day);
+    match state.database.api_key_usage(id, period, query.from, query.to).await {
+        Ok(Some(usage)) => Ok(usage),
+        Ok(None) => Err(ApiError::NotFound(format!("API key {} not found", id))),
+        Err(e) => {
+            warn!("Failed to summarize usage of API key {}: {}", id, e);
+            Err(e.into())
+        }
+    }
+}
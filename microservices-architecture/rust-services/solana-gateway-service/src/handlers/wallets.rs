@@ -0,0 +1,169 @@
+use crate::auth::Principal;
+use crate::client_references;
+use crate::error::{ApiError, ErrorBody};
+use crate::wallets::{self, Wallet};
+use crate::AppState;
+use axum::{
+    extract::{Path, State},
+    response::Json,
+    Extension,
+};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateWalletRequest {
+    pub label: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ImportWalletRequest {
+    pub label: Option<String>,
+    /// Base58 secret key or a `solana-keygen` JSON byte array.
+    pub secret_key: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ExportedWallet {
+    pub id: Uuid,
+    pub pubkey: String,
+    /// Base58-encoded 64-byte keypair.
+    pub secret_key: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/wallets",
+    tag = "wallets",
+    request_body = CreateWalletRequest,
+    responses(
+        (status = 200, description = "Generated wallet, owned by the caller", body = Wallet),
+        (status = 503, description = "Wallet storage not configured", body = ErrorBody)
+    )
+)]
+pub async fn create_wallet(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Json(request): Json<CreateWalletRequest>,
+) -> Result<Json<Wallet>, ApiError> {
+    let principal = principal.as_ref().map(|Extension(principal)| principal);
+    match state.wallets.create(client_references::owner(principal), request.label).await {
+        Ok(wallet) => Ok(Json(wallet)),
+        Err(e) => {
+            warn!("Failed to create wallet: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/wallets/import",
+    tag = "wallets",
+    request_body = ImportWalletRequest,
+    responses(
+        (status = 200, description = "Imported wallet, owned by the caller", body = Wallet),
+        (status = 400, description = "Invalid secret key", body = ErrorBody),
+        (status = 409, description = "Wallet already exists", body = ErrorBody)
+    )
+)]
+pub async fn import_wallet(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Json(request): Json<ImportWalletRequest>,
+) -> Result<Json<Wallet>, ApiError> {
+    let principal = principal.as_ref().map(|Extension(principal)| principal);
+    match state
+        .wallets
+        .import(client_references::owner(principal), request.label, &request.secret_key)
+        .await
+    {
+        Ok(wallet) => Ok(Json(wallet)),
+        Err(e) => {
+            warn!("Failed to import wallet: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/wallets",
+    tag = "wallets",
+    responses((status = 200, description = "The caller's stored wallets, or every wallet for admins", body = [Wallet]))
+)]
+pub async fn list_wallets(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+) -> Result<Json<Vec<Wallet>>, ApiError> {
+    let principal = principal.as_ref().map(|Extension(principal)| principal);
+    match state.wallets.list(wallets::scope(principal)).await {
+        Ok(wallets) => Ok(Json(wallets)),
+        Err(e) => {
+            warn!("Failed to list wallets: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/wallets/{id}",
+    tag = "wallets",
+    params(("id" = Uuid, Path, description = "Wallet ID")),
+    responses(
+        (status = 200, description = "Wallet", body = Wallet),
+        (status = 404, description = "Wallet not found or owned by another caller", body = ErrorBody)
+    )
+)]
+pub async fn get_wallet(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Wallet>, ApiError> {
+    let principal = principal.as_ref().map(|Extension(principal)| principal);
+    match state.wallets.get(wallets::scope(principal), id).await {
+        Ok(wallet) => Ok(Json(wallet)),
+        Err(e) => {
+            warn!("Failed to get wallet {}: {}", id, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/wallets/{id}/export",
+    tag = "wallets",
+    params(("id" = Uuid, Path, description = "Wallet ID")),
+    responses(
+        (status = 200, description = "Decrypted secret key", body = ExportedWallet),
+        (status = 403, description = "Export disabled", body = ErrorBody),
+        (status = 404, description = "Wallet not found", body = ErrorBody)
+    )
+)]
+pub async fn export_wallet(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ExportedWallet>, ApiError> {
+    let exported = async {
+        // Only admins reach export, and they may export any wallet.
+        let wallet = state.wallets.get(None, id).await?;
+        let secret_key = state.wallets.export(id).await?;
+        Ok::<_, anyhow::Error>(ExportedWallet {
+            id,
+            pubkey: wallet.pubkey,
+            secret_key,
+        })
+    };
+
+    match exported.await {
+        Ok(exported) => Ok(Json(exported)),
+        Err(e) => {
+            warn!("Failed to export wallet {}: {}", id, e);
+            Err(e.into())
+        }
+    }
+}
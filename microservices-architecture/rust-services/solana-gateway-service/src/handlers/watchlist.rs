@@ -0,0 +1,167 @@
+use crate::error::{ApiError, ErrorBody};
+use crate::pagination::{PageQuery, Pagination};
+use crate::watchlist::{AccountChange, AuthorityChange, WatchRequest, WatchedAccount};
+use crate::AppState;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::Serialize;
+use tracing::warn;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct AccountChangeLog {
+    pub changes: Vec<AccountChange>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AuthorityChangeLog {
+    pub changes: Vec<AuthorityChange>,
+    pub next_cursor: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/watchlist",
+    tag = "watchlist",
+    request_body = WatchRequest,
+    responses(
+        (status = 200, description = "Watched account; changes are recorded from its first observation on", body = WatchedAccount),
+        (status = 400, description = "Watchlist is full", body = ErrorBody),
+        (status = 409, description = "Account is already watched", body = ErrorBody),
+        (status = 422, description = "Invalid request fields", body = ErrorBody)
+    )
+)]
+pub async fn watch_account(
+    State(state): State<AppState>,
+    Json(request): Json<WatchRequest>,
+) -> Result<Json<WatchedAccount>, ApiError> {
+    request.validate()?;
+    let max_accounts = state.config.watchlist.max_accounts;
+    if state.database.count_watched_accounts().await? >= max_accounts {
+        return Err(ApiError::BadRequest(format!(
+            "Watchlist is full ({} accounts)",
+            max_accounts
+        )));
+    }
+
+    match state
+        .database
+        .insert_watched_account(&request.address, request.label.as_deref())
+        .await
+    {
+        Ok(Some(account)) => Ok(Json(account)),
+        Ok(None) => Err(ApiError::Conflict(format!("Account {} is already watched", request.address))),
+        Err(e) => {
+            warn!("Failed to watch account {}: {}", request.address, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/watchlist",
+    tag = "watchlist",
+    responses((status = 200, description = "Watched accounts with their last observed state", body = [WatchedAccount]))
+)]
+pub async fn list_watchlist(State(state): State<AppState>) -> Result<Json<Vec<WatchedAccount>>, ApiError> {
+    match state
+        .database
+        .list_watched_accounts(state.config.watchlist.max_accounts)
+        .await
+    {
+        Ok(accounts) => Ok(Json(accounts)),
+        Err(e) => {
+            warn!("Failed to list watchlist: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/watchlist/{address}",
+    tag = "watchlist",
+    params(("address" = String, Path, description = "Watched account address")),
+    responses(
+        (status = 204, description = "Account removed along with its recorded changes"),
+        (status = 404, description = "Account is not watched", body = ErrorBody)
+    )
+)]
+pub async fn unwatch_account(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    match state.database.delete_watched_account(&address).await {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(ApiError::NotFound(format!("Account {} is not watched", address))),
+        Err(e) => {
+            warn!("Failed to unwatch account {}: {}", address, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/watchlist/{address}/changes",
+    tag = "watchlist",
+    params(("address" = String, Path, description = "Watched account address"), PageQuery),
+    responses(
+        (status = 200, description = "Detected changes, newest first", body = AccountChangeLog),
+        (status = 400, description = "Invalid paging parameters", body = ErrorBody)
+    )
+)]
+pub async fn get_account_changes(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    pagination: Pagination,
+) -> Result<Json<AccountChangeLog>, ApiError> {
+    match state.database.list_account_changes(&address, &pagination).await {
+        Ok(changes) => {
+            let page = pagination.page(changes, |change| change.id.to_string());
+            Ok(Json(AccountChangeLog {
+                changes: page.items,
+                next_cursor: page.next_cursor,
+            }))
+        }
+        Err(e) => {
+            warn!("Failed to get changes for {}: {}", address, e);
+            Err(e.into())
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/watchlist/{address}/authority-changes",
+    tag = "watchlist",
+    params(("address" = String, Path, description = "Watched account address"), PageQuery),
+    responses(
+        (status = 200, description = "Detected owner and authority changes, newest first", body = AuthorityChangeLog),
+        (status = 400, description = "Invalid paging parameters", body = ErrorBody)
+    )
+)]
+pub async fn get_authority_changes(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    pagination: Pagination,
+) -> Result<Json<AuthorityChangeLog>, ApiError> {
+    match state.database.list_authority_changes(&address, &pagination).await {
+        Ok(changes) => {
+            let page = pagination.page(changes, |change| change.id.to_string());
+            Ok(Json(AuthorityChangeLog {
+                changes: page.items,
+                next_cursor: page.next_cursor,
+            }))
+        }
+        Err(e) => {
+            warn!("Failed to get authority changes for {}: {}", address, e);
+            Err(e.into())
+        }
+    }
+}
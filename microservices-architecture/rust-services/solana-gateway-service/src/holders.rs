@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use spl_token_2022::{extension::StateWithExtensions, state::Mint};
+use utoipa::ToSchema;
+
+/// Largest number of accounts `getTokenLargestAccounts` returns.
+pub const MAX_LARGEST_ACCOUNTS: usize = 20;
+// Token account layout shared by both token programs: mint at 0, amount at 64.
+pub const TOKEN_ACCOUNT_MINT_OFFSET: usize = 0;
+pub const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct TokenHolder {
+    pub token_account: String,
+    /// Wallet owning the token account; absent if it could not be loaded.
+    pub owner: Option<String>,
+    pub amount: u64,
+    pub ui_amount: f64,
+    /// Share of the current supply.
+    pub percent: f64,
+}
+
+/// The largest accounts of a mint and how concentrated its supply is.
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct HolderDistribution {
+    pub mint: String,
+    pub supply: u64,
+    pub decimals: u8,
+    /// Largest token accounts, biggest first.
+    pub holders: Vec<TokenHolder>,
+    /// Share of the supply held by the listed accounts together.
+    pub top_holders_percent: f64,
+    /// Token accounts of the mint with a non-zero balance, when requested.
+    /// One wallet may hold several accounts, so this estimates holders
+    /// from above.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub holder_count: Option<u64>,
+}
+
+/// Supply and decimals of a legacy or Token-2022 mint.
+pub fn parse_supply(data: &[u8]) -> Option<(u64, u8)> {
+    let state = StateWithExtensions::<Mint>::unpack(data).ok()?;
+    Some((state.base.supply, state.base.decimals))
+}
+
+pub fn percent_of(amount: u64, supply: u64) -> f64 {
+    if supply == 0 {
+        return 0.0;
+    }
+    amount as f64 / supply as f64 * 100.0
+}
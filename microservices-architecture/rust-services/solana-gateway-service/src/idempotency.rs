@@ -0,0 +1,147 @@
+use crate::database::{Database, IdempotencyClaim};
+use crate::error::ApiError;
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+const HEADER: &str = "idempotency-key";
+const MAX_KEY_LEN: usize = 255;
+const PURGE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Value of the optional `Idempotency-Key` request header.
+pub struct IdempotencyKey(pub Option<String>);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for IdempotencyKey {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(value) = parts.headers.get(HEADER) else {
+            return Ok(IdempotencyKey(None));
+        };
+        let key = value
+            .to_str()
+            .ok()
+            .filter(|key| !key.is_empty() && key.len() <= MAX_KEY_LEN)
+            .ok_or_else(|| ApiError::BadRequest("Invalid Idempotency-Key header".to_string()))?;
+        Ok(IdempotencyKey(Some(key.to_string())))
+    }
+}
+
+/// Releases a claimed key if the request future is dropped before the handler
+/// finishes, e.g. because the client disconnected, so the key isn't stuck in
+/// progress until it expires.
+struct PendingClaim {
+    database: Arc<Database>,
+    key: String,
+    route: String,
+    armed: bool,
+}
+
+impl Drop for PendingClaim {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        let database = self.database.clone();
+        let key = std::mem::take(&mut self.key);
+        let route = std::mem::take(&mut self.route);
+        tokio::spawn(async move {
+            if let Err(e) = database.release_idempotency_key(&key, &route).await {
+                warn!("Failed to release abandoned idempotency key {}: {}", key, e);
+            }
+        });
+    }
+}
+
+/// Runs `handler` at most once per (key, route). A replay with the same
+/// payload returns the stored response; a failed or abandoned attempt
+/// releases the key so the client can retry.
+pub async fn run_idempotent<R, T, F, Fut>(
+    database: &Arc<Database>,
+    key: Option<&str>,
+    route: &str,
+    request: &R,
+    ttl: Duration,
+    handler: F,
+) -> Result<T, ApiError>
+where
+    R: Serialize,
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T, ApiError>>,
+{
+    let Some(key) = key else {
+        return handler().await;
+    };
+
+    let request_hash = hex_sha256(&serde_json::to_vec(request).unwrap_or_default());
+    match database
+        .claim_idempotency_key(key, route, &request_hash, ttl)
+        .await?
+    {
+        IdempotencyClaim::Claimed => {}
+        IdempotencyClaim::Completed(response) => {
+            return serde_json::from_value(response)
+                .map_err(|e| ApiError::Internal(format!("Corrupt idempotent response: {}", e)));
+        }
+        IdempotencyClaim::InProgress => {
+            return Err(ApiError::Conflict(
+                "A request with this Idempotency-Key is still in progress".to_string(),
+            ));
+        }
+        IdempotencyClaim::PayloadMismatch => {
+            return Err(ApiError::Conflict(
+                "Idempotency-Key was already used with a different payload".to_string(),
+            ));
+        }
+    }
+
+    let mut claim = PendingClaim {
+        database: database.clone(),
+        key: key.to_string(),
+        route: route.to_string(),
+        armed: true,
+    };
+    let result = handler().await;
+    claim.armed = false;
+
+    let stored = match &result {
+        Ok(response) => match serde_json::to_value(response) {
+            Ok(value) => database.complete_idempotency_key(key, route, &value).await,
+            Err(e) => Err(e.into()),
+        },
+        Err(_) => database.release_idempotency_key(key, route).await,
+    };
+    if let Err(e) = stored {
+        warn!("Failed to update idempotency key {}: {}", key, e);
+    }
+
+    result
+}
+
+pub fn spawn_purge(database: Arc<Database>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = database.purge_expired_idempotency_keys().await {
+                warn!("Failed to purge expired idempotency keys: {}", e);
+            }
+            if let Err(e) = database.purge_expired_quotes().await {
+                warn!("Failed to purge expired quotes: {}", e);
+            }
+        }
+    });
+}
+
+fn hex_sha256(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
@@ -0,0 +1,142 @@
+use crate::config::JitoConfig;
+use crate::error::ApiError;
+use crate::request_id;
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::json;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, system_instruction, transaction::Transaction};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+const BUNDLES_PATH: &str = "/api/v1/bundles";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum BundleStatus {
+    Pending,
+    Landed,
+    Failed,
+    Invalid,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcErrorBody>,
+}
+
+#[derive(Deserialize)]
+struct RpcErrorBody {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct InflightStatuses {
+    value: Vec<InflightStatus>,
+}
+
+#[derive(Deserialize)]
+struct InflightStatus {
+    status: BundleStatus,
+}
+
+/// Submits transactions to a Jito block engine as bundles, which land
+/// atomically and skip the public mempool, so swaps can't be sandwiched.
+/// Validators only accept bundles carrying a tip to one of their tip accounts.
+pub struct JitoClient {
+    http: reqwest::Client,
+    config: JitoConfig,
+    tip_accounts: Vec<Pubkey>,
+    next_tip_account: AtomicUsize,
+}
+
+impl JitoClient {
+    pub fn new(config: &JitoConfig) -> Result<Self> {
+        let tip_accounts = config
+            .tip_accounts
+            .iter()
+            .map(|account| Pubkey::from_str(account).with_context(|| format!("Invalid Jito tip account {}", account)))
+            .collect::<Result<Vec<_>>>()?;
+        if config.enabled && tip_accounts.is_empty() {
+            anyhow::bail!("At least one Jito tip account must be configured");
+        }
+
+        Ok(Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(config.request_timeout_secs))
+                .build()?,
+            config: config.clone(),
+            tip_accounts,
+            next_tip_account: AtomicUsize::new(0),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Tip transfer from `payer`, rotating through the tip accounts to spread
+    /// write locks across them.
+    pub fn tip_instruction(&self, payer: &Pubkey) -> Instruction {
+        let index = self.next_tip_account.fetch_add(1, Ordering::Relaxed) % self.tip_accounts.len();
+        system_instruction::transfer(payer, &self.tip_accounts[index], self.config.tip_lamports)
+    }
+
+    /// Sends signed transactions as one bundle and returns its id.
+    pub async fn send_bundle(&self, transactions: &[Transaction]) -> Result<String> {
+        let encoded = transactions
+            .iter()
+            .map(|transaction| Ok(BASE64.encode(bincode::serialize(transaction)?)))
+            .collect::<Result<Vec<_>>>()?;
+        self.call("sendBundle", json!([encoded, { "encoding": "base64" }])).await
+    }
+
+    /// Status of a bundle submitted within the last five minutes.
+    pub async fn bundle_status(&self, bundle_id: &str) -> Result<BundleStatus> {
+        let statuses: InflightStatuses = self.call("getInflightBundleStatuses", json!([[bundle_id]])).await?;
+        Ok(statuses
+            .value
+            .first()
+            .map_or(BundleStatus::Pending, |status| status.status))
+    }
+
+    /// Polls until the bundle lands, is rejected or the configured timeout
+    /// passes, which is reported as still pending.
+    pub async fn wait_for_bundle(&self, bundle_id: &str) -> Result<BundleStatus> {
+        let deadline = Instant::now() + Duration::from_secs(self.config.status_timeout_secs);
+        let interval = Duration::from_millis(self.config.status_poll_interval_ms);
+        loop {
+            match self.bundle_status(bundle_id).await {
+                Ok(BundleStatus::Pending) => {}
+                Ok(status) => return Ok(status),
+                Err(e) => warn!("Failed to poll bundle {}: {}", bundle_id, e),
+            }
+            if Instant::now() + interval > deadline {
+                return Ok(BundleStatus::Pending);
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    async fn call<T: DeserializeOwned>(&self, method: &str, params: serde_json::Value) -> Result<T> {
+        let url = format!("{}{}", self.config.block_engine_url.trim_end_matches('/'), BUNDLES_PATH);
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: RpcResponse<T> = request_id::tag(self.http.post(&url))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| ApiError::ServiceUnavailable(format!("Jito block engine unavailable: {}", e)))?
+            .error_for_status()
+            .map_err(|e| ApiError::ServiceUnavailable(format!("Jito block engine rejected {}: {}", method, e)))?
+            .json()
+            .await?;
+
+        match (response.result, response.error) {
+            (Some(result), _) => Ok(result),
+            (None, Some(error)) => Err(ApiError::BadRequest(format!("Jito {} failed: {}", method, error.message)).into()),
+            (None, None) => anyhow::bail!("Jito {} returned no result", method),
+        }
+    }
+}
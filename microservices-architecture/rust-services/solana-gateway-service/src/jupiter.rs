@@ -0,0 +1,99 @@
+use crate::config::JupiterConfig;
+use crate::error::ApiError;
+use crate::request_id;
+use anyhow::Result;
+use serde::Deserialize;
+use std::time::Duration;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JupiterQuote {
+    #[serde(with = "u64_string")]
+    pub out_amount: u64,
+    /// Fraction of the spot value lost to price impact, e.g. 0.0012.
+    #[serde(default)]
+    pub price_impact_pct: Option<String>,
+    pub route_plan: Vec<RoutePlanStep>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutePlanStep {
+    pub swap_info: SwapInfo,
+    /// Share of the input sent along this step's split.
+    pub percent: u8,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapInfo {
+    pub amm_key: String,
+    pub label: Option<String>,
+    pub input_mint: String,
+    pub output_mint: String,
+    #[serde(with = "u64_string")]
+    pub in_amount: u64,
+    #[serde(with = "u64_string")]
+    pub out_amount: u64,
+    #[serde(with = "u64_string")]
+    pub fee_amount: u64,
+}
+
+/// Quotes from Jupiter's aggregator, for comparison with the gateway's own
+/// routes. Swaps are never executed through it.
+pub struct JupiterClient {
+    http: reqwest::Client,
+    config: JupiterConfig,
+}
+
+impl JupiterClient {
+    pub fn new(config: &JupiterConfig) -> Result<Self> {
+        Ok(Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_millis(config.request_timeout_ms))
+                .build()?,
+            config: config.clone(),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    pub async fn quote(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+        amount_in: u64,
+        slippage_bps: u16,
+    ) -> Result<JupiterQuote> {
+        let mut request = request_id::tag(self.http.get(&self.config.quote_url)).query(&[
+            ("inputMint", input_mint.to_string()),
+            ("outputMint", output_mint.to_string()),
+            ("amount", amount_in.to_string()),
+            ("slippageBps", slippage_bps.to_string()),
+        ]);
+        if let Some(api_key) = &self.config.api_key {
+            request = request.header("x-api-key", api_key);
+        }
+        let quote = request
+            .send()
+            .await
+            .map_err(|e| ApiError::ServiceUnavailable(format!("Jupiter unavailable: {}", e)))?
+            .error_for_status()
+            .map_err(|e| ApiError::ServiceUnavailable(format!("Jupiter rejected the quote: {}", e)))?
+            .json()
+            .await?;
+        Ok(quote)
+    }
+}
+
+mod u64_string {
+    use serde::{Deserialize, Deserializer};
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,106 @@
+use crate::config::{LogFormat, LoggingConfig, TracingConfig};
+use anyhow::Result;
+use opentelemetry::{global, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{
+    propagation::TraceContextPropagator,
+    runtime,
+    trace::{self, Sampler, Tracer},
+    Resource,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing_subscriber::{fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Registry};
+
+// Set once spans are exported, after which trace IDs come from them.
+static TRACING: AtomicBool = AtomicBool::new(false);
+
+/// Handle on the installed log filter, so operators can change verbosity
+/// without a restart.
+#[derive(Clone)]
+pub struct LogFilter {
+    handle: reload::Handle<EnvFilter, Registry>,
+}
+
+impl LogFilter {
+    /// The active filter in `EnvFilter` directive syntax.
+    pub fn current(&self) -> String {
+        self.handle
+            .with_current(|filter| filter.to_string())
+            .unwrap_or_default()
+    }
+
+    /// Parses `directives`, e.g. `solana_gateway_service=trace,tower_http=info`.
+    pub fn parse(directives: &str) -> Result<EnvFilter> {
+        Ok(EnvFilter::try_new(directives)?)
+    }
+
+    /// Replaces the filter with `directives`.
+    pub fn set(&self, directives: &str) -> Result<()> {
+        self.replace(Self::parse(directives)?);
+        Ok(())
+    }
+
+    /// Replaces the filter with one already parsed.
+    pub fn replace(&self, filter: EnvFilter) {
+        // Only fails once the subscriber is gone, when nothing is logged.
+        let _ = self.handle.reload(filter);
+    }
+}
+
+/// Installs the global subscriber, exporting spans when an OTLP endpoint
+/// is configured. JSON lines carry the event's fields at the top level and
+/// the enclosing request span's, including `request_id` and `trace_id`,
+/// under `span`.
+pub fn init(config: &LoggingConfig, trace_config: &TracingConfig) -> Result<LogFilter> {
+    let (filter, handle) = reload::Layer::new(EnvFilter::try_new(&config.level)?);
+    let otel = match &trace_config.otlp_endpoint {
+        Some(endpoint) => Some(tracing_opentelemetry::layer().with_tracer(otlp_tracer(trace_config, endpoint)?)),
+        None => None,
+    };
+    TRACING.store(otel.is_some(), Ordering::Relaxed);
+    let registry = tracing_subscriber::registry().with(filter).with(otel);
+    match config.format {
+        LogFormat::Text => registry.with(fmt::layer()).try_init()?,
+        LogFormat::Pretty => registry.with(fmt::layer().pretty()).try_init()?,
+        LogFormat::Json => registry
+            .with(
+                fmt::layer()
+                    .json()
+                    .flatten_event(true)
+                    .with_current_span(true)
+                    .with_span_list(false),
+            )
+            .try_init()?,
+    }
+    Ok(LogFilter { handle })
+}
+
+/// Whether spans are exported to an OpenTelemetry collector.
+pub fn is_tracing() -> bool {
+    TRACING.load(Ordering::Relaxed)
+}
+
+/// Exports spans still buffered; called on shutdown.
+pub fn shutdown() {
+    if is_tracing() {
+        global::shutdown_tracer_provider();
+    }
+}
+
+/// Batches spans to the OTLP collector at `endpoint` and makes W3C trace
+/// context the propagation format, so request spans continue the caller's
+/// trace.
+fn otlp_tracer(config: &TracingConfig, endpoint: &str) -> Result<Tracer> {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(config.sample_ratio)));
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(endpoint))
+        .with_trace_config(
+            trace::config()
+                .with_sampler(sampler)
+                .with_resource(Resource::new([KeyValue::new("service.name", config.service_name.clone())])),
+        )
+        .install_batch(runtime::Tokio)?;
+    Ok(tracer)
+}
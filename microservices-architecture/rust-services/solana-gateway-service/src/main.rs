@@ -2,12 +2,16 @@ use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
     response::Json,
-    routing::{get, post},
-    Router,
+    Extension,
 };
+use clap::Parser;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use solana_sdk::{hash::Hash, pubkey::Pubkey, signature::Signature};
+use std::net::SocketAddr;
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
 use tower::ServiceBuilder;
 use tower_http::{
     cors::CorsLayer,
@@ -15,16 +19,104 @@ use tower_http::{
 };
 use tracing::{info, warn};
 
+mod activity;
+mod api;
+mod api_keys;
+mod approvals;
+mod audit;
+mod auth;
+mod aws;
+mod balance_history;
+mod blockhash;
+mod cache;
+mod client_references;
+mod clusters;
+mod compliance;
 mod config;
+mod confirmations;
 mod database;
+mod dca;
+mod decoder;
+mod error;
+mod events;
+mod fees;
+mod graphql;
+mod grpc;
+mod holders;
+mod idempotency;
+mod jito;
+mod jupiter;
+mod logging;
+mod maintenance;
+mod memo;
 mod metrics;
+mod middleware;
+mod nfts;
+mod openapi;
+mod pagination;
+mod pools;
+mod price_oracle;
+mod pubsub;
+mod quotes;
+mod rate_limit;
+mod reconciler;
+mod reload;
+mod request_id;
+mod retention;
+mod rpc_pool;
+mod rpc_sender;
+mod secrets;
+mod signer;
 mod solana_client;
+mod squads;
+mod stake;
+mod swap;
+mod tls;
+mod token_registry;
+mod token_safety;
+mod tokens;
+mod validation;
+mod wallets;
+mod watchlist;
+mod webhooks;
+mod wsol;
 mod handlers;
 
+use api_keys::{ApiKeyGrant, ApiKeyStore};
+use approvals::Approval;
+use auth::{Authenticator, Principal};
+use cache::{CacheBypass, ResponseCache};
+use clusters::ClusterRouter;
+use compliance::Compliance;
 use config::Config;
 use database::Database;
+use error::{ApiError, ErrorBody};
+use events::EventPublisher;
+use idempotency::{run_idempotent, IdempotencyKey};
+use jito::JitoClient;
+use jupiter::JupiterClient;
+use logging::LogFilter;
+use maintenance::Maintenance;
 use metrics::Metrics;
-use solana_client::SolanaClient;
+use nfts::NftResolver;
+use pagination::{decode_cursor, encode_cursor, Paginated, Pagination, Sorting};
+use pools::{analytics::PoolAnalytics, feed::PoolFeed, route_cache::RouteCache, volume::PoolStats, Pool, PoolFilter};
+use price_oracle::PriceOracle;
+use pubsub::PubsubManager;
+use rate_limit::RateLimits;
+use reload::ConfigReloader;
+use signer::Signer;
+use solana_client::{
+    AccountInfo, Landing, SignMode, SimulatedTransaction, SolanaClient, TokenBalance, TransactionInfo,
+    UnsignedTransaction,
+};
+use handlers::swap::{ExecuteSwapRequest, SimulatedSwap, SwapResponse, SwapTransactionRequest};
+use swap::{SwapQuote, SwapRequest};
+use token_registry::TokenRegistry;
+use token_safety::TokenSafety;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use wallets::WalletManager;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -32,109 +124,440 @@ pub struct AppState {
     pub database: Arc<Database>,
     pub solana_client: Arc<SolanaClient>,
     pub metrics: Arc<Metrics>,
+    pub pubsub: Arc<PubsubManager>,
+    pub cache: Arc<ResponseCache>,
+    pub rate_limits: Arc<RateLimits>,
+    pub price_oracle: Arc<PriceOracle>,
+    pub wallets: Arc<WalletManager>,
+    /// Signs transfers from its own address that don't name a wallet.
+    pub signer: Option<Arc<dyn Signer>>,
+    pub nfts: Arc<NftResolver>,
+    pub token_registry: Arc<TokenRegistry>,
+    pub token_safety: Arc<TokenSafety>,
+    pub route_cache: Arc<RouteCache>,
+    pub events: Arc<EventPublisher>,
+    pub jito: Arc<JitoClient>,
+    pub jupiter: Arc<JupiterClient>,
+    pub auth: Arc<Authenticator>,
+    pub api_keys: Arc<ApiKeyStore>,
+    pub compliance: Arc<Compliance>,
+    pub maintenance: Arc<Maintenance>,
+    pub pool_reindex: Arc<Notify>,
+    pub pool_feed: Arc<PoolFeed>,
+    pub log_filter: LogFilter,
+    pub reloader: Arc<ConfigReloader>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct HealthResponse {
     pub status: String,
     pub timestamp: String,
     pub version: String,
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct SolanaAccountInfo {
-    pub address: String,
-    pub balance: u64,
-    pub owner: String,
-    pub executable: bool,
-    pub rent_epoch: u64,
-}
-
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct TransactionRequest {
     pub from: String,
+    /// Recipient wallet. For token transfers this is the owner, not the
+    /// token account.
     pub to: String,
+    /// Lamports, or raw token units when `mint` is set.
     pub amount: u64,
+    /// SPL Token or Token-2022 mint to transfer instead of SOL.
+    pub mint: Option<String>,
+    /// Creates the recipient's associated token account when it doesn't
+    /// exist (default true); otherwise such transfers are rejected.
+    pub create_recipient_ata: Option<bool>,
+    /// For wSOL transfers, wraps `amount` from the sender's SOL into a
+    /// temporary wSOL account the transfer is sent from (default true);
+    /// otherwise it is sent from the sender's wSOL account.
+    pub wrap_sol: Option<bool>,
+    /// Attached as an SPL Memo instruction signed by `from`.
     pub memo: Option<String>,
+    /// Overrides the configured priority fee strategy for this transaction.
+    pub priority_fee: Option<fees::PriorityFeeStrategy>,
+    /// Stored wallet that signs and sends the transaction; it must be the
+    /// caller's own, unless the caller is an admin, and its public key must
+    /// equal `from`. Without one, the gateway's own signer signs when
+    /// `from` is its address.
+    pub wallet_id: Option<Uuid>,
+    /// Durable nonce account (with `from` as authority) used instead of a
+    /// recent blockhash, so the transaction doesn't expire before it is sent.
+    pub nonce_account: Option<String>,
+    /// `client` returns the transaction unsigned for `from` to sign instead
+    /// of sending it.
+    #[serde(default)]
+    pub sign_mode: SignMode,
+    /// Validates, builds and simulates the transfer without sending it.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Caller's own reference for the payment, accepted once per API key:
+    /// a transfer repeating one is rejected with 409 rather than sent
+    /// again, however long after the first.
+    pub client_reference: Option<String>,
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct TransactionResponse {
-    pub signature: String,
-    pub status: String,
-    pub slot: u64,
+/// A sent transaction, with `sign_mode: "client"` the transaction for the
+/// caller to sign, for a dry run the simulated transaction, or for a
+/// transfer above the approval threshold the pending approval.
+#[derive(Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum TransactionResponse {
+    Submitted(TransactionInfo),
+    Unsigned(UnsignedTransaction),
+    Simulated(SimulatedTransaction),
+    PendingApproval(Approval),
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct TokenBalance {
-    pub mint: String,
-    pub amount: u64,
-    pub decimals: u8,
-    pub ui_amount: f64,
+#[derive(Parser)]
+#[command(version, about = "Solana gateway service")]
+struct Cli {
+    /// Apply pending database migrations and exit.
+    #[arg(long)]
+    migrate_only: bool,
+    /// Configuration file, without its extension.
+    #[arg(long, default_value = "config/gateway")]
+    config: String,
+    /// Override a setting, taking precedence over the file and environment,
+    /// e.g. `--set swap.max_slippage_bps=300`.
+    #[arg(long = "set", value_name = "KEY=VALUE")]
+    overrides: Vec<String>,
+    /// Print the effective configuration with secrets redacted and exit.
+    #[arg(long)]
+    print_config: bool,
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+
+    // Load configuration, which selects the log level and format
+    let config = match Config::load(&cli.config, &cli.overrides).await {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{:#}", e);
+            std::process::exit(2);
+        }
+    };
+    if cli.print_config {
+        println!("{}", serde_json::to_string_pretty(&config.redacted())?);
+        return Ok(());
+    }
+
     // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_env_filter("solana_gateway_service=debug,tower_http=debug")
-        .init();
+    let log_filter = logging::init(&config.logging, &config.tracing)?;
 
     info!("Starting Solana Gateway Service");
-
-    // Load configuration
-    let config = Config::load()?;
     info!("Configuration loaded successfully");
 
-    // Initialize database
-    let database = Arc::new(Database::new(&config.database_url).await?);
-    info!("Database connection established");
-
-    // Initialize Solana client
-    let solana_client = Arc::new(SolanaClient::new(&config.solana_rpc_url)?);
-    info!("Solana client initialized");
+    let configs = config.cluster_configs();
+    if cli.migrate_only {
+        for config in &configs {
+            connect_database(config, true).await?;
+        }
+        return Ok(());
+    }
 
     // Initialize metrics
-    let metrics = Arc::new(Metrics::new()?);
+    let metrics = Arc::new(Metrics::new(&config.metrics)?);
     info!("Metrics initialized");
 
-    // Create application state
-    let state = AppState {
-        config,
-        database,
-        solana_client,
-        metrics,
-    };
+    // Initialize rate limiting; limits apply across all clusters
+    let rate_limits = Arc::new(RateLimits::new(&config.rate_limit)?);
+    rate_limit::spawn_cleanup(rate_limits.clone());
+
+    // Maintenance switches also apply across all clusters
+    let maintenance = Arc::new(Maintenance::new(&config.maintenance, metrics.clone()));
+
+    // Apply configuration changes on SIGHUP or through the admin API
+    let reloader = Arc::new(ConfigReloader::new(
+        &cli.config,
+        &cli.overrides,
+        &config,
+        rate_limits.clone(),
+        maintenance.clone(),
+        log_filter.clone(),
+    ));
+    reload::spawn_sighup(reloader.clone());
+
+    // Create application state for every cluster
+    let mut states = Vec::with_capacity(configs.len());
+    for config in configs {
+        let state = build_state(
+            config,
+            metrics.clone(),
+            rate_limits.clone(),
+            maintenance.clone(),
+            log_filter.clone(),
+            reloader.clone(),
+        )
+        .await?;
+        // Execute due DCA schedules, which swap through the full state
+        dca::spawn_scheduler(state.clone());
+        states.push(state);
+    }
+
+    // Pick up rotated secrets, including each cluster's database credentials
+    let databases = states
+        .iter()
+        .enumerate()
+        .map(|(i, state)| {
+            let setting = match i {
+                0 => "database_url".to_string(),
+                _ => format!("clusters.{}.database_url", state.config.cluster),
+            };
+            (setting, state.database.clone())
+        })
+        .collect();
+    secrets::spawn_refresh(config.secrets.clone(), databases);
+    let state = states.remove(0);
+
+    // Start the gRPC server alongside the REST API; it serves the default cluster
+    if state.config.grpc.enabled {
+        grpc::spawn_server(state.clone(), state.config.grpc.port);
+    }
 
     // Build the application router
-    let app = Router::new()
-        .route("/health", get(health_check))
-        .route("/metrics", get(handlers::metrics::get_metrics))
-        .route("/api/v1/accounts/:address", get(get_account_info))
-        .route("/api/v1/accounts/:address/balance", get(get_account_balance))
-        .route("/api/v1/accounts/:address/tokens", get(get_token_balances))
-        .route("/api/v1/transactions", post(create_transaction))
-        .route("/api/v1/transactions/:signature", get(get_transaction))
-        .route("/api/v1/tokens/:mint", get(get_token_info))
-        .route("/api/v1/pools", get(get_pools))
-        .route("/api/v1/pools/:pool_id", get(get_pool_info))
-        .route("/api/v1/swap", post(execute_swap))
+    let clusters = states
+        .into_iter()
+        .map(|state| (state.config.cluster.clone(), api::router(state)))
+        .collect();
+    let app = ClusterRouter::new(state.config.cluster.clone(), api::router(state), clusters)
+        .into_router()
         .layer(
             ServiceBuilder::new()
-                .layer(TraceLayer::new_for_http())
+                .layer(axum::middleware::from_fn(middleware::assign_request_id))
+                .layer(TraceLayer::new_for_http().make_span_with(request_id::make_span::<axum::body::Body>))
                 .layer(CorsLayer::permissive())
-        )
-        .with_state(state);
-
-    // Start the server
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await?;
-    info!("Solana Gateway Service listening on 0.0.0.0:8080");
+        );
 
-    axum::serve(listener, app).await?;
+    // Start the server, terminating TLS itself when configured
+    let address = SocketAddr::from(([0, 0, 0, 0], 8080));
+    if config.tls.enabled {
+        let rustls = tls::load(&config.tls).await?;
+        tls::spawn_reload(rustls.clone(), config.tls.clone());
+        info!("Solana Gateway Service listening on https://{}", address);
+        axum_server::bind_rustls(address, rustls)
+            .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+            .await?;
+    } else {
+        let listener = tokio::net::TcpListener::bind(address).await?;
+        info!("Solana Gateway Service listening on {}", address);
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
+    }
 
+    logging::shutdown();
     Ok(())
 }
 
+async fn connect_database(config: &Config, migrate: bool) -> Result<Arc<Database>, Box<dyn std::error::Error>> {
+    let database =
+        Arc::new(Database::new(&config.database_url, &config.database_read_urls, &config.database_pool).await?);
+    info!("Database connection established for {}", config.cluster);
+
+    if migrate {
+        database.migrate().await?;
+        info!("Database migrations applied for {}", config.cluster);
+    }
+    Ok(database)
+}
+
+/// Connects everything one cluster needs and starts its background tasks.
+async fn build_state(
+    config: Config,
+    metrics: Arc<Metrics>,
+    rate_limits: Arc<RateLimits>,
+    maintenance: Arc<Maintenance>,
+    log_filter: LogFilter,
+    reloader: Arc<ConfigReloader>,
+) -> Result<AppState, Box<dyn std::error::Error>> {
+    info!("Initializing cluster {}", config.cluster);
+
+    // Initialize database
+    let database = connect_database(&config, config.run_migrations).await?;
+    database::spawn_pool_metrics(database.clone(), metrics.clone(), config.cluster.clone(), &config.database_pool);
+    database::spawn_replica_checks(database.clone(), config.cluster.clone(), &config.database_pool);
+
+    // Initialize response cache
+    let cache = Arc::new(ResponseCache::connect(&config.cache).await?);
+
+    // Initialize Solana client
+    let solana_client = Arc::new(SolanaClient::new(&config, metrics.clone())?);
+    reloader.register(&config.cluster, solana_client.clone());
+    info!("Solana client initialized");
+
+    // Move traffic off RPC endpoints that fall behind the reference node
+    rpc_pool::spawn_slot_lag_monitor(
+        solana_client.clone(),
+        metrics.clone(),
+        config.cluster.clone(),
+        config.slot_lag.clone(),
+        config.timeouts.rpc(),
+    );
+
+    // Publish RPC endpoint circuit breaker states
+    rpc_pool::spawn_circuit_metrics(solana_client.clone(), metrics.clone(), config.cluster.clone());
+
+    // Keep a recent blockhash cached for transaction builds
+    blockhash::spawn_refresh(solana_client.clone(), config.blockhash.clone());
+
+    // Initialize wallet storage
+    let wallets = Arc::new(WalletManager::new(database.clone(), &config.wallets)?);
+
+    // Load the gateway signing key, or connect to the KMS holding it
+    let signer = signer::from_config(&config.signer).await?;
+
+    // Initialize price oracle
+    let price_oracle = Arc::new(PriceOracle::new(solana_client.clone(), &config.price_oracle)?);
+
+    // Initialize NFT metadata resolution
+    let nfts = Arc::new(NftResolver::new(solana_client.clone(), &config.nfts)?);
+
+    // Load off-chain token metadata and keep it refreshed
+    let token_registry = Arc::new(TokenRegistry::new(database.clone(), &config.token_registry)?);
+    token_registry::spawn_refresh(token_registry.clone(), config.token_registry.clone());
+
+    // Initialize mint risk assessment
+    let token_safety = Arc::new(TokenSafety::new(
+        solana_client.clone(),
+        token_registry.clone(),
+        &config.token_safety,
+    ));
+
+    // Initialize account subscription fan-out
+    let pubsub = Arc::new(PubsubManager::new(config.ws_url()));
+
+    // Start background pool discovery, re-indexing on operator request
+    let pool_reindex = Arc::new(Notify::new());
+    pools::spawn_discovery(
+        solana_client.clone(),
+        database.clone(),
+        config.pool_discovery.clone(),
+        pool_reindex.clone(),
+    );
+
+    // Detect newly created pools and stream them to feed clients
+    let pool_feed = Arc::new(PoolFeed::new(&config.pool_feed));
+    pools::feed::spawn(
+        pool_feed.clone(),
+        solana_client.clone(),
+        database.clone(),
+        token_registry.clone(),
+        config.ws_url(),
+    );
+
+    // Cache swap route searches, evicted as the candle indexer sees reserves move
+    let route_cache = Arc::new(RouteCache::new(&config.route_cache, metrics.clone(), config.cluster.clone()));
+
+    // Start background OHLCV candle indexing
+    pools::candles::spawn_indexer(
+        solana_client.clone(),
+        database.clone(),
+        route_cache.clone(),
+        config.candles.clone(),
+    );
+
+    // Index swap volume and fees through stored pools
+    pools::volume::spawn_indexer(solana_client.clone(), database.clone(), config.pool_volume.clone());
+
+    // Create upcoming transaction partitions and prune expired ones
+    retention::spawn(database.clone(), config.transaction_retention.clone());
+
+    // Initialize event publishing and forward tracked account changes
+    let events = Arc::new(EventPublisher::new(&config.kafka)?);
+    events::spawn_account_forwarder(events.clone(), pubsub.clone());
+
+    // Initialize Jito bundle submission for MEV-protected swaps
+    let jito = Arc::new(JitoClient::new(&config.jito)?);
+
+    // Initialize Jupiter quotes for swap quote comparison
+    let jupiter = Arc::new(JupiterClient::new(&config.jupiter)?);
+
+    // Initialize bearer token authentication
+    let auth = Arc::new(Authenticator::new(&config.auth)?);
+
+    // Initialize issued API key verification
+    let api_keys = Arc::new(ApiKeyStore::new(database.clone(), &config.api_keys));
+    api_keys::spawn_sync(api_keys.clone(), config.api_keys.clone());
+
+    // Screen transfers and swaps against the compliance lists
+    let compliance = Arc::new(Compliance::new(database.clone(), &config.compliance));
+    compliance::spawn_sync(compliance.clone(), config.compliance.clone());
+
+    // Track submitted transactions until they settle
+    confirmations::spawn_tracker(
+        solana_client.clone(),
+        database.clone(),
+        events.clone(),
+        config.confirmations.clone(),
+    );
+
+    // Correct stored statuses the chain disagrees with
+    reconciler::spawn_reconciler(
+        solana_client.clone(),
+        database.clone(),
+        events.clone(),
+        metrics.clone(),
+        config.cluster.clone(),
+        config.reconciler.clone(),
+    );
+
+    // Detect changes on watched accounts and deliver webhook events
+    watchlist::spawn_watcher(
+        solana_client.clone(),
+        database.clone(),
+        events.clone(),
+        config.watchlist.clone(),
+    );
+    webhooks::spawn_dispatcher(database.clone(), config.webhooks.clone());
+
+    // Record watched accounts' balances for portfolio history
+    balance_history::spawn_snapshots(
+        solana_client.clone(),
+        database.clone(),
+        config.balance_snapshots.clone(),
+        config.watchlist.max_accounts,
+    );
+
+    // Expire stale idempotency keys
+    idempotency::spawn_purge(database.clone());
+
+    // Create application state
+    Ok(AppState {
+        config,
+        database,
+        solana_client,
+        metrics,
+        pubsub,
+        cache,
+        rate_limits,
+        price_oracle,
+        wallets,
+        signer,
+        nfts,
+        token_registry,
+        token_safety,
+        route_cache,
+        events,
+        jito,
+        jupiter,
+        auth,
+        api_keys,
+        compliance,
+        maintenance,
+        pool_reindex,
+        pool_feed,
+        log_filter,
+        reloader,
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses((status = 200, description = "Service is running", body = HealthResponse))
+)]
 async fn health_check() -> Json<HealthResponse> {
     Json(HealthResponse {
         status: "healthy".to_string(),
@@ -143,122 +566,674 @@ async fn health_check() -> Json<HealthResponse> {
     })
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/accounts/{address}",
+    tag = "accounts",
+    params(("address" = String, Path, description = "Base58 account address")),
+    responses(
+        (status = 200, description = "Account info", body = AccountInfo),
+        (status = 400, description = "Invalid address", body = ErrorBody),
+        (status = 404, description = "Account not found", body = ErrorBody),
+        (status = 503, description = "RPC unavailable", body = ErrorBody)
+    )
+)]
 async fn get_account_info(
     State(state): State<AppState>,
+    CacheBypass(bypass): CacheBypass,
     Path(address): Path<String>,
-) -> Result<Json<SolanaAccountInfo>, StatusCode> {
-    match state.solana_client.get_account_info(&address).await {
+) -> Result<Json<AccountInfo>, ApiError> {
+    let key = format!("account_info:{}", address);
+    let ttl = state.config.cache.account_info_ttl();
+    let fetch = || state.solana_client.get_account_info(&address);
+
+    match state.cache.get_or_fetch(&key, ttl, bypass, fetch).await {
         Ok(account_info) => Ok(Json(account_info)),
         Err(e) => {
             warn!("Failed to get account info for {}: {}", address, e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(e.into())
         }
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/accounts/{address}/balance",
+    tag = "accounts",
+    params(("address" = String, Path, description = "Base58 account address")),
+    responses(
+        (status = 200, description = "Balance in lamports", body = u64),
+        (status = 400, description = "Invalid address", body = ErrorBody),
+        (status = 503, description = "RPC unavailable", body = ErrorBody)
+    )
+)]
 async fn get_account_balance(
     State(state): State<AppState>,
     Path(address): Path<String>,
-) -> Result<Json<u64>, StatusCode> {
+) -> Result<Json<u64>, ApiError> {
     match state.solana_client.get_balance(&address).await {
         Ok(balance) => Ok(Json(balance)),
         Err(e) => {
             warn!("Failed to get balance for {}: {}", address, e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(e.into())
         }
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/accounts/{address}/tokens",
+    tag = "accounts",
+    params(("address" = String, Path, description = "Base58 owner address")),
+    responses(
+        (status = 200, description = "SPL Token and Token-2022 balances", body = [TokenBalance]),
+        (status = 400, description = "Invalid address", body = ErrorBody),
+        (status = 503, description = "RPC unavailable", body = ErrorBody)
+    )
+)]
 async fn get_token_balances(
     State(state): State<AppState>,
     Path(address): Path<String>,
-) -> Result<Json<Vec<TokenBalance>>, StatusCode> {
+) -> Result<Json<Vec<TokenBalance>>, ApiError> {
     match state.solana_client.get_token_balances(&address).await {
-        Ok(balances) => Ok(Json(balances)),
+        Ok(mut balances) => {
+            state.token_registry.enrich(&mut balances);
+            Ok(Json(balances))
+        }
         Err(e) => {
             warn!("Failed to get token balances for {}: {}", address, e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(e.into())
         }
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/transactions",
+    tag = "transactions",
+    request_body = TransactionRequest,
+    params(("Idempotency-Key" = Option<String>, Header, description = "Deduplicates retried submissions")),
+    responses(
+        (status = 200, description = "Transaction submitted, built for the client to sign, or simulated for a dry run", body = TransactionResponse),
+        (status = 202, description = "Transfer above the approval threshold, held until approved", body = Approval),
+        (status = 400, description = "Invalid request", body = ErrorBody),
+        (status = 403, description = "Destination or mint refused by the compliance lists", body = ErrorBody),
+        (status = 409, description = "Idempotency key conflict, or client_reference already used", body = ErrorBody),
+        (status = 422, description = "Invalid request fields", body = ErrorBody),
+        (status = 429, description = "Rate limit exceeded", body = ErrorBody)
+    )
+)]
 async fn create_transaction(
     State(state): State<AppState>,
+    IdempotencyKey(key): IdempotencyKey,
+    principal: Option<Extension<Principal>>,
     Json(request): Json<TransactionRequest>,
-) -> Result<Json<TransactionResponse>, StatusCode> {
-    match state.solana_client.create_transaction(&request).await {
-        Ok(response) => Ok(Json(response)),
-        Err(e) => {
-            warn!("Failed to create transaction: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
+) -> Result<(StatusCode, Json<TransactionResponse>), ApiError> {
+    state.compliance.check_transfer(&request.to, request.mint.as_deref())?;
+    let dry_run = request.dry_run || state.config.dry_run;
+    if request.sign_mode == SignMode::Client || dry_run {
+        request.validate()?;
+        let built = async {
+            let transaction = state.solana_client.build_unsigned_transfer(&request).await?;
+            if !dry_run {
+                return Ok(TransactionResponse::Unsigned(transaction));
+            }
+            let simulation = state.solana_client.simulate(&transaction).await?;
+            Ok::<_, anyhow::Error>(TransactionResponse::Simulated(SimulatedTransaction { simulation, transaction }))
+        };
+        return match built.await {
+            Ok(response) => Ok((StatusCode::OK, Json(response))),
+            Err(e) => {
+                warn!("Failed to build transaction for {}: {}", request.from, e);
+                Err(e.into())
+            }
+        };
     }
+    let principal = principal.as_ref().map(|Extension(principal)| principal);
+    if approvals::requires_approval(&state.config.approvals, &request) {
+        request.validate()?;
+        return match approvals::request_approval(&state, principal, &request).await {
+            Ok(approval) => Ok((StatusCode::ACCEPTED, Json(TransactionResponse::PendingApproval(approval)))),
+            Err(e) => {
+                warn!("Failed to hold transfer from {} for approval: {}", request.from, e);
+                Err(e.into())
+            }
+        };
+    }
+    let response = submit_transaction(&state, principal, key.as_deref(), &request).await?;
+    Ok((StatusCode::OK, Json(TransactionResponse::Submitted(response))))
+}
+
+/// Signs (with the given wallet, which must be the caller's unless it is an
+/// admin, or the gateway's own signer), sends and records a transfer; one
+/// neither can sign is rejected. Shared by
+/// the REST and gRPC APIs; transfers that need approval are rejected, as
+/// only `POST /api/v1/transactions` can hold them.
+pub async fn submit_transaction(
+    state: &AppState,
+    principal: Option<&Principal>,
+    idempotency_key: Option<&str>,
+    request: &TransactionRequest,
+) -> Result<TransactionInfo, ApiError> {
+    request.validate()?;
+    if approvals::requires_approval(&state.config.approvals, request) {
+        return Err(ApiError::Forbidden(
+            "Transfer needs approval; submit it through POST /api/v1/transactions".to_string(),
+        ));
+    }
+    send_transfer(state, principal, wallets::scope(principal), idempotency_key, request).await
+}
+
+/// `submit_transaction` without the approval check, for approved transfers.
+/// A `client_reference` is claimed for `principal` inside the idempotent
+/// section, so replaying an Idempotency-Key still returns the stored
+/// response. Only wallets of `wallet_owner` may sign, or any when None.
+pub async fn send_transfer(
+    state: &AppState,
+    principal: Option<&Principal>,
+    wallet_owner: Option<&str>,
+    idempotency_key: Option<&str>,
+    request: &TransactionRequest,
+) -> Result<TransactionInfo, ApiError> {
+    if state.config.dry_run {
+        return Err(ApiError::BadRequest("transactions are not sent in dry-run mode".to_string()));
+    }
+    // Checked again here, as approved transfers are sent after the lists
+    // may have changed.
+    state.compliance.check_transfer(&request.to, request.mint.as_deref())?;
+    let submit = || async {
+        let wallet = match request.wallet_id {
+            Some(id) => Some(state.wallets.signer_for(wallet_owner, id, &request.from).await?),
+            None => None,
+        };
+        let signer: &dyn Signer = match &wallet {
+            Some(keypair) => keypair,
+            None => state
+                .signer
+                .as_deref()
+                .filter(|signer| signer.public_key().to_string() == request.from)
+                .ok_or_else(|| ApiError::BadRequest("No signer for `from`; use sign_mode=client".to_string()))?,
+        };
+        let owner = client_references::owner(principal);
+        let reference = client_references::claim(&state.database, owner, request.client_reference.as_deref()).await?;
+        match state.solana_client.create_transaction(request, signer).await {
+            Ok(response) => {
+                if let Some(reference) = reference {
+                    reference.complete(Some(&response.signature), None).await;
+                }
+                if let Err(e) = state.database.record_transaction(&response.to_record(Some(request.amount))).await {
+                    warn!("Failed to persist transaction {}: {}", response.signature, e);
+                }
+                Ok(response)
+            }
+            Err(e) => {
+                warn!("Failed to create transaction: {}", e);
+                Err(ApiError::from(e))
+            }
+        }
+    };
+
+    let ttl = state.config.idempotency_ttl();
+    run_idempotent(&state.database, idempotency_key, "transactions", request, ttl, submit).await
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/transactions/{signature}",
+    tag = "transactions",
+    params(("signature" = String, Path, description = "Base58 transaction signature")),
+    responses(
+        (status = 200, description = "Transaction status", body = TransactionInfo),
+        (status = 400, description = "Invalid signature", body = ErrorBody),
+        (status = 503, description = "RPC unavailable", body = ErrorBody)
+    )
+)]
 async fn get_transaction(
     State(state): State<AppState>,
     Path(signature): Path<String>,
-) -> Result<Json<TransactionResponse>, StatusCode> {
-    match state.solana_client.get_transaction(&signature).await {
-        Ok(transaction) => Ok(Json(transaction)),
+) -> Result<Json<TransactionInfo>, ApiError> {
+    let transaction = lookup_transaction(&state, &signature).await?;
+    Ok(Json(transaction))
+}
+
+/// Serves transactions the gateway tracks from the database, which the
+/// confirmation tracker keeps current, and falls back to RPC for others.
+pub async fn lookup_transaction(state: &AppState, signature: &str) -> Result<TransactionInfo, ApiError> {
+    match state.database.get_transaction(signature).await {
+        Ok(Some(record)) => return Ok(record.into()),
+        Ok(None) => {}
+        Err(e) => warn!("Failed to load stored transaction {}: {}", signature, e),
+    }
+
+    match state.solana_client.get_transaction(signature).await {
+        Ok(transaction) => {
+            if let Err(e) = state.database.record_transaction(&transaction.to_record(None)).await {
+                warn!("Failed to persist transaction {}: {}", signature, e);
+            }
+            Ok(transaction)
+        }
         Err(e) => {
             warn!("Failed to get transaction {}: {}", signature, e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(e.into())
         }
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/tokens/{mint}",
+    tag = "tokens",
+    params(("mint" = String, Path, description = "Base58 mint address")),
+    responses(
+        (status = 200, description = "Mint info with token registry metadata (null when unlisted) and a risk report", body = serde_json::Value),
+        (status = 400, description = "Not a mint account", body = ErrorBody),
+        (status = 404, description = "Mint not found", body = ErrorBody)
+    )
+)]
 async fn get_token_info(
     State(state): State<AppState>,
+    CacheBypass(bypass): CacheBypass,
     Path(mint): Path<String>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    match state.solana_client.get_token_info(&mint).await {
-        Ok(token_info) => Ok(Json(token_info)),
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let key = format!("token_info:{}", mint);
+    let ttl = state.config.cache.token_info_ttl();
+    let fetch = || state.solana_client.get_token_info(&mint);
+
+    match state.cache.get_or_fetch(&key, ttl, bypass, fetch).await {
+        Ok(mut token_info) => {
+            // Merged after the cache so registry updates show immediately.
+            token_info["metadata"] = serde_json::json!(state.token_registry.get(&mint));
+            // Cached separately; a failed assessment leaves `risk` null.
+            let risk = async { state.token_safety.assess(&Pubkey::from_str(&mint)?).await };
+            match risk.await {
+                Ok(report) => token_info["risk"] = serde_json::json!(report),
+                Err(e) => warn!("Failed to assess risk of {}: {}", mint, e),
+            }
+            Ok(Json(token_info))
+        }
         Err(e) => {
             warn!("Failed to get token info for {}: {}", mint, e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(e.into())
         }
     }
 }
 
+// Public sort names for the pool list, mapped onto `solana_pools` columns.
+const POOL_SORT_FIELDS: [(&str, &str); 4] = [
+    ("address", "address"),
+    ("fee", "fee_ppm"),
+    ("created_at", "created_at"),
+    ("updated_at", "updated_at"),
+];
+
+/// Keyset position in the pool list: the last row's sort value and address.
+#[derive(Serialize, Deserialize)]
+struct PoolCursor {
+    key: String,
+    address: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/pools",
+    tag = "pools",
+    params(pagination::PageQuery, pagination::SortQuery, PoolFilter),
+    responses(
+        (status = 200, description = "Discovered pools", body = pagination::PoolPage),
+        (status = 400, description = "Invalid paging, sort or filter parameters", body = ErrorBody)
+    )
+)]
 async fn get_pools(
     State(state): State<AppState>,
-    Query(params): Query<HashMap<String, String>>,
-) -> Result<Json<Vec<serde_json::Value>>, StatusCode> {
-    let limit = params.get("limit").and_then(|s| s.parse().ok()).unwrap_or(50);
-    let offset = params.get("offset").and_then(|s| s.parse().ok()).unwrap_or(0);
+    pagination: Pagination,
+    sorting: Sorting,
+    Query(filter): Query<PoolFilter>,
+) -> Result<Json<Paginated<Pool>>, ApiError> {
+    let column = sorting.column(&POOL_SORT_FIELDS)?;
+    let cursor = pagination
+        .cursor
+        .as_deref()
+        .map(decode_cursor::<PoolCursor>)
+        .transpose()?
+        .map(|cursor| (cursor.key, cursor.address));
 
-    match state.solana_client.get_pools(limit, offset).await {
-        Ok(pools) => Ok(Json(pools)),
+    match state
+        .database
+        .list_pools(&filter, column, sorting.order, cursor, &pagination)
+        .await
+    {
+        Ok(rows) => {
+            let page = pagination.page(rows, |(pool, key)| {
+                encode_cursor(&PoolCursor {
+                    key: key.clone(),
+                    address: pool.id.clone(),
+                })
+            });
+            Ok(Json(page.map(|(pool, _)| pool)))
+        }
         Err(e) => {
             warn!("Failed to get pools: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(e.into())
         }
     }
 }
 
+#[derive(Deserialize, IntoParams)]
+struct PoolInfoQuery {
+    /// Comma-separated, decimal-adjusted trade sizes for price impact;
+    /// defaults to the configured sizes.
+    sizes: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PoolInfo {
+    #[serde(flatten)]
+    pub pool: Pool,
+    pub analytics: PoolAnalytics,
+    /// Indexed swap volume, fees and TVL; absent when they couldn't be
+    /// loaded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats: Option<PoolStats>,
+}
+
+// Upper bound on trade sizes quoted per pool info request.
+const MAX_IMPACT_SIZES: usize = 10;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/pools/{pool_id}",
+    tag = "pools",
+    params(("pool_id" = String, Path, description = "Pool account address"), PoolInfoQuery),
+    responses(
+        (status = 200, description = "Pool state with live reserves, spot price, price impact, depth and 24h stats", body = PoolInfo),
+        (status = 400, description = "Invalid trade sizes", body = ErrorBody),
+        (status = 404, description = "Not a supported pool", body = ErrorBody)
+    )
+)]
 async fn get_pool_info(
     State(state): State<AppState>,
+    CacheBypass(bypass): CacheBypass,
     Path(pool_id): Path<String>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    match state.solana_client.get_pool_info(&pool_id).await {
+    Query(query): Query<PoolInfoQuery>,
+) -> Result<Json<PoolInfo>, ApiError> {
+    let sizes = match &query.sizes {
+        Some(sizes) => sizes
+            .split(',')
+            .map(|size| size.trim().parse::<f64>().ok().filter(|s| s.is_finite() && *s > 0.0))
+            .collect::<Option<Vec<_>>>()
+            .filter(|sizes| sizes.len() <= MAX_IMPACT_SIZES)
+            .ok_or_else(|| {
+                ApiError::BadRequest(format!("sizes must be at most {} positive numbers", MAX_IMPACT_SIZES))
+            })?,
+        None => state.config.pool_analytics.impact_sizes.clone(),
+    };
+
+    let key = format!("pool_info:{}", pool_id);
+    let ttl = state.config.cache.pool_info_ttl();
+    let fetch = || async {
+        let pool = pools::fetch_pool(&state.solana_client, &pool_id).await?;
+        if let Err(e) = state.database.upsert_pools(std::slice::from_ref(&pool)).await {
+            warn!("Failed to store pool {}: {}", pool_id, e);
+        }
+        Ok::<_, anyhow::Error>(pool)
+    };
+
+    let info = async {
+        let pool: Pool = state.cache.get_or_fetch(&key, ttl, bypass, fetch).await?;
+        let mints = [Pubkey::from_str(&pool.token_a)?, Pubkey::from_str(&pool.token_b)?];
+        let decimals = state.solana_client.resolve_mints(&mints).await?;
+        let decimals_of = |mint: &Pubkey| decimals.get(mint).map(|m| m.decimals).ok_or_else(|| {
+            ApiError::Internal(format!("Pool {} has an unknown mint {}", pool_id, mint))
+        });
+        let (decimals_a, decimals_b) = (decimals_of(&mints[0])?, decimals_of(&mints[1])?);
+        let analytics = pool.analytics(decimals_a, decimals_b, &sizes, &state.config.pool_analytics.depth_bps);
+        let stats = pools::volume::pool_stats(&state.database, &state.price_oracle, &pool, decimals_a, decimals_b);
+        let stats = match stats.await {
+            Ok(stats) => Some(stats),
+            Err(e) => {
+                warn!("Failed to get stats of pool {}: {}", pool_id, e);
+                None
+            }
+        };
+        Ok::<_, anyhow::Error>(PoolInfo { pool, analytics, stats })
+    };
+
+    match info.await {
         Ok(pool_info) => Ok(Json(pool_info)),
         Err(e) => {
             warn!("Failed to get pool info for {}: {}", pool_id, e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(e.into())
         }
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/swap",
+    tag = "swap",
+    request_body = ExecuteSwapRequest,
+    params(("Idempotency-Key" = Option<String>, Header, description = "Deduplicates retried submissions")),
+    responses(
+        (status = 200, description = "Swap submitted, built for the client to sign, or simulated for a dry run", body = SwapResponse),
+        (status = 400, description = "Invalid request or output below the slippage limit", body = ErrorBody),
+        (status = 403, description = "Owner or mint refused by the compliance lists", body = ErrorBody),
+        (status = 409, description = "Idempotency key conflict", body = ErrorBody),
+        (status = 422, description = "Invalid request fields", body = ErrorBody),
+        (status = 429, description = "Rate limit exceeded", body = ErrorBody)
+    )
+)]
 async fn execute_swap(
     State(state): State<AppState>,
-    Json(request): Json<serde_json::Value>,
-) -> Result<Json<TransactionResponse>, StatusCode> {
-    match state.solana_client.execute_swap(&request).await {
-        Ok(response) => Ok(Json(response)),
-        Err(e) => {
-            warn!("Failed to execute swap: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+    grant: Option<Extension<ApiKeyGrant>>,
+    IdempotencyKey(key): IdempotencyKey,
+    Json(request): Json<ExecuteSwapRequest>,
+) -> Result<Json<SwapResponse>, ApiError> {
+    let grant = grant.as_ref().map(|Extension(grant)| grant);
+    let dry_run = request.dry_run || state.config.dry_run;
+    request.validate(dry_run)?;
+    quotes::require(&state.config.swap.quotes, &request.swap)?;
+    if request.sign_mode == SignMode::Client || dry_run {
+        // validate() guarantees an owner for both.
+        let owner = request.owner.unwrap_or_default();
+        let request = SwapTransactionRequest { owner, swap: request.swap };
+        let swap = handlers::swap::swap_transaction(&state, &request, dry_run).await?;
+        if !dry_run {
+            return Ok(Json(SwapResponse::Unsigned(swap)));
+        }
+        return match state.solana_client.simulate(&swap.transaction).await {
+            Ok(simulation) => Ok(Json(SwapResponse::Simulated(SimulatedSwap {
+                quote: swap.quote,
+                simulation,
+                transaction: swap.transaction,
+            }))),
+            Err(e) => {
+                warn!("Failed to simulate swap for {}: {}", request.owner, e);
+                Err(e.into())
+            }
+        };
+    }
+    let response = submit_swap(&state, key.as_deref(), &request.swap).await?;
+    state.api_keys.record_swap(grant, &request.swap.input_mint, request.swap.amount_in);
+    Ok(Json(SwapResponse::Submitted(response)))
+}
+
+/// Quotes, validates and submits a swap. Shared by the REST and gRPC APIs.
+pub async fn submit_swap(
+    state: &AppState,
+    idempotency_key: Option<&str>,
+    request: &SwapRequest,
+) -> Result<TransactionInfo, ApiError> {
+    request.validate(&state.config.swap)?;
+    if state.config.dry_run {
+        return Err(ApiError::BadRequest("swaps are not sent in dry-run mode".to_string()));
+    }
+    if request.mev_protection && !state.jito.is_enabled() {
+        return Err(ApiError::BadRequest("MEV protection is not available".to_string()));
+    }
+    if state.signer.is_none() {
+        return Err(ApiError::BadRequest("No gateway signer to execute swaps; use sign_mode=client".to_string()));
+    }
+    // A quoted swap, and any resend of it, goes through the quoted pool.
+    let (request, quoted) = quotes::resolve(&state.config.swap.quotes, request)?;
+    let request = &request;
+    let submit = || async {
+        let executed = async {
+            let (pool, quote) = swap::prepare(
+                &state.solana_client,
+                &state.database,
+                &state.price_oracle,
+                &state.config.swap,
+                request,
+            )
+            .await?;
+            state.compliance.check_swap(None, &quote.input_mint, &quote.output_mint)?;
+            state.token_safety.check_swap_output(&quote.output_mint).await?;
+            if let Some(claims) = &quoted {
+                claims.redeem(&state.config.swap.quotes, &state.database, &quote).await?;
+            }
+            let (transaction, blockhash) = send_swap(state, request, &pool, &quote).await?;
+            state.events.swap_executed(&transaction.signature, &quote);
+            // The swap already went out, so missing records are only logged.
+            let record = transaction.to_record(Some(quote.amount_in));
+            if let Err(e) = state.database.record_transaction(&record).await {
+                warn!("Failed to persist swap {}: {}", transaction.signature, e);
+            }
+            tokio::spawn(settle_swap(
+                state.clone(),
+                request.clone(),
+                quote,
+                transaction.signature.clone(),
+                blockhash,
+            ));
+            Ok::<_, anyhow::Error>(transaction)
+        };
+        match executed.await {
+            Ok(response) => Ok(response),
+            Err(e) => {
+                warn!("Failed to execute swap: {}", e);
+                Err(ApiError::from(e))
+            }
+        }
+    };
+
+    let ttl = state.config.idempotency_ttl();
+    run_idempotent(&state.database, idempotency_key, "swap", request, ttl, submit).await
+}
+
+/// Builds `quote` through `pool` for the gateway's signer, which trades
+/// from its own token accounts, pays the platform fee and has SOL wrapped
+/// and unwrapped around the swap, and sends it.
+async fn send_swap(
+    state: &AppState,
+    request: &SwapRequest,
+    pool: &Pool,
+    quote: &SwapQuote,
+) -> anyhow::Result<(TransactionInfo, Hash)> {
+    let signer = state
+        .signer
+        .as_deref()
+        .ok_or_else(|| ApiError::BadRequest("No gateway signer to execute swaps; use sign_mode=client".to_string()))?;
+    let instructions =
+        swap::transaction_ixs(&state.solana_client, &state.config.swap, pool, quote, &signer.public_key()).await?;
+    let bundle = request.mev_protection.then_some(&*state.jito);
+    state
+        .solana_client
+        .execute_swap(quote, &instructions, signer, bundle)
+        .await
+}
+
+/// Watches a sent swap until it lands, then records its platform fee once
+/// it has confirmed. Each time its blockhash expires before it lands, it is
+/// rebuilt on a fresh one and resent, up to `swap.expiry_retries` times. The swap keeps its quote while the current price still clears the
+/// quoted minimum; past that it is re-quoted, which fails once the price is
+/// outside the request's own limits. Every attempt is recorded, and each
+/// expired one points to the next through `replaced_by`.
+async fn settle_swap(
+    state: AppState,
+    request: SwapRequest,
+    mut quote: SwapQuote,
+    mut signature: String,
+    mut blockhash: Hash,
+) {
+    let mut retries = 0;
+    loop {
+        let resubmitted = async {
+            let sent = Signature::from_str(&signature)?;
+            if state.solana_client.await_landing(&sent, &blockhash).await? == Landing::Landed {
+                record_swap_fee(&state, &sent, &quote).await?;
+                return Ok(None);
+            }
+            if retries == state.config.swap.expiry_retries {
+                info!("Swap {} expired without landing", signature);
+                return Ok(None);
+            }
+            let (current_pool, current) = swap::prepare(
+                &state.solana_client,
+                &state.database,
+                &state.price_oracle,
+                &state.config.swap,
+                &request,
+            )
+            .await?;
+            let pool = if current.expected_amount_out < quote.min_amount_out {
+                info!("Price moved beyond slippage since swap {} was quoted, re-quoting", signature);
+                quote = current;
+                current_pool
+            } else if current_pool.id == quote.pool_id {
+                current_pool
+            } else {
+                pools::fetch_pool(&state.solana_client, &quote.pool_id).await?
+            };
+            let (transaction, next_blockhash) = send_swap(&state, &request, &pool, &quote).await?;
+            state
+                .database
+                .record_transaction(&transaction.to_record(Some(quote.amount_in)))
+                .await?;
+            state.database.replace_transaction(&signature, &transaction.signature).await?;
+            state.events.swap_executed(&transaction.signature, &quote);
+            Ok::<_, anyhow::Error>(Some((transaction.signature, next_blockhash)))
+        };
+        match resubmitted.await {
+            Ok(Some((next, next_blockhash))) => {
+                retries += 1;
+                info!("Swap {} expired, resent as {} (retry {})", signature, next, retries);
+                signature = next;
+                blockhash = next_blockhash;
+            }
+            Ok(None) => return,
+            Err(e) => {
+                warn!("Failed to settle swap {}: {}", signature, e);
+                return;
+            }
+        }
+    }
+}
+
+/// Records the platform fee of a landed swap once it has confirmed. A swap
+/// that failed transferred no fee, and one still unconfirmed after
+/// `confirmations.expiry_secs` is left unrecorded.
+async fn record_swap_fee(state: &AppState, signature: &Signature, quote: &SwapQuote) -> anyhow::Result<()> {
+    if quote.platform_fee == 0 {
+        return Ok(());
+    }
+    let config = &state.config.confirmations;
+    let deadline = Instant::now() + Duration::from_secs(config.expiry_secs.max(0) as u64);
+    loop {
+        let status = state
+            .solana_client
+            .get_signature_statuses(std::slice::from_ref(signature))
+            .await?
+            .pop()
+            .flatten();
+        match status.as_ref().map(confirmations::status_name) {
+            Some("confirmed" | "finalized") => break,
+            Some("failed") => return Ok(()),
+            _ if Instant::now() >= deadline => {
+                warn!("Swap {} did not confirm, platform fee not recorded", signature);
+                return Ok(());
+            }
+            _ => tokio::time::sleep(Duration::from_millis(config.poll_interval_ms)).await,
         }
     }
+    state.database.record_platform_fee(&signature.to_string(), quote).await
 }
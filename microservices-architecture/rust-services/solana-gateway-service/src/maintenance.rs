@@ -0,0 +1,132 @@
+use crate::config::MaintenanceConfig;
+use crate::error::ApiError;
+use crate::metrics::Metrics;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use utoipa::ToSchema;
+
+/// Write route groups that can be put into maintenance independently.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceGroup {
+    /// Transaction creation, submission and the other trading routes.
+    Transactions,
+    /// Swap execution and swap transaction building.
+    Swaps,
+}
+
+impl MaintenanceGroup {
+    pub const ALL: [MaintenanceGroup; 2] = [MaintenanceGroup::Transactions, MaintenanceGroup::Swaps];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MaintenanceGroup::Transactions => "transactions",
+            MaintenanceGroup::Swaps => "swaps",
+        }
+    }
+}
+
+/// Why and since when a route group is in maintenance, as returned in the
+/// 503 body of its routes.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct MaintenanceNotice {
+    pub group: MaintenanceGroup,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub since: DateTime<Utc>,
+}
+
+impl MaintenanceNotice {
+    pub fn message(&self) -> String {
+        let group = match self.group {
+            MaintenanceGroup::Transactions => "Transactions are",
+            MaintenanceGroup::Swaps => "Swaps are",
+        };
+        match &self.reason {
+            Some(reason) => format!("{} paused for maintenance: {}", group, reason),
+            None => format!("{} paused for maintenance", group),
+        }
+    }
+}
+
+#[derive(Default)]
+struct State {
+    /// When each group, indexed as `MaintenanceGroup::ALL`, was paused.
+    paused_since: [Option<DateTime<Utc>>; 2],
+    reason: Option<String>,
+}
+
+fn index(group: MaintenanceGroup) -> usize {
+    match group {
+        MaintenanceGroup::Transactions => 0,
+        MaintenanceGroup::Swaps => 1,
+    }
+}
+
+/// Operator switches that change how requests are served without a restart.
+/// Shared by every cluster; set from `maintenance` in the configuration at
+/// startup and on reload, and through `PUT /admin/maintenance`.
+pub struct Maintenance {
+    state: RwLock<State>,
+    metrics: Arc<Metrics>,
+}
+
+impl Maintenance {
+    pub fn new(config: &MaintenanceConfig, metrics: Arc<Metrics>) -> Self {
+        let maintenance = Self {
+            state: RwLock::new(State::default()),
+            metrics,
+        };
+        maintenance.apply(config);
+        maintenance
+    }
+
+    /// Sets every switch to the configured state.
+    pub fn apply(&self, config: &MaintenanceConfig) {
+        self.set(MaintenanceGroup::Transactions, config.transactions, config.reason.clone());
+        self.set(MaintenanceGroup::Swaps, config.swaps, config.reason.clone());
+    }
+
+    /// The notice for a group's 503 responses, or None while it serves.
+    pub fn notice(&self, group: MaintenanceGroup) -> Option<MaintenanceNotice> {
+        let state = self.state.read().unwrap();
+        let since = state.paused_since[index(group)]?;
+        Some(MaintenanceNotice {
+            group,
+            reason: state.reason.clone(),
+            since,
+        })
+    }
+
+    pub fn is_paused(&self, group: MaintenanceGroup) -> bool {
+        self.notice(group).is_some()
+    }
+
+    /// Rejects a request to a group in maintenance.
+    pub fn check(&self, group: MaintenanceGroup) -> Result<(), ApiError> {
+        match self.notice(group) {
+            Some(notice) => Err(ApiError::Maintenance(notice)),
+            None => Ok(()),
+        }
+    }
+
+    /// Puts a group into maintenance or takes it out; `reason` replaces the
+    /// one shown for every paused group. A group already paused keeps the
+    /// time it was paused at.
+    pub fn set(&self, group: MaintenanceGroup, paused: bool, reason: Option<String>) {
+        let mut state = self.state.write().unwrap();
+        state.reason = reason;
+        let since = &mut state.paused_since[index(group)];
+        *since = match (paused, *since) {
+            (true, Some(since)) => Some(since),
+            (true, None) => Some(Utc::now()),
+            (false, _) => None,
+        };
+        self.metrics.observe_maintenance(group.as_str(), paused);
+    }
+
+    pub fn reason(&self) -> Option<String> {
+        self.state.read().unwrap().reason.clone()
+    }
+}
@@ -0,0 +1,29 @@
+use solana_sdk::{instruction::Instruction, message::VersionedMessage, pubkey::Pubkey};
+
+/// SPL Memo instruction carrying `memo`, signed by `signer` so the memo is
+/// attributed to the sender.
+pub fn instruction(memo: &str, signer: &Pubkey) -> Instruction {
+    spl_memo::build_memo(memo.as_bytes(), &[signer])
+}
+
+/// Whether `program_id` is either version of the Memo program.
+pub fn is_memo_program(program_id: &Pubkey) -> bool {
+    *program_id == spl_memo::id() || *program_id == spl_memo::v1::id()
+}
+
+/// Text of the memos in a transaction, joined with `"; "` when there are
+/// several. The Memo program rejects invalid UTF-8, so anything else comes
+/// from a failed transaction and is replaced rather than dropped.
+pub fn from_message(message: &VersionedMessage) -> Option<String> {
+    let keys = message.static_account_keys();
+    let memos: Vec<String> = message
+        .instructions()
+        .iter()
+        .filter(|instruction| {
+            keys.get(instruction.program_id_index as usize)
+                .map_or(false, is_memo_program)
+        })
+        .map(|instruction| String::from_utf8_lossy(&instruction.data).into_owned())
+        .collect();
+    (!memos.is_empty()).then(|| memos.join("; "))
+}
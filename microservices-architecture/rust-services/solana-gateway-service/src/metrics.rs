@@ -0,0 +1,618 @@
+use crate::config::{MetricsConfig, SloConfig};
+use crate::request_id;
+use anyhow::Result;
+use prometheus::proto::{MetricFamily, MetricType};
+use prometheus::{
+    Encoder, GaugeVec, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const NAMESPACE: &str = "solana_gateway";
+
+pub const OPENMETRICS_FORMAT: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+// Width of the intervals requests are counted in for SLO burn rates.
+const SLO_BUCKET_SECS: u64 = 15;
+
+/// A histogram series: its exposed name and label pairs sorted by name, as
+/// they appear in gathered metric families.
+type SeriesKey = (String, Vec<(String, String)>);
+
+/// A traced observation, exposed on the bucket it fell in so a slow bucket
+/// links to a representative trace.
+#[derive(Clone)]
+struct Exemplar {
+    trace_id: String,
+    value: f64,
+    timestamp: f64,
+}
+
+#[derive(Clone, Copy, Default)]
+struct SloBucket {
+    /// Start of the interval, in units of `SLO_BUCKET_SECS` since the epoch.
+    start: u64,
+    requests: u64,
+    /// Failed with a 5xx status.
+    failed: u64,
+    /// Didn't fail but took longer than the latency threshold.
+    slow: u64,
+}
+
+/// Request outcomes for one objective, covering the longest burn rate
+/// window.
+struct SloTracker {
+    config: SloConfig,
+    buckets: Mutex<VecDeque<SloBucket>>,
+}
+
+impl SloTracker {
+    fn matches(&self, route: &str) -> bool {
+        self.config.routes.iter().any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => route.starts_with(prefix),
+            None => route == pattern,
+        })
+    }
+
+    fn record(&self, now: u64, retain_secs: u64, failed: bool, elapsed: Duration) {
+        let start = now / SLO_BUCKET_SECS;
+        let slow = match &self.config.latency {
+            Some(latency) => !failed && elapsed > Duration::from_millis(latency.threshold_ms),
+            None => false,
+        };
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.back().map_or(true, |bucket| bucket.start != start) {
+            buckets.push_back(SloBucket {
+                start,
+                ..SloBucket::default()
+            });
+        }
+        if let Some(bucket) = buckets.back_mut() {
+            bucket.requests += 1;
+            bucket.failed += u64::from(failed);
+            bucket.slow += u64::from(slow);
+        }
+        let oldest = start.saturating_sub(retain_secs / SLO_BUCKET_SECS);
+        while buckets.front().map_or(false, |bucket| bucket.start < oldest) {
+            buckets.pop_front();
+        }
+    }
+
+    /// Outcomes within the last `window_secs`, summed into one bucket.
+    fn totals(&self, now: u64, window_secs: u64) -> SloBucket {
+        let oldest = (now / SLO_BUCKET_SECS).saturating_sub(window_secs / SLO_BUCKET_SECS);
+        self.buckets
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|bucket| bucket.start >= oldest)
+            .fold(SloBucket::default(), |total, bucket| SloBucket {
+                start: total.start,
+                requests: total.requests + bucket.requests,
+                failed: total.failed + bucket.failed,
+                slow: total.slow + bucket.slow,
+            })
+    }
+}
+
+/// How many times faster than allowed the error budget is being spent:
+/// the observed bad fraction over the fraction the objective permits.
+fn burn_rate(bad: u64, total: u64, objective: f64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    (bad as f64 / total as f64) / (1.0 - objective)
+}
+
+fn window_label(secs: u64) -> String {
+    match secs {
+        secs if secs % 86_400 == 0 => format!("{}d", secs / 86_400),
+        secs if secs % 3_600 == 0 => format!("{}h", secs / 3_600),
+        secs if secs % 60 == 0 => format!("{}m", secs / 60),
+        secs => format!("{}s", secs),
+    }
+}
+
+/// Index of the bucket `value` falls in; `bounds.len()` is the `+Inf`
+/// bucket.
+fn bucket_index(bounds: &[f64], value: f64) -> usize {
+    bounds.iter().position(|&bound| value <= bound).unwrap_or(bounds.len())
+}
+
+fn unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+pub struct Metrics {
+    registry: Registry,
+    http_requests_total: IntCounterVec,
+    http_request_duration_seconds: HistogramVec,
+    rpc_requests_total: IntCounterVec,
+    rpc_request_duration_seconds: HistogramVec,
+    rpc_retries_total: IntCounterVec,
+    rpc_errors_total: IntCounterVec,
+    rpc_circuit_state: IntGaugeVec,
+    rpc_slot_lag: IntGaugeVec,
+    rpc_endpoint_degraded: IntGaugeVec,
+    reconciler_checked_total: IntCounterVec,
+    reconciler_discrepancies_total: IntCounterVec,
+    route_cache_requests_total: IntCounterVec,
+    route_cache_invalidations_total: IntCounterVec,
+    db_pool_connections: IntGaugeVec,
+    db_pool_max_connections: IntGaugeVec,
+    db_pool_acquire_seconds: HistogramVec,
+    slo_objective: GaugeVec,
+    slo_burn_rate: GaugeVec,
+    maintenance_mode: IntGaugeVec,
+    slos: Vec<SloTracker>,
+    slo_windows_secs: Vec<u64>,
+    /// Latest exemplar per bucket of each latency series; None when
+    /// exemplars are disabled.
+    exemplars: Option<Mutex<HashMap<SeriesKey, Vec<Option<Exemplar>>>>>,
+    request_latency_buckets: Vec<f64>,
+    rpc_latency_buckets: Vec<f64>,
+}
+
+impl Metrics {
+    pub fn new(config: &MetricsConfig) -> Result<Self> {
+        let registry = Registry::new_custom(Some(NAMESPACE.to_string()), None)?;
+
+        let http_requests_total = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests by route, method and status"),
+            &["route", "method", "status"],
+        )?;
+        let http_request_duration_seconds = HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "HTTP request latency by route")
+                .buckets(config.request_latency_buckets.clone()),
+            &["route", "method"],
+        )?;
+        let rpc_requests_total = IntCounterVec::new(
+            Opts::new("rpc_requests_total", "Solana RPC calls by method and outcome"),
+            &["method", "outcome"],
+        )?;
+        let rpc_request_duration_seconds = HistogramVec::new(
+            HistogramOpts::new("rpc_request_duration_seconds", "Solana RPC call latency by method")
+                .buckets(config.rpc_latency_buckets.clone()),
+            &["method"],
+        )?;
+
+        let rpc_retries_total = IntCounterVec::new(
+            Opts::new("rpc_retries_total", "Retried Solana RPC calls by method and reason"),
+            &["method", "reason"],
+        )?;
+        let rpc_errors_total = IntCounterVec::new(
+            Opts::new("rpc_errors_total", "Failed Solana RPC calls by method and error kind"),
+            &["method", "kind"],
+        )?;
+        let rpc_circuit_state = IntGaugeVec::new(
+            Opts::new(
+                "rpc_circuit_state",
+                "Circuit breaker state of an RPC endpoint: 0 closed, 1 half-open, 2 open",
+            ),
+            &["cluster", "endpoint"],
+        )?;
+        let rpc_slot_lag = IntGaugeVec::new(
+            Opts::new("rpc_slot_lag", "Slots an RPC endpoint trails the reference node"),
+            &["cluster", "endpoint"],
+        )?;
+        let rpc_endpoint_degraded = IntGaugeVec::new(
+            Opts::new("rpc_endpoint_degraded", "1 while an RPC endpoint lags beyond the configured limit"),
+            &["cluster", "endpoint"],
+        )?;
+
+        let reconciler_checked_total = IntCounterVec::new(
+            Opts::new("reconciler_checked_total", "Stored transactions re-checked against the chain"),
+            &["cluster"],
+        )?;
+        let reconciler_discrepancies_total = IntCounterVec::new(
+            Opts::new("reconciler_discrepancies_total", "Stored transactions corrected by the reconciler, by kind"),
+            &["cluster", "kind"],
+        )?;
+
+        let route_cache_requests_total = IntCounterVec::new(
+            Opts::new("route_cache_requests_total", "Swap route lookups by cache result"),
+            &["cluster", "result"],
+        )?;
+        let route_cache_invalidations_total = IntCounterVec::new(
+            Opts::new("route_cache_invalidations_total", "Pool reserve changes that evicted cached routes"),
+            &["cluster"],
+        )?;
+
+        let db_pool_connections = IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Database pool connections by cluster and state"),
+            &["cluster", "state"],
+        )?;
+        let db_pool_max_connections = IntGaugeVec::new(
+            Opts::new("db_pool_max_connections", "Configured database pool size by cluster"),
+            &["cluster"],
+        )?;
+        let db_pool_acquire_seconds = HistogramVec::new(
+            HistogramOpts::new("db_pool_acquire_seconds", "Time waited for a database connection")
+                .buckets(config.request_latency_buckets.clone()),
+            &["cluster"],
+        )?;
+
+        let slo_objective = GaugeVec::new(
+            Opts::new("slo_objective", "Target fraction of good requests by SLO and indicator"),
+            &["slo", "sli"],
+        )?;
+        let slo_burn_rate = GaugeVec::new(
+            Opts::new(
+                "slo_burn_rate",
+                "Error budget burn rate by SLO, indicator and window; 1 spends the budget exactly as allowed",
+            ),
+            &["slo", "sli", "window"],
+        )?;
+        let maintenance_mode = IntGaugeVec::new(
+            Opts::new("maintenance_mode", "1 while a write route group answers 503 for maintenance"),
+            &["group"],
+        )?;
+        for slo in &config.slos {
+            if let Some(availability) = slo.availability {
+                slo_objective.with_label_values(&[&slo.name, "availability"]).set(availability);
+            }
+            if let Some(latency) = &slo.latency {
+                slo_objective.with_label_values(&[&slo.name, "latency"]).set(latency.target);
+            }
+        }
+
+        registry.register(Box::new(http_requests_total.clone()))?;
+        registry.register(Box::new(http_request_duration_seconds.clone()))?;
+        registry.register(Box::new(rpc_requests_total.clone()))?;
+        registry.register(Box::new(rpc_request_duration_seconds.clone()))?;
+        registry.register(Box::new(rpc_retries_total.clone()))?;
+        registry.register(Box::new(rpc_errors_total.clone()))?;
+        registry.register(Box::new(rpc_circuit_state.clone()))?;
+        registry.register(Box::new(rpc_slot_lag.clone()))?;
+        registry.register(Box::new(rpc_endpoint_degraded.clone()))?;
+        registry.register(Box::new(reconciler_checked_total.clone()))?;
+        registry.register(Box::new(reconciler_discrepancies_total.clone()))?;
+        registry.register(Box::new(route_cache_requests_total.clone()))?;
+        registry.register(Box::new(route_cache_invalidations_total.clone()))?;
+        registry.register(Box::new(db_pool_connections.clone()))?;
+        registry.register(Box::new(db_pool_max_connections.clone()))?;
+        registry.register(Box::new(db_pool_acquire_seconds.clone()))?;
+        registry.register(Box::new(slo_objective.clone()))?;
+        registry.register(Box::new(slo_burn_rate.clone()))?;
+        registry.register(Box::new(maintenance_mode.clone()))?;
+
+        Ok(Self {
+            registry,
+            http_requests_total,
+            http_request_duration_seconds,
+            rpc_requests_total,
+            rpc_request_duration_seconds,
+            rpc_retries_total,
+            rpc_errors_total,
+            rpc_circuit_state,
+            rpc_slot_lag,
+            rpc_endpoint_degraded,
+            reconciler_checked_total,
+            reconciler_discrepancies_total,
+            route_cache_requests_total,
+            route_cache_invalidations_total,
+            db_pool_connections,
+            db_pool_max_connections,
+            db_pool_acquire_seconds,
+            slo_objective,
+            slo_burn_rate,
+            maintenance_mode,
+            slos: config
+                .slos
+                .iter()
+                .map(|slo| SloTracker {
+                    config: slo.clone(),
+                    buckets: Mutex::new(VecDeque::new()),
+                })
+                .collect(),
+            slo_windows_secs: config.slo_windows_secs.clone(),
+            exemplars: config.exemplars.then(|| Mutex::new(HashMap::new())),
+            request_latency_buckets: config.request_latency_buckets.clone(),
+            rpc_latency_buckets: config.rpc_latency_buckets.clone(),
+        })
+    }
+
+    pub fn observe_request(&self, route: &str, method: &str, status: u16, elapsed: Duration) {
+        self.http_requests_total
+            .with_label_values(&[route, method, &status.to_string()])
+            .inc();
+        self.http_request_duration_seconds
+            .with_label_values(&[route, method])
+            .observe(elapsed.as_secs_f64());
+        self.record_exemplar(
+            "http_request_duration_seconds",
+            &[("method", method), ("route", route)],
+            &self.request_latency_buckets,
+            elapsed,
+        );
+
+        let now = unix_secs();
+        let retain_secs = self.slo_windows_secs.iter().copied().max().unwrap_or_default();
+        for slo in self.slos.iter().filter(|slo| slo.matches(route)) {
+            slo.record(now, retain_secs, status >= 500, elapsed);
+        }
+    }
+
+    /// Recomputes every SLO's burn rate for each window from the requests
+    /// recorded since.
+    fn update_burn_rates(&self) {
+        let now = unix_secs();
+        for slo in &self.slos {
+            for &window in &self.slo_windows_secs {
+                let totals = slo.totals(now, window);
+                let label = window_label(window);
+                if let Some(availability) = slo.config.availability {
+                    self.slo_burn_rate
+                        .with_label_values(&[&slo.config.name, "availability", &label])
+                        .set(burn_rate(totals.failed, totals.requests, availability));
+                }
+                if let Some(latency) = &slo.config.latency {
+                    self.slo_burn_rate
+                        .with_label_values(&[&slo.config.name, "latency", &label])
+                        .set(burn_rate(totals.slow, totals.requests - totals.failed, latency.target));
+                }
+            }
+        }
+    }
+
+    pub fn observe_rpc(&self, method: &str, success: bool, elapsed: Duration) {
+        let outcome = if success { "success" } else { "error" };
+        self.rpc_requests_total
+            .with_label_values(&[method, outcome])
+            .inc();
+        self.rpc_request_duration_seconds
+            .with_label_values(&[method])
+            .observe(elapsed.as_secs_f64());
+        self.record_exemplar(
+            "rpc_request_duration_seconds",
+            &[("method", method)],
+            &self.rpc_latency_buckets,
+            elapsed,
+        );
+    }
+
+    /// Keeps the observation as its bucket's exemplar when the current
+    /// request carries a trace ID. `labels` must be sorted by name.
+    fn record_exemplar(&self, name: &str, labels: &[(&str, &str)], bounds: &[f64], elapsed: Duration) {
+        let (Some(exemplars), Some(trace_id)) = (&self.exemplars, request_id::current_trace_id()) else {
+            return;
+        };
+        let value = elapsed.as_secs_f64();
+        let key = (
+            format!("{}_{}", NAMESPACE, name),
+            labels
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        );
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs_f64())
+            .unwrap_or_default();
+        let mut exemplars = exemplars.lock().unwrap();
+        let buckets = exemplars.entry(key).or_insert_with(|| vec![None; bounds.len() + 1]);
+        buckets[bucket_index(bounds, value)] = Some(Exemplar {
+            trace_id,
+            value,
+            timestamp,
+        });
+    }
+
+    pub fn exemplars_enabled(&self) -> bool {
+        self.exemplars.is_some()
+    }
+
+    pub fn observe_rpc_retry(&self, method: &str, reason: &str) {
+        self.rpc_retries_total.with_label_values(&[method, reason]).inc();
+    }
+
+    pub fn observe_rpc_error(&self, method: &str, kind: &str) {
+        self.rpc_errors_total.with_label_values(&[method, kind]).inc();
+    }
+
+    pub fn observe_circuit_state(&self, cluster: &str, endpoint: &str, state: i64) {
+        self.rpc_circuit_state
+            .with_label_values(&[cluster, endpoint])
+            .set(state);
+    }
+
+    pub fn observe_slot_lag(&self, cluster: &str, endpoint: &str, lag: u64, degraded: bool) {
+        self.rpc_slot_lag
+            .with_label_values(&[cluster, endpoint])
+            .set(lag as i64);
+        self.rpc_endpoint_degraded
+            .with_label_values(&[cluster, endpoint])
+            .set(i64::from(degraded));
+    }
+
+    pub fn observe_reconciled(&self, cluster: &str, checked: usize) {
+        self.reconciler_checked_total
+            .with_label_values(&[cluster])
+            .inc_by(checked as u64);
+    }
+
+    pub fn observe_reconciler_discrepancy(&self, cluster: &str, kind: &str) {
+        self.reconciler_discrepancies_total
+            .with_label_values(&[cluster, kind])
+            .inc();
+    }
+
+    pub fn observe_route_cache(&self, cluster: &str, hit: bool) {
+        let result = if hit { "hit" } else { "miss" };
+        self.route_cache_requests_total
+            .with_label_values(&[cluster, result])
+            .inc();
+    }
+
+    pub fn observe_route_cache_invalidation(&self, cluster: &str, pools: usize) {
+        self.route_cache_invalidations_total
+            .with_label_values(&[cluster])
+            .inc_by(pools as u64);
+    }
+
+    pub fn observe_db_pool(&self, cluster: &str, in_use: u32, idle: u32, max: u32, wait: Option<Duration>) {
+        self.db_pool_connections
+            .with_label_values(&[cluster, "in_use"])
+            .set(in_use as i64);
+        self.db_pool_connections
+            .with_label_values(&[cluster, "idle"])
+            .set(idle as i64);
+        self.db_pool_max_connections
+            .with_label_values(&[cluster])
+            .set(max as i64);
+        if let Some(wait) = wait {
+            self.db_pool_acquire_seconds
+                .with_label_values(&[cluster])
+                .observe(wait.as_secs_f64());
+        }
+    }
+
+    pub fn observe_maintenance(&self, group: &str, paused: bool) {
+        self.maintenance_mode
+            .with_label_values(&[group])
+            .set(i64::from(paused));
+    }
+
+    /// Renders every registered metric in the Prometheus text exposition format.
+    pub fn render(&self) -> Result<String> {
+        self.update_burn_rates();
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+
+    /// Renders every registered metric in the OpenMetrics text format, with
+    /// latency buckets carrying their exemplars. Counters are exposed under
+    /// their name without `_total`, with a `_total` sample.
+    pub fn render_openmetrics(&self) -> Result<String> {
+        self.update_burn_rates();
+        let exemplars = match &self.exemplars {
+            Some(exemplars) => exemplars.lock().unwrap().clone(),
+            None => HashMap::new(),
+        };
+        let mut out = String::new();
+        for family in self.registry.gather() {
+            write_family(&mut out, &family, &exemplars)?;
+        }
+        out.push_str("# EOF\n");
+        Ok(out)
+    }
+}
+
+fn write_family(
+    out: &mut String,
+    family: &MetricFamily,
+    exemplars: &HashMap<SeriesKey, Vec<Option<Exemplar>>>,
+) -> std::fmt::Result {
+    let name = family.get_name();
+    let (name, kind) = match family.get_field_type() {
+        MetricType::COUNTER => (name.strip_suffix("_total").unwrap_or(name), "counter"),
+        MetricType::GAUGE => (name, "gauge"),
+        MetricType::HISTOGRAM => (name, "histogram"),
+        MetricType::SUMMARY => (name, "summary"),
+        MetricType::UNTYPED => (name, "unknown"),
+    };
+    writeln!(out, "# TYPE {} {}", name, kind)?;
+    writeln!(out, "# HELP {} {}", name, escape(family.get_help()))?;
+
+    for metric in family.get_metric() {
+        let labels: Vec<(String, String)> = metric
+            .get_label()
+            .iter()
+            .map(|pair| (pair.get_name().to_string(), pair.get_value().to_string()))
+            .collect();
+        match family.get_field_type() {
+            MetricType::COUNTER => {
+                write_sample(out, &format!("{}_total", name), &labels, None, metric.get_counter().get_value())?;
+            }
+            MetricType::GAUGE => write_sample(out, name, &labels, None, metric.get_gauge().get_value())?,
+            MetricType::HISTOGRAM => {
+                let histogram = metric.get_histogram();
+                let series = exemplars.get(&(name.to_string(), labels.clone()));
+                let exemplar = |i: usize| series.and_then(|buckets| buckets.get(i)).and_then(Option::as_ref);
+                let bucket_name = format!("{}_bucket", name);
+                let buckets = histogram.get_bucket();
+                for (i, bucket) in buckets.iter().enumerate() {
+                    let le = ("le", float(bucket.get_upper_bound()));
+                    write_sample(out, &bucket_name, &labels, Some(le), bucket.get_cumulative_count() as f64)?;
+                    write_exemplar(out, exemplar(i))?;
+                }
+                let le = ("le", "+Inf".to_string());
+                write_sample(out, &bucket_name, &labels, Some(le), histogram.get_sample_count() as f64)?;
+                write_exemplar(out, exemplar(buckets.len()))?;
+                write_sample(out, &format!("{}_sum", name), &labels, None, histogram.get_sample_sum())?;
+                write_sample(out, &format!("{}_count", name), &labels, None, histogram.get_sample_count() as f64)?;
+            }
+            // Nothing registered here exports summaries or untyped metrics.
+            MetricType::SUMMARY | MetricType::UNTYPED => {}
+        }
+    }
+    Ok(())
+}
+
+/// Writes a sample line. Bucket lines, those with an `le` label, are left
+/// open for `write_exemplar` to end.
+fn write_sample(
+    out: &mut String,
+    name: &str,
+    labels: &[(String, String)],
+    le: Option<(&str, String)>,
+    value: f64,
+) -> std::fmt::Result {
+    out.push_str(name);
+    let mut pairs = labels
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .chain(le.as_ref().map(|(name, value)| (*name, value.as_str())))
+        .peekable();
+    if pairs.peek().is_some() {
+        out.push('{');
+        for (i, (name, value)) in pairs.enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write!(out, "{}=\"{}\"", name, escape(value))?;
+        }
+        out.push('}');
+    }
+    write!(out, " {}", float(value))?;
+    if le.is_none() {
+        out.push('\n');
+    }
+    Ok(())
+}
+
+/// Ends a bucket line, with its exemplar if it has one.
+fn write_exemplar(out: &mut String, exemplar: Option<&Exemplar>) -> std::fmt::Result {
+    match exemplar {
+        Some(exemplar) => writeln!(
+            out,
+            " # {{trace_id=\"{}\"}} {} {:.3}",
+            exemplar.trace_id,
+            float(exemplar.value),
+            exemplar.timestamp
+        ),
+        None => writeln!(out),
+    }
+}
+
+fn float(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{:.1}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
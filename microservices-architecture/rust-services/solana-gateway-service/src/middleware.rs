@@ -0,0 +1,298 @@
+use crate::api_keys::ApiKeyGrant;
+use crate::audit::{self, NewAuditEntry};
+use crate::auth::{Principal, Role};
+use crate::error::ApiError;
+use crate::maintenance::MaintenanceGroup;
+use crate::rate_limit::RouteGroup;
+use crate::request_id::{self, REQUEST_ID_HEADER, TRACEPARENT_HEADER};
+use crate::AppState;
+use axum::{
+    body::{to_bytes, Body},
+    extract::{ConnectInfo, MatchedPath, Request, State},
+    http::{
+        header::{AUTHORIZATION, CONTENT_LENGTH, RETRY_AFTER},
+        HeaderValue, StatusCode,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+    BoxError,
+};
+use http_body_util::Limited;
+use std::net::SocketAddr;
+use std::time::Instant;
+use tower::load_shed::error::Overloaded;
+use tracing::warn;
+
+const API_KEY_HEADER: &str = "x-api-key";
+const ADMIN_TOKEN_HEADER: &str = "x-admin-token";
+// Matches axum's default JSON body limit, so buffering never accepts a body
+// the handler would have rejected.
+const MAX_AUDITED_BODY: usize = 2 * 1024 * 1024;
+
+/// Assigns every request an ID, keeping a well-formed one sent by the
+/// caller. The ID is visible to the handler through its `X-Request-Id`
+/// header and `request_id::current`, and returned in the response header.
+/// The caller's `traceparent` trace ID, if any, is what
+/// `request_id::current_trace_id` returns when spans aren't exported.
+pub async fn assign_request_id(mut request: Request, next: Next) -> Response {
+    let id = request_id::from_header(request.headers().get(REQUEST_ID_HEADER));
+    let header = HeaderValue::from_str(&id).expect("request IDs are visible ASCII");
+    request.headers_mut().insert(REQUEST_ID_HEADER, header.clone());
+    let trace_id = request_id::trace_id(request.headers().get(TRACEPARENT_HEADER)).map(str::to_string);
+
+    let mut response = request_id::scope(id, trace_id, next.run(request)).await;
+    response.headers_mut().insert(REQUEST_ID_HEADER, header);
+    response
+}
+
+/// Records request count and latency per matched route template, so
+/// `/api/v1/accounts/:address` is one series rather than one per address.
+pub async fn track_metrics(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let route = matched_route(&request);
+    let method = request.method().to_string();
+    let started = Instant::now();
+
+    let response = next.run(request).await;
+
+    state
+        .metrics
+        .observe_request(&route, &method, response.status().as_u16(), started.elapsed());
+    response
+}
+
+pub async fn rate_limit_read(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    rate_limit(&state, RouteGroup::Read, request, next).await
+}
+
+pub async fn rate_limit_trading(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    rate_limit(&state, RouteGroup::Trading, request, next).await
+}
+
+async fn rate_limit(state: &AppState, group: RouteGroup, request: Request, next: Next) -> Response {
+    // Issued keys are limited by ID, so a rotated key keeps its bucket.
+    let grant = request.extensions().get::<ApiKeyGrant>();
+    let client = grant.map_or_else(|| client_id(&request), |grant| grant.id.to_string());
+    let quota = grant.and_then(|grant| grant.quota.as_ref());
+    if let Err(wait) = state.rate_limits.check_quota(group, &client, quota) {
+        // Round up so clients never retry before a token is available.
+        let retry_after = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+        return ApiError::TooManyRequests(retry_after).into_response();
+    }
+    next.run(request).await
+}
+
+pub async fn limit_body_read(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    limit_body(&state, RouteGroup::Read, request, next).await
+}
+
+pub async fn limit_body_trading(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    limit_body(&state, RouteGroup::Trading, request, next).await
+}
+
+/// Rejects bodies above the route's limit: by `Content-Length` before any
+/// of the body is read, otherwise as soon as a streamed body crosses it.
+async fn limit_body(state: &AppState, group: RouteGroup, request: Request, next: Next) -> Response {
+    let limits = &state.config.body_limits;
+    let limit = match limits.routes.get(&matched_route(&request)) {
+        Some(limit) => *limit,
+        None if group == RouteGroup::Read => limits.read_bytes,
+        None => limits.trading_bytes,
+    };
+    let declared = request
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    if declared.map_or(false, |length| length > limit as u64) {
+        return ApiError::PayloadTooLarge(limit).into_response();
+    }
+
+    let response = next.run(request.map(|body| Body::new(Limited::new(body, limit)))).await;
+    // Extractors and the audit layer report a crossed limit in their own
+    // words; answer with the route's limit instead.
+    if response.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        return ApiError::PayloadTooLarge(limit).into_response();
+    }
+    response
+}
+
+pub async fn require_viewer(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    authorize(&state, Role::Viewer, request, next).await
+}
+
+pub async fn require_trader(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    authorize(&state, Role::Trader, request, next).await
+}
+
+pub async fn require_admin(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    authorize(&state, Role::Admin, request, next).await
+}
+
+/// Authenticates the caller (once per request) and checks their role.
+/// Anonymous requests pass only while neither bearer tokens nor API keys
+/// are required.
+async fn authorize(state: &AppState, required: Role, mut request: Request, next: Next) -> Response {
+    let principal = match request.extensions().get::<Principal>() {
+        Some(principal) => Some(principal.clone()),
+        None => match authenticate(state, &mut request).await {
+            Ok(principal) => principal,
+            Err(e) => return e.into_response(),
+        },
+    };
+    // Only the outermost check authenticates and attaches the principal, so
+    // each request made with a key is counted once.
+    let api_key = request
+        .extensions()
+        .get::<ApiKeyGrant>()
+        .map(|grant| grant.id)
+        .filter(|_| request.extensions().get::<Principal>().is_none());
+
+    let response = match principal {
+        Some(principal) if principal.role < required => {
+            ApiError::Forbidden(format!("Requires the {} role", required.as_str())).into_response()
+        }
+        Some(principal) => {
+            request.extensions_mut().insert(principal);
+            next.run(request).await
+        }
+        None if state.auth.is_enabled() || state.api_keys.is_enabled() => {
+            ApiError::Unauthorized("Missing bearer token or API key".to_string()).into_response()
+        }
+        None => next.run(request).await,
+    };
+    if let Some(id) = api_key {
+        state.api_keys.record_response(id, response.status());
+    }
+    response
+}
+
+/// Resolves an issued `X-API-Key`, attaching its grant for rate limiting,
+/// or else a bearer token.
+async fn authenticate(state: &AppState, request: &mut Request) -> Result<Option<Principal>, ApiError> {
+    if state.api_keys.is_enabled() {
+        if let Some(key) = api_key(request).map(str::to_string) {
+            let grant = state.api_keys.verify(&key).await?;
+            let principal = grant.principal();
+            request.extensions_mut().insert(grant);
+            return Ok(Some(principal));
+        }
+    }
+    match bearer_token(request) {
+        Some(token) if state.auth.is_enabled() => state.auth.verify(token).await.map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Admits `/admin` requests carrying the configured `X-Admin-Token`.
+pub async fn require_admin_token(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let Some(token) = request.headers().get(ADMIN_TOKEN_HEADER).and_then(|v| v.to_str().ok()) else {
+        return ApiError::Unauthorized("Missing admin token".to_string()).into_response();
+    };
+    let expected = state.config.admin.token.as_deref().unwrap_or_default();
+    // Comparing digests keeps the comparison time independent of where the
+    // tokens first differ.
+    if expected.is_empty() || audit::hex_sha256(token.as_bytes()) != audit::hex_sha256(expected.as_bytes()) {
+        return ApiError::Unauthorized("Invalid admin token".to_string()).into_response();
+    }
+    next.run(request).await
+}
+
+/// Rejects requests with a 503 maintenance notice while an operator has
+/// paused the route group.
+pub async fn reject_when_paused(
+    State((state, group)): State<(AppState, MaintenanceGroup)>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if let Err(e) = state.maintenance.check(group) {
+        return e.into_response();
+    }
+    next.run(request).await
+}
+
+/// Converts errors from a route group's load-shedding layer. Shed requests
+/// get 503 with a `Retry-After` hint.
+pub fn shed_load(err: BoxError, retry_after: u64) -> Response {
+    if !err.is::<Overloaded>() {
+        return ApiError::Internal(err.to_string()).into_response();
+    }
+    let mut response = ApiError::ServiceUnavailable("Server is at capacity, retry later".to_string()).into_response();
+    response.headers_mut().insert(RETRY_AFTER, HeaderValue::from(retry_after));
+    response
+}
+
+/// Records every mutating request with its actor, body hash, outcome and
+/// resulting transaction signature in the append-only audit log.
+pub async fn audit_mutations(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let route = matched_route(&request);
+    let method = request.method().to_string();
+    let subject = request.extensions().get::<Principal>().map(|p| p.subject.clone());
+    let actor = audit::actor(subject.as_deref(), api_key(&request), peer_ip(&request));
+
+    let (parts, body) = request.into_parts();
+    let body = match to_bytes(body, MAX_AUDITED_BODY).await {
+        Ok(body) => body,
+        Err(_) => return ApiError::PayloadTooLarge(MAX_AUDITED_BODY).into_response(),
+    };
+    let request_hash = audit::hex_sha256(&body);
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+    let (parts, body) = response.into_parts();
+    let body = to_bytes(body, usize::MAX).await.unwrap_or_default();
+    let value = serde_json::from_slice::<serde_json::Value>(&body).ok();
+    let signature = value
+        .as_ref()
+        .and_then(|value| value.get("signature")?.as_str().map(str::to_string));
+    let blocked = value.as_ref().and_then(|value| value.get("code")?.as_str()) == Some("blocked");
+
+    let entry = NewAuditEntry {
+        actor,
+        method,
+        route,
+        request_hash,
+        status_code: parts.status.as_u16(),
+        signature,
+        blocked,
+    };
+    if let Err(e) = state.database.insert_audit_entry(&entry).await {
+        warn!("Failed to write audit entry for {} {}: {}", entry.method, entry.route, e);
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn matched_route(request: &Request) -> String {
+    request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_owned())
+        .unwrap_or_else(|| "unmatched".to_string())
+}
+
+fn api_key(request: &Request) -> Option<&str> {
+    request.headers().get(API_KEY_HEADER).and_then(|v| v.to_str().ok())
+}
+
+fn bearer_token(request: &Request) -> Option<&str> {
+    request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+}
+
+fn peer_ip(request: &Request) -> Option<String> {
+    request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string())
+}
+
+/// Identifies the caller by API key, falling back to the peer address.
+fn client_id(request: &Request) -> String {
+    api_key(request)
+        .map(str::to_string)
+        .or_else(|| peer_ip(request))
+        .unwrap_or_else(|| "anonymous".to_string())
+}
@@ -0,0 +1,258 @@
+use crate::config::NftConfig;
+use crate::error::ApiError;
+use crate::solana_client::SolanaClient;
+use anyhow::Result;
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey, pubkey::Pubkey};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::debug;
+use utoipa::ToSchema;
+
+pub const METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+// `Key::MetadataV1` discriminator of Token Metadata accounts.
+const METADATA_V1_KEY: u8 = 4;
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct Creator {
+    pub address: String,
+    pub verified: bool,
+    /// Percentage of royalties paid to this creator.
+    pub share: u8,
+}
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct Collection {
+    pub key: String,
+    pub verified: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct NftMetadata {
+    pub mint: String,
+    /// Token Metadata PDA the fields were decoded from.
+    pub metadata_account: String,
+    pub update_authority: String,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub creators: Vec<Creator>,
+    pub collection: Option<Collection>,
+    pub primary_sale_happened: bool,
+    pub is_mutable: bool,
+    /// JSON document at `uri`, present only when resolution was requested
+    /// and the fetch succeeded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offchain: Option<serde_json::Value>,
+}
+
+/// Decodes Metaplex Token Metadata accounts and optionally fetches the
+/// off-chain JSON they point to.
+pub struct NftResolver {
+    client: Arc<SolanaClient>,
+    http: reqwest::Client,
+    max_offchain_bytes: usize,
+}
+
+impl NftResolver {
+    pub fn new(client: Arc<SolanaClient>, config: &NftConfig) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.offchain_timeout_secs))
+            .build()?;
+        Ok(Self {
+            client,
+            http,
+            max_offchain_bytes: config.offchain_max_bytes,
+        })
+    }
+
+    pub async fn get_nft(&self, mint: &str, resolve: bool) -> Result<NftMetadata> {
+        let mint = Pubkey::from_str(mint).map_err(|_| ApiError::BadRequest("Invalid mint".to_string()))?;
+        self.get_nfts(&[mint], resolve)
+            .await?
+            .pop()
+            .ok_or_else(|| ApiError::NotFound(format!("No token metadata for mint {}", mint)).into())
+    }
+
+    /// NFTs held by `owner`: token accounts with a balance of exactly one
+    /// on a zero-decimal mint that has a metadata account.
+    pub async fn get_account_nfts(&self, owner: &str, resolve: bool) -> Result<Vec<NftMetadata>> {
+        let mints: Vec<Pubkey> = self
+            .client
+            .get_token_balances(owner)
+            .await?
+            .into_iter()
+            .filter(|balance| balance.amount == 1 && balance.decimals == 0)
+            .filter_map(|balance| Pubkey::from_str(&balance.mint).ok())
+            .collect();
+        self.get_nfts(&mints, resolve).await
+    }
+
+    /// Metadata for each mint that has a decodable metadata account, fetched
+    /// with batched `getMultipleAccounts`. Other mints are omitted.
+    async fn get_nfts(&self, mints: &[Pubkey], resolve: bool) -> Result<Vec<NftMetadata>> {
+        let addresses: Vec<Pubkey> = mints.iter().map(metadata_address).collect();
+        let accounts = self.client.get_multiple_accounts(&addresses).await?;
+
+        let mut nfts: Vec<NftMetadata> = addresses
+            .iter()
+            .zip(accounts)
+            .filter_map(|(address, account)| {
+                let account = account.filter(|a| a.owner == METADATA_PROGRAM_ID)?;
+                parse_metadata(address, &account.data)
+            })
+            .collect();
+
+        if resolve {
+            let documents = join_all(nfts.iter().map(|nft| self.fetch_offchain(&nft.uri))).await;
+            for (nft, document) in nfts.iter_mut().zip(documents) {
+                nft.offchain = document;
+            }
+        }
+        Ok(nfts)
+    }
+
+    /// Off-chain metadata is best effort: unreachable, oversized or invalid
+    /// documents are logged and left out.
+    async fn fetch_offchain(&self, uri: &str) -> Option<serde_json::Value> {
+        if !(uri.starts_with("https://") || uri.starts_with("http://")) {
+            return None;
+        }
+
+        let fetch = async {
+            let response = self.http.get(uri).send().await?.error_for_status()?;
+            if response.content_length().map_or(false, |len| len as usize > self.max_offchain_bytes) {
+                anyhow::bail!("document exceeds {} bytes", self.max_offchain_bytes);
+            }
+            let body = response.bytes().await?;
+            if body.len() > self.max_offchain_bytes {
+                anyhow::bail!("document exceeds {} bytes", self.max_offchain_bytes);
+            }
+            Ok(serde_json::from_slice(&body)?)
+        };
+
+        match fetch.await {
+            Ok(document) => Some(document),
+            Err(e) => {
+                debug!("Failed to resolve off-chain metadata at {}: {}", uri, e);
+                None
+            }
+        }
+    }
+}
+
+pub fn metadata_address(mint: &Pubkey) -> Pubkey {
+    let seeds = &[b"metadata".as_ref(), METADATA_PROGRAM_ID.as_ref(), mint.as_ref()];
+    Pubkey::find_program_address(seeds, &METADATA_PROGRAM_ID).0
+}
+
+/// Decodes the Borsh-serialized `Metadata` struct up to the collection
+/// field; later optional fields (uses, programmable config) are ignored.
+pub(crate) fn parse_metadata(address: &Pubkey, data: &[u8]) -> Option<NftMetadata> {
+    let mut reader = BorshReader { data, offset: 0 };
+    if reader.u8()? != METADATA_V1_KEY {
+        return None;
+    }
+
+    let update_authority = reader.pubkey()?;
+    let mint = reader.pubkey()?;
+    let name = reader.string()?;
+    let symbol = reader.string()?;
+    let uri = reader.string()?;
+    let seller_fee_basis_points = reader.u16()?;
+    let creators = match reader.option()? {
+        false => Vec::new(),
+        true => (0..reader.u32()?)
+            .map(|_| {
+                Some(Creator {
+                    address: reader.pubkey()?.to_string(),
+                    verified: reader.bool()?,
+                    share: reader.u8()?,
+                })
+            })
+            .collect::<Option<_>>()?,
+    };
+    let primary_sale_happened = reader.bool()?;
+    let is_mutable = reader.bool()?;
+
+    // Older accounts end before these optional fields.
+    let collection = (|| {
+        if reader.option()? {
+            reader.u8()?; // edition nonce
+        }
+        if reader.option()? {
+            reader.u8()?; // token standard
+        }
+        if !reader.option()? {
+            return None;
+        }
+        let verified = reader.bool()?;
+        Some(Collection {
+            key: reader.pubkey()?.to_string(),
+            verified,
+        })
+    })();
+
+    Some(NftMetadata {
+        mint: mint.to_string(),
+        metadata_account: address.to_string(),
+        update_authority: update_authority.to_string(),
+        name,
+        symbol,
+        uri,
+        seller_fee_basis_points,
+        creators,
+        collection,
+        primary_sale_happened,
+        is_mutable,
+        offchain: None,
+    })
+}
+
+struct BorshReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl BorshReader<'_> {
+    fn take(&mut self, len: usize) -> Option<&[u8]> {
+        let bytes = self.data.get(self.offset..self.offset.checked_add(len)?)?;
+        self.offset += len;
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn bool(&mut self) -> Option<bool> {
+        Some(self.u8()? != 0)
+    }
+
+    fn option(&mut self) -> Option<bool> {
+        self.bool()
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes(self.take(2)?.try_into().ok()?))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn pubkey(&mut self) -> Option<Pubkey> {
+        Pubkey::try_from(self.take(32)?).ok()
+    }
+
+    /// Metaplex pads names, symbols and URIs with NUL bytes to a fixed size.
+    fn string(&mut self) -> Option<String> {
+        let len = self.u32()? as usize;
+        let bytes = self.take(len)?;
+        Some(String::from_utf8_lossy(bytes).trim_end_matches('\0').to_string())
+    }
+}
@@ -0,0 +1,295 @@
+use crate::config::Config;
+use crate::AppState;
+use axum::{response::Json, Router};
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Solana Gateway Service"),
+    paths(
+        crate::health_check,
+        crate::handlers::health::liveness,
+        crate::handlers::health::readiness,
+        crate::handlers::metrics::get_metrics,
+        crate::get_account_info,
+        crate::api::v2::get_account,
+        crate::handlers::accounts::get_accounts_batch,
+        crate::handlers::accounts::get_decoded_account,
+        crate::get_account_balance,
+        crate::get_token_balances,
+        crate::handlers::token_accounts::create_token_account,
+        crate::handlers::token_accounts::cleanup_token_accounts,
+        crate::handlers::history::get_account_transactions,
+        crate::handlers::activity::get_account_activity,
+        crate::handlers::balance_history::get_balance_history,
+        crate::create_transaction,
+        crate::handlers::transactions::submit_signed_transaction,
+        crate::handlers::transactions::get_signature_statuses,
+        crate::get_transaction,
+        crate::handlers::subscriptions::stream_transaction_status,
+        crate::handlers::subscriptions::stream_program_logs,
+        crate::get_token_info,
+        crate::handlers::holders::get_token_holders,
+        crate::handlers::nfts::get_nft,
+        crate::handlers::nfts::get_account_nfts,
+        crate::handlers::fees::get_priority_fees,
+        crate::handlers::rent::get_rent_exemption,
+        crate::handlers::signatures::verify_signature,
+        crate::handlers::pda::derive_pda,
+        crate::handlers::prices::get_price,
+        crate::handlers::prices::get_prices,
+        crate::handlers::rpc::rpc_passthrough,
+        crate::handlers::graphql::graphql,
+        crate::get_pools,
+        crate::handlers::subscriptions::stream_new_pools,
+        crate::get_pool_info,
+        crate::handlers::candles::get_pool_candles,
+        crate::handlers::liquidity::deposit_liquidity,
+        crate::handlers::liquidity::withdraw_liquidity,
+        crate::execute_swap,
+        crate::handlers::swap::build_swap_transaction,
+        crate::handlers::swap::get_swap_routes,
+        crate::handlers::swap::get_swap_quotes,
+        crate::handlers::swap::quote_swap,
+        crate::handlers::swap::build_route_transaction,
+        crate::handlers::stats::get_fee_stats,
+        crate::handlers::usage::get_my_usage,
+        crate::handlers::wallets::create_wallet,
+        crate::handlers::wallets::import_wallet,
+        crate::handlers::wallets::list_wallets,
+        crate::handlers::wallets::get_wallet,
+        crate::handlers::wallets::export_wallet,
+        crate::handlers::audit::get_audit_log,
+        crate::handlers::nonces::get_nonce,
+        crate::handlers::nonces::create_nonce,
+        crate::handlers::nonces::advance_nonce,
+        crate::handlers::stakes::get_stake_accounts,
+        crate::handlers::stakes::delegate_stake,
+        crate::handlers::stakes::deactivate_stake,
+        crate::handlers::stakes::withdraw_stake,
+        crate::handlers::watchlist::watch_account,
+        crate::handlers::watchlist::list_watchlist,
+        crate::handlers::watchlist::unwatch_account,
+        crate::handlers::watchlist::get_account_changes,
+        crate::handlers::watchlist::get_authority_changes,
+        crate::handlers::dca::create_dca_schedule,
+        crate::handlers::dca::list_dca_schedules,
+        crate::handlers::dca::get_dca_schedule,
+        crate::handlers::dca::get_dca_executions,
+        crate::handlers::dca::pause_dca_schedule,
+        crate::handlers::dca::resume_dca_schedule,
+        crate::handlers::dca::cancel_dca_schedule,
+        crate::handlers::approvals::list_approvals,
+        crate::handlers::approvals::get_approval,
+        crate::handlers::approvals::approve,
+        crate::handlers::admin::create_api_key,
+        crate::handlers::admin::list_api_keys,
+        crate::handlers::admin::set_api_key_scopes,
+        crate::handlers::admin::set_api_key_rate_limit,
+        crate::handlers::admin::get_api_key_usage,
+        crate::handlers::admin::revoke_api_key,
+        crate::handlers::admin::rotate_api_key,
+        crate::handlers::admin::get_maintenance,
+        crate::handlers::admin::set_maintenance,
+        crate::handlers::admin::get_log_level,
+        crate::handlers::admin::set_log_level,
+        crate::handlers::admin::reload_config,
+        crate::handlers::admin::list_compliance_entries,
+        crate::handlers::admin::add_compliance_entry,
+        crate::handlers::admin::remove_compliance_entry,
+        crate::handlers::admin::flush_caches,
+        crate::handlers::admin::get_rpc_endpoints,
+        crate::handlers::admin::reindex_pools,
+    ),
+    components(schemas(
+        crate::HealthResponse,
+        crate::handlers::health::ProbeResponse,
+        crate::handlers::health::DependencyStatus,
+        crate::TransactionRequest,
+        crate::TransactionResponse,
+        crate::handlers::transactions::SignedTransactionRequest,
+        crate::handlers::transactions::SignatureStatusesRequest,
+        crate::handlers::transactions::SignatureStatus,
+        crate::handlers::transactions::SignatureStatuses,
+        crate::solana_client::SignMode,
+        crate::swap::SwapRequest,
+        crate::swap::SwapQuote,
+        crate::handlers::swap::ExecuteSwapRequest,
+        crate::handlers::swap::SwapResponse,
+        crate::handlers::swap::SwapTransactionRequest,
+        crate::handlers::swap::SwapTransaction,
+        crate::handlers::swap::SimulatedSwap,
+        crate::swap::RouteSwapRequest,
+        crate::handlers::swap::RouteTransactionRequest,
+        crate::handlers::swap::RouteTransaction,
+        crate::swap::QuoteComparison,
+        crate::swap::SourceQuote,
+        crate::swap::QuoteSource,
+        crate::swap::QuoteHop,
+        crate::quotes::SignedQuote,
+        crate::swap::FeeSummary,
+        crate::pools::routing::Route,
+        crate::pools::routing::RouteHop,
+        crate::error::ErrorBody,
+        crate::maintenance::MaintenanceNotice,
+        crate::maintenance::MaintenanceGroup,
+        crate::validation::FieldError,
+        crate::solana_client::AccountInfo,
+        crate::api::v2::AccountV2,
+        crate::handlers::accounts::BatchAccountsRequest,
+        crate::handlers::accounts::BatchAccountsResponse,
+        crate::handlers::rent::RentExemption,
+        crate::holders::HolderDistribution,
+        crate::holders::TokenHolder,
+        crate::token_safety::RiskReport,
+        crate::token_safety::RiskFlag,
+        crate::token_safety::RiskKind,
+        crate::token_safety::RiskLevel,
+        crate::handlers::signatures::MessageEncoding,
+        crate::handlers::signatures::VerifySignatureRequest,
+        crate::handlers::signatures::SignatureVerification,
+        crate::handlers::pda::PdaRequest,
+        crate::handlers::pda::PdaSeed,
+        crate::handlers::pda::SeedType,
+        crate::handlers::pda::ProgramDerivedAddress,
+        crate::decoder::DecodedAccount,
+        crate::solana_client::TokenBalance,
+        crate::handlers::token_accounts::CreateTokenAccountRequest,
+        crate::handlers::token_accounts::TokenAccountCreation,
+        crate::handlers::token_accounts::TokenAccountCleanupRequest,
+        crate::handlers::token_accounts::EmptyTokenAccount,
+        crate::handlers::token_accounts::TokenAccountCleanup,
+        crate::solana_client::TransactionInfo,
+        crate::handlers::subscriptions::TransactionStatusEvent,
+        crate::pubsub::LogUpdate,
+        crate::tokens::TokenProgram,
+        crate::token_registry::TokenMetadata,
+        crate::tokens::TokenExtensions,
+        crate::tokens::TransferFee,
+        crate::nfts::NftMetadata,
+        crate::nfts::Creator,
+        crate::nfts::Collection,
+        crate::fees::PriorityFeeStrategy,
+        crate::fees::PriorityFeeEstimate,
+        crate::price_oracle::PriceQuote,
+        crate::pools::Dex,
+        crate::pools::Pool,
+        crate::pools::ClmmState,
+        crate::pools::feed::NewPool,
+        crate::pools::feed::NewPoolToken,
+        crate::PoolInfo,
+        crate::pools::analytics::PoolAnalytics,
+        crate::pools::candles::Candle,
+        crate::pools::candles::CandleInterval,
+        crate::pools::analytics::PriceImpact,
+        crate::pools::analytics::DepthLevel,
+        crate::pools::volume::PoolStats,
+        crate::pools::liquidity::LiquidityQuote,
+        crate::handlers::liquidity::DepositRequest,
+        crate::handlers::liquidity::WithdrawRequest,
+        crate::handlers::liquidity::LiquidityTransaction,
+        crate::pagination::SortOrder,
+        crate::pagination::PoolPage,
+        crate::database::TransactionRecord,
+        crate::handlers::history::TransactionHistory,
+        crate::handlers::activity::ActivityFeed,
+        crate::activity::Activity,
+        crate::activity::ActivityEvent,
+        crate::handlers::balance_history::BalanceHistory,
+        crate::balance_history::BalancePoint,
+        crate::balance_history::SnapshotBalance,
+        crate::wallets::Wallet,
+        crate::handlers::wallets::CreateWalletRequest,
+        crate::handlers::wallets::ImportWalletRequest,
+        crate::handlers::wallets::ExportedWallet,
+        crate::audit::AuditEntry,
+        crate::handlers::audit::AuditLog,
+        crate::solana_client::NonceInfo,
+        crate::solana_client::NonceOperation,
+        crate::handlers::nonces::NonceWalletRequest,
+        crate::stake::StakeAccount,
+        crate::stake::StakeActivation,
+        crate::stake::Delegation,
+        crate::stake::StakeReward,
+        crate::solana_client::UnsignedTransaction,
+        crate::solana_client::Simulation,
+        crate::solana_client::SimulatedTransaction,
+        crate::handlers::stakes::DelegateStakeRequest,
+        crate::handlers::stakes::DeactivateStakeRequest,
+        crate::handlers::stakes::WithdrawStakeRequest,
+        crate::watchlist::WatchRequest,
+        crate::watchlist::WatchedAccount,
+        crate::watchlist::AccountChange,
+        crate::handlers::watchlist::AccountChangeLog,
+        crate::watchlist::AuthorityChange,
+        crate::handlers::watchlist::AuthorityChangeLog,
+        crate::dca::DcaRequest,
+        crate::dca::DcaSchedule,
+        crate::dca::DcaExecution,
+        crate::handlers::dca::DcaExecutionLog,
+        crate::approvals::Approval,
+        crate::handlers::admin::CreatedApiKey,
+        crate::api_keys::ApiKey,
+        crate::api_keys::NewApiKey,
+        crate::api_keys::ApiKeyScopes,
+        crate::api_keys::ApiKeyRateLimit,
+        crate::api_keys::ApiKeyUsage,
+        crate::api_keys::UsagePeriod,
+        crate::api_keys::MintVolume,
+        crate::handlers::admin::RotatedApiKey,
+        crate::handlers::admin::MaintenanceStatus,
+        crate::handlers::admin::LogLevel,
+        crate::reload::ReloadReport,
+        crate::handlers::admin::CacheFlush,
+        crate::compliance::ComplianceEntry,
+        crate::compliance::NewComplianceEntry,
+        crate::compliance::ListName,
+        crate::compliance::EntryKind,
+        crate::handlers::rpc::RpcPassthroughRequest,
+        crate::handlers::rpc::RpcPassthroughResponse,
+        crate::handlers::rpc::RpcErrorObject,
+        crate::rpc_pool::EndpointStatus,
+        crate::rpc_pool::CircuitState,
+    )),
+    tags(
+        (name = "health", description = "Liveness and metrics"),
+        (name = "accounts", description = "Account, balance and history queries"),
+        (name = "transactions", description = "Transaction submission and lookup"),
+        (name = "tokens", description = "Mint information"),
+        (name = "nfts", description = "Metaplex NFT metadata"),
+        (name = "fees", description = "Priority fee estimation"),
+        (name = "prices", description = "Oracle prices"),
+        (name = "pools", description = "DEX pool discovery and liquidity provisioning"),
+        (name = "programs", description = "Program log streams"),
+        (name = "swap", description = "Swap quoting, routing and execution"),
+        (name = "wallets", description = "Encrypted signing keypairs"),
+        (name = "approvals", description = "Multi-party approval of large transfers"),
+        (name = "audit", description = "Audit log of mutating requests"),
+        (name = "nonces", description = "Durable nonce accounts"),
+        (name = "stakes", description = "Stake accounts and staking transactions"),
+        (name = "watchlist", description = "Account change detection and alerting"),
+        (name = "dca", description = "Recurring swap schedules"),
+        (name = "stats", description = "Platform revenue reporting"),
+        (name = "usage", description = "Per-API-key request, error and swap volume analytics"),
+        (name = "signatures", description = "Off-chain message signature checks"),
+        (name = "rpc", description = "Allowlisted JSON-RPC passthrough to the Solana node"),
+        (name = "graphql", description = "Read-only GraphQL over accounts, token balances, transactions and pools"),
+        (name = "admin", description = "Operational controls, authenticated with `X-Admin-Token`"),
+    )
+)]
+pub struct ApiDoc;
+
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// Swagger UI pointed at `/api/v1/openapi.json`, mounted only when enabled.
+pub fn swagger_ui(config: &Config) -> Router<AppState> {
+    if !config.swagger_ui {
+        return Router::new();
+    }
+    SwaggerUi::new("/swagger-ui")
+        .config(utoipa_swagger_ui::Config::from("/api/v1/openapi.json"))
+        .into()
+}
@@ -0,0 +1,148 @@
+use crate::error::ApiError;
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Query},
+    http::request::Parts,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+pub const DEFAULT_PAGE_LIMIT: i64 = 50;
+pub const MAX_PAGE_LIMIT: i64 = 1000;
+
+#[derive(Deserialize, IntoParams)]
+pub struct PageQuery {
+    /// Page size (default 50, at most 1000).
+    pub limit: Option<i64>,
+    /// Opaque `next_cursor` from the previous page.
+    #[serde(alias = "before")]
+    pub cursor: Option<String>,
+    /// Rows to skip; an alternative to `cursor`.
+    pub offset: Option<i64>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct SortQuery {
+    /// Field to sort by; the accepted names depend on the endpoint.
+    pub sort: Option<String>,
+    pub order: Option<SortOrder>,
+}
+
+/// Validated paging parameters: either cursor (keyset) or offset mode.
+pub struct Pagination {
+    pub limit: i64,
+    pub cursor: Option<String>,
+    pub offset: Option<i64>,
+}
+
+pub struct Sorting {
+    pub field: Option<String>,
+    pub order: SortOrder,
+}
+
+/// One page of a list endpoint. `next_cursor` is set whenever the page is
+/// full and more rows may follow.
+#[derive(Serialize, ToSchema)]
+#[aliases(PoolPage = Paginated<crate::pools::Pool>)]
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Paginated<T> {
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Paginated<U> {
+        Paginated {
+            items: self.items.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+impl Pagination {
+    /// Builds the page response; `cursor_of` produces the cursor that
+    /// resumes after the given (last) item.
+    pub fn page<T>(&self, items: Vec<T>, cursor_of: impl Fn(&T) -> String) -> Paginated<T> {
+        let next_cursor = if items.len() as i64 == self.limit {
+            items.last().map(cursor_of)
+        } else {
+            None
+        };
+        Paginated { items, next_cursor }
+    }
+}
+
+impl Sorting {
+    /// Maps the requested sort field onto one of `allowed` (public name,
+    /// column) pairs, defaulting to the first.
+    pub fn column(&self, allowed: &[(&str, &'static str)]) -> Result<&'static str, ApiError> {
+        match &self.field {
+            None => Ok(allowed[0].1),
+            Some(field) => allowed
+                .iter()
+                .find(|(name, _)| name == field)
+                .map(|(_, column)| *column)
+                .ok_or_else(|| {
+                    let names: Vec<&str> = allowed.iter().map(|(name, _)| *name).collect();
+                    ApiError::BadRequest(format!("sort must be one of: {}", names.join(", ")))
+                }),
+        }
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Pagination {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<PageQuery>::try_from_uri(&parts.uri)
+            .map_err(|e| ApiError::BadRequest(format!("Invalid pagination parameters: {}", e)))?;
+        if query.cursor.is_some() && query.offset.is_some() {
+            return Err(ApiError::BadRequest("Use either cursor or offset, not both".to_string()));
+        }
+        if query.offset.map_or(false, |offset| offset < 0) {
+            return Err(ApiError::BadRequest("offset must not be negative".to_string()));
+        }
+
+        Ok(Pagination {
+            limit: query.limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT),
+            cursor: query.cursor,
+            offset: query.offset,
+        })
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Sorting {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<SortQuery>::try_from_uri(&parts.uri)
+            .map_err(|e| ApiError::BadRequest(format!("Invalid sort parameters: {}", e)))?;
+        Ok(Sorting {
+            field: query.sort,
+            order: query.order.unwrap_or_default(),
+        })
+    }
+}
+
+/// Cursors are base58-encoded JSON so their structure stays private to the
+/// endpoint that issued them.
+pub fn encode_cursor<T: Serialize>(value: &T) -> String {
+    bs58::encode(serde_json::to_vec(value).unwrap_or_default()).into_string()
+}
+
+pub fn decode_cursor<T: DeserializeOwned>(cursor: &str) -> Result<T, ApiError> {
+    bs58::decode(cursor)
+        .into_vec()
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .ok_or_else(|| ApiError::BadRequest("Invalid cursor".to_string()))
+}
@@ -0,0 +1,108 @@
+use super::liquidity::{self, DepositParams, LiquidityPlan, WithdrawParams};
+use super::{Pool, PoolQuote};
+use crate::error::ApiError;
+use crate::solana_client::SolanaClient;
+use crate::wsol;
+use anyhow::Result;
+use axum::async_trait;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use std::str::FromStr;
+
+/// What one DEX program needs to take part in quoting and routing: reading
+/// its pool accounts, pricing a trade against live state and producing the
+/// swap instruction.
+#[async_trait]
+pub trait DexAdapter: Send + Sync {
+    fn decode_pool(&self, address: &Pubkey, data: &[u8]) -> Option<Pool>;
+
+    /// Quotes an exact-input swap against live pool state. Returns None if
+    /// `input_mint` isn't traded by the pool.
+    async fn quote(&self, client: &SolanaClient, pool: &Pool, input_mint: &str, amount_in: u64)
+        -> Result<Option<PoolQuote>>;
+
+    /// Instructions swapping `params.amount_in` through `pool`, failing
+    /// on-chain if the output is below `params.min_amount_out`. The owner's
+    /// token accounts must already exist.
+    async fn build_swap_ix(&self, client: &SolanaClient, pool: &Pool, params: &SwapParams) -> Result<Vec<Instruction>>;
+
+    /// Quotes and builds adding liquidity for LP tokens. Only DEXes whose
+    /// pools issue fungible LP tokens support it; others refuse.
+    async fn build_deposit(&self, _client: &SolanaClient, pool: &Pool, _params: &DepositParams) -> Result<LiquidityPlan> {
+        Err(liquidity::unsupported(pool))
+    }
+
+    /// Quotes and builds burning LP tokens for both sides of the pool.
+    async fn build_withdraw(
+        &self,
+        _client: &SolanaClient,
+        pool: &Pool,
+        _params: &WithdrawParams,
+    ) -> Result<LiquidityPlan> {
+        Err(liquidity::unsupported(pool))
+    }
+}
+
+/// An exact-input swap by `owner` between its associated token accounts.
+/// Only the classic token program is supported by every DEX, so the input
+/// account is derived under it; the output account is derived under
+/// `output_program`, the output mint's owner, where it is created.
+pub struct SwapParams {
+    pub owner: Pubkey,
+    pub input_mint: Pubkey,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub output_program: Pubkey,
+    /// Temporary wSOL account used instead of the owner's associated one on
+    /// a native SOL side.
+    pub wsol_account: Option<Pubkey>,
+}
+
+/// Direction of a swap through one pool and the owner's token accounts on
+/// either side of it.
+pub(super) struct SwapSides {
+    pub a_to_b: bool,
+    pub source: Pubkey,
+    pub destination: Pubkey,
+}
+
+impl SwapSides {
+    pub fn owner_account_a(&self) -> Pubkey {
+        if self.a_to_b {
+            self.source
+        } else {
+            self.destination
+        }
+    }
+
+    pub fn owner_account_b(&self) -> Pubkey {
+        if self.a_to_b {
+            self.destination
+        } else {
+            self.source
+        }
+    }
+}
+
+impl SwapParams {
+    /// Whether `mint`'s side of the swap goes through `wsol_account`.
+    pub fn wraps(&self, mint: &Pubkey) -> bool {
+        self.wsol_account.is_some() && wsol::is_native(mint)
+    }
+
+    pub(super) fn sides(&self, pool: &Pool) -> Result<SwapSides> {
+        let a_to_b = pool
+            .is_a_to_b(&self.input_mint.to_string())
+            .ok_or_else(|| ApiError::BadRequest("input_mint is not traded by this pool".to_string()))?;
+        let output_mint = Pubkey::from_str(if a_to_b { &pool.token_b } else { &pool.token_a })?;
+        let account = |mint: &Pubkey, program: &Pubkey| match self.wsol_account {
+            Some(account) if wsol::is_native(mint) => account,
+            _ => get_associated_token_address_with_program_id(&self.owner, mint, program),
+        };
+        Ok(SwapSides {
+            a_to_b,
+            source: account(&self.input_mint, &spl_token::id()),
+            destination: account(&output_mint, &self.output_program),
+        })
+    }
+}
@@ -0,0 +1,139 @@
+use super::{Pool, PoolQuote};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Outcome of swapping a fixed size against the current pool state.
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct PriceImpact {
+    pub input_mint: String,
+    /// Decimal-adjusted input size.
+    pub amount_in: f64,
+    /// Decimal-adjusted output after fees.
+    pub amount_out: f64,
+    /// Shortfall against the spot price, excluding the pool fee.
+    pub price_impact_bps: f64,
+}
+
+/// Input needed to move the spot price by `price_move_bps`, decimal adjusted.
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct DepthLevel {
+    pub price_move_bps: u32,
+    /// Token A sold to push the A price down by this much.
+    pub token_a_in: f64,
+    /// Token B sold to push the B price down by this much.
+    pub token_b_in: f64,
+}
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct PoolAnalytics {
+    /// Token B per token A, decimal adjusted. Absent for empty pools.
+    pub spot_price: Option<f64>,
+    pub price_impacts: Vec<PriceImpact>,
+    pub depth: Vec<DepthLevel>,
+}
+
+/// Pool state as virtual liquidity and square-root price in raw units.
+/// Constant-product reserves map to L = sqrt(x * y), sqrt(P) = sqrt(y / x);
+/// concentrated pools use their on-chain values, which are only exact while
+/// the price stays in the current tick range.
+#[derive(Clone, Copy)]
+struct Curve {
+    liquidity: f64,
+    sqrt_price: f64,
+}
+
+impl Pool {
+    fn curve(&self) -> Option<Curve> {
+        let curve = match &self.clmm {
+            Some(clmm) => Curve {
+                liquidity: clmm.liquidity as f64,
+                sqrt_price: clmm.sqrt_price_x64 as f64 / 2f64.powi(64),
+            },
+            None => {
+                let (a, b) = (self.reserve_a as f64, self.reserve_b as f64);
+                if a == 0.0 {
+                    return None;
+                }
+                Curve {
+                    liquidity: (a * b).sqrt(),
+                    sqrt_price: (b / a).sqrt(),
+                }
+            }
+        };
+        (curve.liquidity > 0.0 && curve.sqrt_price > 0.0).then_some(curve)
+    }
+
+    /// Shortfall of `quote` against the spot price, excluding the pool fee.
+    /// Absent for empty pools.
+    pub fn price_impact_bps(&self, input_mint: &str, amount_in: u64, quote: &PoolQuote) -> Option<f64> {
+        let curve = self.curve()?;
+        let raw_price = curve.sqrt_price * curve.sqrt_price;
+        let raw_price = if *input_mint == self.token_a { raw_price } else { 1.0 / raw_price };
+        Some(impact_bps(raw_price, amount_in, quote))
+    }
+
+    /// Spot price, price impact for each of `sizes` (decimal-adjusted input
+    /// amounts, applied to both directions) and depth at each of `depth_bps`.
+    pub fn analytics(&self, decimals_a: u8, decimals_b: u8, sizes: &[f64], depth_bps: &[u32]) -> PoolAnalytics {
+        let Some(curve) = self.curve() else {
+            return PoolAnalytics {
+                spot_price: None,
+                price_impacts: Vec::new(),
+                depth: Vec::new(),
+            };
+        };
+        let scale_a = 10f64.powi(decimals_a as i32);
+        let scale_b = 10f64.powi(decimals_b as i32);
+        let raw_price = curve.sqrt_price * curve.sqrt_price;
+
+        let directions = [
+            (&self.token_a, scale_a, scale_b, raw_price),
+            (&self.token_b, scale_b, scale_a, 1.0 / raw_price),
+        ];
+        let price_impacts = directions
+            .iter()
+            .flat_map(|&(input_mint, scale_in, scale_out, raw_price)| {
+                sizes.iter().filter_map(move |&size| {
+                    let amount_in = (size * scale_in) as u64;
+                    let quote = self.quote_exact_in(input_mint, amount_in)?;
+                    Some(PriceImpact {
+                        input_mint: input_mint.clone(),
+                        amount_in: size,
+                        amount_out: quote.amount_out as f64 / scale_out,
+                        price_impact_bps: impact_bps(raw_price, amount_in, &quote),
+                    })
+                })
+            })
+            .collect();
+
+        let depth = depth_bps
+            .iter()
+            .filter(|&&bps| bps > 0 && bps < 10_000)
+            .map(|&bps| {
+                let ratio = (1.0 - bps as f64 / 10_000.0).sqrt();
+                let Curve { liquidity, sqrt_price } = curve;
+                DepthLevel {
+                    price_move_bps: bps,
+                    token_a_in: liquidity * (1.0 / (sqrt_price * ratio) - 1.0 / sqrt_price) / scale_a,
+                    token_b_in: liquidity * (sqrt_price / ratio - sqrt_price) / scale_b,
+                }
+            })
+            .collect();
+
+        PoolAnalytics {
+            spot_price: Some(raw_price * scale_a / scale_b),
+            price_impacts,
+            depth,
+        }
+    }
+}
+
+/// `raw_price` is output per input in raw units.
+fn impact_bps(raw_price: f64, amount_in: u64, quote: &PoolQuote) -> f64 {
+    let ideal_out = amount_in.saturating_sub(quote.fee_amount) as f64 * raw_price;
+    if ideal_out > 0.0 {
+        (1.0 - quote.amount_out as f64 / ideal_out).max(0.0) * 10_000.0
+    } else {
+        0.0
+    }
+}
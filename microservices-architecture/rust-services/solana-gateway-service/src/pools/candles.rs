@@ -0,0 +1,175 @@
+use super::route_cache::RouteCache;
+use super::Pool;
+use crate::config::CandleConfig;
+use crate::database::Database;
+use crate::solana_client::SolanaClient;
+use anyhow::Result;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum CandleInterval {
+    #[serde(rename = "1m")]
+    OneMinute,
+    #[serde(rename = "5m")]
+    FiveMinutes,
+    #[serde(rename = "1h")]
+    OneHour,
+    #[serde(rename = "1d")]
+    OneDay,
+}
+
+impl CandleInterval {
+    pub const ALL: [CandleInterval; 4] = [
+        CandleInterval::OneMinute,
+        CandleInterval::FiveMinutes,
+        CandleInterval::OneHour,
+        CandleInterval::OneDay,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CandleInterval::OneMinute => "1m",
+            CandleInterval::FiveMinutes => "5m",
+            CandleInterval::OneHour => "1h",
+            CandleInterval::OneDay => "1d",
+        }
+    }
+
+    pub fn seconds(self) -> i64 {
+        match self {
+            CandleInterval::OneMinute => 60,
+            CandleInterval::FiveMinutes => 300,
+            CandleInterval::OneHour => 3_600,
+            CandleInterval::OneDay => 86_400,
+        }
+    }
+
+    /// Start of the bucket containing `at`.
+    pub fn bucket_start(self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let start = at.timestamp() - at.timestamp().rem_euclid(self.seconds());
+        Utc.timestamp_opt(start, 0).unwrap()
+    }
+}
+
+/// OHLCV candle; prices are token B per token A and volumes are decimal
+/// adjusted.
+#[derive(Clone, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct Candle {
+    pub bucket_start: DateTime<Utc>,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume_a: f64,
+    pub volume_b: f64,
+}
+
+/// Price and reserve movement of one pool since its previous sample.
+pub struct PoolSample {
+    pub pool_address: String,
+    pub price: f64,
+    pub volume_a: f64,
+    pub volume_b: f64,
+}
+
+/// Samples stored pools on a schedule and folds each sample into a candle per
+/// interval. Volume is the absolute reserve change between samples, so
+/// trades that cancel out within one sampling period aren't counted.
+/// Reserve movements also evict cached swap routes through the pool.
+pub fn spawn_indexer(
+    client: Arc<SolanaClient>,
+    database: Arc<Database>,
+    route_cache: Arc<RouteCache>,
+    config: CandleConfig,
+) {
+    if !config.enabled {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.sample_interval_secs));
+        let mut previous: HashMap<String, (u64, u64)> = HashMap::new();
+        loop {
+            interval.tick().await;
+            match sample_pools(&client, &database, &route_cache, config.max_pools, &mut previous).await {
+                Ok(samples) => {
+                    let now = Utc::now();
+                    if let Err(e) = database.record_pool_samples(&samples, now).await {
+                        warn!("Failed to store pool candles: {}", e);
+                    } else {
+                        info!("Indexed {} pool samples", samples.len());
+                    }
+                }
+                Err(e) => warn!("Pool sampling failed: {}", e),
+            }
+        }
+    });
+}
+
+async fn sample_pools(
+    client: &SolanaClient,
+    database: &Database,
+    route_cache: &RouteCache,
+    max_pools: i64,
+    previous: &mut HashMap<String, (u64, u64)>,
+) -> Result<Vec<PoolSample>> {
+    let addresses = database
+        .list_pool_addresses(max_pools)
+        .await?
+        .iter()
+        .filter_map(|address| Pubkey::from_str(address).ok())
+        .collect::<Vec<_>>();
+    let pools = super::fetch_pools(client, &addresses).await?;
+    route_cache.observe_reserves(pools.iter().filter_map(|pool| {
+        let previous = previous.get(&pool.id)?;
+        Some((pool.id.as_str(), *previous, (pool.reserve_a, pool.reserve_b)))
+    }));
+
+    let mints: Vec<Pubkey> = pools
+        .iter()
+        .flat_map(|pool| [&pool.token_a, &pool.token_b])
+        .filter_map(|mint| Pubkey::from_str(mint).ok())
+        .collect();
+    let decimals = client.resolve_mints(&mints).await?;
+    let decimals_of = |mint: &str| {
+        Pubkey::from_str(mint)
+            .ok()
+            .and_then(|mint| decimals.get(&mint))
+            .map(|metadata| metadata.decimals)
+    };
+
+    Ok(pools
+        .iter()
+        .filter_map(|pool| {
+            let (decimals_a, decimals_b) = (decimals_of(&pool.token_a)?, decimals_of(&pool.token_b)?);
+            let sample = sample(pool, decimals_a, decimals_b, previous.get(&pool.id).copied());
+            previous.insert(pool.id.clone(), (pool.reserve_a, pool.reserve_b));
+            sample
+        })
+        .collect())
+}
+
+fn sample(pool: &Pool, decimals_a: u8, decimals_b: u8, previous: Option<(u64, u64)>) -> Option<PoolSample> {
+    let price = pool.analytics(decimals_a, decimals_b, &[], &[]).spot_price?;
+    let (volume_a, volume_b) = match previous {
+        Some((reserve_a, reserve_b)) => (
+            pool.reserve_a.abs_diff(reserve_a) as f64 / 10f64.powi(decimals_a as i32),
+            pool.reserve_b.abs_diff(reserve_b) as f64 / 10f64.powi(decimals_b as i32),
+        ),
+        None => (0.0, 0.0),
+    };
+    Some(PoolSample {
+        pool_address: pool.id.clone(),
+        price,
+        volume_a,
+        volume_b,
+    })
+}
@@ -0,0 +1,230 @@
+//! Live feed of newly created pools. Every supported DEX program gets a
+//! `programSubscribe`; a pool account is new when storing it finds no row
+//! for it yet. New pools are stored with their vault reserves and streamed,
+//! with their tokens' metadata, to `/api/v1/pools/stream`. Pools that
+//! neither discovery nor a running feed has seen, such as ones created
+//! while the gateway was down, are reported on their first write.
+
+use super::{refresh_state, Dex, Pool, PoolFilter};
+use crate::config::PoolFeedConfig;
+use crate::database::Database;
+use crate::solana_client::SolanaClient;
+use crate::token_registry::{TokenMetadata, TokenRegistry};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use serde::Serialize;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    nonblocking::pubsub_client::PubsubClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::RpcFilterType,
+};
+use solana_sdk::{account::Account, commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+// Wait before re-opening a program subscription that ended.
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(5);
+
+/// One side of a new pool.
+#[derive(Clone, Serialize, ToSchema)]
+pub struct NewPoolToken {
+    pub mint: String,
+    /// Absent when the mint account couldn't be read.
+    pub decimals: Option<u8>,
+    /// Absent for mints not in the token registry, which is most new ones.
+    pub metadata: Option<TokenMetadata>,
+}
+
+/// A pool seen for the first time, as sent by `/api/v1/pools/stream`.
+/// `pool` holds its reserves when it was detected, i.e. its initial
+/// liquidity.
+#[derive(Clone, Serialize, ToSchema)]
+pub struct NewPool {
+    pub pool: Pool,
+    pub token_a: NewPoolToken,
+    pub token_b: NewPoolToken,
+    /// Slot of the write the pool was detected in.
+    pub slot: u64,
+    pub detected_at: DateTime<Utc>,
+}
+
+impl NewPool {
+    pub fn matches(&self, filter: &PoolFilter) -> bool {
+        filter.dex.map_or(true, |dex| dex == self.pool.dex)
+            && filter
+                .token
+                .as_ref()
+                .map_or(true, |mint| *mint == self.pool.token_a || *mint == self.pool.token_b)
+    }
+}
+
+/// Fans new pools out to every connected stream client.
+pub struct PoolFeed {
+    enabled: bool,
+    sender: broadcast::Sender<NewPool>,
+}
+
+impl PoolFeed {
+    pub fn new(config: &PoolFeedConfig) -> Self {
+        let (sender, _) = broadcast::channel(config.channel_capacity.max(1));
+        Self {
+            enabled: config.enabled,
+            sender,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<NewPool> {
+        self.sender.subscribe()
+    }
+}
+
+/// Keeps a program subscription open for every supported DEX, re-opening
+/// it whenever it ends.
+pub fn spawn(
+    feed: Arc<PoolFeed>,
+    client: Arc<SolanaClient>,
+    database: Arc<Database>,
+    registry: Arc<TokenRegistry>,
+    ws_url: String,
+) {
+    if !feed.enabled {
+        return;
+    }
+
+    for dex in Dex::ALL {
+        let detector = Detector {
+            dex,
+            feed: feed.clone(),
+            client: client.clone(),
+            database: database.clone(),
+            registry: registry.clone(),
+            known: HashSet::new(),
+        };
+        let ws_url = ws_url.clone();
+        tokio::spawn(async move {
+            let mut detector = detector;
+            loop {
+                if let Err(e) = detector.run(&ws_url).await {
+                    warn!("New pool subscription for {} ended: {}", dex.as_str(), e);
+                }
+                tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+            }
+        });
+    }
+}
+
+struct Detector {
+    dex: Dex,
+    feed: Arc<PoolFeed>,
+    client: Arc<SolanaClient>,
+    database: Arc<Database>,
+    registry: Arc<TokenRegistry>,
+    /// Pools already stored, which later writes to them are skipped for.
+    known: HashSet<Pubkey>,
+}
+
+impl Detector {
+    async fn run(&mut self, ws_url: &str) -> Result<()> {
+        let client = PubsubClient::new(ws_url).await?;
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::DataSize(self.dex.account_len() as u64)]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        let (mut notifications, unsubscribe) = client.program_subscribe(&self.dex.program_id(), Some(config)).await?;
+        info!("Watching {} for new pools", self.dex.as_str());
+
+        while let Some(response) = notifications.next().await {
+            let Ok(address) = Pubkey::from_str(&response.value.pubkey) else {
+                continue;
+            };
+            if self.known.contains(&address) {
+                continue;
+            }
+            let Some(account) = response.value.account.decode::<Account>() else {
+                continue;
+            };
+            // Accounts that don't decode yet may be pools still being
+            // initialized, so they aren't marked as known.
+            let Some(pool) = self.dex.decode(&address, &account.data) else {
+                continue;
+            };
+            match self.detect(pool, response.context.slot).await {
+                Ok(()) => {
+                    self.known.insert(address);
+                }
+                Err(e) => warn!("Failed to check pool {} for the new pool feed: {}", address, e),
+            }
+        }
+
+        drop(notifications);
+        unsubscribe().await;
+        Ok(())
+    }
+
+    /// Stores the pool if it is new, then loads its reserves and token
+    /// details and sends it to stream clients. Reserves that can't be loaded
+    /// are left as decoded, which is zero for most DEXes.
+    async fn detect(&self, mut pool: Pool, slot: u64) -> Result<()> {
+        let Some(detected_at) = self.database.insert_new_pool(&pool).await? else {
+            return Ok(());
+        };
+
+        info!("Detected new {} pool {}", pool.dex.as_str(), pool.id);
+        // The pool is already stored, so it is sent even without reserves
+        // rather than never.
+        match refresh_state(&self.client, std::slice::from_mut(&mut pool)).await {
+            Ok(()) => {
+                if let Err(e) = self.database.upsert_pools(std::slice::from_ref(&pool)).await {
+                    warn!("Failed to store reserves of new pool {}: {}", pool.id, e);
+                }
+            }
+            Err(e) => warn!("Failed to load reserves of new pool {}: {}", pool.id, e),
+        }
+
+        let mints = [&pool.token_a, &pool.token_b]
+            .into_iter()
+            .filter_map(|mint| Pubkey::from_str(mint).ok())
+            .collect::<Vec<_>>();
+        let resolved = match self.client.resolve_mints(&mints).await {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                warn!("Failed to resolve mints of new pool {}: {}", pool.id, e);
+                Default::default()
+            }
+        };
+        let token = |mint: &str| NewPoolToken {
+            mint: mint.to_string(),
+            decimals: Pubkey::from_str(mint)
+                .ok()
+                .and_then(|mint| resolved.get(&mint))
+                .map(|metadata| metadata.decimals),
+            metadata: self.registry.get(mint),
+        };
+
+        // Nobody listening isn't an error; the pool is stored either way.
+        let _ = self.feed.sender.send(NewPool {
+            token_a: token(&pool.token_a),
+            token_b: token(&pool.token_b),
+            pool,
+            slot,
+            detected_at,
+        });
+        Ok(())
+    }
+}
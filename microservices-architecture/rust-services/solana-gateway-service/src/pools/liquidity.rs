@@ -0,0 +1,76 @@
+//! Adding and removing liquidity in pools with fungible LP tokens.
+
+use super::Pool;
+use crate::error::ApiError;
+use serde::Serialize;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use utoipa::ToSchema;
+
+/// A deposit of `amount` of `mint`, with the other side of the pool added
+/// in proportion to the reserves.
+pub struct DepositParams {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub slippage_bps: u16,
+}
+
+/// A withdrawal burning `lp_amount` LP tokens for both sides of the pool.
+pub struct WithdrawParams {
+    pub owner: Pubkey,
+    pub lp_amount: u64,
+    pub slippage_bps: u16,
+}
+
+/// Expected amounts of a deposit or withdrawal at current reserves, and the
+/// bounds enforced on-chain.
+#[derive(Serialize, ToSchema)]
+pub struct LiquidityQuote {
+    pub pool_id: String,
+    pub lp_mint: String,
+    /// Raw units of the pool's token A deposited or received.
+    pub amount_a: u64,
+    pub amount_b: u64,
+    /// The most of token A a deposit may take, or the least a withdrawal
+    /// pays out.
+    pub limit_a: u64,
+    pub limit_b: u64,
+    /// LP tokens minted by a deposit or burned by a withdrawal.
+    pub lp_amount: u64,
+    pub slippage_bps: u16,
+}
+
+/// A quoted deposit or withdrawal and the instructions executing it.
+pub struct LiquidityPlan {
+    pub quote: LiquidityQuote,
+    pub instructions: Vec<Instruction>,
+}
+
+pub(super) fn unsupported(pool: &Pool) -> anyhow::Error {
+    ApiError::BadRequest(format!(
+        "{} pools don't support liquidity provisioning",
+        pool.dex.as_str()
+    ))
+    .into()
+}
+
+/// `amount * numerator / denominator`, rounded up when `ceil`.
+pub(super) fn proportion(amount: u64, numerator: u64, denominator: u64, ceil: bool) -> Option<u64> {
+    if denominator == 0 {
+        return None;
+    }
+    let product = amount as u128 * numerator as u128;
+    let result = if ceil {
+        product.div_ceil(denominator as u128)
+    } else {
+        product / denominator as u128
+    };
+    u64::try_from(result).ok()
+}
+
+/// `amount` plus `slippage_bps`, rounded up.
+pub(super) fn slippage_ceiling(amount: u64, slippage_bps: u16) -> u64 {
+    (amount as u128 * (10_000 + slippage_bps as u128))
+        .div_ceil(10_000)
+        .min(u64::MAX as u128) as u64
+}
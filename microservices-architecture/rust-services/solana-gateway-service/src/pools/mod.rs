@@ -0,0 +1,475 @@
+pub mod adapter;
+pub mod analytics;
+pub mod candles;
+pub mod feed;
+pub mod liquidity;
+pub mod orca;
+pub mod raydium;
+pub mod raydium_clmm;
+pub mod route_cache;
+pub mod routing;
+pub mod volume;
+
+use adapter::DexAdapter;
+use crate::config::PoolDiscoveryConfig;
+use crate::database::Database;
+use crate::error::ApiError;
+use crate::solana_client::SolanaClient;
+use crate::tokens::{parse_token_account, TokenProgram};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tracing::{info, warn};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema, async_graphql::Enum)]
+#[serde(rename_all = "snake_case")]
+pub enum Dex {
+    RaydiumAmm,
+    RaydiumClmm,
+    OrcaWhirlpool,
+}
+
+impl Dex {
+    pub const ALL: [Dex; 3] = [Dex::RaydiumAmm, Dex::RaydiumClmm, Dex::OrcaWhirlpool];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Dex::RaydiumAmm => "raydium_amm",
+            Dex::RaydiumClmm => "raydium_clmm",
+            Dex::OrcaWhirlpool => "orca_whirlpool",
+        }
+    }
+
+    pub fn program_id(self) -> Pubkey {
+        match self {
+            Dex::RaydiumAmm => raydium::PROGRAM_ID,
+            Dex::RaydiumClmm => raydium_clmm::PROGRAM_ID,
+            Dex::OrcaWhirlpool => orca::PROGRAM_ID,
+        }
+    }
+
+    pub fn from_program(owner: &Pubkey) -> Option<Dex> {
+        Dex::ALL.into_iter().find(|dex| dex.program_id() == *owner)
+    }
+
+    pub fn adapter(self) -> &'static dyn DexAdapter {
+        match self {
+            Dex::RaydiumAmm => &raydium::RaydiumAmmAdapter,
+            Dex::RaydiumClmm => &raydium_clmm::RaydiumClmmAdapter,
+            Dex::OrcaWhirlpool => &orca::OrcaWhirlpoolAdapter,
+        }
+    }
+
+    pub fn decode(self, address: &Pubkey, data: &[u8]) -> Option<Pool> {
+        self.adapter().decode_pool(address, data)
+    }
+
+    fn account_len(self) -> usize {
+        match self {
+            Dex::RaydiumAmm => raydium::ACCOUNT_LEN,
+            Dex::RaydiumClmm => raydium_clmm::ACCOUNT_LEN,
+            Dex::OrcaWhirlpool => orca::ACCOUNT_LEN,
+        }
+    }
+
+    fn mint_offsets(self) -> [usize; 2] {
+        match self {
+            Dex::RaydiumAmm => [raydium::BASE_MINT_OFFSET, raydium::QUOTE_MINT_OFFSET],
+            Dex::RaydiumClmm => [raydium_clmm::MINT_0_OFFSET, raydium_clmm::MINT_1_OFFSET],
+            Dex::OrcaWhirlpool => [orca::MINT_A_OFFSET, orca::MINT_B_OFFSET],
+        }
+    }
+
+    /// `getProgramAccounts` filter sets for discovery: one full scan when no
+    /// mints are tracked, otherwise one query per (mint, side) pair.
+    fn discovery_filters(self, tracked_mints: &[Pubkey]) -> Vec<Vec<RpcFilterType>> {
+        let size = RpcFilterType::DataSize(self.account_len() as u64);
+        if tracked_mints.is_empty() {
+            return vec![vec![size]];
+        }
+
+        tracked_mints
+            .iter()
+            .flat_map(|mint| {
+                self.mint_offsets().map(|offset| {
+                    vec![
+                        size.clone(),
+                        RpcFilterType::Memcmp(Memcmp::new_base58_encoded(offset, mint.as_ref())),
+                    ]
+                })
+            })
+            .collect()
+    }
+}
+
+impl FromStr for Dex {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Dex::ALL
+            .into_iter()
+            .find(|dex| dex.as_str() == s)
+            .ok_or_else(|| anyhow::anyhow!("Unknown DEX: {}", s))
+    }
+}
+
+/// Concentrated-liquidity state; large integers are serialized as strings.
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct ClmmState {
+    #[serde(with = "u128_string")]
+    #[schema(value_type = String)]
+    pub liquidity: u128,
+    #[serde(with = "u128_string")]
+    #[schema(value_type = String)]
+    pub sqrt_price_x64: u128,
+    pub tick_current: i32,
+    pub tick_spacing: u16,
+    /// Raydium `AmmConfig` account holding the pool's fee tier.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amm_config: Option<String>,
+}
+
+/// Net liquidity added when the price crosses an initialized tick upwards
+/// (and removed when it crosses downwards).
+#[derive(Clone, Copy, Debug)]
+pub struct TickLiquidity {
+    pub tick: i32,
+    pub liquidity_net: i128,
+}
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct Pool {
+    pub id: String,
+    pub dex: Dex,
+    pub token_a: String,
+    pub token_b: String,
+    pub vault_a: String,
+    pub vault_b: String,
+    /// Vault token balances, in raw (non-decimal-adjusted) units.
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    /// Swap fee in parts per million (3000 = 0.3%).
+    pub fee_ppm: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clmm: Option<ClmmState>,
+}
+
+/// Filters accepted by `GET /api/v1/pools`.
+#[derive(Deserialize, IntoParams)]
+pub struct PoolFilter {
+    pub dex: Option<Dex>,
+    /// Only pools with this mint on either side.
+    pub token: Option<String>,
+}
+
+/// Expected result of an exact-input swap against current pool state.
+pub struct PoolQuote {
+    pub output_mint: String,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+}
+
+impl Pool {
+    /// Quotes swapping `amount_in` of `input_mint`. Constant-product pools use
+    /// the vault reserves; concentrated pools assume the trade stays within
+    /// the current tick range. Returns None if the mint isn't in the pool.
+    pub fn quote_exact_in(&self, input_mint: &str, amount_in: u64) -> Option<PoolQuote> {
+        self.quote_exact_in_across(input_mint, amount_in, &[])
+    }
+
+    /// Like `quote_exact_in`, but a concentrated pool's liquidity changes at
+    /// each of `ticks` (initialized ticks in the swap direction, nearest
+    /// first) the price crosses. Past the last tick it is held constant.
+    pub fn quote_exact_in_across(&self, input_mint: &str, amount_in: u64, ticks: &[TickLiquidity]) -> Option<PoolQuote> {
+        let a_to_b = self.is_a_to_b(input_mint)?;
+
+        let fee_amount = (amount_in as u128 * self.fee_ppm as u128).div_ceil(1_000_000) as u64;
+        let net_in = amount_in - fee_amount;
+        let amount_out = match &self.clmm {
+            Some(clmm) => clmm.amount_out(net_in, a_to_b, ticks),
+            None if a_to_b => constant_product_out(net_in, self.reserve_a, self.reserve_b),
+            None => constant_product_out(net_in, self.reserve_b, self.reserve_a),
+        };
+
+        Some(PoolQuote {
+            output_mint: if a_to_b { self.token_b.clone() } else { self.token_a.clone() },
+            amount_out,
+            fee_amount,
+        })
+    }
+
+    fn is_a_to_b(&self, input_mint: &str) -> Option<bool> {
+        if input_mint == self.token_a {
+            Some(true)
+        } else if input_mint == self.token_b {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+impl ClmmState {
+    /// Walks the price through each range between initialized ticks,
+    /// consuming input until it runs out. Selling token A moves the price
+    /// down, selling token B moves it up.
+    fn amount_out(&self, amount_in: u64, a_to_b: bool, ticks: &[TickLiquidity]) -> u64 {
+        let mut liquidity = self.liquidity as f64;
+        let mut sqrt_price = self.sqrt_price_x64 as f64 / 2f64.powi(64);
+        let mut remaining = amount_in as f64;
+        let mut out = 0.0;
+
+        let mut ticks = ticks.iter();
+        while remaining > 0.0 && liquidity > 0.0 {
+            let next = ticks.next();
+            let target = next.map(|tick| 1.0001f64.powf(tick.tick as f64 / 2.0));
+            if a_to_b {
+                let to_target = target.map(|target| liquidity * (1.0 / target - 1.0 / sqrt_price));
+                match target.zip(to_target) {
+                    Some((target, needed)) if needed <= remaining => {
+                        out += liquidity * (sqrt_price - target);
+                        remaining -= needed;
+                        sqrt_price = target;
+                    }
+                    _ => {
+                        let new_sqrt_price = liquidity * sqrt_price / (liquidity + remaining * sqrt_price);
+                        out += liquidity * (sqrt_price - new_sqrt_price);
+                        break;
+                    }
+                }
+            } else {
+                let to_target = target.map(|target| liquidity * (target - sqrt_price));
+                match target.zip(to_target) {
+                    Some((target, needed)) if needed <= remaining => {
+                        out += liquidity * (1.0 / sqrt_price - 1.0 / target);
+                        remaining -= needed;
+                        sqrt_price = target;
+                    }
+                    _ => {
+                        let new_sqrt_price = sqrt_price + remaining / liquidity;
+                        out += liquidity * (1.0 / sqrt_price - 1.0 / new_sqrt_price);
+                        break;
+                    }
+                }
+            }
+
+            let liquidity_net = next.map_or(0.0, |tick| tick.liquidity_net as f64);
+            liquidity += if a_to_b { -liquidity_net } else { liquidity_net };
+        }
+        out.max(0.0).floor() as u64
+    }
+}
+
+/// Quotes against live state through the pool's DEX adapter, which loads
+/// the ticks a concentrated-liquidity trade may cross.
+pub async fn quote_live(client: &SolanaClient, pool: &Pool, input_mint: &str, amount_in: u64) -> Result<Option<PoolQuote>> {
+    pool.dex.adapter().quote(client, pool, input_mint, amount_in).await
+}
+
+/// Quotes every pool and returns the one paying out the most, with its
+/// quote. Pools that fail to quote are skipped.
+pub async fn best_quote(
+    client: &SolanaClient,
+    pools: Vec<Pool>,
+    input_mint: &str,
+    amount_in: u64,
+) -> Option<(Pool, PoolQuote)> {
+    let quotes = futures::future::join_all(pools.iter().map(|pool| quote_live(client, pool, input_mint, amount_in))).await;
+    pools
+        .into_iter()
+        .zip(quotes)
+        .filter_map(|(pool, quote)| match quote {
+            Ok(quote) => Some((pool, quote?)),
+            Err(e) => {
+                warn!("Failed to quote pool {}: {}", pool.id, e);
+                None
+            }
+        })
+        .max_by_key(|(_, quote)| quote.amount_out)
+}
+
+/// Initialized ticks on the side of `tick_current` a swap moves towards,
+/// nearest first.
+fn swap_order(ticks: impl IntoIterator<Item = TickLiquidity>, tick_current: i32, a_to_b: bool) -> Vec<TickLiquidity> {
+    let mut ticks: Vec<TickLiquidity> = ticks
+        .into_iter()
+        .filter(|tick| if a_to_b { tick.tick <= tick_current } else { tick.tick > tick_current })
+        .collect();
+    if a_to_b {
+        ticks.sort_by(|a, b| b.tick.cmp(&a.tick));
+    } else {
+        ticks.sort_by_key(|tick| tick.tick);
+    }
+    ticks
+}
+
+fn constant_product_out(amount_in: u64, reserve_in: u64, reserve_out: u64) -> u64 {
+    let denominator = reserve_in as u128 + amount_in as u128;
+    if denominator == 0 {
+        return 0;
+    }
+    (reserve_out as u128 * amount_in as u128 / denominator) as u64
+}
+
+pub async fn discover_pools(client: &SolanaClient, tracked_mints: &[Pubkey]) -> Result<Vec<Pool>> {
+    let mut seen = HashSet::new();
+    let mut pools = Vec::new();
+
+    for dex in Dex::ALL {
+        for filters in dex.discovery_filters(tracked_mints) {
+            for (address, account) in client.get_program_accounts(&dex.program_id(), filters).await? {
+                if !seen.insert(address) {
+                    continue;
+                }
+                if let Some(pool) = dex.decode(&address, &account.data) {
+                    pools.push(pool);
+                }
+            }
+        }
+    }
+
+    refresh_state(client, &mut pools).await?;
+    Ok(pools)
+}
+
+pub async fn fetch_pool(client: &SolanaClient, address: &str) -> Result<Pool> {
+    let pubkey = Pubkey::from_str(address)?;
+    let account = client.get_raw_account(&pubkey).await?;
+    let mut pool = Dex::from_program(&account.owner)
+        .and_then(|dex| dex.decode(&pubkey, &account.data))
+        .ok_or_else(|| ApiError::NotFound("Account is not a supported pool".to_string()))?;
+
+    refresh_state(client, std::slice::from_mut(&mut pool)).await?;
+    Ok(pool)
+}
+
+/// Current state of many pools. Accounts that aren't supported pools are
+/// skipped.
+pub async fn fetch_pools(client: &SolanaClient, addresses: &[Pubkey]) -> Result<Vec<Pool>> {
+    let accounts = client.get_multiple_accounts(addresses).await?;
+    let mut pools: Vec<Pool> = addresses
+        .iter()
+        .zip(accounts)
+        .filter_map(|(address, account)| {
+            let account = account?;
+            Dex::from_program(&account.owner)?.decode(address, &account.data)
+        })
+        .collect();
+
+    refresh_state(client, &mut pools).await?;
+    Ok(pools)
+}
+
+/// Fills in what pool accounts don't hold themselves: vault balances, and
+/// fee tiers kept in separate config accounts.
+async fn refresh_state(client: &SolanaClient, pools: &mut [Pool]) -> Result<()> {
+    raydium_clmm::resolve_fees(client, pools).await?;
+
+    let vaults = pools
+        .iter()
+        .flat_map(|pool| [pool.vault_a.as_str(), pool.vault_b.as_str()])
+        .map(Pubkey::from_str)
+        .collect::<Result<Vec<_>, _>>()?;
+    let accounts = client.get_multiple_accounts(&vaults).await?;
+
+    for (pool, vaults) in pools.iter_mut().zip(accounts.chunks(2)) {
+        pool.reserve_a = token_amount(&vaults[0]);
+        pool.reserve_b = token_amount(&vaults[1]);
+    }
+    Ok(())
+}
+
+fn token_amount(account: &Option<Account>) -> u64 {
+    account
+        .as_ref()
+        .and_then(|a| parse_token_account(TokenProgram::Token, &a.data))
+        .map_or(0, |a| a.amount)
+}
+
+/// Periodically rescans the supported DEX programs and upserts the results so
+/// list endpoints can paginate from the database instead of the RPC node.
+/// Notifying `reindex` starts a scan right away.
+pub fn spawn_discovery(
+    client: Arc<SolanaClient>,
+    database: Arc<Database>,
+    config: PoolDiscoveryConfig,
+    reindex: Arc<Notify>,
+) {
+    if !config.enabled {
+        return;
+    }
+
+    let tracked_mints: Vec<Pubkey> = config
+        .tracked_mints
+        .iter()
+        .filter_map(|mint| Pubkey::from_str(mint).ok())
+        .collect();
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs));
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = reindex.notified() => info!("Pool re-indexing requested"),
+            }
+            match discover_pools(&client, &tracked_mints).await {
+                Ok(pools) => {
+                    info!("Discovered {} pools", pools.len());
+                    if let Err(e) = database.upsert_pools(&pools).await {
+                        warn!("Failed to store discovered pools: {}", e);
+                    }
+                }
+                Err(e) => warn!("Pool discovery failed: {}", e),
+            }
+        }
+    });
+}
+
+pub(crate) fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+    Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap())
+}
+
+pub(crate) fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(data[offset..offset + 2].try_into().unwrap())
+}
+
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+pub(crate) fn read_i32(data: &[u8], offset: usize) -> i32 {
+    i32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+pub(crate) fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+pub(crate) fn read_i128(data: &[u8], offset: usize) -> i128 {
+    i128::from_le_bytes(data[offset..offset + 16].try_into().unwrap())
+}
+
+pub(crate) fn read_u128(data: &[u8], offset: usize) -> u128 {
+    u128::from_le_bytes(data[offset..offset + 16].try_into().unwrap())
+}
+
+mod u128_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,202 @@
+//! Orca Whirlpool concentrated-liquidity pool accounts.
+
+use super::adapter::{DexAdapter, SwapParams};
+use super::{read_i128, read_i32, read_pubkey, read_u128, read_u16, swap_order, ClmmState, Dex, Pool, PoolQuote, TickLiquidity};
+use crate::solana_client::SolanaClient;
+use anyhow::Result;
+use axum::async_trait;
+use sha2::{Digest, Sha256};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+};
+use std::str::FromStr;
+
+pub const PROGRAM_ID: Pubkey = pubkey!("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+pub const ACCOUNT_LEN: usize = 653;
+
+const TICK_SPACING_OFFSET: usize = 41;
+const FEE_RATE_OFFSET: usize = 45;
+const LIQUIDITY_OFFSET: usize = 49;
+const SQRT_PRICE_OFFSET: usize = 65;
+const TICK_CURRENT_OFFSET: usize = 81;
+pub const MINT_A_OFFSET: usize = 101;
+const VAULT_A_OFFSET: usize = 133;
+pub const MINT_B_OFFSET: usize = 181;
+const VAULT_B_OFFSET: usize = 213;
+
+const TICK_ARRAY_SEED: &[u8] = b"tick_array";
+const ORACLE_SEED: &[u8] = b"oracle";
+const TICKS_PER_ARRAY: i32 = 88;
+const TICKS_OFFSET: usize = 12;
+const TICK_LEN: usize = 113;
+const TICK_LIQUIDITY_NET_OFFSET: usize = 1;
+// A swap always passes exactly this many tick arrays.
+const SWAP_TICK_ARRAYS: i32 = 3;
+
+// Price bounds of the program, used as "no limit" for a swap.
+const MIN_SQRT_PRICE_X64: u128 = 4_295_048_016;
+const MAX_SQRT_PRICE_X64: u128 = 79_226_673_515_401_279_992_447_579_055;
+
+/// Anchor account discriminator for `Whirlpool`.
+fn discriminator() -> [u8; 8] {
+    let hash = Sha256::digest(b"account:Whirlpool");
+    hash[..8].try_into().unwrap()
+}
+
+/// Anchor instruction discriminator for `swap`.
+fn swap_discriminator() -> [u8; 8] {
+    let hash = Sha256::digest(b"global:swap");
+    hash[..8].try_into().unwrap()
+}
+
+pub fn decode(address: &Pubkey, data: &[u8]) -> Option<Pool> {
+    if data.len() != ACCOUNT_LEN || data[..8] != discriminator() {
+        return None;
+    }
+
+    Some(Pool {
+        id: address.to_string(),
+        dex: Dex::OrcaWhirlpool,
+        token_a: read_pubkey(data, MINT_A_OFFSET).to_string(),
+        token_b: read_pubkey(data, MINT_B_OFFSET).to_string(),
+        vault_a: read_pubkey(data, VAULT_A_OFFSET).to_string(),
+        vault_b: read_pubkey(data, VAULT_B_OFFSET).to_string(),
+        reserve_a: 0,
+        reserve_b: 0,
+        // Whirlpool fee rates are stored in hundredths of a basis point.
+        fee_ppm: read_u16(data, FEE_RATE_OFFSET) as u32,
+        clmm: Some(ClmmState {
+            liquidity: read_u128(data, LIQUIDITY_OFFSET),
+            sqrt_price_x64: read_u128(data, SQRT_PRICE_OFFSET),
+            tick_current: read_i32(data, TICK_CURRENT_OFFSET),
+            tick_spacing: read_u16(data, TICK_SPACING_OFFSET),
+            amm_config: None,
+        }),
+    })
+}
+
+/// The tick arrays a swap from the current price walks through, in order,
+/// with the initialized ticks of those that exist. Like the Orca SDK, a
+/// B-to-A swap starts from the array holding the next tick up, since the
+/// price may sit right at an array's upper edge.
+async fn swap_tick_arrays(
+    client: &SolanaClient,
+    whirlpool: &Pubkey,
+    clmm: &ClmmState,
+    a_to_b: bool,
+) -> Result<Vec<(Pubkey, Option<Vec<TickLiquidity>>)>> {
+    let tick_spacing = clmm.tick_spacing.max(1) as i32;
+    let ticks_per_array = tick_spacing * TICKS_PER_ARRAY;
+    let shift = if a_to_b { 0 } else { tick_spacing };
+    let first = (clmm.tick_current + shift).div_euclid(ticks_per_array) * ticks_per_array;
+    let step = if a_to_b { -ticks_per_array } else { ticks_per_array };
+
+    let starts: Vec<i32> = (0..SWAP_TICK_ARRAYS).map(|i| first + i * step).collect();
+    let addresses: Vec<Pubkey> = starts
+        .iter()
+        .map(|start| tick_array_address(whirlpool, *start))
+        .collect();
+    let accounts = client.get_multiple_accounts(&addresses).await?;
+    Ok(addresses
+        .into_iter()
+        .zip(starts)
+        .zip(accounts)
+        .map(|((address, start), account)| {
+            let ticks = account.map(|account| parse_tick_array(&account.data, start, tick_spacing));
+            (address, ticks)
+        })
+        .collect())
+}
+
+fn tick_array_address(whirlpool: &Pubkey, start_index: i32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[TICK_ARRAY_SEED, whirlpool.as_ref(), start_index.to_string().as_bytes()],
+        &PROGRAM_ID,
+    )
+    .0
+}
+
+/// Whirlpool ticks don't store their index; it follows from the array's
+/// start and the pool's tick spacing.
+fn parse_tick_array(data: &[u8], start_index: i32, tick_spacing: i32) -> Vec<TickLiquidity> {
+    (0..TICKS_PER_ARRAY)
+        .map(|i| (start_index + i * tick_spacing, TICKS_OFFSET + i as usize * TICK_LEN))
+        .take_while(|(_, offset)| offset + TICK_LEN <= data.len())
+        .filter(|(_, offset)| data[*offset] != 0)
+        .map(|(tick, offset)| TickLiquidity {
+            tick,
+            liquidity_net: read_i128(data, offset + TICK_LIQUIDITY_NET_OFFSET),
+        })
+        .collect()
+}
+
+pub struct OrcaWhirlpoolAdapter;
+
+#[async_trait]
+impl DexAdapter for OrcaWhirlpoolAdapter {
+    fn decode_pool(&self, address: &Pubkey, data: &[u8]) -> Option<Pool> {
+        decode(address, data)
+    }
+
+    async fn quote(
+        &self,
+        client: &SolanaClient,
+        pool: &Pool,
+        input_mint: &str,
+        amount_in: u64,
+    ) -> Result<Option<PoolQuote>> {
+        let (Some(clmm), Some(a_to_b)) = (&pool.clmm, pool.is_a_to_b(input_mint)) else {
+            return Ok(pool.quote_exact_in(input_mint, amount_in));
+        };
+        let arrays = swap_tick_arrays(client, &Pubkey::from_str(&pool.id)?, clmm, a_to_b).await?;
+        let ticks = swap_order(arrays.into_iter().flat_map(|(_, ticks)| ticks.unwrap_or_default()), clmm.tick_current, a_to_b);
+        Ok(pool.quote_exact_in_across(input_mint, amount_in, &ticks))
+    }
+
+    /// `swap` with three tick arrays; missing ones are replaced by the last
+    /// existing array, which the program accepts as the end of the path.
+    async fn build_swap_ix(&self, client: &SolanaClient, pool: &Pool, params: &SwapParams) -> Result<Vec<Instruction>> {
+        let sides = params.sides(pool)?;
+        let whirlpool = Pubkey::from_str(&pool.id)?;
+        let data = client.get_raw_account(&whirlpool).await?.data;
+        let current =
+            decode(&whirlpool, &data).ok_or_else(|| anyhow::anyhow!("Account {} is not a Whirlpool", whirlpool))?;
+        let clmm = current.clmm.as_ref().expect("decoded Whirlpools carry their state");
+
+        let mut tick_arrays = Vec::new();
+        for (address, ticks) in swap_tick_arrays(client, &whirlpool, clmm, sides.a_to_b).await? {
+            let last = tick_arrays.last().copied();
+            match (ticks, last) {
+                (Some(_), _) => tick_arrays.push(address),
+                (None, Some(last)) => tick_arrays.push(last),
+                (None, None) => anyhow::bail!("Whirlpool {} has no liquidity at its current price", whirlpool),
+            }
+        }
+        let (oracle, _) = Pubkey::find_program_address(&[ORACLE_SEED, whirlpool.as_ref()], &PROGRAM_ID);
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(params.owner, true),
+            AccountMeta::new(whirlpool, false),
+            AccountMeta::new(sides.owner_account_a(), false),
+            AccountMeta::new(Pubkey::from_str(&current.vault_a)?, false),
+            AccountMeta::new(sides.owner_account_b(), false),
+            AccountMeta::new(Pubkey::from_str(&current.vault_b)?, false),
+        ];
+        accounts.extend(tick_arrays.into_iter().map(|address| AccountMeta::new(address, false)));
+        accounts.push(AccountMeta::new(oracle, false));
+
+        let sqrt_price_limit = if sides.a_to_b { MIN_SQRT_PRICE_X64 } else { MAX_SQRT_PRICE_X64 };
+        let mut instruction_data = swap_discriminator().to_vec();
+        instruction_data.extend_from_slice(&params.amount_in.to_le_bytes());
+        instruction_data.extend_from_slice(&params.min_amount_out.to_le_bytes());
+        instruction_data.extend_from_slice(&sqrt_price_limit.to_le_bytes());
+        // amount_specified_is_input, then a_to_b.
+        instruction_data.push(1);
+        instruction_data.push(sides.a_to_b as u8);
+
+        Ok(vec![Instruction::new_with_bytes(PROGRAM_ID, &instruction_data, accounts)])
+    }
+}
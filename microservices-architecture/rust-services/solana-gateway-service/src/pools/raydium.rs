@@ -0,0 +1,365 @@
+//! Raydium AMM v4 (`LiquidityStateV4`) pool accounts.
+
+use super::adapter::{DexAdapter, SwapParams};
+use super::liquidity::{proportion, slippage_ceiling, DepositParams, LiquidityPlan, LiquidityQuote, WithdrawParams};
+use super::routing::slippage_floor;
+use super::{read_pubkey, read_u64, Dex, Pool, PoolQuote};
+use crate::error::ApiError;
+use crate::solana_client::SolanaClient;
+use anyhow::{Context, Result};
+use axum::async_trait;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+};
+use spl_associated_token_account::{get_associated_token_address, instruction::create_associated_token_account_idempotent};
+use std::str::FromStr;
+
+pub const PROGRAM_ID: Pubkey = pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+pub const ACCOUNT_LEN: usize = 752;
+
+const TRADE_FEE_NUMERATOR_OFFSET: usize = 144;
+const TRADE_FEE_DENOMINATOR_OFFSET: usize = 152;
+// PnL the pool has accrued in its vaults but not yet taken out, which isn't
+// counted as liquidity.
+const BASE_NEED_TAKE_PNL_OFFSET: usize = 192;
+const QUOTE_NEED_TAKE_PNL_OFFSET: usize = 200;
+const BASE_VAULT_OFFSET: usize = 336;
+const QUOTE_VAULT_OFFSET: usize = 368;
+pub const BASE_MINT_OFFSET: usize = 400;
+pub const QUOTE_MINT_OFFSET: usize = 432;
+const LP_MINT_OFFSET: usize = 464;
+const OPEN_ORDERS_OFFSET: usize = 496;
+const MARKET_OFFSET: usize = 528;
+const MARKET_PROGRAM_OFFSET: usize = 560;
+const TARGET_ORDERS_OFFSET: usize = 592;
+const LP_RESERVE_OFFSET: usize = 720;
+
+// OpenBook `MarketStateV3`, whose accounts every AMM v4 swap must pass.
+const MARKET_VAULT_SIGNER_NONCE_OFFSET: usize = 45;
+const MARKET_BASE_VAULT_OFFSET: usize = 117;
+const MARKET_QUOTE_VAULT_OFFSET: usize = 165;
+const MARKET_EVENT_QUEUE_OFFSET: usize = 253;
+const MARKET_BIDS_OFFSET: usize = 285;
+const MARKET_ASKS_OFFSET: usize = 317;
+
+const AUTHORITY_SEED: &[u8] = b"amm authority";
+const DEPOSIT: u8 = 3;
+const WITHDRAW: u8 = 4;
+const SWAP_BASE_IN: u8 = 9;
+
+pub fn decode(address: &Pubkey, data: &[u8]) -> Option<Pool> {
+    if data.len() != ACCOUNT_LEN {
+        return None;
+    }
+
+    let numerator = read_u64(data, TRADE_FEE_NUMERATOR_OFFSET);
+    let denominator = read_u64(data, TRADE_FEE_DENOMINATOR_OFFSET);
+    let fee_ppm = numerator
+        .saturating_mul(1_000_000)
+        .checked_div(denominator)
+        .unwrap_or(0) as u32;
+
+    Some(Pool {
+        id: address.to_string(),
+        dex: Dex::RaydiumAmm,
+        token_a: read_pubkey(data, BASE_MINT_OFFSET).to_string(),
+        token_b: read_pubkey(data, QUOTE_MINT_OFFSET).to_string(),
+        vault_a: read_pubkey(data, BASE_VAULT_OFFSET).to_string(),
+        vault_b: read_pubkey(data, QUOTE_VAULT_OFFSET).to_string(),
+        reserve_a: 0,
+        reserve_b: 0,
+        fee_ppm,
+        clmm: None,
+    })
+}
+
+pub struct RaydiumAmmAdapter;
+
+#[async_trait]
+impl DexAdapter for RaydiumAmmAdapter {
+    fn decode_pool(&self, address: &Pubkey, data: &[u8]) -> Option<Pool> {
+        decode(address, data)
+    }
+
+    async fn quote(
+        &self,
+        _client: &SolanaClient,
+        pool: &Pool,
+        input_mint: &str,
+        amount_in: u64,
+    ) -> Result<Option<PoolQuote>> {
+        Ok(pool.quote_exact_in(input_mint, amount_in))
+    }
+
+    /// `swapBaseIn`, which also takes the pool's OpenBook market accounts.
+    async fn build_swap_ix(&self, client: &SolanaClient, pool: &Pool, params: &SwapParams) -> Result<Vec<Instruction>> {
+        let sides = params.sides(pool)?;
+        let amm = AmmAccounts::load(client, pool).await?;
+        let market = amm.market(client).await?;
+
+        let accounts = vec![
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(amm.address, false),
+            AccountMeta::new_readonly(amm.authority, false),
+            AccountMeta::new(amm.open_orders(), false),
+            AccountMeta::new(amm.target_orders(), false),
+            AccountMeta::new(amm.base_vault(), false),
+            AccountMeta::new(amm.quote_vault(), false),
+            AccountMeta::new_readonly(market.program, false),
+            AccountMeta::new(market.address, false),
+            AccountMeta::new(market.bids(), false),
+            AccountMeta::new(market.asks(), false),
+            AccountMeta::new(market.event_queue(), false),
+            AccountMeta::new(market.base_vault(), false),
+            AccountMeta::new(market.quote_vault(), false),
+            AccountMeta::new_readonly(market.vault_signer, false),
+            AccountMeta::new(sides.source, false),
+            AccountMeta::new(sides.destination, false),
+            AccountMeta::new_readonly(params.owner, true),
+        ];
+        let mut instruction_data = vec![SWAP_BASE_IN];
+        instruction_data.extend_from_slice(&params.amount_in.to_le_bytes());
+        instruction_data.extend_from_slice(&params.min_amount_out.to_le_bytes());
+
+        Ok(vec![Instruction::new_with_bytes(PROGRAM_ID, &instruction_data, accounts)])
+    }
+
+    /// `deposit` with the requested side fixed (`baseSide`); the program
+    /// takes the other side in proportion to its reserves, up to the
+    /// slippage ceiling. The LP token account is created if missing.
+    async fn build_deposit(&self, client: &SolanaClient, pool: &Pool, params: &DepositParams) -> Result<LiquidityPlan> {
+        let amm = AmmAccounts::load(client, pool).await?;
+        let market = amm.market(client).await?;
+        let (reserve_a, reserve_b) = amm.reserves(pool);
+        let base_side = pool
+            .is_a_to_b(&params.mint.to_string())
+            .ok_or_else(|| ApiError::BadRequest("mint is not traded by this pool".to_string()))?;
+        let (fixed_reserve, other_reserve) = if base_side { (reserve_a, reserve_b) } else { (reserve_b, reserve_a) };
+
+        let empty = || ApiError::BadRequest(format!("Pool {} has no liquidity to deposit against", pool.id));
+        let other_amount = proportion(params.amount, other_reserve, fixed_reserve, true).ok_or_else(empty)?;
+        let lp_amount = proportion(params.amount, amm.lp_reserve(), fixed_reserve, false).ok_or_else(empty)?;
+        let other_limit = slippage_ceiling(other_amount, params.slippage_bps);
+        let (amount_a, amount_b, limit_a, limit_b) = if base_side {
+            (params.amount, other_amount, params.amount, other_limit)
+        } else {
+            (other_amount, params.amount, other_limit, params.amount)
+        };
+
+        let lp_mint = amm.lp_mint();
+        let owner_lp = get_associated_token_address(&params.owner, &lp_mint);
+        let accounts = vec![
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(amm.address, false),
+            AccountMeta::new_readonly(amm.authority, false),
+            AccountMeta::new_readonly(amm.open_orders(), false),
+            AccountMeta::new(amm.target_orders(), false),
+            AccountMeta::new(lp_mint, false),
+            AccountMeta::new(amm.base_vault(), false),
+            AccountMeta::new(amm.quote_vault(), false),
+            AccountMeta::new_readonly(market.address, false),
+            AccountMeta::new(get_associated_token_address(&params.owner, &amm.base_mint()), false),
+            AccountMeta::new(get_associated_token_address(&params.owner, &amm.quote_mint()), false),
+            AccountMeta::new(owner_lp, false),
+            AccountMeta::new_readonly(params.owner, true),
+            AccountMeta::new_readonly(market.event_queue(), false),
+        ];
+        let mut instruction_data = vec![DEPOSIT];
+        instruction_data.extend_from_slice(&limit_a.to_le_bytes());
+        instruction_data.extend_from_slice(&limit_b.to_le_bytes());
+        // baseSide: 0 fixes the base (A) amount, 1 the quote (B) amount.
+        instruction_data.extend_from_slice(&u64::from(!base_side).to_le_bytes());
+
+        Ok(LiquidityPlan {
+            quote: LiquidityQuote {
+                pool_id: pool.id.clone(),
+                lp_mint: lp_mint.to_string(),
+                amount_a,
+                amount_b,
+                limit_a,
+                limit_b,
+                lp_amount,
+                slippage_bps: params.slippage_bps,
+            },
+            instructions: vec![
+                create_associated_token_account_idempotent(&params.owner, &params.owner, &lp_mint, &spl_token::id()),
+                Instruction::new_with_bytes(PROGRAM_ID, &instruction_data, accounts),
+            ],
+        })
+    }
+
+    /// `withdraw` with minimum amounts on both sides. The owner's token
+    /// accounts for both mints are created if missing.
+    async fn build_withdraw(&self, client: &SolanaClient, pool: &Pool, params: &WithdrawParams) -> Result<LiquidityPlan> {
+        let amm = AmmAccounts::load(client, pool).await?;
+        let market = amm.market(client).await?;
+        let (reserve_a, reserve_b) = amm.reserves(pool);
+        let lp_reserve = amm.lp_reserve();
+        if params.lp_amount > lp_reserve {
+            return Err(ApiError::BadRequest(format!("lp_amount exceeds the pool's {} LP tokens", lp_reserve)).into());
+        }
+
+        let empty = || ApiError::BadRequest(format!("Pool {} has no liquidity to withdraw", pool.id));
+        let amount_a = proportion(params.lp_amount, reserve_a, lp_reserve, false).ok_or_else(empty)?;
+        let amount_b = proportion(params.lp_amount, reserve_b, lp_reserve, false).ok_or_else(empty)?;
+        let limit_a = slippage_floor(amount_a, params.slippage_bps);
+        let limit_b = slippage_floor(amount_b, params.slippage_bps);
+
+        let (base_mint, quote_mint, lp_mint) = (amm.base_mint(), amm.quote_mint(), amm.lp_mint());
+        let owner_base = get_associated_token_address(&params.owner, &base_mint);
+        let owner_quote = get_associated_token_address(&params.owner, &quote_mint);
+        let accounts = vec![
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(amm.address, false),
+            AccountMeta::new_readonly(amm.authority, false),
+            AccountMeta::new(amm.open_orders(), false),
+            AccountMeta::new(amm.target_orders(), false),
+            AccountMeta::new(lp_mint, false),
+            AccountMeta::new(amm.base_vault(), false),
+            AccountMeta::new(amm.quote_vault(), false),
+            AccountMeta::new_readonly(market.program, false),
+            AccountMeta::new(market.address, false),
+            AccountMeta::new(market.base_vault(), false),
+            AccountMeta::new(market.quote_vault(), false),
+            AccountMeta::new_readonly(market.vault_signer, false),
+            AccountMeta::new(get_associated_token_address(&params.owner, &lp_mint), false),
+            AccountMeta::new(owner_base, false),
+            AccountMeta::new(owner_quote, false),
+            AccountMeta::new_readonly(params.owner, true),
+            AccountMeta::new(market.event_queue(), false),
+            AccountMeta::new(market.bids(), false),
+            AccountMeta::new(market.asks(), false),
+        ];
+        let mut instruction_data = vec![WITHDRAW];
+        instruction_data.extend_from_slice(&params.lp_amount.to_le_bytes());
+        instruction_data.extend_from_slice(&limit_a.to_le_bytes());
+        instruction_data.extend_from_slice(&limit_b.to_le_bytes());
+
+        Ok(LiquidityPlan {
+            quote: LiquidityQuote {
+                pool_id: pool.id.clone(),
+                lp_mint: lp_mint.to_string(),
+                amount_a,
+                amount_b,
+                limit_a,
+                limit_b,
+                lp_amount: params.lp_amount,
+                slippage_bps: params.slippage_bps,
+            },
+            instructions: vec![
+                create_associated_token_account_idempotent(&params.owner, &params.owner, &base_mint, &spl_token::id()),
+                create_associated_token_account_idempotent(&params.owner, &params.owner, &quote_mint, &spl_token::id()),
+                Instruction::new_with_bytes(PROGRAM_ID, &instruction_data, accounts),
+            ],
+        })
+    }
+}
+
+/// A freshly read AMM account and the accounts derived from it.
+struct AmmAccounts {
+    address: Pubkey,
+    authority: Pubkey,
+    data: Vec<u8>,
+}
+
+impl AmmAccounts {
+    async fn load(client: &SolanaClient, pool: &Pool) -> Result<Self> {
+        let address = Pubkey::from_str(&pool.id)?;
+        let data = client.get_raw_account(&address).await?.data;
+        if data.len() != ACCOUNT_LEN {
+            anyhow::bail!("Account {} is not a Raydium AMM pool", address);
+        }
+        let (authority, _) = Pubkey::find_program_address(&[AUTHORITY_SEED], &PROGRAM_ID);
+        Ok(Self { address, authority, data })
+    }
+
+    /// The pool's OpenBook market, whose accounts swaps and withdrawals pass.
+    async fn market(&self, client: &SolanaClient) -> Result<MarketAccounts> {
+        let program = read_pubkey(&self.data, MARKET_PROGRAM_OFFSET);
+        let address = read_pubkey(&self.data, MARKET_OFFSET);
+        let data = client.get_raw_account(&address).await?.data;
+        if data.len() < MARKET_ASKS_OFFSET + 32 {
+            anyhow::bail!("Account {} is not an OpenBook market", address);
+        }
+        let nonce = read_u64(&data, MARKET_VAULT_SIGNER_NONCE_OFFSET);
+        let vault_signer = Pubkey::create_program_address(&[address.as_ref(), &nonce.to_le_bytes()], &program)
+            .context("Invalid market vault signer nonce")?;
+        Ok(MarketAccounts {
+            program,
+            address,
+            vault_signer,
+            data,
+        })
+    }
+
+    /// Vault balances less PnL not yet taken, as the program counts them.
+    fn reserves(&self, pool: &Pool) -> (u64, u64) {
+        (
+            pool.reserve_a.saturating_sub(read_u64(&self.data, BASE_NEED_TAKE_PNL_OFFSET)),
+            pool.reserve_b.saturating_sub(read_u64(&self.data, QUOTE_NEED_TAKE_PNL_OFFSET)),
+        )
+    }
+
+    fn lp_reserve(&self) -> u64 {
+        read_u64(&self.data, LP_RESERVE_OFFSET)
+    }
+
+    fn base_mint(&self) -> Pubkey {
+        read_pubkey(&self.data, BASE_MINT_OFFSET)
+    }
+
+    fn quote_mint(&self) -> Pubkey {
+        read_pubkey(&self.data, QUOTE_MINT_OFFSET)
+    }
+
+    fn lp_mint(&self) -> Pubkey {
+        read_pubkey(&self.data, LP_MINT_OFFSET)
+    }
+
+    fn base_vault(&self) -> Pubkey {
+        read_pubkey(&self.data, BASE_VAULT_OFFSET)
+    }
+
+    fn quote_vault(&self) -> Pubkey {
+        read_pubkey(&self.data, QUOTE_VAULT_OFFSET)
+    }
+
+    fn open_orders(&self) -> Pubkey {
+        read_pubkey(&self.data, OPEN_ORDERS_OFFSET)
+    }
+
+    fn target_orders(&self) -> Pubkey {
+        read_pubkey(&self.data, TARGET_ORDERS_OFFSET)
+    }
+}
+
+struct MarketAccounts {
+    program: Pubkey,
+    address: Pubkey,
+    vault_signer: Pubkey,
+    data: Vec<u8>,
+}
+
+impl MarketAccounts {
+    fn base_vault(&self) -> Pubkey {
+        read_pubkey(&self.data, MARKET_BASE_VAULT_OFFSET)
+    }
+
+    fn quote_vault(&self) -> Pubkey {
+        read_pubkey(&self.data, MARKET_QUOTE_VAULT_OFFSET)
+    }
+
+    fn event_queue(&self) -> Pubkey {
+        read_pubkey(&self.data, MARKET_EVENT_QUEUE_OFFSET)
+    }
+
+    fn bids(&self) -> Pubkey {
+        read_pubkey(&self.data, MARKET_BIDS_OFFSET)
+    }
+
+    fn asks(&self) -> Pubkey {
+        read_pubkey(&self.data, MARKET_ASKS_OFFSET)
+    }
+}
@@ -0,0 +1,235 @@
+//! Raydium concentrated-liquidity (CLMM) `PoolState`, `AmmConfig` and
+//! `TickArrayState` accounts.
+
+use super::adapter::{DexAdapter, SwapParams};
+use super::{
+    read_i128, read_i32, read_pubkey, read_u128, read_u16, read_u32, swap_order, ClmmState, Dex, Pool, PoolQuote,
+    TickLiquidity,
+};
+use crate::solana_client::SolanaClient;
+use anyhow::Result;
+use axum::async_trait;
+use sha2::{Digest, Sha256};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+pub const PROGRAM_ID: Pubkey = pubkey!("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK");
+pub const ACCOUNT_LEN: usize = 1544;
+
+const AMM_CONFIG_OFFSET: usize = 9;
+pub const MINT_0_OFFSET: usize = 73;
+pub const MINT_1_OFFSET: usize = 105;
+const VAULT_0_OFFSET: usize = 137;
+const VAULT_1_OFFSET: usize = 169;
+const OBSERVATION_OFFSET: usize = 201;
+const TICK_SPACING_OFFSET: usize = 235;
+const LIQUIDITY_OFFSET: usize = 237;
+const SQRT_PRICE_OFFSET: usize = 253;
+const TICK_CURRENT_OFFSET: usize = 269;
+
+// `AmmConfig` fee rates are in parts per million already.
+const TRADE_FEE_RATE_OFFSET: usize = 47;
+
+const TICK_ARRAY_SEED: &[u8] = b"tick_array";
+const BITMAP_EXTENSION_SEED: &[u8] = b"pool_tick_array_bitmap_extension";
+const TICKS_PER_ARRAY: i32 = 60;
+const TICKS_OFFSET: usize = 44;
+const TICK_LEN: usize = 168;
+const TICK_LIQUIDITY_NET_OFFSET: usize = 4;
+const TICK_LIQUIDITY_GROSS_OFFSET: usize = 20;
+// Tick arrays loaded in the swap direction, starting with the current one;
+// the same window the Raydium SDK passes to a swap.
+const SWAP_TICK_ARRAYS: i32 = 3;
+
+/// Anchor account discriminator for `PoolState`.
+fn discriminator() -> [u8; 8] {
+    let hash = Sha256::digest(b"account:PoolState");
+    hash[..8].try_into().unwrap()
+}
+
+/// Anchor instruction discriminator for `swap`.
+fn swap_discriminator() -> [u8; 8] {
+    let hash = Sha256::digest(b"global:swap");
+    hash[..8].try_into().unwrap()
+}
+
+/// Decodes a pool. Its fee rate lives in the `AmmConfig` account and is
+/// filled in by `resolve_fees`.
+pub fn decode(address: &Pubkey, data: &[u8]) -> Option<Pool> {
+    if data.len() != ACCOUNT_LEN || data[..8] != discriminator() {
+        return None;
+    }
+
+    Some(Pool {
+        id: address.to_string(),
+        dex: Dex::RaydiumClmm,
+        token_a: read_pubkey(data, MINT_0_OFFSET).to_string(),
+        token_b: read_pubkey(data, MINT_1_OFFSET).to_string(),
+        vault_a: read_pubkey(data, VAULT_0_OFFSET).to_string(),
+        vault_b: read_pubkey(data, VAULT_1_OFFSET).to_string(),
+        reserve_a: 0,
+        reserve_b: 0,
+        fee_ppm: 0,
+        clmm: Some(ClmmState {
+            liquidity: read_u128(data, LIQUIDITY_OFFSET),
+            sqrt_price_x64: read_u128(data, SQRT_PRICE_OFFSET),
+            tick_current: read_i32(data, TICK_CURRENT_OFFSET),
+            tick_spacing: read_u16(data, TICK_SPACING_OFFSET),
+            amm_config: Some(read_pubkey(data, AMM_CONFIG_OFFSET).to_string()),
+        }),
+    })
+}
+
+/// Sets the fee tier of every freshly decoded Raydium CLMM pool from its
+/// `AmmConfig`. Pools share a handful of configs, so each is fetched once.
+pub async fn resolve_fees(client: &SolanaClient, pools: &mut [Pool]) -> Result<()> {
+    let mut configs: Vec<Pubkey> = pools
+        .iter()
+        .filter_map(|pool| pool.clmm.as_ref()?.amm_config.as_deref())
+        .filter_map(|config| config.parse().ok())
+        .collect();
+    configs.sort();
+    configs.dedup();
+    if configs.is_empty() {
+        return Ok(());
+    }
+
+    let accounts = client.get_multiple_accounts(&configs).await?;
+    let fee_rates: HashMap<String, u32> = configs
+        .iter()
+        .zip(accounts)
+        .filter_map(|(config, account)| {
+            let account = account?;
+            (account.owner == PROGRAM_ID && account.data.len() >= TRADE_FEE_RATE_OFFSET + 4)
+                .then(|| (config.to_string(), read_u32(&account.data, TRADE_FEE_RATE_OFFSET)))
+        })
+        .collect();
+
+    for pool in pools.iter_mut() {
+        let config = pool.clmm.as_ref().and_then(|clmm| clmm.amm_config.as_ref());
+        if let Some(&fee_rate) = config.and_then(|config| fee_rates.get(config)) {
+            pool.fee_ppm = fee_rate;
+        }
+    }
+    Ok(())
+}
+
+/// Existing tick arrays a swap from the current price walks through, in
+/// order, with their initialized ticks.
+async fn swap_tick_arrays(
+    client: &SolanaClient,
+    pool_id: &Pubkey,
+    clmm: &ClmmState,
+    a_to_b: bool,
+) -> Result<Vec<(Pubkey, Vec<TickLiquidity>)>> {
+    let ticks_per_array = clmm.tick_spacing.max(1) as i32 * TICKS_PER_ARRAY;
+    let first = clmm.tick_current.div_euclid(ticks_per_array) * ticks_per_array;
+    let step = if a_to_b { -ticks_per_array } else { ticks_per_array };
+
+    let addresses: Vec<Pubkey> = (0..SWAP_TICK_ARRAYS)
+        .map(|i| tick_array_address(pool_id, first + i * step))
+        .collect();
+    let accounts = client.get_multiple_accounts(&addresses).await?;
+    Ok(addresses
+        .into_iter()
+        .zip(accounts)
+        .filter_map(|(address, account)| Some((address, parse_tick_array(&account?.data))))
+        .collect())
+}
+
+pub struct RaydiumClmmAdapter;
+
+#[async_trait]
+impl DexAdapter for RaydiumClmmAdapter {
+    fn decode_pool(&self, address: &Pubkey, data: &[u8]) -> Option<Pool> {
+        decode(address, data)
+    }
+
+    async fn quote(
+        &self,
+        client: &SolanaClient,
+        pool: &Pool,
+        input_mint: &str,
+        amount_in: u64,
+    ) -> Result<Option<PoolQuote>> {
+        let (Some(clmm), Some(a_to_b)) = (&pool.clmm, pool.is_a_to_b(input_mint)) else {
+            return Ok(pool.quote_exact_in(input_mint, amount_in));
+        };
+        let arrays = swap_tick_arrays(client, &Pubkey::from_str(&pool.id)?, clmm, a_to_b).await?;
+        let ticks = swap_order(arrays.into_iter().flat_map(|(_, ticks)| ticks), clmm.tick_current, a_to_b);
+        Ok(pool.quote_exact_in_across(input_mint, amount_in, &ticks))
+    }
+
+    /// `swap` through the tick arrays the trade may cross, preceded by the
+    /// pool's bitmap extension so wide-ranging pools can be traversed.
+    async fn build_swap_ix(&self, client: &SolanaClient, pool: &Pool, params: &SwapParams) -> Result<Vec<Instruction>> {
+        let sides = params.sides(pool)?;
+        let pool_id = Pubkey::from_str(&pool.id)?;
+        let data = client.get_raw_account(&pool_id).await?.data;
+        let current = decode(&pool_id, &data)
+            .ok_or_else(|| anyhow::anyhow!("Account {} is not a Raydium CLMM pool", pool_id))?;
+        let clmm = current.clmm.as_ref().expect("decoded CLMM pools carry their state");
+
+        let tick_arrays = swap_tick_arrays(client, &pool_id, clmm, sides.a_to_b).await?;
+        let Some(((first, _), rest)) = tick_arrays.split_first() else {
+            anyhow::bail!("Pool {} has no liquidity at its current price", pool_id);
+        };
+        let (input_vault, output_vault) = if sides.a_to_b {
+            (&current.vault_a, &current.vault_b)
+        } else {
+            (&current.vault_b, &current.vault_a)
+        };
+        let (bitmap_extension, _) =
+            Pubkey::find_program_address(&[BITMAP_EXTENSION_SEED, pool_id.as_ref()], &PROGRAM_ID);
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(params.owner, true),
+            AccountMeta::new_readonly(read_pubkey(&data, AMM_CONFIG_OFFSET), false),
+            AccountMeta::new(pool_id, false),
+            AccountMeta::new(sides.source, false),
+            AccountMeta::new(sides.destination, false),
+            AccountMeta::new(Pubkey::from_str(input_vault)?, false),
+            AccountMeta::new(Pubkey::from_str(output_vault)?, false),
+            AccountMeta::new(read_pubkey(&data, OBSERVATION_OFFSET), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(*first, false),
+            AccountMeta::new_readonly(bitmap_extension, false),
+        ];
+        accounts.extend(rest.iter().map(|(address, _)| AccountMeta::new(*address, false)));
+
+        let mut instruction_data = swap_discriminator().to_vec();
+        instruction_data.extend_from_slice(&params.amount_in.to_le_bytes());
+        instruction_data.extend_from_slice(&params.min_amount_out.to_le_bytes());
+        // A zero limit lets the price move as far as the trade requires.
+        instruction_data.extend_from_slice(&0u128.to_le_bytes());
+        // The amount is the exact input.
+        instruction_data.push(1);
+
+        Ok(vec![Instruction::new_with_bytes(PROGRAM_ID, &instruction_data, accounts)])
+    }
+}
+
+fn tick_array_address(pool_id: &Pubkey, start_index: i32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[TICK_ARRAY_SEED, pool_id.as_ref(), &start_index.to_be_bytes()],
+        &PROGRAM_ID,
+    )
+    .0
+}
+
+fn parse_tick_array(data: &[u8]) -> Vec<TickLiquidity> {
+    (0..TICKS_PER_ARRAY as usize)
+        .map(|i| TICKS_OFFSET + i * TICK_LEN)
+        .take_while(|offset| offset + TICK_LEN <= data.len())
+        .filter(|&offset| read_u128(data, offset + TICK_LIQUIDITY_GROSS_OFFSET) != 0)
+        .map(|offset| TickLiquidity {
+            tick: read_i32(data, offset),
+            liquidity_net: read_i128(data, offset + TICK_LIQUIDITY_NET_OFFSET),
+        })
+        .collect()
+}
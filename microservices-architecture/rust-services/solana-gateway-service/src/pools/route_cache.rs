@@ -0,0 +1,124 @@
+use super::routing::{self, Route};
+use super::Pool;
+use crate::config::RouteCacheConfig;
+use crate::database::Database;
+use crate::metrics::Metrics;
+use crate::solana_client::SolanaClient;
+use anyhow::Result;
+use moka::future::Cache;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+// (input mint, output mint, amount bucket)
+type RouteKey = (String, String, u32);
+
+/// Route searches cached by pair and order size. The search quotes every
+/// candidate pool of the pair; a hit only re-quotes the routes it found,
+/// against the pool state they were found with, until the TTL passes or
+/// the pools' reserves move.
+pub struct RouteCache {
+    routes: Option<Cache<RouteKey, Arc<Vec<(Route, Vec<Pool>)>>>>,
+    config: RouteCacheConfig,
+    metrics: Arc<Metrics>,
+    cluster: String,
+}
+
+impl RouteCache {
+    pub fn new(config: &RouteCacheConfig, metrics: Arc<Metrics>, cluster: String) -> Self {
+        let routes = config.enabled.then(|| {
+            Cache::builder()
+                .max_capacity(config.capacity)
+                .time_to_live(Duration::from_secs(config.ttl_secs))
+                .support_invalidation_closures()
+                .build()
+        });
+        Self {
+            routes,
+            config: config.clone(),
+            metrics,
+            cluster,
+        }
+    }
+
+    /// Same as [`routing::find_routes`], served from cache when a search for
+    /// the pair and a similar amount is still fresh.
+    pub async fn find_routes(
+        &self,
+        client: &SolanaClient,
+        database: &Database,
+        input_mint: &str,
+        output_mint: &str,
+        amount_in: u64,
+    ) -> Result<Vec<(Route, Vec<Pool>)>> {
+        let Some(cache) = &self.routes else {
+            return routing::find_routes(client, database, input_mint, output_mint, amount_in).await;
+        };
+
+        let key = (input_mint.to_string(), output_mint.to_string(), bucket(amount_in));
+        if let Some(routes) = cache.get(&key).await {
+            self.metrics.observe_route_cache(&self.cluster, true);
+            return Ok(routing::requote(client, &routes, amount_in).await);
+        }
+        self.metrics.observe_route_cache(&self.cluster, false);
+
+        let routes = routing::find_routes(client, database, input_mint, output_mint, amount_in).await?;
+        // An empty search is retried next time rather than cached.
+        if !routes.is_empty() {
+            cache.insert(key, Arc::new(routes.clone())).await;
+        }
+        Ok(routes)
+    }
+
+    /// Evicts cached routes through pools whose reserves moved by at least
+    /// the configured threshold. `changes` holds each pool's previous and
+    /// current reserves.
+    pub fn observe_reserves<'a>(&self, changes: impl IntoIterator<Item = (&'a str, (u64, u64), (u64, u64))>) {
+        let Some(cache) = &self.routes else {
+            return;
+        };
+        let threshold = self.config.invalidation_bps;
+        let moved: HashSet<String> = changes
+            .into_iter()
+            .filter(|(_, (prev_a, prev_b), (reserve_a, reserve_b))| {
+                change_bps(*prev_a, *reserve_a) >= threshold || change_bps(*prev_b, *reserve_b) >= threshold
+            })
+            .map(|(pool_id, _, _)| pool_id.to_string())
+            .collect();
+        if moved.is_empty() {
+            return;
+        }
+
+        self.metrics.observe_route_cache_invalidation(&self.cluster, moved.len());
+        let evicted = cache.invalidate_entries_if(move |_, routes| {
+            routes
+                .iter()
+                .any(|(_, pools)| pools.iter().any(|pool| moved.contains(&pool.id)))
+        });
+        if let Err(e) = evicted {
+            warn!("Failed to invalidate cached routes: {}", e);
+        }
+    }
+
+    pub fn flush(&self) -> u64 {
+        let Some(cache) = &self.routes else {
+            return 0;
+        };
+        let entries = cache.entry_count();
+        cache.invalidate_all();
+        entries
+    }
+}
+
+// Powers of two, so amounts within 2x of each other usually share routes.
+fn bucket(amount_in: u64) -> u32 {
+    u64::BITS - amount_in.leading_zeros()
+}
+
+fn change_bps(previous: u64, current: u64) -> u32 {
+    if previous == 0 {
+        return if current == 0 { 0 } else { u32::MAX };
+    }
+    (previous.abs_diff(current) as u128 * 10_000 / previous as u128).min(u32::MAX as u128) as u32
+}
@@ -0,0 +1,254 @@
+//! Single- and two-hop routes between two mints over the indexed pools.
+
+use super::adapter::SwapParams;
+use super::{fetch_pools, quote_live, Dex, Pool, PoolQuote};
+use crate::database::Database;
+use crate::solana_client::SolanaClient;
+use anyhow::Result;
+use futures::future::join_all;
+use serde::Serialize;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use std::collections::HashMap;
+use std::str::FromStr;
+use tracing::warn;
+use utoipa::ToSchema;
+
+// Direct pools of the pair quoted per search.
+const MAX_DIRECT_POOLS: i64 = 20;
+// (first pool, second pool) pairs considered for two-hop routes.
+const MAX_TWO_HOP_PAIRS: i64 = 50;
+
+/// One swap of a route, quoted against live pool state.
+#[derive(Clone, Serialize, ToSchema)]
+pub struct RouteHop {
+    pub pool_id: String,
+    pub dex: Dex,
+    pub input_mint: String,
+    pub output_mint: String,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+    /// Shortfall against the pool's spot price, excluding its fee.
+    pub price_impact_bps: Option<f64>,
+}
+
+#[derive(Clone, Serialize, ToSchema)]
+pub struct Route {
+    pub input_mint: String,
+    pub output_mint: String,
+    pub amount_in: u64,
+    /// Output of the last hop after every pool's fee and price impact.
+    pub amount_out: u64,
+    pub hops: Vec<RouteHop>,
+}
+
+impl Route {
+    fn new(hops: Vec<RouteHop>) -> Route {
+        let (first, last) = (&hops[0], &hops[hops.len() - 1]);
+        Route {
+            input_mint: first.input_mint.clone(),
+            output_mint: last.output_mint.clone(),
+            amount_in: first.amount_in,
+            amount_out: last.amount_out,
+            hops,
+        }
+    }
+}
+
+fn hop(pool: &Pool, input_mint: &str, amount_in: u64, quote: &PoolQuote) -> RouteHop {
+    RouteHop {
+        pool_id: pool.id.clone(),
+        dex: pool.dex,
+        input_mint: input_mint.to_string(),
+        output_mint: quote.output_mint.clone(),
+        amount_in,
+        amount_out: quote.amount_out,
+        fee_amount: quote.fee_amount,
+        price_impact_bps: pool.price_impact_bps(input_mint, amount_in, quote),
+    }
+}
+
+/// Quotes `amount_in` through every pool, skipping pools that fail to quote
+/// or don't trade `input_mint`.
+async fn quote_all<'a>(
+    client: &SolanaClient,
+    pools: Vec<&'a Pool>,
+    input_mint: &str,
+    amount_in: u64,
+) -> Vec<(&'a Pool, PoolQuote)> {
+    let quotes = join_all(pools.iter().map(|pool| quote_live(client, pool, input_mint, amount_in))).await;
+    pools
+        .into_iter()
+        .zip(quotes)
+        .filter_map(|(pool, quote)| match quote {
+            Ok(quote) => Some((pool, quote?)),
+            Err(e) => {
+                warn!("Failed to quote pool {}: {}", pool.id, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Every direct route of the pair and the best two-hop route through each
+/// intermediate mint, best output first, each with the pools it trades
+/// through. Hops are quoted against live state, so the comparison accounts
+/// for fees and price impact.
+pub async fn find_routes(
+    client: &SolanaClient,
+    database: &Database,
+    input_mint: &str,
+    output_mint: &str,
+    amount_in: u64,
+) -> Result<Vec<(Route, Vec<Pool>)>> {
+    let direct = database
+        .list_pair_pool_addresses(input_mint, output_mint, MAX_DIRECT_POOLS)
+        .await?;
+    let pairs = database
+        .list_two_hop_pool_addresses(input_mint, output_mint, MAX_TWO_HOP_PAIRS)
+        .await?;
+
+    let mut addresses = direct
+        .iter()
+        .chain(pairs.iter().flat_map(|(first, _, second)| [first, second]))
+        .map(|address| Pubkey::from_str(address))
+        .collect::<Result<Vec<_>, _>>()?;
+    addresses.sort();
+    addresses.dedup();
+    let pools: HashMap<String, Pool> = fetch_pools(client, &addresses)
+        .await?
+        .into_iter()
+        .map(|pool| (pool.id.clone(), pool))
+        .collect();
+
+    let mut first_legs: Vec<&Pool> = direct
+        .iter()
+        .chain(pairs.iter().map(|(first, _, _)| first))
+        .filter_map(|address| pools.get(address))
+        .collect();
+    first_legs.sort_by(|a, b| a.id.cmp(&b.id));
+    first_legs.dedup_by(|a, b| a.id == b.id);
+    let first_quotes = quote_all(client, first_legs, input_mint, amount_in).await;
+
+    let mut routes: Vec<(Route, Vec<Pool>)> = first_quotes
+        .iter()
+        .filter(|(_, quote)| quote.output_mint == output_mint)
+        .map(|(pool, quote)| (Route::new(vec![hop(pool, input_mint, amount_in, quote)]), vec![(*pool).clone()]))
+        .collect();
+
+    // A larger intermediate amount never yields less output, so only the
+    // best first hop into each intermediate mint needs a second hop.
+    let mut best_first: HashMap<&str, &(&Pool, PoolQuote)> = HashMap::new();
+    for leg in first_quotes.iter().filter(|(_, quote)| quote.output_mint != output_mint) {
+        let best = best_first.entry(leg.1.output_mint.as_str()).or_insert(leg);
+        if leg.1.amount_out > best.1.amount_out {
+            *best = leg;
+        }
+    }
+
+    let second_legs = best_first.iter().map(|(&via, (first_pool, first_quote))| {
+        let seconds: Vec<&Pool> = pairs
+            .iter()
+            .filter(|(first, pair_via, _)| *first == first_pool.id && pair_via == via)
+            .filter_map(|(_, _, second)| pools.get(second))
+            .collect();
+        async move {
+            let quotes = quote_all(client, seconds, via, first_quote.amount_out).await;
+            let (second_pool, second_quote) = quotes
+                .into_iter()
+                .filter(|(_, quote)| quote.output_mint == output_mint)
+                .max_by_key(|(_, quote)| quote.amount_out)?;
+            let hops = vec![
+                hop(first_pool, input_mint, amount_in, first_quote),
+                hop(second_pool, via, first_quote.amount_out, &second_quote),
+            ];
+            Some((Route::new(hops), vec![(*first_pool).clone(), second_pool.clone()]))
+        }
+    });
+    routes.extend(join_all(second_legs).await.into_iter().flatten());
+
+    routes.sort_by(|a, b| b.0.amount_out.cmp(&a.0.amount_out));
+    Ok(routes)
+}
+
+/// Quotes `amount_in` along the pools of previously found routes, best
+/// output first. Routes with a hop that no longer quotes are dropped.
+pub async fn requote(
+    client: &SolanaClient,
+    routes: &[(Route, Vec<Pool>)],
+    amount_in: u64,
+) -> Vec<(Route, Vec<Pool>)> {
+    let requoted = routes.iter().map(|(route, pools)| async move {
+        let mut hops = Vec::with_capacity(pools.len());
+        let (mut input_mint, mut amount) = (route.input_mint.clone(), amount_in);
+        for pool in pools {
+            let quote = match quote_live(client, pool, &input_mint, amount).await {
+                Ok(quote) => quote?,
+                Err(e) => {
+                    warn!("Failed to quote pool {}: {}", pool.id, e);
+                    return None;
+                }
+            };
+            hops.push(hop(pool, &input_mint, amount, &quote));
+            (input_mint, amount) = (quote.output_mint, quote.amount_out);
+        }
+        Some((Route::new(hops), pools.clone()))
+    });
+    let mut routes: Vec<_> = join_all(requoted).await.into_iter().flatten().collect();
+    routes.sort_by(|a, b| b.0.amount_out.cmp(&a.0.amount_out));
+    routes
+}
+
+/// Instructions executing `route` as one transaction by `owner`, creating
+/// its token accounts for every mint received along the way. Native SOL
+/// goes through `wsol_account` instead when set.
+///
+/// Each hop after the first swaps exactly the floor the previous hop was
+/// allowed to slip to, so the route can't fail for want of input; any
+/// intermediate tokens received above that floor stay in the owner's
+/// account. The last hop enforces `min_amount_out`.
+pub async fn build_route_ixs(
+    client: &SolanaClient,
+    route: &Route,
+    pools: &[Pool],
+    owner: &Pubkey,
+    min_amount_out: u64,
+    slippage_bps: u16,
+    wsol_account: Option<Pubkey>,
+) -> Result<Vec<Instruction>> {
+    let mut instructions = Vec::new();
+    let mut amount_in = route.amount_in;
+    for (i, (hop, pool)) in route.hops.iter().zip(pools).enumerate() {
+        let output_mint = Pubkey::from_str(&hop.output_mint)?;
+        let min_out = if i + 1 == route.hops.len() {
+            min_amount_out
+        } else {
+            slippage_floor(hop.amount_out, slippage_bps)
+        };
+        let params = SwapParams {
+            owner: *owner,
+            input_mint: Pubkey::from_str(&hop.input_mint)?,
+            amount_in,
+            min_amount_out: min_out,
+            output_program: client.mint_program(&output_mint).await?.id(),
+            wsol_account,
+        };
+        if !params.wraps(&output_mint) {
+            instructions.push(create_associated_token_account_idempotent(
+                owner,
+                owner,
+                &output_mint,
+                &params.output_program,
+            ));
+        }
+        instructions.extend(pool.dex.adapter().build_swap_ix(client, pool, &params).await?);
+        amount_in = min_out;
+    }
+    Ok(instructions)
+}
+
+/// `amount` less `slippage_bps`, rounded down.
+pub fn slippage_floor(amount: u64, slippage_bps: u16) -> u64 {
+    (amount as u128 * 10_000u128.saturating_sub(slippage_bps as u128) / 10_000) as u64
+}
@@ -0,0 +1,227 @@
+use super::candles::CandleInterval;
+use super::Pool;
+use crate::activity::{account_keys, token_balances};
+use crate::config::PoolVolumeConfig;
+use crate::database::Database;
+use crate::price_oracle::PriceOracle;
+use crate::solana_client::SolanaClient;
+use anyhow::Result;
+use chrono::{DateTime, TimeZone, Utc};
+use futures::future::try_join_all;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+/// Swaps through one pool within one hour, decimal adjusted.
+#[derive(Clone)]
+pub struct HourlyVolume {
+    pub hour_start: DateTime<Utc>,
+    pub volume_a: f64,
+    pub volume_b: f64,
+    pub fees_a: f64,
+    pub fees_b: f64,
+    pub swaps: i32,
+}
+
+/// Sums of a pool's hourly buckets over the trailing 24 hours.
+#[derive(sqlx::FromRow)]
+pub struct PoolVolume {
+    pub volume_a: f64,
+    pub volume_b: f64,
+    pub fees_a: f64,
+    pub fees_b: f64,
+    pub swaps: i64,
+}
+
+/// Trading activity and value locked. Token amounts are decimal adjusted;
+/// USD figures need fresh oracle prices and are omitted otherwise.
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct PoolStats {
+    pub volume_a_24h: f64,
+    pub volume_b_24h: f64,
+    pub fees_a_24h: f64,
+    pub fees_b_24h: f64,
+    pub swaps_24h: i64,
+    pub volume_usd_24h: Option<f64>,
+    pub fees_usd_24h: Option<f64>,
+    pub tvl_usd: Option<f64>,
+}
+
+/// Folds swaps through stored pools into hourly volume and fee buckets.
+/// Each round reads the pool's signatures back to the last one indexed, so
+/// every transaction is counted once; a pool whose transactions can't all
+/// be fetched is retried from the same point next round.
+pub fn spawn_indexer(client: Arc<SolanaClient>, database: Arc<Database>, config: PoolVolumeConfig) {
+    if !config.enabled {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.poll_interval_secs.max(1)));
+        loop {
+            interval.tick().await;
+            if let Err(e) = index_pools(&client, &database, &config).await {
+                warn!("Pool volume indexing failed: {}", e);
+            }
+        }
+    });
+}
+
+async fn index_pools(client: &SolanaClient, database: &Database, config: &PoolVolumeConfig) -> Result<()> {
+    let addresses = database
+        .list_pool_addresses(config.max_pools)
+        .await?
+        .iter()
+        .filter_map(|address| Pubkey::from_str(address).ok())
+        .collect::<Vec<_>>();
+    let pools = super::fetch_pools(client, &addresses).await?;
+    let cursors = database.get_pool_swap_cursors().await?;
+
+    let mut swaps = 0;
+    for pool in &pools {
+        // One failing pool doesn't hold up the others.
+        match index_pool(client, database, pool, cursors.get(&pool.id), config.max_signatures).await {
+            Ok(indexed) => swaps += indexed,
+            Err(e) => warn!("Failed to index swaps of pool {}: {}", pool.id, e),
+        }
+    }
+    database.prune_pool_volume(config.retention_days).await?;
+    info!("Indexed {} swaps across {} pools", swaps, pools.len());
+    Ok(())
+}
+
+async fn index_pool(
+    client: &SolanaClient,
+    database: &Database,
+    pool: &Pool,
+    cursor: Option<&String>,
+    max_signatures: usize,
+) -> Result<i32> {
+    let address = Pubkey::from_str(&pool.id)?;
+    let signatures = client.get_signatures_for_address(&address, None, max_signatures).await?;
+    let Some(newest) = signatures.first().map(|status| status.signature.clone()) else {
+        return Ok(0);
+    };
+    if cursor == Some(&newest) {
+        return Ok(0);
+    }
+
+    let vaults = [Pubkey::from_str(&pool.vault_a)?, Pubkey::from_str(&pool.vault_b)?];
+    let transactions = try_join_all(
+        signatures
+            .iter()
+            .take_while(|status| Some(&status.signature) != cursor)
+            .filter(|status| status.err.is_none())
+            .map(|status| async move {
+                let signature = Signature::from_str(&status.signature)?;
+                client.get_raw_transaction(&signature).await
+            }),
+    )
+    .await?;
+
+    let mut hours: BTreeMap<DateTime<Utc>, HourlyVolume> = BTreeMap::new();
+    for transaction in &transactions {
+        let Some(swap) = decode_swap(pool, &vaults, transaction) else {
+            continue;
+        };
+        let hour = hours.entry(swap.hour_start).or_insert_with(|| HourlyVolume {
+            hour_start: swap.hour_start,
+            volume_a: 0.0,
+            volume_b: 0.0,
+            fees_a: 0.0,
+            fees_b: 0.0,
+            swaps: 0,
+        });
+        hour.volume_a += swap.volume_a;
+        hour.volume_b += swap.volume_b;
+        hour.fees_a += swap.fees_a;
+        hour.fees_b += swap.fees_b;
+        hour.swaps += 1;
+    }
+
+    let hours: Vec<HourlyVolume> = hours.into_values().collect();
+    database.record_pool_volume(&pool.id, &hours, &newest).await?;
+    Ok(hours.iter().map(|hour| hour.swaps).sum())
+}
+
+/// A swap pays into one vault and draws from the other; deposits and
+/// withdrawals move both vaults the same way and aren't counted. Several
+/// swaps through the pool in one transaction count as their net trade. The
+/// fee is charged on the input side at the pool's current fee tier.
+fn decode_swap(
+    pool: &Pool,
+    vaults: &[Pubkey; 2],
+    transaction: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Option<HourlyVolume> {
+    let decoded = transaction.transaction.transaction.decode()?;
+    let meta = transaction.transaction.meta.as_ref()?;
+    let block_time = Utc.timestamp_opt(transaction.block_time?, 0).single()?;
+
+    let keys = account_keys(&decoded.message, meta);
+    let tokens = token_balances(&keys, meta);
+    let change = |vault: &Pubkey| {
+        let balance = tokens.get(vault)?;
+        let change = balance.post as i128 - balance.pre as i128;
+        Some((change, change.unsigned_abs() as f64 / 10f64.powi(balance.decimals as i32)))
+    };
+    let ((change_a, volume_a), (change_b, volume_b)) = (change(&vaults[0])?, change(&vaults[1])?);
+    if change_a.signum() * change_b.signum() != -1 {
+        return None;
+    }
+
+    let fee_rate = pool.fee_ppm as f64 / 1_000_000.0;
+    Some(HourlyVolume {
+        hour_start: CandleInterval::OneHour.bucket_start(block_time),
+        volume_a,
+        volume_b,
+        fees_a: if change_a > 0 { volume_a * fee_rate } else { 0.0 },
+        fees_b: if change_b > 0 { volume_b * fee_rate } else { 0.0 },
+        swaps: 1,
+    })
+}
+
+/// Indexed 24h figures of `pool`, valued with non-stale oracle prices. TVL
+/// and fees need prices for both tokens; volume needs either, since every
+/// swap moves both.
+pub async fn pool_stats(
+    database: &Database,
+    price_oracle: &PriceOracle,
+    pool: &Pool,
+    decimals_a: u8,
+    decimals_b: u8,
+) -> Result<PoolStats> {
+    let mints = [pool.token_a.clone(), pool.token_b.clone()];
+    let (volume, quotes) = tokio::try_join!(
+        database.get_pool_volume_24h(&pool.id),
+        price_oracle.get_prices(&mints),
+    )?;
+    let prices: HashMap<&str, f64> = quotes
+        .iter()
+        .filter(|quote| !quote.stale)
+        .map(|quote| (quote.mint.as_str(), quote.price))
+        .collect();
+    let price_a = prices.get(pool.token_a.as_str()).copied();
+    let price_b = prices.get(pool.token_b.as_str()).copied();
+    let both = price_a.zip(price_b);
+
+    let reserve_a = pool.reserve_a as f64 / 10f64.powi(decimals_a as i32);
+    let reserve_b = pool.reserve_b as f64 / 10f64.powi(decimals_b as i32);
+    Ok(PoolStats {
+        volume_usd_24h: price_a
+            .map(|price| volume.volume_a * price)
+            .or_else(|| price_b.map(|price| volume.volume_b * price)),
+        fees_usd_24h: both.map(|(a, b)| volume.fees_a * a + volume.fees_b * b),
+        tvl_usd: both.map(|(a, b)| reserve_a * a + reserve_b * b),
+        volume_a_24h: volume.volume_a,
+        volume_b_24h: volume.volume_b,
+        fees_a_24h: volume.fees_a,
+        fees_b_24h: volume.fees_b,
+        swaps_24h: volume.swaps,
+    })
+}
@@ -0,0 +1,160 @@
+use crate::config::PriceOracleConfig;
+use crate::error::ApiError;
+use crate::pools::{read_i32, read_u32, read_u64};
+use crate::solana_client::SolanaClient;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+// Pyth v2 price account layout.
+const PYTH_MAGIC: u32 = 0xa1b2_c3d4;
+const PYTH_PRICE_ACCOUNT_TYPE: u32 = 3;
+const PYTH_STATUS_TRADING: u32 = 1;
+const EXPONENT_OFFSET: usize = 20;
+const AGG_PRICE_OFFSET: usize = 208;
+const AGG_CONF_OFFSET: usize = 216;
+const AGG_STATUS_OFFSET: usize = 224;
+const AGG_PUB_SLOT_OFFSET: usize = 232;
+const PRICE_ACCOUNT_MIN_LEN: usize = 240;
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct PriceQuote {
+    pub mint: String,
+    pub source: String,
+    /// Oracle account the price was read from.
+    pub feed: String,
+    /// USD price, already scaled by the feed exponent.
+    pub price: f64,
+    /// Half-width of the confidence interval, in USD.
+    pub confidence: f64,
+    pub publish_slot: u64,
+    /// True when the aggregate isn't trading or is older than the configured
+    /// staleness window.
+    pub stale: bool,
+}
+
+struct PythPrice {
+    price: i64,
+    confidence: u64,
+    exponent: i32,
+    trading: bool,
+    publish_slot: u64,
+}
+
+/// Reads USD prices from Pyth price accounts configured per mint.
+pub struct PriceOracle {
+    client: Arc<SolanaClient>,
+    feeds: HashMap<String, Pubkey>,
+    max_staleness_slots: u64,
+    max_deviation_bps: u32,
+}
+
+impl PriceOracle {
+    pub fn new(client: Arc<SolanaClient>, config: &PriceOracleConfig) -> Result<Self> {
+        let feeds = config
+            .feeds
+            .iter()
+            .map(|(mint, feed)| {
+                let feed = Pubkey::from_str(feed)
+                    .with_context(|| format!("Invalid price feed for mint {}", mint))?;
+                Ok((mint.clone(), feed))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            client,
+            feeds,
+            max_staleness_slots: config.max_staleness_slots,
+            max_deviation_bps: config.max_deviation_bps,
+        })
+    }
+
+    pub async fn get_price(&self, mint: &str) -> Result<PriceQuote> {
+        self.get_prices(&[mint.to_string()])
+            .await?
+            .pop()
+            .ok_or_else(|| ApiError::NotFound(format!("No price feed for mint {}", mint)).into())
+    }
+
+    /// Prices for every mint that has a configured feed, fetched with one
+    /// `getMultipleAccounts` call. Mints without a feed are omitted.
+    pub async fn get_prices(&self, mints: &[String]) -> Result<Vec<PriceQuote>> {
+        let known: Vec<(&String, Pubkey)> = mints
+            .iter()
+            .filter_map(|mint| self.feeds.get(mint).map(|feed| (mint, *feed)))
+            .collect();
+        if known.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let keys: Vec<Pubkey> = known.iter().map(|(_, feed)| *feed).collect();
+        let (accounts, current_slot) =
+            tokio::try_join!(self.client.get_multiple_accounts(&keys), self.client.get_slot())?;
+
+        let mut quotes = Vec::with_capacity(known.len());
+        for ((mint, feed), account) in known.into_iter().zip(accounts) {
+            let Some(price) = account.as_ref().and_then(|a| parse_pyth_price(&a.data)) else {
+                return Err(ApiError::Internal(format!("Price feed {} is not a Pyth price account", feed)).into());
+            };
+            let scale = 10f64.powi(price.exponent);
+            quotes.push(PriceQuote {
+                mint: mint.clone(),
+                source: "pyth".to_string(),
+                feed: feed.to_string(),
+                price: price.price as f64 * scale,
+                confidence: price.confidence as f64 * scale,
+                publish_slot: price.publish_slot,
+                stale: !price.trading
+                    || current_slot.saturating_sub(price.publish_slot) > self.max_staleness_slots,
+            });
+        }
+        Ok(quotes)
+    }
+
+    /// Rejects a quoted `rate` (output tokens per input token, decimal
+    /// adjusted) that strays too far from the oracle cross rate. Pairs without
+    /// fresh prices on both sides are not checked.
+    pub async fn check_rate(&self, input_mint: &str, output_mint: &str, rate: f64) -> Result<()> {
+        let quotes = self
+            .get_prices(&[input_mint.to_string(), output_mint.to_string()])
+            .await?;
+        let [input, output] = quotes.as_slice() else {
+            return Ok(());
+        };
+        if input.stale || output.stale || output.price <= 0.0 {
+            return Ok(());
+        }
+
+        let oracle_rate = input.price / output.price;
+        let deviation_bps = ((rate - oracle_rate).abs() / oracle_rate * 10_000.0) as u64;
+        if deviation_bps > u64::from(self.max_deviation_bps) {
+            return Err(ApiError::BadRequest(format!(
+                "Quoted rate deviates {} bps from the oracle price",
+                deviation_bps
+            ))
+            .into());
+        }
+        Ok(())
+    }
+}
+
+fn parse_pyth_price(data: &[u8]) -> Option<PythPrice> {
+    if data.len() < PRICE_ACCOUNT_MIN_LEN
+        || read_u32(data, 0) != PYTH_MAGIC
+        || read_u32(data, 8) != PYTH_PRICE_ACCOUNT_TYPE
+    {
+        return None;
+    }
+
+    Some(PythPrice {
+        price: read_u64(data, AGG_PRICE_OFFSET) as i64,
+        confidence: read_u64(data, AGG_CONF_OFFSET),
+        exponent: read_i32(data, EXPONENT_OFFSET),
+        trading: read_u32(data, AGG_STATUS_OFFSET) == PYTH_STATUS_TRADING,
+        publish_slot: read_u64(data, AGG_PUB_SLOT_OFFSET),
+    })
+}
@@ -0,0 +1,199 @@
+use anyhow::Result;
+use futures::StreamExt;
+use serde::Serialize;
+use solana_account_decoder::{UiAccount, UiAccountData, UiAccountEncoding};
+use solana_client::{
+    nonblocking::pubsub_client::PubsubClient,
+    rpc_config::{RpcAccountInfoConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter},
+    rpc_response::{Response, RpcLogsResponse},
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+// Buffered notifications per account or program before slow clients start lagging.
+const CHANNEL_CAPACITY: usize = 64;
+
+// How often an idle upstream subscription checks whether anyone is still listening.
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Serialize)]
+pub struct AccountUpdate {
+    pub address: String,
+    pub slot: u64,
+    pub lamports: u64,
+    pub owner: String,
+    pub executable: bool,
+    pub rent_epoch: u64,
+    pub data: UiAccountData,
+}
+
+impl AccountUpdate {
+    fn from_response(pubkey: &Pubkey, response: Response<UiAccount>) -> Self {
+        let account = response.value;
+        Self {
+            address: pubkey.to_string(),
+            slot: response.context.slot,
+            lamports: account.lamports,
+            owner: account.owner,
+            executable: account.executable,
+            rent_epoch: account.rent_epoch,
+            data: account.data,
+        }
+    }
+}
+
+/// Logs of one transaction that mentioned a program.
+#[derive(Clone, Serialize, ToSchema)]
+pub struct LogUpdate {
+    pub program: String,
+    pub signature: String,
+    pub slot: u64,
+    pub error: Option<String>,
+    pub logs: Vec<String>,
+}
+
+impl LogUpdate {
+    fn from_response(program: &Pubkey, response: Response<RpcLogsResponse>) -> Self {
+        let logs = response.value;
+        Self {
+            program: program.to_string(),
+            signature: logs.signature,
+            slot: response.context.slot,
+            error: logs.err.map(|e| e.to_string()),
+            logs: logs.logs,
+        }
+    }
+}
+
+/// Shares one upstream `accountSubscribe` per account, and one
+/// `logsSubscribe` per program, between all connected clients and tears it
+/// down once the last client goes away.
+pub struct PubsubManager {
+    ws_url: String,
+    channels: Mutex<HashMap<Pubkey, broadcast::Sender<AccountUpdate>>>,
+    log_channels: Mutex<HashMap<Pubkey, broadcast::Sender<LogUpdate>>>,
+}
+
+impl PubsubManager {
+    pub fn new(ws_url: String) -> Self {
+        Self {
+            ws_url,
+            channels: Mutex::new(HashMap::new()),
+            log_channels: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn subscribe_account(self: &Arc<Self>, pubkey: Pubkey) -> broadcast::Receiver<AccountUpdate> {
+        let mut channels = self.channels.lock().unwrap();
+        if let Some(sender) = channels.get(&pubkey) {
+            return sender.subscribe();
+        }
+
+        let (sender, receiver) = broadcast::channel(CHANNEL_CAPACITY);
+        channels.insert(pubkey, sender.clone());
+
+        let manager = Arc::clone(self);
+        tokio::spawn(async move {
+            if let Err(e) = manager.run_account_subscription(pubkey, &sender).await {
+                warn!("Account subscription for {} ended: {}", pubkey, e);
+            }
+            manager.channels.lock().unwrap().remove(&pubkey);
+        });
+
+        receiver
+    }
+
+    pub fn subscribe_logs(self: &Arc<Self>, program: Pubkey) -> broadcast::Receiver<LogUpdate> {
+        let mut channels = self.log_channels.lock().unwrap();
+        if let Some(sender) = channels.get(&program) {
+            return sender.subscribe();
+        }
+
+        let (sender, receiver) = broadcast::channel(CHANNEL_CAPACITY);
+        channels.insert(program, sender.clone());
+
+        let manager = Arc::clone(self);
+        tokio::spawn(async move {
+            if let Err(e) = manager.run_logs_subscription(program, &sender).await {
+                warn!("Logs subscription for {} ended: {}", program, e);
+            }
+            manager.log_channels.lock().unwrap().remove(&program);
+        });
+
+        receiver
+    }
+
+    async fn run_account_subscription(
+        &self,
+        pubkey: Pubkey,
+        sender: &broadcast::Sender<AccountUpdate>,
+    ) -> Result<()> {
+        let client = PubsubClient::new(&self.ws_url).await?;
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            ..RpcAccountInfoConfig::default()
+        };
+        let (mut notifications, unsubscribe) = client.account_subscribe(&pubkey, Some(config)).await?;
+        info!("Opened upstream account subscription for {}", pubkey);
+
+        let mut idle_check = tokio::time::interval(IDLE_CHECK_INTERVAL);
+        loop {
+            tokio::select! {
+                notification = notifications.next() => {
+                    let Some(response) = notification else { break };
+                    if sender.send(AccountUpdate::from_response(&pubkey, response)).is_err() {
+                        break;
+                    }
+                }
+                _ = idle_check.tick() => {
+                    if sender.receiver_count() == 0 {
+                        break;
+                    }
+                }
+            }
+        }
+
+        drop(notifications);
+        unsubscribe().await;
+        info!("Closed upstream account subscription for {}", pubkey);
+        Ok(())
+    }
+
+    async fn run_logs_subscription(&self, program: Pubkey, sender: &broadcast::Sender<LogUpdate>) -> Result<()> {
+        let client = PubsubClient::new(&self.ws_url).await?;
+        let config = RpcTransactionLogsConfig {
+            commitment: Some(CommitmentConfig::confirmed()),
+        };
+        let filter = RpcTransactionLogsFilter::Mentions(vec![program.to_string()]);
+        let (mut notifications, unsubscribe) = client.logs_subscribe(filter, config).await?;
+        info!("Opened upstream logs subscription for {}", program);
+
+        let mut idle_check = tokio::time::interval(IDLE_CHECK_INTERVAL);
+        loop {
+            tokio::select! {
+                notification = notifications.next() => {
+                    let Some(response) = notification else { break };
+                    if sender.send(LogUpdate::from_response(&program, response)).is_err() {
+                        break;
+                    }
+                }
+                _ = idle_check.tick() => {
+                    if sender.receiver_count() == 0 {
+                        break;
+                    }
+                }
+            }
+        }
+
+        drop(notifications);
+        unsubscribe().await;
+        info!("Closed upstream logs subscription for {}", program);
+        Ok(())
+    }
+}
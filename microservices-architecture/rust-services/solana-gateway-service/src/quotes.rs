@@ -0,0 +1,155 @@
+//! Signed, expiring swap quotes. A quote ID carries the quoted terms and
+//! their expiry, signed with `swap.quotes.secret`, so any replica can check
+//! it without shared state. Executing a quote records its ID, so each is
+//! used at most once.
+
+use crate::config::SwapQuoteConfig;
+use crate::database::Database;
+use crate::error::ApiError;
+use crate::swap::{SwapQuote, SwapRequest};
+use anyhow::Result;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A quote as returned by `POST /api/v1/swap/quote`.
+#[derive(Serialize, ToSchema)]
+pub struct SignedQuote {
+    /// Passed as `quote_id` to execute the quoted swap. Absent when signed
+    /// quotes are disabled.
+    pub quote_id: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub quote: SwapQuote,
+}
+
+/// The terms a quote ID was signed over.
+#[derive(Serialize, Deserialize)]
+pub struct QuoteClaims {
+    pub id: Uuid,
+    pub pool_id: String,
+    pub input_mint: String,
+    pub output_mint: String,
+    pub amount_in: u64,
+    pub expected_amount_out: u64,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Signs `quote` when signed quotes are enabled.
+pub fn sign(config: &SwapQuoteConfig, quote: SwapQuote) -> Result<SignedQuote> {
+    let Some(secret) = config.secret.as_deref().filter(|_| config.enabled) else {
+        return Ok(SignedQuote {
+            quote_id: None,
+            expires_at: None,
+            quote,
+        });
+    };
+    let expires_at = Utc::now() + Duration::seconds(config.ttl_secs as i64);
+    let claims = QuoteClaims {
+        id: Uuid::new_v4(),
+        pool_id: quote.pool_id.clone(),
+        input_mint: quote.input_mint.clone(),
+        output_mint: quote.output_mint.clone(),
+        amount_in: quote.amount_in,
+        expected_amount_out: quote.expected_amount_out,
+        expires_at,
+    };
+    let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims)?);
+    let signature = URL_SAFE_NO_PAD.encode(mac(secret, &payload).finalize().into_bytes());
+    Ok(SignedQuote {
+        quote_id: Some(format!("{}.{}", payload, signature)),
+        expires_at: Some(expires_at),
+        quote,
+    })
+}
+
+/// Rejects a swap that doesn't reference a quote while signed quotes are
+/// enabled. Only applied to swaps requested by clients; the gateway's own
+/// swaps, such as DCA fills, quote themselves.
+pub fn require(config: &SwapQuoteConfig, request: &SwapRequest) -> Result<(), ApiError> {
+    if config.enabled && request.quote_id.is_none() {
+        return Err(ApiError::BadRequest("quote_id is required; quote the swap first".to_string()));
+    }
+    Ok(())
+}
+
+/// Verifies the request's quote ID, if it has one. Returns the request to
+/// execute, which goes through the quoted pool, with the quote's terms.
+pub fn resolve(
+    config: &SwapQuoteConfig,
+    request: &SwapRequest,
+) -> Result<(SwapRequest, Option<QuoteClaims>), ApiError> {
+    let Some(quote_id) = &request.quote_id else {
+        return Ok((request.clone(), None));
+    };
+    let claims = verify(config, quote_id, request)?;
+    let request = SwapRequest {
+        pool_id: Some(claims.pool_id.clone()),
+        ..request.clone()
+    };
+    Ok((request, Some(claims)))
+}
+
+/// Checks a quote ID's signature and expiry, and that `request` is the
+/// swap that was quoted.
+fn verify(config: &SwapQuoteConfig, quote_id: &str, request: &SwapRequest) -> Result<QuoteClaims, ApiError> {
+    let invalid = || ApiError::BadRequest("Invalid quote_id".to_string());
+    let secret = config
+        .secret
+        .as_deref()
+        .filter(|_| config.enabled)
+        .ok_or_else(|| ApiError::BadRequest("Signed quotes are not enabled".to_string()))?;
+    let (payload, signature) = quote_id.split_once('.').ok_or_else(invalid)?;
+    let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| invalid())?;
+    mac(secret, payload).verify_slice(&signature).map_err(|_| invalid())?;
+    let claims: QuoteClaims = URL_SAFE_NO_PAD
+        .decode(payload)
+        .ok()
+        .and_then(|payload| serde_json::from_slice(&payload).ok())
+        .ok_or_else(invalid)?;
+
+    if claims.expires_at <= Utc::now() {
+        return Err(ApiError::BadRequest("Quote has expired; request a new one".to_string()));
+    }
+    let matches = request.input_mint == claims.input_mint
+        && request.amount_in == claims.amount_in
+        && request.pool_id.as_ref().map_or(true, |pool_id| *pool_id == claims.pool_id)
+        && request.output_mint.as_ref().map_or(true, |mint| *mint == claims.output_mint);
+    if !matches {
+        return Err(ApiError::BadRequest("Swap differs from the quoted one".to_string()));
+    }
+    Ok(claims)
+}
+
+impl QuoteClaims {
+    /// Rejects execution when the current output has fallen more than
+    /// `max_price_move_bps` below the quoted one.
+    fn check_price(&self, config: &SwapQuoteConfig, current: &SwapQuote) -> Result<(), ApiError> {
+        let floor = self.expected_amount_out as u128 * (10_000 - config.max_price_move_bps as u128) / 10_000;
+        if (current.expected_amount_out as u128) < floor {
+            return Err(ApiError::Conflict(format!(
+                "Price moved since the quote: {} expected now, {} quoted",
+                current.expected_amount_out, self.expected_amount_out
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks the price against the quoted one and uses the quote up.
+    pub async fn redeem(&self, config: &SwapQuoteConfig, database: &Database, current: &SwapQuote) -> Result<()> {
+        self.check_price(config, current)?;
+        if !database.claim_quote(self.id, self.expires_at).await? {
+            return Err(ApiError::Conflict("Quote was already executed".to_string()).into());
+        }
+        Ok(())
+    }
+}
+
+fn mac(secret: &str, payload: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(payload.as_bytes());
+    mac
+}
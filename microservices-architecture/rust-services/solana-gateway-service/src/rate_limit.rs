@@ -0,0 +1,153 @@
+use crate::config::{ClientQuota, RateLimitConfig};
+use anyhow::{Context, Result};
+use governor::{
+    clock::{Clock, DefaultClock},
+    DefaultDirectRateLimiter, DefaultKeyedRateLimiter, Quota, RateLimiter,
+};
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Route groups with independent quotas. Trading routes submit transactions
+/// and get much stricter limits than reads.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RouteGroup {
+    Read,
+    Trading,
+}
+
+struct ClientLimiters {
+    read: DefaultDirectRateLimiter,
+    trading: DefaultDirectRateLimiter,
+}
+
+/// The buckets built from the configured limits.
+pub struct ConfiguredLimits {
+    enabled: bool,
+    global: DefaultDirectRateLimiter,
+    read: DefaultKeyedRateLimiter<String>,
+    trading: DefaultKeyedRateLimiter<String>,
+    overrides: HashMap<String, Arc<ClientLimiters>>,
+}
+
+/// Token-bucket limits: one global bucket shared by all clients, plus a bucket
+/// per client (API key, or IP address for anonymous callers) per route group.
+pub struct RateLimits {
+    /// Replaced as a whole by `reload`, which starts every configured
+    /// bucket full.
+    configured: RwLock<Arc<ConfiguredLimits>>,
+    /// Buckets for API keys with quotas set through `/admin/api-keys`,
+    /// rebuilt when the quota changes.
+    issued: Mutex<HashMap<String, (ClientQuota, Arc<ClientLimiters>)>>,
+}
+
+impl ConfiguredLimits {
+    pub fn new(config: &RateLimitConfig) -> Result<Self> {
+        let overrides = config
+            .api_keys
+            .iter()
+            .map(|(key, quota)| Ok((key.clone(), Arc::new(ClientLimiters::new(quota)?))))
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            enabled: config.enabled,
+            global: RateLimiter::direct(Quota::per_second(non_zero(config.global_per_second)?)),
+            read: RateLimiter::keyed(per_minute(config.read_per_minute)?),
+            trading: RateLimiter::keyed(per_minute(config.trading_per_minute)?),
+            overrides,
+        })
+    }
+}
+
+impl RateLimits {
+    pub fn new(config: &RateLimitConfig) -> Result<Self> {
+        Ok(Self {
+            configured: RwLock::new(Arc::new(ConfiguredLimits::new(config)?)),
+            issued: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Applies changed limits. Quotas of issued API keys are kept.
+    pub fn reload(&self, limits: ConfiguredLimits) {
+        *self.configured.write().unwrap() = Arc::new(limits);
+    }
+
+    /// Takes one token for `client` in `group`, or returns how long the
+    /// client should wait before retrying.
+    pub fn check(&self, group: RouteGroup, client: &str) -> Result<(), Duration> {
+        self.check_quota(group, client, None)
+    }
+
+    /// Like `check`, with `quota` (an issued API key's own limits) taking
+    /// precedence over the configured ones.
+    pub fn check_quota(&self, group: RouteGroup, client: &str, quota: Option<&ClientQuota>) -> Result<(), Duration> {
+        let configured = self.configured.read().unwrap().clone();
+        if !configured.enabled {
+            return Ok(());
+        }
+
+        let clock = DefaultClock::default();
+        let wait = |not_until: governor::NotUntil<_>| not_until.wait_time_from(clock.now());
+
+        configured.global.check().map_err(wait)?;
+        let limiters = match quota {
+            Some(quota) => self.issued(client, quota),
+            None => configured.overrides.get(client).cloned(),
+        };
+        match (limiters, group) {
+            (Some(limiters), RouteGroup::Read) => limiters.read.check().map_err(wait),
+            (Some(limiters), RouteGroup::Trading) => limiters.trading.check().map_err(wait),
+            (None, RouteGroup::Read) => configured.read.check_key(&client.to_string()).map_err(wait),
+            (None, RouteGroup::Trading) => configured.trading.check_key(&client.to_string()).map_err(wait),
+        }
+    }
+
+    fn issued(&self, client: &str, quota: &ClientQuota) -> Option<Arc<ClientLimiters>> {
+        let mut issued = self.issued.lock().unwrap();
+        match issued.get(client) {
+            Some((current, limiters)) if current == quota => Some(limiters.clone()),
+            _ => {
+                let limiters = Arc::new(ClientLimiters::new(quota).ok()?);
+                issued.insert(client.to_string(), (quota.clone(), limiters.clone()));
+                Some(limiters)
+            }
+        }
+    }
+
+    /// Drops buckets of clients that have been idle long enough to be full.
+    pub fn retain_recent(&self) {
+        let configured = self.configured.read().unwrap().clone();
+        configured.read.retain_recent();
+        configured.trading.retain_recent();
+    }
+}
+
+impl ClientLimiters {
+    fn new(quota: &ClientQuota) -> Result<Self> {
+        Ok(Self {
+            read: RateLimiter::direct(per_minute(quota.read_per_minute)?),
+            trading: RateLimiter::direct(per_minute(quota.trading_per_minute)?),
+        })
+    }
+}
+
+pub fn spawn_cleanup(limits: Arc<RateLimits>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            limits.retain_recent();
+        }
+    });
+}
+
+fn per_minute(requests: u32) -> Result<Quota> {
+    Ok(Quota::per_minute(non_zero(requests)?))
+}
+
+fn non_zero(value: u32) -> Result<NonZeroU32> {
+    NonZeroU32::new(value).context("Rate limits must be greater than zero")
+}
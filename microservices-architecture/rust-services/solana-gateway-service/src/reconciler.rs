@@ -0,0 +1,105 @@
+use crate::confirmations::{record_settled, status_name};
+use crate::config::ReconcilerConfig;
+use crate::database::Database;
+use crate::events::EventPublisher;
+use crate::metrics::Metrics;
+use crate::solana_client::SolanaClient;
+use anyhow::Result;
+use solana_sdk::signature::Signature;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Re-checks stored transactions that stayed pending or confirmed past the
+/// confirmation tracker's window against the full ledger, and corrects
+/// records the chain disagrees with:
+///
+/// - `expired`: a pending transaction never landed and its blockhash is long
+///   invalid.
+/// - `dropped`: a confirmed transaction's block was abandoned by a fork and
+///   the transaction never landed again.
+/// - `reorged`: the transaction landed in a different slot than recorded.
+/// - `status`: the chain has moved on (usually to finalized or failed)
+///   without the tracker noticing.
+pub fn spawn_reconciler(
+    client: Arc<SolanaClient>,
+    database: Arc<Database>,
+    events: Arc<EventPublisher>,
+    metrics: Arc<Metrics>,
+    cluster: String,
+    config: ReconcilerConfig,
+) {
+    if !config.enabled {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs.max(1)));
+        loop {
+            interval.tick().await;
+            if let Err(e) = reconcile(&client, &database, &events, &metrics, &cluster, &config).await {
+                warn!("Transaction reconciliation failed: {}", e);
+            }
+        }
+    });
+}
+
+async fn reconcile(
+    client: &SolanaClient,
+    database: &Database,
+    events: &EventPublisher,
+    metrics: &Metrics,
+    cluster: &str,
+    config: &ReconcilerConfig,
+) -> Result<()> {
+    let created_before = chrono::Utc::now() - chrono::Duration::seconds(config.min_age_secs);
+    let stale: Vec<_> = database
+        .stale_transactions(created_before, config.batch_size)
+        .await?
+        .into_iter()
+        .filter_map(|tx| {
+            let signature = Signature::from_str(&tx.signature).ok()?;
+            Some((tx, signature))
+        })
+        .collect();
+    if stale.is_empty() {
+        return Ok(());
+    }
+
+    let signatures: Vec<Signature> = stale.iter().map(|(_, signature)| *signature).collect();
+    let statuses = client.get_signature_statuses_with_history(&signatures).await?;
+    metrics.observe_reconciled(cluster, stale.len());
+
+    for ((tx, _), status) in stale.iter().zip(statuses) {
+        let (next, slot, kind) = match &status {
+            None if tx.status == "pending" => ("expired", None, "expired"),
+            None => ("dropped", None, "dropped"),
+            Some(status) => {
+                let next = status_name(status);
+                let moved = tx.slot.is_some_and(|slot| slot as u64 != status.slot);
+                match (next == tx.status, moved) {
+                    (_, true) => (next, Some(status.slot), "reorged"),
+                    (false, false) => (next, Some(status.slot), "status"),
+                    (true, false) => continue,
+                }
+            }
+        };
+
+        info!(
+            "Reconciled transaction {}: {} in slot {} -> {} ({})",
+            tx.signature,
+            tx.status,
+            tx.slot.map_or_else(|| "-".to_string(), |slot| slot.to_string()),
+            next,
+            kind
+        );
+        metrics.observe_reconciler_discrepancy(cluster, kind);
+        database.update_transaction_status(&tx.signature, next, slot).await?;
+        if next != tx.status {
+            events.transaction_status(&tx.signature, next, slot);
+        }
+        record_settled(client, database, &tx.signature, next).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,240 @@
+//! Applying a changed configuration without a restart, on SIGHUP or through
+//! `POST /admin/config/reload`. Rate limits, priority fee settings, RPC
+//! endpoints, maintenance switches and the log level are swapped in place;
+//! requests in flight finish with the settings they started with.
+
+use crate::config::Config;
+use crate::logging::LogFilter;
+use crate::maintenance::Maintenance;
+use crate::rate_limit::{ConfiguredLimits, RateLimits};
+use crate::solana_client::SolanaClient;
+use anyhow::Result;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+/// Top-level settings a reload applies, entirely or in part. Changes to any
+/// other setting are reported as needing a restart.
+const RELOADABLE: [&str; 9] = [
+    "rate_limit",
+    "maintenance",
+    "fees",
+    "logging",
+    "solana_rpc_url",
+    "solana_rpc_endpoints",
+    "rpc_failover",
+    "rpc_http",
+    "clusters",
+];
+
+#[derive(Serialize, ToSchema)]
+pub struct ReloadReport {
+    /// Settings that changed and were applied, e.g. `rate_limit` or
+    /// `clusters.devnet.solana_rpc_endpoints`.
+    pub applied: Vec<String>,
+    /// Settings that differ from the running configuration but only take
+    /// effect on restart.
+    pub restart_required: Vec<String>,
+}
+
+/// Reloads the configuration from the sources it was loaded from at
+/// startup and applies what changed to every cluster.
+pub struct ConfigReloader {
+    file: String,
+    overrides: Vec<String>,
+    /// The configuration in effect; held across a reload so reloads apply
+    /// one at a time.
+    running: tokio::sync::Mutex<Config>,
+    rate_limits: Arc<RateLimits>,
+    maintenance: Arc<Maintenance>,
+    log_filter: LogFilter,
+    clients: Mutex<Vec<(String, Arc<SolanaClient>)>>,
+}
+
+impl ConfigReloader {
+    pub fn new(
+        file: &str,
+        overrides: &[String],
+        config: &Config,
+        rate_limits: Arc<RateLimits>,
+        maintenance: Arc<Maintenance>,
+        log_filter: LogFilter,
+    ) -> Self {
+        Self {
+            file: file.to_string(),
+            overrides: overrides.to_vec(),
+            running: tokio::sync::Mutex::new(config.clone()),
+            rate_limits,
+            maintenance,
+            log_filter,
+            clients: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Adds a cluster's client, whose endpoints and fee settings follow
+    /// reloads.
+    pub fn register(&self, cluster: &str, client: Arc<SolanaClient>) {
+        self.clients.lock().unwrap().push((cluster.to_string(), client));
+    }
+
+    /// Loads and validates the configuration, then applies the reloadable
+    /// changes. Everything that can fail, the log filter, rate limiters and
+    /// RPC pools, is built before anything is swapped in, so a reload is
+    /// applied entirely or not at all.
+    pub async fn reload(&self) -> Result<ReloadReport> {
+        let new = Config::load(&self.file, &self.overrides).await?;
+        let mut running = self.running.lock().await;
+        let mut report = ReloadReport {
+            applied: Vec::new(),
+            restart_required: restart_required(&running, &new),
+        };
+        let clients = self.clients.lock().unwrap().clone();
+
+        let rate_limits = changed(&running.rate_limit, &new.rate_limit)
+            .then(|| ConfiguredLimits::new(&new.rate_limit))
+            .transpose()?;
+        let log_filter = (running.logging.level != new.logging.level)
+            .then(|| LogFilter::parse(&new.logging.level))
+            .transpose()?;
+        let new_clusters = new.cluster_configs();
+        let mut endpoint_changes = Vec::new();
+        for old in running.cluster_configs() {
+            let Some(updated) = new_clusters.iter().find(|updated| updated.cluster == old.cluster) else {
+                continue;
+            };
+            let endpoints = |config: &Config| {
+                (
+                    config.rpc_endpoints(),
+                    config.rpc_failover.clone(),
+                    config.rpc_http.clone(),
+                )
+            };
+            if !changed(&endpoints(&old), &endpoints(updated)) {
+                continue;
+            }
+            let pools = clients
+                .iter()
+                .filter(|(cluster, _)| *cluster == old.cluster)
+                .map(|(_, client)| Ok((client.clone(), SolanaClient::rpc_pool(updated)?)))
+                .collect::<Result<Vec<_>>>()?;
+            endpoint_changes.push((updated, pools));
+        }
+
+        let mut next = running.clone();
+        if let Some(limits) = rate_limits {
+            self.rate_limits.reload(limits);
+            next.rate_limit = new.rate_limit.clone();
+            report.applied.push("rate_limit".to_string());
+        }
+        // Switches flipped through the admin API since stay until the
+        // configured state itself changes.
+        if changed(&running.maintenance, &new.maintenance) {
+            self.maintenance.apply(&new.maintenance);
+            next.maintenance = new.maintenance.clone();
+            report.applied.push("maintenance".to_string());
+        }
+        if changed(&running.fees, &new.fees) {
+            for (_, client) in &clients {
+                client.set_fees(&new.fees);
+            }
+            next.fees = new.fees.clone();
+            report.applied.push("fees".to_string());
+        }
+        if let Some(filter) = log_filter {
+            self.log_filter.replace(filter);
+            next.logging.level = new.logging.level.clone();
+            report.applied.push("logging.level".to_string());
+        }
+        for (updated, pools) in endpoint_changes {
+            for (client, pool) in pools {
+                client.set_endpoints(pool);
+            }
+            report.applied.push(match next.clusters.get_mut(&updated.cluster) {
+                Some(cluster) => {
+                    cluster.solana_rpc_url = updated.solana_rpc_url.clone();
+                    cluster.solana_rpc_endpoints = updated.solana_rpc_endpoints.clone();
+                    format!("clusters.{}.solana_rpc_endpoints", updated.cluster)
+                }
+                None => "solana_rpc_endpoints".to_string(),
+            });
+        }
+        next.solana_rpc_url = new.solana_rpc_url.clone();
+        next.solana_rpc_endpoints = new.solana_rpc_endpoints.clone();
+        next.rpc_failover = new.rpc_failover.clone();
+        next.rpc_http = new.rpc_http.clone();
+        *running = next;
+
+        if report.applied.is_empty() {
+            info!("Configuration reloaded, nothing to apply");
+        } else {
+            info!("Configuration reloaded, applied {}", report.applied.join(", "));
+        }
+        if !report.restart_required.is_empty() {
+            warn!(
+                "Changed settings {} take effect on restart",
+                report.restart_required.join(", ")
+            );
+        }
+        Ok(report)
+    }
+}
+
+fn changed<T: Serialize>(old: &T, new: &T) -> bool {
+    serde_json::to_value(old).ok() != serde_json::to_value(new).ok()
+}
+
+/// Changed settings a reload doesn't apply, including added or removed
+/// clusters and changed cluster databases.
+fn restart_required(old: &Config, new: &Config) -> Vec<String> {
+    let (Ok(serde_json::Value::Object(old_fields)), Ok(serde_json::Value::Object(new_fields))) =
+        (serde_json::to_value(old), serde_json::to_value(new))
+    else {
+        return Vec::new();
+    };
+    let mut settings: Vec<String> = new_fields
+        .iter()
+        .filter(|(key, value)| !RELOADABLE.contains(&key.as_str()) && old_fields.get(*key) != Some(*value))
+        .map(|(key, _)| key.clone())
+        .collect();
+    if changed(&old.logging.format, &new.logging.format) {
+        settings.push("logging.format".to_string());
+    }
+    let mut clusters: Vec<&String> = old.clusters.keys().chain(new.clusters.keys()).collect();
+    clusters.sort();
+    clusters.dedup();
+    for name in clusters {
+        let restart = match (old.clusters.get(name), new.clusters.get(name)) {
+            (Some(old), Some(new)) => {
+                old.database_url != new.database_url
+                    || old.database_read_urls != new.database_read_urls
+                    || old.solana_ws_url != new.solana_ws_url
+            }
+            _ => true,
+        };
+        if restart {
+            settings.push(format!("clusters.{}", name));
+        }
+    }
+    settings.sort();
+    settings
+}
+
+/// Reloads on SIGHUP.
+pub fn spawn_sighup(reloader: Arc<ConfigReloader>) {
+    tokio::spawn(async move {
+        let mut hangups = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+            Ok(hangups) => hangups,
+            Err(e) => {
+                warn!("Failed to listen for SIGHUP, reload through the admin API only: {}", e);
+                return;
+            }
+        };
+        while hangups.recv().await.is_some() {
+            info!("SIGHUP received, reloading configuration");
+            if let Err(e) = reloader.reload().await {
+                warn!("Failed to reload configuration: {:#}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,114 @@
+use crate::logging;
+use axum::http::{HeaderMap, HeaderValue, Request};
+use opentelemetry::{
+    global,
+    propagation::Extractor,
+    trace::{TraceContextExt, TraceId},
+};
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+// Longest caller-supplied ID that is passed through rather than replaced.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+    static TRACE_ID: Option<String>;
+}
+
+/// ID of the API request the current task is serving, if any.
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Trace ID of the current span when spans are exported. Otherwise that of
+/// the `traceparent` the caller sent with the request the current task is
+/// serving, if any.
+pub fn current_trace_id() -> Option<String> {
+    if logging::is_tracing() {
+        return span_trace_id(&Span::current());
+    }
+    TRACE_ID.try_with(|trace_id| trace_id.clone()).ok().flatten()
+}
+
+fn span_trace_id(span: &Span) -> Option<String> {
+    let trace_id = span.context().span().span_context().trace_id();
+    (trace_id != TraceId::INVALID).then(|| trace_id.to_string())
+}
+
+/// Runs `future` with `id` as the current request ID and `trace_id` as its
+/// trace ID.
+pub async fn scope<F: std::future::Future>(id: String, trace_id: Option<String>, future: F) -> F::Output {
+    REQUEST_ID.scope(id, TRACE_ID.scope(trace_id, future)).await
+}
+
+/// Forwards the current request ID to a downstream service.
+pub fn tag(request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    match current() {
+        Some(id) => request.header(REQUEST_ID_HEADER, id),
+        None => request,
+    }
+}
+
+/// The caller's `X-Request-Id` if it is usable as a header value and log
+/// field, otherwise a fresh UUID.
+pub fn from_header(value: Option<&HeaderValue>) -> String {
+    value
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN)
+        .filter(|id| id.bytes().all(|b| b.is_ascii_graphic()))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string())
+}
+
+/// Trace ID of a W3C `traceparent` header
+/// (`version-traceid-parentid-flags`), when well formed.
+pub fn trace_id(value: Option<&HeaderValue>) -> Option<&str> {
+    let trace_id = value?.to_str().ok()?.split('-').nth(1)?;
+    (trace_id.len() == 32 && trace_id.bytes().all(|b| b.is_ascii_hexdigit())).then_some(trace_id)
+}
+
+/// Span for `TraceLayer` carrying the ID assigned by
+/// `middleware::assign_request_id` and the distributed trace ID, so every
+/// event logged while handling the request can be correlated with them.
+/// When spans are exported, the span continues the caller's `traceparent`
+/// or starts a trace; otherwise the trace ID is the caller's.
+pub fn make_span<B>(request: &Request<B>) -> Span {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    let span = tracing::info_span!(
+        "request",
+        method = %request.method(),
+        uri = %request.uri(),
+        request_id = %request_id,
+        trace_id = tracing::field::Empty,
+    );
+    let trace_id = if logging::is_tracing() {
+        let parent = global::get_text_map_propagator(|propagator| propagator.extract(&Headers(request.headers())));
+        span.set_parent(parent);
+        span_trace_id(&span)
+    } else {
+        trace_id(request.headers().get(TRACEPARENT_HEADER)).map(str::to_string)
+    };
+    span.record("trace_id", trace_id.as_deref().unwrap_or_default());
+    span
+}
+
+/// Reads propagated trace context from request headers.
+struct Headers<'a>(&'a HeaderMap);
+
+impl Extractor for Headers<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|name| name.as_str()).collect()
+    }
+}
@@ -0,0 +1,211 @@
+//! Upkeep of the monthly `solana_transactions` partitions: upcoming months
+//! are created before any transaction needs them, and months past the
+//! retention period are archived to S3 as Parquet and dropped whole, which
+//! keeps history queries from slowing down as the table ages.
+
+use crate::aws::AwsClient;
+use crate::config::{TransactionArchiveConfig, TransactionRetentionConfig};
+use crate::database::{Database, TransactionRecord};
+use anyhow::{anyhow, Result};
+use arrow::array::{ArrayRef, Int64Array, ListBuilder, StringArray, StringBuilder, TimestampMicrosecondArray};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+const PARTITION_PREFIX: &str = "solana_transactions_";
+
+fn partition_name(month: NaiveDate) -> String {
+    format!("{}{}", PARTITION_PREFIX, month.format("%Y_%m"))
+}
+
+/// The first day of the month a partition holds, or None for tables not
+/// named by `partition_name`.
+fn partition_month(name: &str) -> Option<NaiveDate> {
+    let (year, month) = name.strip_prefix(PARTITION_PREFIX)?.split_once('_')?;
+    NaiveDate::from_ymd_opt(year.parse().ok()?, month.parse().ok()?, 1)
+}
+
+pub fn spawn(database: Arc<Database>, config: TransactionRetentionConfig) {
+    // A partition is only dropped once it is archived, so without a working
+    // archive client nothing is pruned.
+    let archive = match &config.archive.bucket {
+        None => Ok(None),
+        Some(bucket) => AwsClient::new(
+            "s3",
+            "",
+            config.archive.region.as_deref(),
+            config.archive.endpoint.as_deref(),
+            config.archive.timeout_ms,
+        )
+        .map(|client| Some((client, bucket.clone()))),
+    };
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs.max(1)));
+        loop {
+            interval.tick().await;
+            if let Err(e) = create_upcoming(&database, config.premake_months).await {
+                warn!("Failed to create transaction partitions: {}", e);
+            }
+            let Some(retention_months) = config.retention_months else {
+                continue;
+            };
+            match &archive {
+                Ok(archive) => {
+                    if let Err(e) = prune(&database, &config.archive, archive.as_ref(), retention_months).await {
+                        warn!("Failed to prune transaction partitions: {:#}", e);
+                    }
+                }
+                Err(e) => warn!("Transaction partitions not pruned, archive client unavailable: {}", e),
+            }
+        }
+    });
+}
+
+fn current_month() -> NaiveDate {
+    let today = Utc::now().date_naive();
+    today.with_day(1).unwrap_or(today)
+}
+
+async fn create_upcoming(database: &Database, premake_months: u32) -> Result<()> {
+    let current = current_month();
+    for offset in 0..=premake_months {
+        let month = current + Months::new(offset);
+        database
+            .create_transaction_partition(&partition_name(month), month, month + Months::new(1))
+            .await?;
+    }
+    Ok(())
+}
+
+/// Archives (when configured) and drops every partition whose month ended
+/// more than `retention_months` whole months ago.
+async fn prune(
+    database: &Database,
+    config: &TransactionArchiveConfig,
+    archive: Option<&(AwsClient, String)>,
+    retention_months: u32,
+) -> Result<()> {
+    let cutoff = current_month() - Months::new(retention_months);
+    let expired = database
+        .transaction_partitions()
+        .await?
+        .into_iter()
+        .filter(|name| partition_month(name).map_or(false, |month| month < cutoff));
+    for partition in expired {
+        if let Some((client, bucket)) = archive {
+            let rows = archive_partition(database, config, client, bucket, &partition).await?;
+            info!(
+                "Archived {} transactions of {} to s3://{}/{}",
+                rows, partition, bucket, config.prefix
+            );
+        }
+        database.drop_transaction_partition(&partition).await?;
+        info!("Dropped transaction partition {}", partition);
+    }
+    Ok(())
+}
+
+/// Writes a partition to `<prefix><partition>.parquet`. The file is built
+/// in memory, reading `batch_size` rows at a time, and uploaded at once.
+async fn archive_partition(
+    database: &Database,
+    config: &TransactionArchiveConfig,
+    client: &AwsClient,
+    bucket: &str,
+    partition: &str,
+) -> Result<usize> {
+    let schema = archive_schema();
+    let properties = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+    let mut writer = ArrowWriter::try_new(Vec::new(), schema.clone(), Some(properties))?;
+
+    let mut rows = 0;
+    let mut after = None;
+    loop {
+        let records = database
+            .transaction_partition_page(partition, after, config.batch_size)
+            .await?;
+        let Some(last) = records.last() else {
+            break;
+        };
+        after = Some((last.created_at, last.signature.clone()));
+        rows += records.len();
+        writer.write(&record_batch(&schema, &records)?)?;
+        if (records.len() as i64) < config.batch_size {
+            break;
+        }
+    }
+
+    let body = writer.into_inner()?;
+    let key = format!("{}{}.parquet", config.prefix, partition);
+    client
+        .put_object(bucket, &key, "application/vnd.apache.parquet", body)
+        .await
+        .map_err(|e| anyhow!("Failed to upload {}: {}", key, e))?;
+    Ok(rows)
+}
+
+fn archive_schema() -> SchemaRef {
+    let timestamp = DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()));
+    Arc::new(Schema::new(vec![
+        Field::new("signature", DataType::Utf8, false),
+        Field::new("slot", DataType::Int64, true),
+        Field::new("status", DataType::Utf8, false),
+        Field::new(
+            "accounts",
+            DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+            false,
+        ),
+        Field::new("amount", DataType::Int64, true),
+        Field::new("fee", DataType::Int64, true),
+        Field::new("block_time", timestamp.clone(), true),
+        Field::new("memo", DataType::Utf8, true),
+        Field::new("replaced_by", DataType::Utf8, true),
+        Field::new("created_at", timestamp, false),
+    ]))
+}
+
+fn record_batch(schema: &SchemaRef, records: &[TransactionRecord]) -> Result<RecordBatch> {
+    let mut accounts = ListBuilder::new(StringBuilder::new());
+    for record in records {
+        for account in &record.accounts {
+            accounts.values().append_value(account);
+        }
+        accounts.append(true);
+    }
+    let timestamps = |at: fn(&TransactionRecord) -> Option<DateTime<Utc>>| {
+        TimestampMicrosecondArray::from(
+            records
+                .iter()
+                .map(|record| at(record).map(|at| at.timestamp_micros()))
+                .collect::<Vec<_>>(),
+        )
+        .with_timezone("UTC")
+    };
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(
+            records.iter().map(|r| r.signature.as_str()),
+        )),
+        Arc::new(Int64Array::from(records.iter().map(|r| r.slot).collect::<Vec<_>>())),
+        Arc::new(StringArray::from_iter_values(records.iter().map(|r| r.status.as_str()))),
+        Arc::new(accounts.finish()),
+        Arc::new(Int64Array::from(records.iter().map(|r| r.amount).collect::<Vec<_>>())),
+        Arc::new(Int64Array::from(records.iter().map(|r| r.fee).collect::<Vec<_>>())),
+        Arc::new(timestamps(|r| r.block_time)),
+        Arc::new(StringArray::from(
+            records.iter().map(|r| r.memo.as_deref()).collect::<Vec<_>>(),
+        )),
+        Arc::new(StringArray::from(
+            records.iter().map(|r| r.replaced_by.as_deref()).collect::<Vec<_>>(),
+        )),
+        Arc::new(timestamps(|r| Some(r.created_at))),
+    ];
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
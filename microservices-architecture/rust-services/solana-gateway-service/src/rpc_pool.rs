@@ -0,0 +1,363 @@
+use crate::config::{RpcEndpointConfig, RpcFailoverConfig, RpcHttpConfig, SlotLagConfig};
+use crate::metrics::Metrics;
+use crate::rpc_sender::TaggedHttpSender;
+use crate::solana_client::SolanaClient;
+use serde::Serialize;
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    nonblocking::rpc_client::RpcClient,
+    rpc_client::RpcClientConfig,
+    rpc_request::RpcError,
+};
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+// JSON-RPC error code returned by nodes that are behind or otherwise unhealthy.
+const NODE_UNHEALTHY_CODE: i64 = -32005;
+
+// JSON-RPC error codes for requests the node can't serve yet because it
+// hasn't caught up: block not available, and minContextSlot not reached.
+const BLOCK_NOT_AVAILABLE_CODE: i64 = -32004;
+const MIN_CONTEXT_SLOT_NOT_REACHED_CODE: i64 = -32016;
+
+// How often circuit breaker states are published as metrics.
+const CIRCUIT_METRICS_INTERVAL: Duration = Duration::from_secs(15);
+
+// Smoothing factor for the latency and error-rate moving averages.
+const EWMA_ALPHA: f64 = 0.2;
+
+pub struct RpcEndpoint {
+    pub url: String,
+    pub weight: u32,
+    pub client: Arc<RpcClient>,
+    health: Mutex<EndpointHealth>,
+}
+
+#[derive(Default)]
+struct EndpointHealth {
+    latency_ms: f64,
+    error_rate: f64,
+    consecutive_failures: u32,
+    quarantines: u32,
+    quarantined_until: Option<Instant>,
+    slot_lag: Option<u64>,
+    degraded: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    Closed,
+    /// Quarantine has ended but the endpoint hasn't succeeded since; one
+    /// more failure quarantines it again.
+    HalfOpen,
+    /// Quarantined.
+    Open,
+}
+
+impl CircuitState {
+    /// Value of the `rpc_circuit_state` gauge.
+    pub fn gauge_value(self) -> i64 {
+        match self {
+            CircuitState::Closed => 0,
+            CircuitState::HalfOpen => 1,
+            CircuitState::Open => 2,
+        }
+    }
+}
+
+/// Failover (circuit-breaker) state of one endpoint.
+#[derive(Serialize, ToSchema)]
+pub struct EndpointStatus {
+    /// Without its query string, which often carries a provider API key.
+    pub url: String,
+    pub weight: u32,
+    /// Moving average of successful call latency.
+    pub latency_ms: f64,
+    /// Moving average of the failure rate, from 0 to 1.
+    pub error_rate: f64,
+    pub consecutive_failures: u32,
+    pub circuit: CircuitState,
+    /// Quarantines since the endpoint last succeeded.
+    pub quarantines: u32,
+    /// Seconds until a quarantined endpoint is tried again.
+    pub quarantined_for_secs: Option<u64>,
+    /// Slots behind the reference node at the last lag check.
+    pub slot_lag: Option<u64>,
+    /// Lagging too far behind; used only when no other endpoint is available.
+    pub degraded: bool,
+}
+
+pub struct RpcPool {
+    endpoints: Vec<RpcEndpoint>,
+    failover: RpcFailoverConfig,
+}
+
+impl RpcEndpoint {
+    fn new(config: &RpcEndpointConfig, timeout: Duration, http: &RpcHttpConfig) -> Self {
+        Self {
+            url: config.url.clone(),
+            weight: config.weight.max(1),
+            // A timed-out attempt surfaces as a reqwest error, which counts as
+            // an endpoint failure and triggers failover.
+            client: Arc::new(RpcClient::new_sender(
+                TaggedHttpSender::new(config.url.clone(), timeout, http),
+                RpcClientConfig::with_commitment(CommitmentConfig::confirmed()),
+            )),
+            health: Mutex::new(EndpointHealth::default()),
+        }
+    }
+
+    fn score(&self, now: Instant) -> Option<f64> {
+        let health = self.health.lock().unwrap();
+        if health.quarantined_until.map_or(false, |until| until > now) {
+            return None;
+        }
+        Some(self.weight as f64 * (1.0 - health.error_rate) / (1.0 + health.latency_ms / 100.0))
+    }
+
+    fn quarantined_until(&self) -> Option<Instant> {
+        self.health.lock().unwrap().quarantined_until
+    }
+
+    fn is_degraded(&self) -> bool {
+        self.health.lock().unwrap().degraded
+    }
+}
+
+impl RpcPool {
+    pub fn new(
+        endpoints: &[RpcEndpointConfig],
+        failover: RpcFailoverConfig,
+        timeout: Duration,
+        http: &RpcHttpConfig,
+    ) -> Self {
+        Self {
+            endpoints: endpoints
+                .iter()
+                .map(|endpoint| RpcEndpoint::new(endpoint, timeout, http))
+                .collect(),
+            failover,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    /// Picks the healthiest endpoint not listed in `exclude`, preferring
+    /// ones that keep up with the cluster. When every candidate is
+    /// quarantined, the one whose quarantine ends first is used.
+    pub fn select(&self, exclude: &[usize]) -> Option<usize> {
+        let now = Instant::now();
+        let candidates = || (0..self.endpoints.len()).filter(|i| !exclude.contains(i));
+        let healthiest = |allow_degraded: bool| {
+            candidates()
+                .filter(|&i| allow_degraded || !self.endpoints[i].is_degraded())
+                .filter_map(|i| self.endpoints[i].score(now).map(|score| (i, score)))
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(i, _)| i)
+        };
+
+        healthiest(false)
+            .or_else(|| healthiest(true))
+            .or_else(|| candidates().min_by_key(|&i| self.endpoints[i].quarantined_until()))
+    }
+
+    pub fn status(&self) -> Vec<EndpointStatus> {
+        let now = Instant::now();
+        self.endpoints
+            .iter()
+            .map(|endpoint| {
+                let health = endpoint.health.lock().unwrap();
+                let circuit = match health.quarantined_until {
+                    Some(until) if until > now => CircuitState::Open,
+                    _ if health.quarantines > 0 => CircuitState::HalfOpen,
+                    _ => CircuitState::Closed,
+                };
+                EndpointStatus {
+                    url: endpoint.url.split('?').next().unwrap_or_default().to_string(),
+                    weight: endpoint.weight,
+                    latency_ms: health.latency_ms,
+                    error_rate: health.error_rate,
+                    consecutive_failures: health.consecutive_failures,
+                    circuit,
+                    quarantines: health.quarantines,
+                    quarantined_for_secs: health
+                        .quarantined_until
+                        .filter(|until| *until > now)
+                        .map(|until| (until - now).as_secs()),
+                    slot_lag: health.slot_lag,
+                    degraded: health.degraded,
+                }
+            })
+            .collect()
+    }
+
+    pub fn endpoint(&self, index: usize) -> &RpcEndpoint {
+        &self.endpoints[index]
+    }
+
+    pub fn record_success(&self, index: usize, elapsed: Duration) {
+        let mut health = self.endpoints[index].health.lock().unwrap();
+        let latency_ms = elapsed.as_secs_f64() * 1000.0;
+        health.latency_ms = if health.latency_ms == 0.0 {
+            latency_ms
+        } else {
+            EWMA_ALPHA * latency_ms + (1.0 - EWMA_ALPHA) * health.latency_ms
+        };
+        health.error_rate *= 1.0 - EWMA_ALPHA;
+        health.consecutive_failures = 0;
+        health.quarantines = 0;
+        health.quarantined_until = None;
+    }
+
+    /// Asks every endpoint for its slot and records how far it trails
+    /// `reference_slot`. Endpoints that don't answer keep their last state;
+    /// the circuit breaker deals with those.
+    pub async fn check_slot_lag(&self, reference_slot: u64, max_lag: u64) {
+        let slots = futures::future::join_all(self.endpoints.iter().map(|endpoint| endpoint.client.get_slot())).await;
+        for (endpoint, slot) in self.endpoints.iter().zip(slots) {
+            let slot = match slot {
+                Ok(slot) => slot,
+                Err(e) => {
+                    tracing::warn!("Failed to get slot from RPC endpoint {}: {}", endpoint.url, e);
+                    continue;
+                }
+            };
+            let lag = reference_slot.saturating_sub(slot);
+            let degraded = lag > max_lag;
+
+            let mut health = endpoint.health.lock().unwrap();
+            if degraded && !health.degraded {
+                tracing::warn!("RPC endpoint {} is {} slots behind, marking it degraded", endpoint.url, lag);
+            } else if !degraded && health.degraded {
+                tracing::info!("RPC endpoint {} caught up ({} slots behind)", endpoint.url, lag);
+            }
+            health.slot_lag = Some(lag);
+            health.degraded = degraded;
+        }
+    }
+
+    pub fn record_failure(&self, index: usize) {
+        let endpoint = &self.endpoints[index];
+        let mut health = endpoint.health.lock().unwrap();
+        health.error_rate = EWMA_ALPHA + (1.0 - EWMA_ALPHA) * health.error_rate;
+        health.consecutive_failures += 1;
+
+        if health.consecutive_failures >= self.failover.failure_threshold {
+            let backoff = self
+                .failover
+                .quarantine_base_secs
+                .saturating_mul(1 << health.quarantines.min(16))
+                .min(self.failover.quarantine_max_secs);
+            health.quarantines += 1;
+            health.consecutive_failures = 0;
+            health.quarantined_until = Some(Instant::now() + Duration::from_secs(backoff));
+            tracing::warn!(
+                "Quarantining RPC endpoint {} for {}s after repeated failures",
+                endpoint.url,
+                backoff
+            );
+        }
+    }
+}
+
+/// Periodically measures how far each endpoint trails the reference node,
+/// publishing the lag as metrics.
+pub fn spawn_slot_lag_monitor(
+    client: Arc<SolanaClient>,
+    metrics: Arc<Metrics>,
+    cluster: String,
+    config: SlotLagConfig,
+    timeout: Duration,
+) {
+    let Some(reference_url) = config.reference_url else {
+        return;
+    };
+    let reference = RpcClient::new_with_timeout_and_commitment(reference_url, timeout, CommitmentConfig::confirmed());
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs.max(1)));
+        loop {
+            interval.tick().await;
+            let reference_slot = match reference.get_slot().await {
+                Ok(slot) => slot,
+                Err(e) => {
+                    tracing::warn!("Failed to get slot from reference RPC node: {}", e);
+                    continue;
+                }
+            };
+            client.check_slot_lag(reference_slot, config.max_lag_slots).await;
+            for endpoint in client.endpoint_status() {
+                if let Some(lag) = endpoint.slot_lag {
+                    metrics.observe_slot_lag(&cluster, &endpoint.url, lag, endpoint.degraded);
+                }
+            }
+        }
+    });
+}
+
+/// Periodically publishes every endpoint's circuit breaker state. Sampled
+/// rather than set on each call, so an open circuit moves to half-open in
+/// the metrics once its quarantine ends even if no call has been made.
+pub fn spawn_circuit_metrics(client: Arc<SolanaClient>, metrics: Arc<Metrics>, cluster: String) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CIRCUIT_METRICS_INTERVAL);
+        loop {
+            interval.tick().await;
+            for endpoint in client.endpoint_status() {
+                metrics.observe_circuit_state(&cluster, &endpoint.url, endpoint.circuit.gauge_value());
+            }
+        }
+    });
+}
+
+/// Classifies a failed call for the `rpc_errors_total` metric, separating
+/// provider problems (`timeout`, `rate_limited`, `node_behind`,
+/// `unavailable`, `invalid_response`) from ones caused by the request
+/// (`account_not_found`, `rejected`, `other`).
+pub fn error_kind(err: &ClientError) -> &'static str {
+    match err.kind() {
+        ClientErrorKind::Reqwest(err) if err.is_timeout() => "timeout",
+        ClientErrorKind::Io(err) if err.kind() == std::io::ErrorKind::TimedOut => "timeout",
+        ClientErrorKind::Reqwest(err) if err.status().map(|status| status.as_u16()) == Some(429) => "rate_limited",
+        ClientErrorKind::Reqwest(err) if err.is_decode() => "invalid_response",
+        ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_) => "unavailable",
+        ClientErrorKind::SerdeJson(_) | ClientErrorKind::RpcError(RpcError::ParseError(_)) => "invalid_response",
+        ClientErrorKind::RpcError(RpcError::RpcRequestError(_)) => "unavailable",
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => match *code {
+            NODE_UNHEALTHY_CODE | BLOCK_NOT_AVAILABLE_CODE | MIN_CONTEXT_SLOT_NOT_REACHED_CODE => "node_behind",
+            _ => "rejected",
+        },
+        ClientErrorKind::RpcError(RpcError::ForUser(message)) if message.starts_with("AccountNotFound") => {
+            "account_not_found"
+        }
+        ClientErrorKind::TransactionError(_) => "rejected",
+        _ => "other",
+    }
+}
+
+/// Whether the provider rejected the call with HTTP 429. The node is healthy
+/// but throttling us, so it's skipped for this call without being quarantined.
+pub fn is_rate_limited(err: &ClientError) -> bool {
+    match err.kind() {
+        ClientErrorKind::Reqwest(err) => err.status().map(|status| status.as_u16()) == Some(429),
+        _ => false,
+    }
+}
+
+/// Whether an error says something about the endpoint rather than the request,
+/// in which case the call should be retried against another node.
+pub fn is_endpoint_failure(err: &ClientError) -> bool {
+    match err.kind() {
+        ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_) => true,
+        ClientErrorKind::RpcError(RpcError::RpcRequestError(_)) => true,
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+            *code == NODE_UNHEALTHY_CODE
+        }
+        _ => false,
+    }
+}
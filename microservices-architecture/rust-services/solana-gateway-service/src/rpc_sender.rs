@@ -0,0 +1,116 @@
+use crate::config::RpcHttpConfig;
+use crate::request_id;
+use axum::async_trait;
+use serde_json::Value;
+use solana_client::{
+    client_error::{ClientError, Result as ClientResult},
+    rpc_custom_error::{
+        NodeUnhealthyErrorData, JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+        JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE,
+    },
+    rpc_request::{RpcError, RpcRequest, RpcResponseErrorData},
+    rpc_sender::{RpcSender, RpcTransportStats},
+};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+/// JSON-RPC over HTTP, like the Solana client's own sender, but tagging
+/// every call with the ID of the API request it serves in `X-Request-Id`.
+/// Rate limiting surfaces as a 429 error for the pool to handle rather than
+/// being retried here.
+pub struct TaggedHttpSender {
+    http: reqwest::Client,
+    url: String,
+    next_id: AtomicU64,
+    stats: Mutex<RpcTransportStats>,
+    // Caps in-flight requests when `max_connections_per_host` is set.
+    slots: Option<Semaphore>,
+}
+
+impl TaggedHttpSender {
+    pub fn new(url: String, timeout: Duration, config: &RpcHttpConfig) -> Self {
+        let mut http = reqwest::Client::builder()
+            .timeout(timeout)
+            .connect_timeout(Duration::from_millis(config.connect_timeout_ms))
+            .pool_max_idle_per_host(config.pool_max_idle_per_host)
+            .pool_idle_timeout(Duration::from_secs(config.pool_idle_timeout_secs))
+            .tcp_keepalive(config.tcp_keepalive_secs.map(Duration::from_secs))
+            .tcp_nodelay(config.tcp_nodelay)
+            .http2_adaptive_window(true);
+        if let Some(interval) = config.http2_keep_alive_secs {
+            http = http
+                .http2_keep_alive_interval(Duration::from_secs(interval))
+                .http2_keep_alive_while_idle(true);
+        }
+        if config.http2_prior_knowledge {
+            http = http.http2_prior_knowledge();
+        }
+        Self {
+            http: http.build().expect("default TLS backend is available"),
+            url,
+            next_id: AtomicU64::new(0),
+            stats: Mutex::new(RpcTransportStats::default()),
+            slots: config.max_connections_per_host.map(|max| Semaphore::new(max.max(1))),
+        }
+    }
+}
+
+#[async_trait]
+impl RpcSender for TaggedHttpSender {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let body = request.build_request_json(id, params);
+
+        let _slot = match &self.slots {
+            Some(slots) => Some(slots.acquire().await.expect("RPC slots are never closed")),
+            None => None,
+        };
+        let started = Instant::now();
+        let response = request_id::tag(self.http.post(&self.url))
+            .json(&body)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        {
+            let mut stats = self.stats.lock().unwrap();
+            stats.request_count += 1;
+            stats.elapsed_time += started.elapsed();
+        }
+
+        let mut json: Value = response?.json().await?;
+        if let Some(error) = json.get("error") {
+            return Err(response_error(error));
+        }
+        Ok(json["result"].take())
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.stats.lock().unwrap().clone()
+    }
+
+    fn url(&self) -> String {
+        self.url.clone()
+    }
+}
+
+/// Keeps the error data the Solana client interprets: the simulation
+/// result of a failed preflight and how far an unhealthy node lags.
+fn response_error(error: &Value) -> ClientError {
+    let code = error["code"].as_i64().unwrap_or_default();
+    let message = error["message"].as_str().unwrap_or_default().to_string();
+    let data = error["data"].clone();
+    let data = match code {
+        JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE => serde_json::from_value(data)
+            .map(RpcResponseErrorData::SendTransactionPreflightFailure)
+            .unwrap_or(RpcResponseErrorData::Empty),
+        JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY => serde_json::from_value::<NodeUnhealthyErrorData>(data)
+            .map(|data| RpcResponseErrorData::NodeUnhealthy {
+                num_slots_behind: data.num_slots_behind,
+            })
+            .unwrap_or(RpcResponseErrorData::Empty),
+        _ => RpcResponseErrorData::Empty,
+    };
+    RpcError::RpcResponseError { code, message, data }.into()
+}
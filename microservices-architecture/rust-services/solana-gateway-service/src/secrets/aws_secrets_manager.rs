@@ -0,0 +1,50 @@
+//! AWS Secrets Manager secrets, read through the JSON API.
+
+use super::{split_reference, SecretProvider};
+use crate::aws::AwsClient;
+use crate::config::AwsSecretsManagerConfig;
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+
+pub struct AwsSecretsManagerProvider {
+    client: AwsClient,
+}
+
+impl AwsSecretsManagerProvider {
+    pub fn new(config: &AwsSecretsManagerConfig, timeout_ms: u64) -> Result<Self> {
+        let client = AwsClient::new(
+            "secretsmanager",
+            "secretsmanager",
+            config.region.as_deref(),
+            config.endpoint.as_deref(),
+            timeout_ms,
+        )?;
+        Ok(Self { client })
+    }
+}
+
+#[axum::async_trait]
+impl SecretProvider for AwsSecretsManagerProvider {
+    /// Without a field the whole secret string is used; with one the secret
+    /// must be a JSON object, as written by the console's key/value editor.
+    async fn fetch(&self, reference: &str) -> Result<String> {
+        let (secret_id, field) = split_reference(reference)?;
+        let response = self
+            .client
+            .call("GetSecretValue", json!({ "SecretId": secret_id }))
+            .await?;
+        let secret = response["SecretString"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Secret {} has no string value", secret_id))?;
+        let Some(field) = field else {
+            return Ok(secret.to_string());
+        };
+        let fields: Value =
+            serde_json::from_str(secret).map_err(|_| anyhow!("Secret {} is not a JSON object", secret_id))?;
+        match &fields[field] {
+            Value::String(value) => Ok(value.clone()),
+            Value::Null => Err(anyhow!("Secret {} has no field {}", secret_id, field)),
+            value => Ok(value.to_string()),
+        }
+    }
+}
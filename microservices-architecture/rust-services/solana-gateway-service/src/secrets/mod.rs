@@ -0,0 +1,107 @@
+//! Settings fetched from a secret store instead of the config file or
+//! environment. `Config::load` resolves them at startup and `spawn_refresh`
+//! picks up rotated values.
+
+pub mod aws_secrets_manager;
+pub mod vault;
+
+use crate::config::{SecretsBackend, SecretsConfig};
+use crate::database::Database;
+use anyhow::{anyhow, Context, Result};
+use futures::future::try_join_all;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Reads secrets from one store.
+#[axum::async_trait]
+pub trait SecretProvider: Send + Sync {
+    /// The value `reference` names: a secret path and, after `#`, the field
+    /// within it.
+    async fn fetch(&self, reference: &str) -> Result<String>;
+}
+
+pub fn from_config(config: &SecretsConfig) -> Result<Option<Arc<dyn SecretProvider>>> {
+    let provider: Arc<dyn SecretProvider> = match config.backend {
+        SecretsBackend::Disabled => return Ok(None),
+        SecretsBackend::Vault => Arc::new(vault::VaultProvider::new(&config.vault, config.timeout_ms)?),
+        SecretsBackend::AwsSecretsManager => Arc::new(aws_secrets_manager::AwsSecretsManagerProvider::new(
+            &config.aws_secrets_manager,
+            config.timeout_ms,
+        )?),
+    };
+    Ok(Some(provider))
+}
+
+/// Fetches every configured setting, keyed by setting name.
+pub async fn resolve(provider: &dyn SecretProvider, config: &SecretsConfig) -> Result<HashMap<String, String>> {
+    let values = try_join_all(config.settings.iter().map(|(setting, reference)| async move {
+        let value = provider
+            .fetch(reference)
+            .await
+            .with_context(|| format!("Failed to fetch secret for {}", setting))?;
+        Ok::<_, anyhow::Error>((setting.clone(), value))
+    }))
+    .await?;
+    Ok(values.into_iter().collect())
+}
+
+/// Splits a reference into its path and optional field.
+fn split_reference(reference: &str) -> Result<(&str, Option<&str>)> {
+    match reference.split_once('#') {
+        Some((path, field)) if !path.is_empty() && !field.is_empty() => Ok((path, Some(field))),
+        Some(_) => Err(anyhow!("Secret reference {} is not <path>#<field>", reference)),
+        None => Ok((reference, None)),
+    }
+}
+
+/// Re-fetches the configured settings periodically. New database
+/// connections use a rotated `database_url`; other settings are only read
+/// at startup, so changes to them are logged. `databases` pairs each
+/// cluster's database with the setting holding its URL.
+pub fn spawn_refresh(config: SecretsConfig, databases: Vec<(String, Arc<Database>)>) {
+    if config.refresh_secs == 0 || config.settings.is_empty() {
+        return;
+    }
+    let provider = match from_config(&config) {
+        Ok(Some(provider)) => provider,
+        Ok(None) => return,
+        Err(e) => {
+            warn!("Secret refresh disabled: {}", e);
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        let mut known: Option<HashMap<String, String>> = None;
+        let mut interval = tokio::time::interval(Duration::from_secs(config.refresh_secs));
+        loop {
+            interval.tick().await;
+            let values = match resolve(provider.as_ref(), &config).await {
+                Ok(values) => values,
+                Err(e) => {
+                    warn!("Failed to refresh secrets: {:#}", e);
+                    continue;
+                }
+            };
+            let Some(previous) = known.replace(values.clone()) else {
+                continue;
+            };
+            for (setting, value) in &values {
+                if previous.get(setting) == Some(value) {
+                    continue;
+                }
+                match databases
+                    .iter()
+                    .find(|(database_setting, _)| database_setting == setting)
+                {
+                    Some((_, database)) => match database.set_url(value) {
+                        Ok(()) => info!("Database credentials for {} rotated", setting),
+                        Err(e) => warn!("Rotated {} is not a valid database URL: {}", setting, e),
+                    },
+                    None => warn!("Secret for {} changed; restart to apply it", setting),
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,67 @@
+//! HashiCorp Vault KV version 2 secrets, read through the HTTP API.
+
+use super::{split_reference, SecretProvider};
+use crate::config::VaultConfig;
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::time::Duration;
+
+pub struct VaultProvider {
+    http: reqwest::Client,
+    address: String,
+    mount: String,
+    token: String,
+    namespace: Option<String>,
+}
+
+impl VaultProvider {
+    pub fn new(config: &VaultConfig, timeout_ms: u64) -> Result<Self> {
+        let address = config
+            .address
+            .clone()
+            .ok_or_else(|| anyhow!("secrets.vault.address is required for the vault backend"))?;
+        let token = config
+            .token
+            .clone()
+            .or_else(|| std::env::var("VAULT_TOKEN").ok())
+            .ok_or_else(|| anyhow!("secrets.vault.token or VAULT_TOKEN is required for the vault backend"))?;
+        Ok(Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_millis(timeout_ms))
+                .build()?,
+            address: address.trim_end_matches('/').to_string(),
+            mount: config.mount.trim_matches('/').to_string(),
+            token,
+            namespace: config.namespace.clone(),
+        })
+    }
+}
+
+#[axum::async_trait]
+impl SecretProvider for VaultProvider {
+    async fn fetch(&self, reference: &str) -> Result<String> {
+        let (path, field) = split_reference(reference)?;
+        let field = field.ok_or_else(|| anyhow!("Vault secret reference {} names no field", reference))?;
+        let url = format!("{}/v1/{}/data/{}", self.address, self.mount, path.trim_matches('/'));
+        let mut request = self.http.get(url).header("X-Vault-Token", &self.token);
+        if let Some(namespace) = &self.namespace {
+            request = request.header("X-Vault-Namespace", namespace);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        let body: Value = response.json().await?;
+        if !status.is_success() {
+            return Err(anyhow!(
+                "Vault read of {} failed with {}: {}",
+                path,
+                status,
+                body["errors"]
+            ));
+        }
+        match &body["data"]["data"][field] {
+            Value::String(value) => Ok(value.clone()),
+            Value::Null => Err(anyhow!("Vault secret {} has no field {}", path, field)),
+            value => Ok(value.to_string()),
+        }
+    }
+}
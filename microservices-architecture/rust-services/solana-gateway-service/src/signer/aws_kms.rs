@@ -0,0 +1,69 @@
+//! AWS KMS ed25519 keys, called through the KMS JSON API.
+
+use super::{ed25519_public_key, signature_from_bytes, verified, Signer};
+use crate::aws::AwsClient;
+use crate::config::AwsKmsConfig;
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde_json::{json, Value};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+pub struct AwsKmsSigner {
+    client: AwsClient,
+    key_id: String,
+    public_key: Pubkey,
+}
+
+impl AwsKmsSigner {
+    pub async fn new(config: &AwsKmsConfig, timeout_ms: u64) -> Result<Self> {
+        let key_id = config
+            .key_id
+            .clone()
+            .ok_or_else(|| anyhow!("signer.aws_kms.key_id is required for the aws_kms backend"))?;
+        let client = AwsClient::new(
+            "kms",
+            "TrentService",
+            config.region.as_deref(),
+            config.endpoint.as_deref(),
+            timeout_ms,
+        )?;
+
+        let response = client.call("GetPublicKey", json!({ "KeyId": key_id })).await?;
+        let spki = BASE64.decode(field(&response, "PublicKey")?)?;
+        Ok(Self {
+            client,
+            key_id,
+            public_key: ed25519_public_key(&spki)?,
+        })
+    }
+}
+
+#[axum::async_trait]
+impl Signer for AwsKmsSigner {
+    fn public_key(&self) -> Pubkey {
+        self.public_key
+    }
+
+    async fn sign(&self, message: &[u8]) -> Result<Signature> {
+        let response = self
+            .client
+            .call(
+                "Sign",
+                json!({
+                    "KeyId": self.key_id,
+                    "Message": BASE64.encode(message),
+                    "MessageType": "RAW",
+                    "SigningAlgorithm": "ED25519_SHA_512",
+                }),
+            )
+            .await?;
+        let signature = signature_from_bytes(&BASE64.decode(field(&response, "Signature")?)?)?;
+        verified(&self.public_key, message, signature)
+    }
+}
+
+fn field<'a>(response: &'a Value, name: &str) -> Result<&'a str> {
+    response[name]
+        .as_str()
+        .ok_or_else(|| anyhow!("KMS response has no {}", name))
+}
@@ -0,0 +1,113 @@
+//! Google Cloud KMS ed25519 key versions, called through the REST API.
+
+use super::{ed25519_public_key, signature_from_bytes, verified, Signer};
+use crate::config::GcpKmsConfig;
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+const API_URL: &str = "https://cloudkms.googleapis.com/v1";
+const METADATA_TOKEN_URL: &str =
+    "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+
+// Metadata tokens are refreshed this long before they expire.
+const TOKEN_EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+#[derive(Deserialize)]
+struct MetadataToken {
+    access_token: String,
+    expires_in: u64,
+}
+
+pub struct GcpKmsSigner {
+    http: reqwest::Client,
+    key_version: String,
+    /// A configured token, used as is.
+    access_token: Option<String>,
+    /// The metadata server token and when it expires.
+    cached_token: Mutex<Option<(String, Instant)>>,
+    public_key: Pubkey,
+}
+
+impl GcpKmsSigner {
+    pub async fn new(config: &GcpKmsConfig, timeout_ms: u64) -> Result<Self> {
+        let key_version = config
+            .key_version
+            .clone()
+            .ok_or_else(|| anyhow!("signer.gcp_kms.key_version is required for the gcp_kms backend"))?;
+
+        let mut signer = Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_millis(timeout_ms))
+                .build()?,
+            key_version,
+            access_token: config.access_token.clone(),
+            cached_token: Mutex::new(None),
+            public_key: Pubkey::default(),
+        };
+        let url = format!("{}/{}/publicKey", API_URL, signer.key_version);
+        let response = signer.send(signer.http.get(url)).await?;
+        let pem = response["pem"]
+            .as_str()
+            .ok_or_else(|| anyhow!("KMS public key response has no pem"))?;
+        let spki: String = pem.lines().filter(|line| !line.starts_with("-----")).collect();
+        signer.public_key = ed25519_public_key(&BASE64.decode(spki)?)?;
+        Ok(signer)
+    }
+
+    async fn token(&self) -> Result<String> {
+        if let Some(token) = &self.access_token {
+            return Ok(token.clone());
+        }
+        let mut cached = self.cached_token.lock().await;
+        if let Some((token, expires_at)) = cached.as_ref() {
+            if Instant::now() + TOKEN_EXPIRY_MARGIN < *expires_at {
+                return Ok(token.clone());
+            }
+        }
+        let token: MetadataToken = self
+            .http
+            .get(METADATA_TOKEN_URL)
+            .header("Metadata-Flavor", "Google")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let expires_at = Instant::now() + Duration::from_secs(token.expires_in);
+        *cached = Some((token.access_token.clone(), expires_at));
+        Ok(token.access_token)
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<Value> {
+        let response = request.bearer_auth(self.token().await?).send().await?;
+        let status = response.status();
+        let body: Value = response.json().await?;
+        if !status.is_success() {
+            return Err(anyhow!("KMS request failed with {}: {}", status, body));
+        }
+        Ok(body)
+    }
+}
+
+#[axum::async_trait]
+impl Signer for GcpKmsSigner {
+    fn public_key(&self) -> Pubkey {
+        self.public_key
+    }
+
+    async fn sign(&self, message: &[u8]) -> Result<Signature> {
+        let url = format!("{}/{}:asymmetricSign", API_URL, self.key_version);
+        let request = self.http.post(url).json(&json!({ "data": BASE64.encode(message) }));
+        let response = self.send(request).await?;
+        let signature = response["signature"]
+            .as_str()
+            .ok_or_else(|| anyhow!("KMS sign response has no signature"))?;
+        let signature = signature_from_bytes(&BASE64.decode(signature)?)?;
+        verified(&self.public_key, message, signature)
+    }
+}
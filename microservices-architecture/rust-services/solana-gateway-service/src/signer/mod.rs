@@ -0,0 +1,111 @@
+pub mod aws_kms;
+pub mod gcp_kms;
+
+use crate::config::{SignerBackend, SignerConfig};
+use anyhow::{anyhow, Context, Result};
+use futures::future::try_join_all;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signature},
+    transaction::Transaction,
+};
+use std::sync::Arc;
+use tracing::info;
+
+/// Produces ed25519 signatures for one key. Remote backends hold the key
+/// outside the process, so signing is async and may fail.
+#[axum::async_trait]
+pub trait Signer: Send + Sync {
+    fn public_key(&self) -> Pubkey;
+
+    async fn sign(&self, message: &[u8]) -> Result<Signature>;
+}
+
+#[axum::async_trait]
+impl Signer for Keypair {
+    fn public_key(&self) -> Pubkey {
+        solana_sdk::signer::Signer::pubkey(self)
+    }
+
+    async fn sign(&self, message: &[u8]) -> Result<Signature> {
+        Ok(solana_sdk::signer::Signer::sign_message(self, message))
+    }
+}
+
+/// The configured gateway signer. KMS backends fetch their public key here,
+/// so a misconfigured key fails startup rather than the first transfer.
+pub async fn from_config(config: &SignerConfig) -> Result<Option<Arc<dyn Signer>>> {
+    let signer: Arc<dyn Signer> = match config.backend {
+        SignerBackend::Disabled => return Ok(None),
+        SignerBackend::KeypairFile => match (&config.keypair, &config.keypair_path) {
+            (Some(keypair), _) => {
+                let bytes: Vec<u8> =
+                    serde_json::from_str(keypair).context("signer.keypair must be a JSON array of bytes")?;
+                Arc::new(Keypair::from_bytes(&bytes).map_err(|e| anyhow!("Invalid signer.keypair: {}", e))?)
+            }
+            (None, Some(path)) => {
+                let keypair = read_keypair_file(path)
+                    .map_err(|e| anyhow!("{}", e))
+                    .with_context(|| format!("Failed to read keypair {}", path))?;
+                Arc::new(keypair)
+            }
+            (None, None) => {
+                return Err(anyhow!(
+                    "signer.keypair_path or signer.keypair is required for the keypair_file backend"
+                ))
+            }
+        },
+        SignerBackend::AwsKms => Arc::new(aws_kms::AwsKmsSigner::new(&config.aws_kms, config.timeout_ms).await?),
+        SignerBackend::GcpKms => Arc::new(gcp_kms::GcpKmsSigner::new(&config.gcp_kms, config.timeout_ms).await?),
+    };
+    info!("Gateway signer is {}", signer.public_key());
+    Ok(Some(signer))
+}
+
+/// Signs `transaction` for its current blockhash, requesting all signatures
+/// at once. Every signer must be a required signer of the message and every
+/// required signer must be given.
+pub async fn sign_transaction(transaction: &mut Transaction, signers: &[&dyn Signer]) -> Result<()> {
+    let message = transaction.message_data();
+    let signatures = try_join_all(signers.iter().map(|signer| signer.sign(&message))).await?;
+
+    let required = &transaction.message.account_keys[..transaction.message.header.num_required_signatures as usize];
+    let mut placed = vec![Signature::default(); required.len()];
+    for (signer, signature) in signers.iter().zip(signatures) {
+        let key = signer.public_key();
+        let index = required
+            .iter()
+            .position(|required| *required == key)
+            .ok_or_else(|| anyhow!("{} is not a signer of the transaction", key))?;
+        placed[index] = signature;
+    }
+    if let Some(index) = placed.iter().position(|signature| *signature == Signature::default()) {
+        return Err(anyhow!("Transaction is missing the signature of {}", required[index]));
+    }
+    transaction.signatures = placed;
+    Ok(())
+}
+
+/// Rejects a signature a remote backend returned that doesn't verify, which
+/// means it signed with a different key than it reported.
+fn verified(key: &Pubkey, message: &[u8], signature: Signature) -> Result<Signature> {
+    if !signature.verify(key.as_ref(), message) {
+        return Err(anyhow!("KMS returned a signature that doesn't verify against {}", key));
+    }
+    Ok(signature)
+}
+
+/// The raw key at the end of a DER `SubjectPublicKeyInfo`, the form both
+/// KMS providers return public keys in.
+fn ed25519_public_key(spki: &[u8]) -> Result<Pubkey> {
+    // An ed25519 SPKI is a fixed 12-byte header followed by the key.
+    const ED25519_SPKI_HEADER: [u8; 12] = [0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00];
+    if spki.len() != 44 || spki[..12] != ED25519_SPKI_HEADER {
+        return Err(anyhow!("KMS key is not an ed25519 key"));
+    }
+    Ok(Pubkey::try_from(&spki[12..])?)
+}
+
+fn signature_from_bytes(bytes: &[u8]) -> Result<Signature> {
+    Signature::try_from(bytes).map_err(|_| anyhow!("KMS returned a {}-byte signature", bytes.len()))
+}
@@ -1,21 +1,146 @@
-use crate::config::Config;
+use crate::blockhash::BlockhashCache;
+use crate::config::{Config, FeeConfig, RpcRetryConfig};
+use crate::database::TransactionRecord;
+use crate::error::ApiError;
+use crate::fees::{
+    compute_budget_instructions, padded_unit_limit, PriorityFeeEstimate, PriorityFeeStrategy, MAX_COMPUTE_UNIT_LIMIT,
+};
+use crate::holders::{
+    self, HolderDistribution, TokenHolder, MAX_LARGEST_ACCOUNTS, TOKEN_ACCOUNT_AMOUNT_OFFSET,
+    TOKEN_ACCOUNT_MINT_OFFSET,
+};
+use crate::jito::{BundleStatus, JitoClient};
+use crate::memo;
+use crate::metrics::Metrics;
+use crate::rpc_pool::{error_kind, is_endpoint_failure, is_rate_limited, EndpointStatus, RpcPool};
+use crate::signer::{self, Signer};
+use crate::stake::{StakeAccount, STAKER_OFFSET, WITHDRAWER_OFFSET};
+use crate::swap::SwapQuote;
+use crate::token_registry::TokenMetadata;
+use crate::wsol;
+use crate::tokens::{
+    parse_mint, parse_token_account, MintMetadata, ParsedTokenAccount, TokenExtensions, TokenProgram,
+};
 use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use moka::future::Cache;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
-use solana_client::rpc_client::RpcClient;
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind, Result as ClientResult},
+    nonblocking::rpc_client::RpcClient,
+    nonce_utils,
+    rpc_client::GetConfirmedSignaturesForAddress2Config,
+    rpc_config::{
+        RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSimulateTransactionConfig, RpcTransactionConfig,
+    },
+    rpc_filter::{Memcmp, RpcFilterType},
+    rpc_request::RpcRequest,
+    rpc_response::{
+        Response, RpcConfirmedTransactionStatusWithSignature, RpcKeyedAccount, RpcSimulateTransactionResult,
+    },
+};
 use solana_sdk::{
+    account::Account,
     commitment_config::CommitmentConfig,
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    instruction::Instruction,
+    message::Message,
+    nonce::State as NonceState,
+    program_option::COption,
+    program_pack::Pack,
     pubkey::Pubkey,
-    signature::Signature,
-    transaction::Transaction,
+    signature::{Keypair, Signature},
+    system_instruction,
+    transaction::{Transaction, TransactionError, VersionedTransaction},
+};
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, TransactionStatus, UiTransactionEncoding,
 };
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id, instruction::create_associated_token_account_idempotent,
+};
+use spl_token_2022::extension::StateWithExtensions;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+use tracing::warn;
+use utoipa::ToSchema;
+
+// Upper bound on keys accepted by a single getMultipleAccounts request.
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+// Upper bound on signatures accepted by a single getSignatureStatuses request.
+const MAX_SIGNATURE_STATUSES: usize = 256;
+// How often a sent transaction is checked while waiting for it to land.
+const LANDING_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+fn is_idempotent(method: &str) -> bool {
+    method != "sendTransaction"
+}
+
+fn is_blockhash_not_found(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<ClientError>()
+        .and_then(|err| err.get_transaction_error())
+        == Some(TransactionError::BlockhashNotFound)
+}
+
+/// Whether a wSOL transfer is funded from the sender's SOL through a
+/// temporary wSOL account rather than sent from its own wSOL account.
+fn wraps_sol(request: &crate::TransactionRequest) -> bool {
+    let native = request
+        .mint
+        .as_deref()
+        .and_then(|mint| Pubkey::from_str(mint).ok())
+        .map_or(false, |mint| wsol::is_native(&mint));
+    native && request.wrap_sol.unwrap_or(true)
+}
+
+/// Supply, decimals and authorities of a classic or Token-2022 mint account,
+/// with its program and its transfer fee, null for mints without one.
+fn token_info(mint: &str, account: &Account) -> Option<serde_json::Value> {
+    let program = TokenProgram::from_owner(&account.owner)?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&account.data).ok()?;
+    let metadata = parse_mint(&account.data)?;
+    let authority = |authority: COption<Pubkey>| Option::<Pubkey>::from(authority).map(|p| p.to_string());
+    Some(serde_json::json!({
+        "mint": mint,
+        "program": program,
+        "supply": state.base.supply,
+        "decimals": state.base.decimals,
+        "mint_authority": authority(state.base.mint_authority),
+        "freeze_authority": authority(state.base.freeze_authority),
+        "transfer_fee": metadata.transfer_fee,
+    }))
+}
+
+/// Lamports a transfer takes from the sender's SOL balance: its amount for
+/// SOL and for wSOL wrapped from SOL, none for other token transfers.
+fn lamports_out(request: &crate::TransactionRequest) -> u64 {
+    if request.mint.is_none() || wraps_sol(request) {
+        request.amount
+    } else {
+        0
+    }
+}
 
 #[derive(Clone)]
 pub struct SolanaClient {
-    rpc_client: RpcClient,
+    /// Replaced by `set_endpoints`; calls in flight keep the pool they
+    /// started with.
+    pool: Arc<RwLock<Arc<RpcPool>>>,
+    mint_cache: Cache<Pubkey, MintMetadata>,
+    metrics: Arc<Metrics>,
+    fees: Arc<RwLock<FeeConfig>>,
+    retry: RpcRetryConfig,
+    blockhash: Arc<BlockhashCache>,
+    passthrough_methods: Arc<HashSet<&'static str>>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct AccountInfo {
     pub address: String,
     pub balance: u64,
@@ -24,160 +149,1379 @@ pub struct AccountInfo {
     pub rent_epoch: u64,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct TokenBalance {
     pub mint: String,
     pub amount: u64,
     pub decimals: u8,
     pub ui_amount: f64,
+    pub program: TokenProgram,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<TokenExtensions>,
+    /// Symbol, name and logo from the token registry, when the mint is listed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<TokenMetadata>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct TransactionInfo {
     pub signature: String,
     pub status: String,
-    pub slot: u64,
+    /// Slot the transaction landed in; null until it is confirmed.
+    pub slot: Option<u64>,
+    #[serde(default)]
+    pub accounts: Vec<String>,
+    pub fee: Option<u64>,
+    pub block_time: Option<i64>,
+    /// Text of the transaction's SPL Memo instructions.
+    pub memo: Option<String>,
+    /// The transaction resent after this one expired unlanded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaced_by: Option<String>,
 }
 
-impl SolanaClient {
-    pub fn new(rpc_url: &str) -> Result<Self> {
-        let rpc_client = RpcClient::new_with_commitment(
-            rpc_url.to_string(),
-            CommitmentConfig::confirmed(),
-        );
+/// What became of a sent transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Landing {
+    /// The cluster has seen it, successful or not.
+    Landed,
+    /// Its blockhash expired first, so it never will.
+    Expired,
+}
 
-        Ok(Self { rpc_client })
-    }
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct NonceInfo {
+    pub address: String,
+    pub authority: String,
+    /// Stored durable nonce, used in place of a recent blockhash.
+    pub blockhash: String,
+    pub lamports_per_signature: u64,
+}
 
-    pub async fn get_account_info(&self, address: &str) -> Result<AccountInfo> {
-        let pubkey = Pubkey::from_str(address)?;
-        let account = self.rpc_client.get_account(&pubkey)?;
+/// An owner's associated token account for one mint.
+pub struct AssociatedTokenAccount {
+    pub address: Pubkey,
+    pub program: TokenProgram,
+    pub exists: bool,
+}
+
+/// A token account held by an owner, with the lamports closing it returns.
+pub struct OwnedTokenAccount {
+    pub address: Pubkey,
+    pub lamports: u64,
+    pub account: ParsedTokenAccount,
+}
+
+/// A transaction for the caller to sign, serialized in wire format.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct UnsignedTransaction {
+    /// Base64 bincode-serialized transaction with empty signatures.
+    pub transaction: String,
+    pub fee_payer: String,
+    pub blockhash: String,
+}
+
+/// Outcome of simulating a transaction without broadcasting it.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct Simulation {
+    pub success: bool,
+    /// Error the transaction would fail with.
+    pub error: Option<String>,
+    pub units_consumed: Option<u64>,
+    /// Network fee in lamports; unknown for durable nonce transactions.
+    pub fee: Option<u64>,
+    pub logs: Vec<String>,
+}
+
+/// A dry-run transfer: the transaction that would have been sent and how
+/// it would have fared.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct SimulatedTransaction {
+    pub simulation: Simulation,
+    pub transaction: UnsignedTransaction,
+}
+
+/// Who signs a transaction the gateway builds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SignMode {
+    /// The gateway signs with a stored wallet, or with its own signer when
+    /// `from` is the signer's address, and sends the transaction.
+    #[default]
+    Gateway,
+    /// The transaction is returned unsigned for the caller's wallet to sign
+    /// and send through `POST /api/v1/transactions/submit`.
+    Client,
+}
 
-        Ok(AccountInfo {
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct NonceOperation {
+    pub address: String,
+    pub signature: String,
+}
+
+impl UnsignedTransaction {
+    fn new(transaction: &Transaction) -> Result<Self> {
+        Ok(Self {
+            transaction: BASE64.encode(bincode::serialize(transaction)?),
+            fee_payer: transaction.message.account_keys[0].to_string(),
+            blockhash: transaction.message.recent_blockhash.to_string(),
+        })
+    }
+}
+
+impl AccountInfo {
+    fn new(address: &str, account: &Account) -> Self {
+        Self {
             address: address.to_string(),
             balance: account.lamports,
             owner: account.owner.to_string(),
             executable: account.executable,
             rent_epoch: account.rent_epoch,
+        }
+    }
+}
+
+impl From<TransactionRecord> for TransactionInfo {
+    fn from(record: TransactionRecord) -> Self {
+        Self {
+            signature: record.signature,
+            status: record.status,
+            slot: record.slot.map(|s| s as u64),
+            accounts: record.accounts,
+            fee: record.fee.map(|f| f as u64),
+            block_time: record.block_time.map(|t| t.timestamp()),
+            memo: record.memo,
+            replaced_by: record.replaced_by,
+        }
+    }
+}
+
+impl TransactionInfo {
+    pub fn to_record(&self, amount: Option<u64>) -> TransactionRecord {
+        TransactionRecord {
+            signature: self.signature.clone(),
+            slot: self.slot.map(|s| s as i64),
+            status: self.status.clone(),
+            accounts: self.accounts.clone(),
+            amount: amount.map(|a| a as i64),
+            fee: self.fee.map(|f| f as i64),
+            block_time: self
+                .block_time
+                .and_then(|t| chrono::DateTime::from_timestamp(t, 0)),
+            memo: self.memo.clone(),
+            replaced_by: self.replaced_by.clone(),
+            created_at: chrono::Utc::now(),
+        }
+    }
+}
+
+/// `RpcRequest::Custom` takes a static method name, so each allowed
+/// passthrough method is leaked once per process, however many clients are
+/// built or reloads happen.
+fn static_method(method: &str) -> &'static str {
+    static METHODS: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+    let mut methods = METHODS.get_or_init(Default::default).lock().unwrap();
+    if let Some(&method) = methods.get(method) {
+        return method;
+    }
+    let method: &'static str = Box::leak(method.to_string().into_boxed_str());
+    methods.insert(method);
+    method
+}
+
+impl SolanaClient {
+    pub fn new(config: &Config, metrics: Arc<Metrics>) -> Result<Self> {
+        let mint_cache = Cache::builder()
+            .max_capacity(config.mint_cache_capacity)
+            .time_to_live(Duration::from_secs(config.mint_cache_ttl_secs))
+            .build();
+
+        Ok(Self {
+            pool: Arc::new(RwLock::new(Arc::new(Self::rpc_pool(config)?))),
+            mint_cache,
+            metrics,
+            fees: Arc::new(RwLock::new(config.fees.clone())),
+            retry: config.rpc_retry.clone(),
+            blockhash: Arc::new(BlockhashCache::new(&config.blockhash)),
+            passthrough_methods: Arc::new(
+                config
+                    .rpc_passthrough
+                    .methods
+                    .iter()
+                    .map(|method| static_method(method))
+                    .collect(),
+            ),
         })
     }
 
+    fn pool(&self) -> Arc<RpcPool> {
+        self.pool.read().unwrap().clone()
+    }
+
+    fn fees(&self) -> FeeConfig {
+        self.fees.read().unwrap().clone()
+    }
+
+    /// The RPC endpoints of `config` with its failover settings, starting
+    /// with no health history.
+    pub fn rpc_pool(config: &Config) -> Result<RpcPool> {
+        let endpoints = config.rpc_endpoints();
+        if endpoints.is_empty() {
+            anyhow::bail!("At least one Solana RPC endpoint must be configured");
+        }
+        Ok(RpcPool::new(
+            &endpoints,
+            config.rpc_failover.clone(),
+            config.timeouts.rpc(),
+            &config.rpc_http,
+        ))
+    }
+
+    /// Replaces the RPC endpoints and their failover settings with `pool`,
+    /// built by `rpc_pool`.
+    pub fn set_endpoints(&self, pool: RpcPool) {
+        *self.pool.write().unwrap() = Arc::new(pool);
+    }
+
+    /// Replaces the priority fee and compute unit settings.
+    pub fn set_fees(&self, fees: &FeeConfig) {
+        *self.fees.write().unwrap() = fees.clone();
+    }
+
+    /// Failover state of every configured RPC endpoint.
+    pub fn endpoint_status(&self) -> Vec<EndpointStatus> {
+        self.pool().status()
+    }
+
+    /// Updates every endpoint's lag behind `reference_slot`, moving traffic
+    /// off endpoints more than `max_lag` slots behind.
+    pub async fn check_slot_lag(&self, reference_slot: u64, max_lag: u64) {
+        self.pool().check_slot_lag(reference_slot, max_lag).await
+    }
+
+    /// Drops cached mint metadata and returns how many entries were cached.
+    pub fn flush_mint_cache(&self) -> u64 {
+        let entries = self.mint_cache.entry_count();
+        self.mint_cache.invalidate_all();
+        entries
+    }
+
+    /// Runs an RPC call with failover, retrying idempotent methods with
+    /// exponential backoff when every endpoint failed transiently.
+    async fn rpc<T, F, Fut>(&self, method: &'static str, call: F) -> Result<T>
+    where
+        F: Fn(Arc<RpcClient>) -> Fut,
+        Fut: Future<Output = ClientResult<T>>,
+    {
+        // Resending a transaction could land it twice, so writes get one pass.
+        let max_attempts = if is_idempotent(method) {
+            self.retry.max_attempts.max(1)
+        } else {
+            1
+        };
+
+        let mut attempt = 1;
+        loop {
+            let err = match self.rpc_once(method, &call).await {
+                Ok(value) => return Ok(value),
+                Err(err) => err,
+            };
+            let rate_limited = is_rate_limited(&err);
+            if attempt >= max_attempts || !(rate_limited || is_endpoint_failure(&err)) {
+                return Err(err.into());
+            }
+
+            let delay = self.retry_delay(attempt, rate_limited);
+            let reason = if rate_limited { "rate_limited" } else { "unavailable" };
+            self.metrics.observe_rpc_retry(method, reason);
+            warn!(
+                "{} failed on every endpoint ({}), retrying in {}ms: {}",
+                method,
+                reason,
+                delay.as_millis(),
+                err
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Runs an RPC call against the healthiest endpoint, failing over to the
+    /// next one when the error points at the node rather than the request.
+    async fn rpc_once<T, F, Fut>(&self, method: &'static str, call: &F) -> ClientResult<T>
+    where
+        F: Fn(Arc<RpcClient>) -> Fut,
+        Fut: Future<Output = ClientResult<T>>,
+    {
+        let pool = self.pool();
+        let mut tried = Vec::new();
+        loop {
+            let index = pool.select(&tried).ok_or_else(|| {
+                ClientError::from(ClientErrorKind::Custom("No RPC endpoints available".to_string()))
+            })?;
+            let endpoint = pool.endpoint(index);
+            let started = Instant::now();
+
+            let result = call(endpoint.client.clone()).await;
+            self.metrics.observe_rpc(method, result.is_ok(), started.elapsed());
+            if let Err(err) = &result {
+                self.metrics.observe_rpc_error(method, error_kind(err));
+            }
+
+            match result {
+                Err(err) if is_rate_limited(&err) || is_endpoint_failure(&err) => {
+                    // Throttling says nothing about node health.
+                    if !is_rate_limited(&err) {
+                        pool.record_failure(index);
+                    }
+                    tried.push(index);
+                    if tried.len() >= pool.len() {
+                        return Err(err);
+                    }
+                    warn!("RPC endpoint {} failed, failing over: {}", endpoint.url, err);
+                }
+                result => {
+                    pool.record_success(index, started.elapsed());
+                    return result;
+                }
+            }
+        }
+    }
+
+    /// Full-jitter exponential backoff; a 429 waits at least the configured
+    /// rate-limit delay.
+    fn retry_delay(&self, attempt: u32, rate_limited: bool) -> Duration {
+        let ceiling = self
+            .retry
+            .base_delay_ms
+            .saturating_mul(1 << (attempt - 1).min(16))
+            .min(self.retry.max_delay_ms);
+        let mut delay = rand::thread_rng().gen_range(0..=ceiling);
+        if rate_limited {
+            delay = delay.max(self.retry.rate_limit_delay_ms);
+        }
+        Duration::from_millis(delay)
+    }
+
+    pub async fn get_account_info(&self, address: &str) -> Result<AccountInfo> {
+        let pubkey = Pubkey::from_str(address)?;
+        let account = self.rpc("getAccountInfo", |rpc| async move { rpc.get_account(&pubkey).await }).await?;
+        Ok(AccountInfo::new(address, &account))
+    }
+
+    /// Account info for many addresses in as few `getMultipleAccounts` calls
+    /// as possible. Missing accounts are `None`, in the order requested.
+    pub async fn get_account_infos(&self, addresses: &[String]) -> Result<Vec<Option<AccountInfo>>> {
+        let pubkeys = addresses
+            .iter()
+            .map(|address| Pubkey::from_str(address))
+            .collect::<Result<Vec<_>, _>>()?;
+        let accounts = self.get_multiple_accounts(&pubkeys).await?;
+
+        Ok(addresses
+            .iter()
+            .zip(accounts)
+            .map(|(address, account)| account.map(|account| AccountInfo::new(address, &account)))
+            .collect())
+    }
+
     pub async fn get_balance(&self, address: &str) -> Result<u64> {
         let pubkey = Pubkey::from_str(address)?;
-        let balance = self.rpc_client.get_balance(&pubkey)?;
+        let balance = self.rpc("getBalance", |rpc| async move { rpc.get_balance(&pubkey).await }).await?;
         Ok(balance)
     }
 
     pub async fn get_token_balances(&self, address: &str) -> Result<Vec<TokenBalance>> {
         let pubkey = Pubkey::from_str(address)?;
-        
-        // Get all token accounts for the address
-        let token_accounts = self.rpc_client.get_token_accounts_by_owner(
-            &pubkey,
-            solana_client::rpc_request::TokenAccountsFilter::ProgramId(
-                spl_token::id(),
-            ),
+        let token_accounts: Vec<ParsedTokenAccount> = self
+            .owned_token_accounts(&pubkey)
+            .await?
+            .into_iter()
+            .map(|owned| owned.account)
+            .collect();
+
+        let mints: Vec<Pubkey> = token_accounts.iter().map(|account| account.mint).collect();
+        let mint_metadata = self.resolve_mints(&mints).await?;
+
+        Ok(token_accounts
+            .into_iter()
+            .map(|account| {
+                let mint = mint_metadata.get(&account.mint);
+                let decimals = mint.map_or(0, |m| m.decimals);
+                TokenBalance {
+                    mint: account.mint.to_string(),
+                    amount: account.amount,
+                    decimals,
+                    ui_amount: account.amount as f64 / 10_f64.powi(decimals as i32),
+                    program: account.program,
+                    extensions: TokenExtensions::new(&account, mint),
+                    metadata: None,
+                }
+            })
+            .collect())
+    }
+
+    /// Every token account held by `owner` under either token program.
+    pub async fn owned_token_accounts(&self, owner: &Pubkey) -> Result<Vec<OwnedTokenAccount>> {
+        let (legacy, token_2022) = futures::try_join!(
+            self.token_accounts_by_owner(owner, &TokenProgram::Token.id()),
+            self.token_accounts_by_owner(owner, &TokenProgram::Token2022.id()),
         )?;
 
-        let mut balances = Vec::new();
-        
-        for account in token_accounts {
-            if let Ok(account_data) = spl_token::state::Account::unpack(&account.account.data) {
-                balances.push(TokenBalance {
-                    mint: account_data.mint.to_string(),
-                    amount: account_data.amount,
-                    decimals: 0, // Would need to fetch from mint account
-                    ui_amount: account_data.amount as f64 / 10_f64.powi(0), // Would use actual decimals
-                });
+        let parse = |program: TokenProgram| {
+            move |(address, account): (Pubkey, Account)| {
+                Some(OwnedTokenAccount {
+                    address,
+                    lamports: account.lamports,
+                    account: parse_token_account(program, &account.data)?,
+                })
+            }
+        };
+        Ok(legacy
+            .into_iter()
+            .filter_map(parse(TokenProgram::Token))
+            .chain(token_2022.into_iter().filter_map(parse(TokenProgram::Token2022)))
+            .collect())
+    }
+
+    /// Fetches raw token accounts held by `owner` under the given token program.
+    /// `getTokenAccountsByOwner` is requested with base64 encoding so the data
+    /// can be unpacked directly instead of going through jsonParsed output.
+    async fn token_accounts_by_owner(
+        &self,
+        owner: &Pubkey,
+        program_id: &Pubkey,
+    ) -> Result<Vec<(Pubkey, Account)>> {
+        let params = serde_json::json!([
+            owner.to_string(),
+            { "programId": program_id.to_string() },
+            { "encoding": "base64", "commitment": "confirmed" },
+        ]);
+
+        let response: Response<Vec<RpcKeyedAccount>> = self
+            .rpc("getTokenAccountsByOwner", |rpc| {
+                let params = params.clone();
+                async move { rpc.send(RpcRequest::GetTokenAccountsByOwner, params).await }
+            })
+            .await?;
+
+        Ok(response
+            .value
+            .into_iter()
+            .filter_map(|keyed| {
+                let pubkey = Pubkey::from_str(&keyed.pubkey).ok()?;
+                let account = keyed.account.decode::<Account>()?;
+                Some((pubkey, account))
+            })
+            .collect())
+    }
+
+    /// Resolves mint metadata, serving from the in-memory cache and batching
+    /// the remaining mints into `getMultipleAccounts` calls.
+    pub async fn resolve_mints(&self, mints: &[Pubkey]) -> Result<HashMap<Pubkey, MintMetadata>> {
+        let mut resolved = HashMap::new();
+        let mut missing = Vec::new();
+
+        for mint in mints {
+            if resolved.contains_key(mint) || missing.contains(mint) {
+                continue;
+            }
+            match self.mint_cache.get(mint).await {
+                Some(metadata) => {
+                    resolved.insert(*mint, metadata);
+                }
+                None => missing.push(*mint),
             }
         }
 
-        Ok(balances)
+        let accounts = self.get_multiple_accounts(&missing).await?;
+        for (mint, account) in missing.iter().zip(accounts) {
+            let Some(metadata) = account.and_then(|a| parse_mint(&a.data)) else {
+                continue;
+            };
+            self.mint_cache.insert(*mint, metadata.clone()).await;
+            resolved.insert(*mint, metadata);
+        }
+
+        Ok(resolved)
     }
 
-    pub async fn create_transaction(&self, request: &crate::TransactionRequest) -> Result<TransactionInfo> {
-        // This is a simplified implementation
-        // In a real implementation, you would:
-        // 1. Create a proper Solana transaction
-        // 2. Sign it with the appropriate keypair
-        // 3. Send it to the network
-        // 4. Return the transaction signature
+    pub async fn get_slot(&self) -> Result<u64> {
+        self.rpc("getSlot", |rpc| async move { rpc.get_slot().await }).await
+    }
+
+    /// Recent statuses for any number of signatures, in the order given.
+    pub async fn get_signature_statuses(&self, signatures: &[Signature]) -> Result<Vec<Option<TransactionStatus>>> {
+        let mut statuses = Vec::with_capacity(signatures.len());
+        for chunk in signatures.chunks(MAX_SIGNATURE_STATUSES) {
+            let response = self
+                .rpc("getSignatureStatuses", |rpc| async move { rpc.get_signature_statuses(chunk).await })
+                .await?;
+            statuses.extend(response.value);
+        }
+        Ok(statuses)
+    }
+
+    /// Like `get_signature_statuses`, but also searches the ledger beyond the
+    /// recent status cache, so old transactions are found as well.
+    pub async fn get_signature_statuses_with_history(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<Vec<Option<TransactionStatus>>> {
+        let mut statuses = Vec::with_capacity(signatures.len());
+        for chunk in signatures.chunks(MAX_SIGNATURE_STATUSES) {
+            let response = self
+                .rpc("getSignatureStatuses", |rpc| async move {
+                    rpc.get_signature_statuses_with_history(chunk).await
+                })
+                .await?;
+            statuses.extend(response.value);
+        }
+        Ok(statuses)
+    }
+
+    pub async fn get_block_time(&self, slot: u64) -> Result<i64> {
+        self.rpc("getBlockTime", |rpc| async move { rpc.get_block_time(slot).await }).await
+    }
+
+    /// The cached blockhash when it is fresh enough, otherwise a new one.
+    pub async fn latest_blockhash(&self) -> Result<Hash> {
+        match self.blockhash.get() {
+            Some(hash) => Ok(hash),
+            None => self.refresh_blockhash().await,
+        }
+    }
+
+    /// Fetches the latest blockhash and caches it for later builds.
+    pub async fn refresh_blockhash(&self) -> Result<Hash> {
+        let hash = self
+            .rpc("getLatestBlockhash", |rpc| async move { rpc.get_latest_blockhash().await })
+            .await?;
+        self.blockhash.store(hash);
+        Ok(hash)
+    }
+
+    pub async fn get_nonce(&self, address: &Pubkey) -> Result<NonceInfo> {
+        let account = self.get_raw_account(address).await?;
+        let data = nonce_utils::data_from_account(&account)
+            .map_err(|_| ApiError::BadRequest("Account is not an initialized nonce account".to_string()))?;
+
+        Ok(NonceInfo {
+            address: address.to_string(),
+            authority: data.authority.to_string(),
+            blockhash: data.blockhash().to_string(),
+            lamports_per_signature: data.fee_calculator.lamports_per_signature,
+        })
+    }
+
+    pub async fn minimum_balance_for_rent_exemption(&self, data_len: usize) -> Result<u64> {
+        self.rpc("getMinimumBalanceForRentExemption", |rpc| async move {
+            rpc.get_minimum_balance_for_rent_exemption(data_len).await
+        })
+        .await
+    }
+
+    /// Creates and funds a new nonce account with `payer` as its authority.
+    pub async fn create_nonce_account(&self, payer: &dyn Signer) -> Result<NonceOperation> {
+        let nonce = Keypair::new();
+        let rent = self.minimum_balance_for_rent_exemption(NonceState::size()).await?;
+        let instructions = system_instruction::create_nonce_account(
+            &payer.public_key(),
+            &nonce.public_key(),
+            &payer.public_key(),
+            rent,
+        );
+
+        let signature = self.sign_and_send(&instructions, &[payer, &nonce]).await?;
+        Ok(NonceOperation {
+            address: nonce.public_key().to_string(),
+            signature: signature.to_string(),
+        })
+    }
+
+    pub async fn advance_nonce_account(&self, address: &Pubkey, authority: &dyn Signer) -> Result<NonceOperation> {
+        let instruction = system_instruction::advance_nonce_account(address, &authority.public_key());
+        let signature = self.sign_and_send(&[instruction], &[authority]).await?;
+        Ok(NonceOperation {
+            address: address.to_string(),
+            signature: signature.to_string(),
+        })
+    }
+
+    /// Signs with a fresh blockhash and sends; the first signer pays fees.
+    pub async fn sign_and_send(&self, instructions: &[Instruction], signers: &[&dyn Signer]) -> Result<Signature> {
+        let blockhash = self.latest_blockhash().await?;
+        let message = Message::new_with_blockhash(instructions, Some(&signers[0].public_key()), &blockhash);
+        let mut transaction = Transaction::new_unsigned(message);
+        signer::sign_transaction(&mut transaction, signers).await?;
+        self.send_with_fresh_blockhash(&mut transaction, signers).await
+    }
+
+    /// Sends a signed transaction. If the node doesn't know its blockhash
+    /// (it expired, or the node lags behind the one it came from), the
+    /// transaction is re-signed once with a freshly fetched blockhash, which
+    /// `transaction` is left with.
+    async fn send_with_fresh_blockhash(
+        &self,
+        transaction: &mut Transaction,
+        signers: &[&dyn Signer],
+    ) -> Result<Signature> {
+        match self.send_transaction(transaction).await {
+            Err(e) if is_blockhash_not_found(&e) => {
+                let stale = transaction.message.recent_blockhash;
+                self.blockhash.invalidate(&stale);
+                let blockhash = self.refresh_blockhash().await?;
+                warn!("Blockhash {} not found, resending with {}", stale, blockhash);
+                transaction.message.recent_blockhash = blockhash;
+                signer::sign_transaction(transaction, signers).await?;
+                self.send_transaction(transaction).await
+            }
+            result => result,
+        }
+    }
+
+    async fn send_transaction(&self, transaction: &Transaction) -> Result<Signature> {
+        self.rpc("sendTransaction", |rpc| async move { rpc.send_transaction(transaction).await })
+            .await
+    }
+
+    /// Like `sign_and_send`, but appends a tip and submits the transaction as
+    /// a Jito bundle, waiting until the bundle lands or is rejected. Returns
+    /// the signature with the blockhash the transaction was built on.
+    pub async fn sign_and_send_bundle(
+        &self,
+        jito: &JitoClient,
+        instructions: &[Instruction],
+        signers: &[&dyn Signer],
+    ) -> Result<(Signature, Hash)> {
+        let payer = signers[0].public_key();
+        let mut instructions = instructions.to_vec();
+        instructions.push(jito.tip_instruction(&payer));
+        let blockhash = self.latest_blockhash().await?;
+        let mut transaction =
+            Transaction::new_unsigned(Message::new_with_blockhash(&instructions, Some(&payer), &blockhash));
+        signer::sign_transaction(&mut transaction, signers).await?;
+
+        let bundle_id = jito.send_bundle(std::slice::from_ref(&transaction)).await?;
+        match jito.wait_for_bundle(&bundle_id).await? {
+            BundleStatus::Failed | BundleStatus::Invalid => {
+                Err(ApiError::Conflict(format!("Bundle {} was not included", bundle_id)).into())
+            }
+            // Still pending bundles are left to the confirmation tracker.
+            BundleStatus::Pending | BundleStatus::Landed => Ok((transaction.signatures[0], blockhash)),
+        }
+    }
+
+    /// Stake accounts where `owner` is the staker or withdrawer authority.
+    /// With `include_rewards`, each account carries its inflation reward
+    /// from the previous epoch.
+    pub async fn get_stake_accounts(&self, owner: &Pubkey, include_rewards: bool) -> Result<Vec<StakeAccount>> {
+        let by_authority = |offset| {
+            vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(offset, owner.as_ref()))]
+        };
+        let stake_program = solana_sdk::stake::program::id();
+        let (as_staker, as_withdrawer, epoch_info) = tokio::try_join!(
+            self.get_program_accounts(&stake_program, by_authority(STAKER_OFFSET)),
+            self.get_program_accounts(&stake_program, by_authority(WITHDRAWER_OFFSET)),
+            self.rpc("getEpochInfo", |rpc| async move { rpc.get_epoch_info().await }),
+        )?;
+
+        let mut seen = HashSet::new();
+        let mut stakes: Vec<StakeAccount> = as_staker
+            .into_iter()
+            .chain(as_withdrawer)
+            .filter(|(address, _)| seen.insert(*address))
+            .filter_map(|(address, account)| {
+                StakeAccount::parse(&address, account.lamports, &account.data, epoch_info.epoch)
+            })
+            .collect();
+
+        if include_rewards && !stakes.is_empty() && epoch_info.epoch > 0 {
+            let addresses: Vec<Pubkey> = stakes
+                .iter()
+                .filter_map(|stake| Pubkey::from_str(&stake.address).ok())
+                .collect();
+            let addresses = &addresses;
+            let previous_epoch = epoch_info.epoch - 1;
+            let rewards = self
+                .rpc("getInflationReward", |rpc| async move {
+                    rpc.get_inflation_reward(addresses, Some(previous_epoch)).await
+                })
+                .await?;
+            for (stake, reward) in stakes.iter_mut().zip(rewards) {
+                stake.last_reward = reward.map(Into::into);
+            }
+        }
+        Ok(stakes)
+    }
+
+    /// Serializes `instructions` into an unsigned transaction paid by `payer`.
+    pub async fn build_unsigned_transaction(
+        &self,
+        instructions: &[Instruction],
+        payer: &Pubkey,
+    ) -> Result<UnsignedTransaction> {
+        let blockhash = self.latest_blockhash().await?;
+        let mut transaction = Transaction::new_unsigned(Message::new(instructions, Some(payer)));
+        transaction.message.recent_blockhash = blockhash;
+        UnsignedTransaction::new(&transaction)
+    }
+
+    /// Builds a transfer like `create_transaction` but returns it for `from`
+    /// to sign instead of sending it.
+    pub async fn build_unsigned_transfer(&self, request: &crate::TransactionRequest) -> Result<UnsignedTransaction> {
+        UnsignedTransaction::new(&self.build_transfer_transaction(request).await?)
+    }
+
+    /// Sends a transaction signed by the caller, legacy or versioned, after
+    /// checking that every required signature is present and valid.
+    pub async fn send_signed_transaction(&self, transaction: &VersionedTransaction) -> Result<Signature> {
+        if !transaction.verify_with_results().into_iter().all(|valid| valid) {
+            return Err(ApiError::BadRequest("Transaction is missing signatures or has an invalid one".to_string()).into());
+        }
+        self.rpc("sendTransaction", |rpc| async move { rpc.send_transaction(transaction).await })
+            .await
+    }
+
+    pub async fn get_raw_account(&self, pubkey: &Pubkey) -> Result<Account> {
+        self.rpc("getAccountInfo", |rpc| async move { rpc.get_account(pubkey).await })
+            .await
+    }
+
+    /// `getMultipleAccounts` over any number of keys, split into batches the
+    /// RPC node accepts. Results are returned in the order of `keys`.
+    pub async fn get_multiple_accounts(&self, keys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        let mut accounts = Vec::with_capacity(keys.len());
+        for chunk in keys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            accounts.extend(
+                self.rpc("getMultipleAccounts", |rpc| async move { rpc.get_multiple_accounts(chunk).await })
+                    .await?,
+            );
+        }
+        Ok(accounts)
+    }
+
+    pub async fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: Vec<RpcFilterType>,
+    ) -> Result<Vec<(Pubkey, Account)>> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+
+        self.rpc("getProgramAccounts", |rpc| {
+            let config = config.clone();
+            async move { rpc.get_program_accounts_with_config(program_id, config).await }
+        })
+        .await
+    }
+
+    /// The largest accounts of `mint` with their owners and supply shares,
+    /// and optionally the number of funded token accounts. Counting scans
+    /// every account of the mint, so it is only done on request.
+    pub async fn get_token_holders(&self, mint: &Pubkey, limit: usize, count: bool) -> Result<HolderDistribution> {
+        let account = self.get_raw_account(mint).await?;
+        let (program, (supply, decimals)) = TokenProgram::from_owner(&account.owner)
+            .zip(holders::parse_supply(&account.data))
+            .ok_or_else(|| ApiError::BadRequest("Account is not a valid mint".to_string()))?;
+
+        let largest = self
+            .rpc("getTokenLargestAccounts", |rpc| async move { rpc.get_token_largest_accounts(mint).await })
+            .await?;
+        let largest: Vec<(Pubkey, u64)> = largest
+            .into_iter()
+            .take(limit.min(MAX_LARGEST_ACCOUNTS))
+            .filter_map(|balance| Some((Pubkey::from_str(&balance.address).ok()?, balance.amount.amount.parse().ok()?)))
+            .collect();
+        let addresses: Vec<Pubkey> = largest.iter().map(|(address, _)| *address).collect();
+        let accounts = self.get_multiple_accounts(&addresses).await?;
+
+        let scale = 10f64.powi(decimals as i32);
+        let holders: Vec<TokenHolder> = largest
+            .iter()
+            .zip(accounts)
+            .map(|((address, amount), account)| TokenHolder {
+                token_account: address.to_string(),
+                owner: account
+                    .and_then(|account| parse_token_account(program, &account.data))
+                    .map(|parsed| parsed.owner.to_string()),
+                amount: *amount,
+                ui_amount: *amount as f64 / scale,
+                percent: holders::percent_of(*amount, supply),
+            })
+            .collect();
+        let held: u64 = holders.iter().map(|holder| holder.amount).sum();
+
+        let holder_count = if count {
+            Some(self.count_funded_token_accounts(mint, program).await?)
+        } else {
+            None
+        };
+        Ok(HolderDistribution {
+            mint: mint.to_string(),
+            supply,
+            decimals,
+            holders,
+            top_holders_percent: holders::percent_of(held, supply),
+            holder_count,
+        })
+    }
+
+    /// Token accounts of `mint` with a non-zero balance, fetching only each
+    /// account's amount.
+    async fn count_funded_token_accounts(&self, mint: &Pubkey, program: TokenProgram) -> Result<u64> {
+        let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            TOKEN_ACCOUNT_MINT_OFFSET,
+            mint.as_ref(),
+        ))];
+        // Token-2022 accounts grow with their extensions.
+        if program == TokenProgram::Token {
+            filters.push(RpcFilterType::DataSize(spl_token::state::Account::LEN as u64));
+        }
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: Some(UiDataSliceConfig {
+                    offset: TOKEN_ACCOUNT_AMOUNT_OFFSET,
+                    length: 8,
+                }),
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        let program_id = program.id();
+        let accounts = self
+            .rpc("getProgramAccounts", |rpc| {
+                let (program_id, config) = (&program_id, config.clone());
+                async move { rpc.get_program_accounts_with_config(program_id, config).await }
+            })
+            .await?;
+        Ok(accounts
+            .iter()
+            .filter(|(_, account)| account.data.iter().any(|byte| *byte != 0))
+            .count() as u64)
+    }
+
+    pub async fn estimate_priority_fees(&self, accounts: &[Pubkey]) -> Result<PriorityFeeEstimate> {
+        let fees = self
+            .rpc("getRecentPrioritizationFees", |rpc| async move {
+                rpc.get_recent_prioritization_fees(accounts).await
+            })
+            .await?;
+
+        Ok(PriorityFeeEstimate::from_samples(
+            fees.into_iter().map(|fee| fee.prioritization_fee).collect(),
+        ))
+    }
+
+    /// Builds an unsigned SOL or SPL token transfer with ComputeBudget
+    /// instructions prepended according to the request's (or the configured)
+    /// priority fee strategy.
+    pub async fn build_transfer_transaction(&self, request: &crate::TransactionRequest) -> Result<Transaction> {
+        let from = Pubkey::from_str(&request.from)?;
+        let to = Pubkey::from_str(&request.to)?;
+
+        let fees = self.fees();
+        let strategy = request.priority_fee.unwrap_or(fees.strategy);
+        let unit_price = match strategy {
+            PriorityFeeStrategy::Custom(price) => price,
+            _ => self
+                .estimate_priority_fees(&[from, to])
+                .await?
+                .price_for(strategy)
+                .min(fees.max_unit_price),
+        };
+
+        // The limit is settled by simulating the finished transaction.
+        let mut instructions = compute_budget_instructions(MAX_COMPUTE_UNIT_LIMIT, unit_price);
+        instructions.extend(self.transfer_instructions(request).await?);
+
+        // A durable nonce replaces the blockhash and must be advanced by the
+        // first instruction of the transaction.
+        let blockhash = match &request.nonce_account {
+            Some(nonce_account) => {
+                let nonce_account = Pubkey::from_str(nonce_account)?;
+                let nonce = self.get_nonce(&nonce_account).await?;
+                if nonce.authority != request.from {
+                    return Err(ApiError::BadRequest("`from` is not the nonce account's authority".to_string()).into());
+                }
+                instructions.insert(0, system_instruction::advance_nonce_account(&nonce_account, &from));
+                Hash::from_str(&nonce.blockhash)?
+            }
+            None => self.latest_blockhash().await?,
+        };
+        let unit_limit = self.compute_unit_limit(&instructions, &from).await;
+        let placeholder = ComputeBudgetInstruction::set_compute_unit_limit(MAX_COMPUTE_UNIT_LIMIT);
+        for instruction in instructions.iter_mut().filter(|instruction| **instruction == placeholder) {
+            *instruction = ComputeBudgetInstruction::set_compute_unit_limit(unit_limit);
+        }
+        let message = Message::new_with_blockhash(&instructions, Some(&from), &blockhash);
+        self.check_sender_rent(&from, lamports_out(request), &message).await?;
+
+        Ok(Transaction::new_unsigned(message))
+    }
+
+    /// The transfer of `request` and its memo, without compute budget
+    /// instructions.
+    pub async fn transfer_instructions(&self, request: &crate::TransactionRequest) -> Result<Vec<Instruction>> {
+        let from = Pubkey::from_str(&request.from)?;
+        let to = Pubkey::from_str(&request.to)?;
+
+        let mut instructions = match &request.mint {
+            Some(mint) => {
+                let mint = Pubkey::from_str(mint)?;
+                let create_ata = request.create_recipient_ata.unwrap_or(true);
+                if wraps_sol(request) {
+                    let account = wsol::TempAccount::new(&from)?;
+                    let source = Some(account.address);
+                    let transfer = self
+                        .token_transfer_instructions(&from, &to, &mint, request.amount, create_ata, source)
+                        .await?;
+                    let mut instructions = account.open(request.amount)?;
+                    instructions.extend(transfer);
+                    instructions.push(account.close()?);
+                    instructions
+                } else {
+                    self.token_transfer_instructions(&from, &to, &mint, request.amount, create_ata, None)
+                        .await?
+                }
+            }
+            None => vec![system_instruction::transfer(&from, &to, request.amount)],
+        };
+        if let Some(memo) = &request.memo {
+            instructions.push(memo::instruction(memo, &from));
+        }
+        Ok(instructions)
+    }
+
+    /// Rejects a transfer that would leave `from` funded but below its
+    /// rent-exempt minimum, which the runtime fails with
+    /// InsufficientFundsForRent. Emptying the account is allowed.
+    /// `lamports_out` is what the transfer moves besides the fee.
+    async fn check_sender_rent(&self, from: &Pubkey, lamports_out: u64, message: &Message) -> Result<()> {
+        // A missing sender fails on its own when the transaction is sent.
+        let Some(account) = self.get_multiple_accounts(&[*from]).await?.pop().flatten() else {
+            return Ok(());
+        };
+        // Durable nonce messages can't be priced; the fee is left out.
+        let fee = self
+            .rpc("getFeeForMessage", |rpc| async move { rpc.get_fee_for_message(message).await })
+            .await
+            .unwrap_or_default();
+
+        let Some(remaining) = account.lamports.checked_sub(lamports_out.saturating_add(fee)) else {
+            return Err(ApiError::BadRequest(format!(
+                "`from` holds {} lamports, short of the {} the transfer and fee need",
+                account.lamports,
+                lamports_out.saturating_add(fee)
+            ))
+            .into());
+        };
+        if remaining == 0 {
+            return Ok(());
+        }
+        let minimum = self.minimum_balance_for_rent_exemption(account.data.len()).await?;
+        if remaining < minimum {
+            return Err(ApiError::BadRequest(format!(
+                "Transfer would leave `from` with {} lamports, below its rent-exempt minimum of {}",
+                remaining, minimum
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Compute units to request for `instructions`: what they consume in a
+    /// simulation plus the configured margin, or the configured limit when
+    /// simulation is disabled or fails. `instructions` should request the
+    /// maximum limit so the simulation itself can't run out.
+    async fn compute_unit_limit(&self, instructions: &[Instruction], payer: &Pubkey) -> u32 {
+        let fees = self.fees();
+        if !fees.simulate_compute_units {
+            return fees.compute_unit_limit;
+        }
+        match self.simulate_compute_units(instructions, payer).await {
+            Ok(units) => padded_unit_limit(units, fees.compute_unit_margin_pct),
+            Err(e) => {
+                warn!("Failed to estimate compute units, using {}: {}", fees.compute_unit_limit, e);
+                fees.compute_unit_limit
+            }
+        }
+    }
+
+    /// Compute units consumed simulating `instructions` unsigned against the
+    /// latest blockhash.
+    async fn simulate_compute_units(&self, instructions: &[Instruction], payer: &Pubkey) -> Result<u64> {
+        let transaction = Transaction::new_unsigned(Message::new(instructions, Some(payer)));
+        let simulation = self.simulate_transaction(&transaction).await?;
+        if let Some(err) = simulation.err {
+            anyhow::bail!("Simulation failed: {}", err);
+        }
+        simulation
+            .units_consumed
+            .ok_or_else(|| anyhow::anyhow!("Simulation did not report compute units"))
+    }
+
+    async fn simulate_transaction(&self, transaction: &Transaction) -> Result<RpcSimulateTransactionResult> {
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(CommitmentConfig::processed()),
+            ..RpcSimulateTransactionConfig::default()
+        };
+        let simulation = self
+            .rpc("simulateTransaction", |rpc| {
+                let config = config.clone();
+                async move { rpc.simulate_transaction_with_config(transaction, config).await }
+            })
+            .await?;
+        Ok(simulation.value)
+    }
+
+    /// Forwards an allowlisted JSON-RPC call through the pool and returns
+    /// the node's raw result.
+    pub async fn passthrough(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value> {
+        let method = *self
+            .passthrough_methods
+            .get(method)
+            .ok_or_else(|| ApiError::Forbidden(format!("RPC method {} is not allowed", method)))?;
+        let result = self
+            .rpc(method, |rpc| {
+                let params = params.clone();
+                async move { rpc.send(RpcRequest::Custom { method }, params).await }
+            })
+            .await?;
+        Ok(result)
+    }
+
+    /// Simulates a built transaction, unsigned, and prices its message. A
+    /// failing transaction is reported in the result, not as an error.
+    pub async fn simulate(&self, unsigned: &UnsignedTransaction) -> Result<Simulation> {
+        let transaction: Transaction = bincode::deserialize(&BASE64.decode(&unsigned.transaction)?)?;
+        let simulation = self.simulate_transaction(&transaction).await?;
+        // Durable nonces aren't recent blockhashes, so they can't be priced.
+        let fee = self
+            .rpc("getFeeForMessage", |rpc| {
+                let message = &transaction.message;
+                async move { rpc.get_fee_for_message(message).await }
+            })
+            .await
+            .ok();
+        Ok(Simulation {
+            success: simulation.err.is_none(),
+            error: simulation.err.map(|err| err.to_string()),
+            units_consumed: simulation.units_consumed,
+            fee,
+            logs: simulation.logs.unwrap_or_default(),
+        })
+    }
+
+    /// The token program owning `mint`, SPL Token or Token-2022.
+    pub async fn mint_program(&self, mint: &Pubkey) -> Result<TokenProgram> {
+        let mint_account = self.get_raw_account(mint).await?;
+        let program = TokenProgram::from_owner(&mint_account.owner)
+            .filter(|_| parse_mint(&mint_account.data).is_some())
+            .ok_or_else(|| ApiError::BadRequest("Account is not a valid mint".to_string()))?;
+        Ok(program)
+    }
+
+    /// Derives `owner`'s ATA for `mint` under the mint's token program and
+    /// checks whether it has been created.
+    pub async fn associated_token_account(&self, owner: &Pubkey, mint: &Pubkey) -> Result<AssociatedTokenAccount> {
+        let program = self.mint_program(mint).await?;
+        let address = get_associated_token_address_with_program_id(owner, mint, &program.id());
+        let exists = self.get_multiple_accounts(&[address]).await?[0].is_some();
+        Ok(AssociatedTokenAccount { address, program, exists })
+    }
+
+    /// `transferChecked` between the owners' associated token accounts, or
+    /// from `source` when set, preceded by an idempotent ATA creation when
+    /// the recipient has none.
+    async fn token_transfer_instructions(
+        &self,
+        from: &Pubkey,
+        to: &Pubkey,
+        mint: &Pubkey,
+        amount: u64,
+        create_ata: bool,
+        source: Option<Pubkey>,
+    ) -> Result<Vec<Instruction>> {
+        let mint_account = self.get_raw_account(mint).await?;
+        let program = TokenProgram::from_owner(&mint_account.owner)
+            .ok_or_else(|| ApiError::BadRequest("Account is not a valid mint".to_string()))?;
+        let decimals = parse_mint(&mint_account.data)
+            .ok_or_else(|| ApiError::BadRequest("Account is not a valid mint".to_string()))?
+            .decimals;
+
+        let source = source.unwrap_or_else(|| get_associated_token_address_with_program_id(from, mint, &program.id()));
+        let destination = get_associated_token_address_with_program_id(to, mint, &program.id());
+
+        let mut instructions = Vec::new();
+        let destination_exists = self.get_multiple_accounts(&[destination]).await?[0].is_some();
+        if !destination_exists {
+            if !create_ata {
+                return Err(ApiError::BadRequest(
+                    "Recipient has no associated token account for this mint".to_string(),
+                )
+                .into());
+            }
+            instructions.push(create_associated_token_account_idempotent(from, to, mint, &program.id()));
+        }
+        instructions.push(spl_token_2022::instruction::transfer_checked(
+            &program.id(),
+            &source,
+            mint,
+            &destination,
+            from,
+            &[],
+            amount,
+            decimals,
+        )?);
+        Ok(instructions)
+    }
+
+    /// Builds the transfer, signs it with `signer` and sends it.
+    pub async fn create_transaction(
+        &self,
+        request: &crate::TransactionRequest,
+        signer: &dyn Signer,
+    ) -> Result<TransactionInfo> {
+        let mut transaction = self.build_transfer_transaction(request).await?;
+        signer::sign_transaction(&mut transaction, &[signer]).await?;
+        // A durable nonce can't be swapped for a fresh blockhash.
+        let signature = if request.nonce_account.is_some() {
+            self.send_transaction(&transaction).await?
+        } else {
+            self.send_with_fresh_blockhash(&mut transaction, &[signer]).await?
+        };
 
-        let signature = Signature::new_unique();
-        
         Ok(TransactionInfo {
             signature: signature.to_string(),
             status: "pending".to_string(),
-            slot: 0, // Would get from transaction confirmation
+            slot: None,
+            accounts: vec![request.from.clone(), request.to.clone()],
+            fee: None,
+            block_time: None,
+            memo: request.memo.clone(),
+            replaced_by: None,
         })
     }
 
     pub async fn get_transaction(&self, signature: &str) -> Result<TransactionInfo> {
-        let sig = Signature::from_str(signature)?;
-        let transaction = self.rpc_client.get_transaction(&sig, solana_client::rpc_config::RpcTransactionConfig::default())?;
+        let transaction = self.get_raw_transaction(&Signature::from_str(signature)?).await?;
+
+        let meta = transaction.transaction.meta.as_ref();
+        let decoded = transaction.transaction.transaction.decode();
+        let accounts = decoded
+            .as_ref()
+            .map(|tx| {
+                tx.message
+                    .static_account_keys()
+                    .iter()
+                    .map(|key| key.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
 
         Ok(TransactionInfo {
             signature: signature.to_string(),
-            status: if transaction.meta.as_ref().map_or(false, |m| m.err.is_none()) {
+            status: if meta.map_or(false, |m| m.err.is_none()) {
                 "confirmed".to_string()
             } else {
                 "failed".to_string()
             },
-            slot: transaction.slot,
+            slot: Some(transaction.slot),
+            accounts,
+            fee: meta.map(|m| m.fee),
+            block_time: transaction.block_time,
+            memo: decoded.and_then(|tx| memo::from_message(&tx.message)),
+            replaced_by: None,
+        })
+    }
+
+    /// The transaction with its status meta, base64-encoded so the message
+    /// can be decoded locally. Versioned transactions are included.
+    pub async fn get_raw_transaction(&self, signature: &Signature) -> Result<EncodedConfirmedTransactionWithStatusMeta> {
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            max_supported_transaction_version: Some(0),
+            ..RpcTransactionConfig::default()
+        };
+        self.rpc("getTransaction", |rpc| async move {
+            rpc.get_transaction_with_config(signature, config).await
         })
+        .await
+    }
+
+    /// Signatures of transactions that touched `address`, newest first,
+    /// starting after `before` when given.
+    pub async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before: Option<Signature>,
+        limit: usize,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        self.rpc("getSignaturesForAddress", |rpc| async move {
+            let config = GetConfirmedSignaturesForAddress2Config {
+                before,
+                until: None,
+                limit: Some(limit),
+                commitment: Some(CommitmentConfig::confirmed()),
+            };
+            rpc.get_signatures_for_address_with_config(address, config).await
+        })
+        .await
     }
 
     pub async fn get_token_info(&self, mint: &str) -> Result<serde_json::Value> {
         let pubkey = Pubkey::from_str(mint)?;
-        let account = self.rpc_client.get_account(&pubkey)?;
-        
-        if let Ok(mint_data) = spl_token::state::Mint::unpack(&account.data) {
-            Ok(serde_json::json!({
-                "mint": mint,
-                "supply": mint_data.supply,
-                "decimals": mint_data.decimals,
-                "mint_authority": mint_data.mint_authority.map(|p| p.to_string()),
-                "freeze_authority": mint_data.freeze_authority.map(|p| p.to_string()),
-            }))
-        } else {
-            Err(anyhow::anyhow!("Invalid mint account"))
+        let account = self.rpc("getAccountInfo", |rpc| async move { rpc.get_account(&pubkey).await }).await?;
+        token_info(mint, &account).ok_or_else(|| ApiError::BadRequest("Account is not a valid mint".to_string()).into())
+    }
+
+    /// Signs the swap's instructions with `signer`, which pays for the
+    /// transaction, and sends it, as a tipped Jito bundle when `bundle` is
+    /// set. Returns it with the blockhash it was sent on.
+    pub async fn execute_swap(
+        &self,
+        quote: &SwapQuote,
+        instructions: &[Instruction],
+        signer: &dyn Signer,
+        bundle: Option<&JitoClient>,
+    ) -> Result<(TransactionInfo, Hash)> {
+        let owner = signer.public_key();
+        let (signature, blockhash) = match bundle {
+            Some(jito) => self.sign_and_send_bundle(jito, instructions, &[signer]).await?,
+            None => {
+                let blockhash = self.latest_blockhash().await?;
+                let message = Message::new_with_blockhash(instructions, Some(&owner), &blockhash);
+                let mut signed = Transaction::new_unsigned(message);
+                signer::sign_transaction(&mut signed, &[signer]).await?;
+                let signature = self.send_with_fresh_blockhash(&mut signed, &[signer]).await?;
+                (signature, signed.message.recent_blockhash)
+            }
+        };
+
+        let transaction = TransactionInfo {
+            signature: signature.to_string(),
+            status: "pending".to_string(),
+            slot: None,
+            accounts: vec![owner.to_string(), quote.pool_id.clone()],
+            fee: None,
+            block_time: None,
+            memo: None,
+            replaced_by: None,
+        };
+        Ok((transaction, blockhash))
+    }
+
+    /// Waits until the cluster has seen `signature`, or until `blockhash`
+    /// is no longer valid and it never will.
+    pub async fn await_landing(&self, signature: &Signature, blockhash: &Hash) -> Result<Landing> {
+        loop {
+            // Checked before the status, so a transaction landing in between
+            // isn't taken for expired.
+            let valid = self
+                .rpc("isBlockhashValid", |rpc| async move {
+                    rpc.is_blockhash_valid(blockhash, CommitmentConfig::processed()).await
+                })
+                .await?;
+            let status = self.get_signature_statuses(std::slice::from_ref(signature)).await?;
+            if status.first().map_or(false, Option::is_some) {
+                return Ok(Landing::Landed);
+            }
+            if !valid {
+                return Ok(Landing::Expired);
+            }
+            tokio::time::sleep(LANDING_POLL_INTERVAL).await;
         }
     }
+}
 
-    pub async fn get_pools(&self, limit: usize, offset: usize) -> Result<Vec<serde_json::Value>> {
-        // This would typically query a DEX program for available pools
-        // For now, return mock data
-        Ok(vec![
-            serde_json::json!({
-                "id": "pool_1",
-                "token_a": "So11111111111111111111111111111111111111112",
-                "token_b": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
-                "liquidity": 1000000,
-                "volume_24h": 50000
-            })
-        ])
-    }
-
-    pub async fn get_pool_info(&self, pool_id: &str) -> Result<serde_json::Value> {
-        // This would query the specific pool
-        Ok(serde_json::json!({
-            "id": pool_id,
-            "token_a": "So11111111111111111111111111111111111111112",
-            "token_b": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
-            "liquidity": 1000000,
-            "volume_24h": 50000,
-            "fees_24h": 1000
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn native_transfer(wrap_sol: Option<bool>) -> crate::TransactionRequest {
+        serde_json::from_value(serde_json::json!({
+            "from": Pubkey::new_unique().to_string(),
+            "to": Pubkey::new_unique().to_string(),
+            "amount": 1_000_000,
+            "mint": spl_token::native_mint::id().to_string(),
+            "wrap_sol": wrap_sol,
         }))
+        .unwrap()
     }
 
-    pub async fn execute_swap(&self, request: &serde_json::Value) -> Result<TransactionInfo> {
-        // This would execute a swap transaction
-        let signature = Signature::new_unique();
-        
-        Ok(TransactionInfo {
-            signature: signature.to_string(),
-            status: "pending".to_string(),
-            slot: 0,
-        })
+    /// A Token-2022 mint account with a 1.5% transfer fee capped at 5000.
+    fn transfer_fee_mint() -> Account {
+        use spl_token_2022::extension::{transfer_fee::TransferFeeConfig, ExtensionType, StateWithExtensionsMut};
+        use spl_token_2022::state::Mint;
+
+        let len = ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferFeeConfig]).unwrap();
+        let mut data = vec![0; len];
+        let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+        config.newer_transfer_fee.transfer_fee_basis_points = 150.into();
+        config.newer_transfer_fee.maximum_fee = 5_000.into();
+        state.base = Mint {
+            mint_authority: COption::None,
+            supply: 1_000_000,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        Account {
+            lamports: 1,
+            data,
+            owner: spl_token_2022::id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn token_info_reads_token_2022_mints_with_their_transfer_fee() {
+        let info = token_info("mint", &transfer_fee_mint()).unwrap();
+        assert_eq!(info["program"], "spl-token-2022");
+        assert_eq!(info["supply"], 1_000_000);
+        assert_eq!(info["decimals"], 6);
+        assert_eq!(info["transfer_fee"]["basis_points"], 150);
+        assert_eq!(info["transfer_fee"]["maximum_fee"], 5_000);
+    }
+
+    #[test]
+    fn token_info_rejects_accounts_outside_the_token_programs() {
+        let account = Account {
+            owner: Pubkey::new_unique(),
+            ..transfer_fee_mint()
+        };
+        assert!(token_info("mint", &account).is_none());
+    }
+
+    #[test]
+    fn native_mint_transfer_wraps_sol_by_default() {
+        let request = native_transfer(None);
+        assert!(wraps_sol(&request));
+        assert_eq!(lamports_out(&request), 1_000_000);
+    }
+
+    #[test]
+    fn native_mint_transfer_without_wrapping_spends_no_sol() {
+        let request = native_transfer(Some(false));
+        assert!(!wraps_sol(&request));
+        assert_eq!(lamports_out(&request), 0);
     }
 }
@@ -0,0 +1,179 @@
+//! Squads v4 multisig: proposing vault transactions on behalf of a member.
+
+use crate::config::SquadsConfig;
+use crate::error::ApiError;
+use crate::pools::read_u64;
+use crate::signer::Signer;
+use crate::solana_client::SolanaClient;
+use crate::TransactionRequest;
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    pubkey,
+    pubkey::Pubkey,
+    system_program,
+};
+use std::str::FromStr;
+
+pub const PROGRAM_ID: Pubkey = pubkey!("SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf");
+
+const SEED_PREFIX: &[u8] = b"multisig";
+const SEED_VAULT: &[u8] = b"vault";
+const SEED_TRANSACTION: &[u8] = b"transaction";
+const SEED_PROPOSAL: &[u8] = b"proposal";
+
+// Discriminator, create key, config authority, threshold and time lock
+// precede the index of the multisig's latest transaction.
+const TRANSACTION_INDEX_OFFSET: usize = 78;
+
+/// A vault transaction created and approved by the proposing member.
+pub struct Proposal {
+    pub signature: String,
+    pub proposal: Pubkey,
+    pub transaction_index: u64,
+}
+
+/// Anchor discriminator of `name` in the given namespace.
+fn discriminator(namespace: &str, name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("{}:{}", namespace, name).as_bytes());
+    hash[..8].try_into().unwrap()
+}
+
+pub fn multisig_address(config: &SquadsConfig) -> Result<Pubkey> {
+    let multisig = config
+        .multisig
+        .as_deref()
+        .ok_or_else(|| ApiError::ServiceUnavailable("approvals.squads.multisig is not configured".to_string()))?;
+    Ok(Pubkey::from_str(multisig)?)
+}
+
+pub fn vault_address(multisig: &Pubkey, vault_index: u8) -> Pubkey {
+    Pubkey::find_program_address(&[SEED_PREFIX, multisig.as_ref(), SEED_VAULT, &[vault_index]], &PROGRAM_ID).0
+}
+
+fn transaction_address(multisig: &Pubkey, index: u64) -> Pubkey {
+    let index = index.to_le_bytes();
+    Pubkey::find_program_address(&[SEED_PREFIX, multisig.as_ref(), SEED_TRANSACTION, &index], &PROGRAM_ID).0
+}
+
+fn proposal_address(multisig: &Pubkey, index: u64) -> Pubkey {
+    let index = index.to_le_bytes();
+    Pubkey::find_program_address(
+        &[SEED_PREFIX, multisig.as_ref(), SEED_TRANSACTION, &index, SEED_PROPOSAL],
+        &PROGRAM_ID,
+    )
+    .0
+}
+
+/// Proposes the transfer in `request`, whose `from` is the vault, as the
+/// multisig's next vault transaction and casts `member`'s approval. The
+/// other members approve and execute it through Squads.
+pub async fn propose(
+    client: &SolanaClient,
+    config: &SquadsConfig,
+    member: &dyn Signer,
+    request: &TransactionRequest,
+) -> Result<Proposal> {
+    let multisig = multisig_address(config)?;
+    let account = client.get_raw_account(&multisig).await?;
+    if account.owner != PROGRAM_ID || account.data.get(..8) != Some(&discriminator("account", "Multisig")[..]) {
+        return Err(ApiError::BadRequest(format!("Account {} is not a Squads multisig", multisig)).into());
+    }
+    let transaction_index = read_u64(&account.data, TRANSACTION_INDEX_OFFSET) + 1;
+
+    let vault = vault_address(&multisig, config.vault_index);
+    let instructions = client.transfer_instructions(request).await?;
+    let message = transaction_message(&Message::new(&instructions, Some(&vault)));
+
+    let transaction = transaction_address(&multisig, transaction_index);
+    let proposal = proposal_address(&multisig, transaction_index);
+    let member_key = member.public_key();
+
+    let mut create_data = discriminator("global", "vault_transaction_create").to_vec();
+    create_data.push(config.vault_index);
+    // No ephemeral signers.
+    create_data.push(0);
+    create_data.extend_from_slice(&(message.len() as u32).to_le_bytes());
+    create_data.extend_from_slice(&message);
+    // No memo.
+    create_data.push(0);
+
+    let mut proposal_data = discriminator("global", "proposal_create").to_vec();
+    proposal_data.extend_from_slice(&transaction_index.to_le_bytes());
+    // Not a draft, so it can be voted on right away.
+    proposal_data.push(0);
+
+    let mut approve_data = discriminator("global", "proposal_approve").to_vec();
+    // No memo.
+    approve_data.push(0);
+
+    let instructions = [
+        Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &create_data,
+            vec![
+                AccountMeta::new(multisig, false),
+                AccountMeta::new(transaction, false),
+                AccountMeta::new_readonly(member_key, true),
+                AccountMeta::new(member_key, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        ),
+        Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &proposal_data,
+            vec![
+                AccountMeta::new_readonly(multisig, false),
+                AccountMeta::new(proposal, false),
+                AccountMeta::new_readonly(member_key, true),
+                AccountMeta::new(member_key, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        ),
+        Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &approve_data,
+            vec![
+                AccountMeta::new_readonly(multisig, false),
+                AccountMeta::new(member_key, true),
+                AccountMeta::new(proposal, false),
+            ],
+        ),
+    ];
+
+    let signature = client.sign_and_send(&instructions, &[member]).await?;
+    Ok(Proposal {
+        signature: signature.to_string(),
+        proposal,
+        transaction_index,
+    })
+}
+
+/// Serializes a compiled message as the program's `TransactionMessage`:
+/// signer and writable counts, then keys and instructions with one-byte
+/// length prefixes (two bytes for instruction data), and no lookup tables.
+fn transaction_message(message: &Message) -> Vec<u8> {
+    let header = &message.header;
+    let num_signers = header.num_required_signatures;
+    let num_writable_signers = num_signers - header.num_readonly_signed_accounts;
+    let num_writable_non_signers =
+        message.account_keys.len() as u8 - num_signers - header.num_readonly_unsigned_accounts;
+
+    let mut data = vec![num_signers, num_writable_signers, num_writable_non_signers];
+    data.push(message.account_keys.len() as u8);
+    for key in &message.account_keys {
+        data.extend_from_slice(key.as_ref());
+    }
+    data.push(message.instructions.len() as u8);
+    for instruction in &message.instructions {
+        data.push(instruction.program_id_index);
+        data.push(instruction.accounts.len() as u8);
+        data.extend_from_slice(&instruction.accounts);
+        data.extend_from_slice(&(instruction.data.len() as u16).to_le_bytes());
+        data.extend_from_slice(&instruction.data);
+    }
+    data.push(0);
+    data
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use solana_account_decoder::parse_stake::{parse_stake, StakeAccountType, UiStakeAccount};
+use solana_client::rpc_response::RpcInflationReward;
+use solana_sdk::{clock::Epoch, pubkey::Pubkey};
+use utoipa::ToSchema;
+
+// Offsets of the staker and withdrawer authorities in stake account data.
+pub const STAKER_OFFSET: usize = 12;
+pub const WITHDRAWER_OFFSET: usize = 44;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StakeActivation {
+    Inactive,
+    Activating,
+    Active,
+    Deactivating,
+}
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct Delegation {
+    /// Vote account of the validator the stake is delegated to.
+    pub voter: String,
+    pub stake: u64,
+    pub activation_epoch: u64,
+    /// `u64::MAX` until the stake is deactivated.
+    pub deactivation_epoch: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct StakeReward {
+    pub epoch: u64,
+    pub amount: u64,
+    pub post_balance: u64,
+    pub commission: Option<u8>,
+}
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct StakeAccount {
+    pub address: String,
+    pub lamports: u64,
+    pub staker: String,
+    pub withdrawer: String,
+    pub rent_exempt_reserve: u64,
+    /// Unix timestamp before which withdrawals need the lockup custodian.
+    pub lockup_unix_timestamp: i64,
+    pub lockup_epoch: u64,
+    pub delegation: Option<Delegation>,
+    pub activation: StakeActivation,
+    /// Inflation reward credited in the last completed epoch, when requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_reward: Option<StakeReward>,
+}
+
+impl StakeAccount {
+    /// Decodes initialized and delegated stake accounts; `current_epoch`
+    /// decides the activation state. Partial warmup and cooldown are
+    /// reported as activating or deactivating.
+    pub fn parse(address: &Pubkey, lamports: u64, data: &[u8], current_epoch: Epoch) -> Option<Self> {
+        let account = match parse_stake(data).ok()? {
+            StakeAccountType::Initialized(account) | StakeAccountType::Delegated(account) => account,
+            _ => return None,
+        };
+        let UiStakeAccount { meta, stake } = account;
+
+        let delegation = match stake {
+            Some(stake) => Some(Delegation {
+                voter: stake.delegation.voter,
+                stake: stake.delegation.stake.parse().ok()?,
+                activation_epoch: stake.delegation.activation_epoch.parse().ok()?,
+                deactivation_epoch: stake.delegation.deactivation_epoch.parse().ok()?,
+            }),
+            None => None,
+        };
+        let activation = match &delegation {
+            None => StakeActivation::Inactive,
+            Some(d) if d.deactivation_epoch != u64::MAX => {
+                if d.deactivation_epoch >= current_epoch {
+                    StakeActivation::Deactivating
+                } else {
+                    StakeActivation::Inactive
+                }
+            }
+            Some(d) if d.activation_epoch >= current_epoch => StakeActivation::Activating,
+            Some(_) => StakeActivation::Active,
+        };
+
+        Some(Self {
+            address: address.to_string(),
+            lamports,
+            staker: meta.authorized.staker,
+            withdrawer: meta.authorized.withdrawer,
+            rent_exempt_reserve: meta.rent_exempt_reserve.parse().ok()?,
+            lockup_unix_timestamp: meta.lockup.unix_timestamp,
+            lockup_epoch: meta.lockup.epoch,
+            delegation,
+            activation,
+            last_reward: None,
+        })
+    }
+}
+
+impl From<RpcInflationReward> for StakeReward {
+    fn from(reward: RpcInflationReward) -> Self {
+        Self {
+            epoch: reward.epoch,
+            amount: reward.amount,
+            post_balance: reward.post_balance,
+            commission: reward.commission,
+        }
+    }
+}
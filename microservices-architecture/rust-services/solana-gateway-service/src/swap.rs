@@ -0,0 +1,566 @@
+use crate::config::SwapConfig;
+use crate::database::Database;
+use crate::error::ApiError;
+use crate::pools::route_cache::RouteCache;
+use crate::pools::routing::{self, Route, RouteHop};
+use crate::pools::adapter::SwapParams;
+use crate::pools::{self, Dex, Pool, PoolQuote};
+use crate::price_oracle::PriceOracle;
+use crate::solana_client::SolanaClient;
+use crate::wsol;
+use crate::AppState;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id, instruction::create_associated_token_account_idempotent,
+};
+use std::str::FromStr;
+use tracing::warn;
+use utoipa::ToSchema;
+
+// Stored pools of a pair compared when the request names no pool.
+const MAX_PAIR_POOLS: i64 = 20;
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct SwapRequest {
+    /// Pool to trade through. When omitted, every known pool trading
+    /// `input_mint` against `output_mint` is quoted and the best is used.
+    pub pool_id: Option<String>,
+    pub input_mint: String,
+    /// Required when `pool_id` is omitted.
+    pub output_mint: Option<String>,
+    /// Raw units of `input_mint`.
+    pub amount_in: u64,
+    /// Absolute floor on the output; the swap is rejected if the current
+    /// quote is already below it.
+    pub min_amount_out: Option<u64>,
+    /// Tolerated shortfall from the current quote, in basis points.
+    pub slippage_bps: Option<u16>,
+    /// Submits the swap as a tipped Jito bundle instead of through the
+    /// public RPC, protecting it from sandwiching.
+    #[serde(default)]
+    pub mev_protection: bool,
+    /// From `POST /api/v1/swap/quote`; required when signed quotes are
+    /// enabled. The swap goes through the quoted pool, and is refused once
+    /// the quote expired or the price moved too far from it.
+    pub quote_id: Option<String>,
+}
+
+/// A swap along the best single- or two-hop route between two mints.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct RouteSwapRequest {
+    pub input_mint: String,
+    pub output_mint: String,
+    /// Raw units of `input_mint`.
+    pub amount_in: u64,
+    /// Absolute floor on the route's final output.
+    pub min_amount_out: Option<u64>,
+    /// Tolerated shortfall in basis points, applied to every hop.
+    pub slippage_bps: Option<u16>,
+}
+
+/// Expected output of a swap and the minimum enforced on execution.
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct SwapQuote {
+    pub pool_id: String,
+    pub dex: Dex,
+    pub input_mint: String,
+    pub output_mint: String,
+    pub amount_in: u64,
+    /// Part of `amount_in` paid to the platform treasury; the rest is
+    /// swapped.
+    pub platform_fee: u64,
+    pub expected_amount_out: u64,
+    pub min_amount_out: u64,
+    pub fee_amount: u64,
+}
+
+/// Where a compared quote comes from: the gateway's router, Jupiter, or the
+/// best direct pool of one DEX.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteSource {
+    Router,
+    Jupiter,
+    RaydiumAmm,
+    RaydiumClmm,
+    OrcaWhirlpool,
+}
+
+impl From<Dex> for QuoteSource {
+    fn from(dex: Dex) -> Self {
+        match dex {
+            Dex::RaydiumAmm => QuoteSource::RaydiumAmm,
+            Dex::RaydiumClmm => QuoteSource::RaydiumClmm,
+            Dex::OrcaWhirlpool => QuoteSource::OrcaWhirlpool,
+        }
+    }
+}
+
+/// One swap of a compared quote. Jupiter's split routes list a hop per leg.
+#[derive(Serialize, ToSchema)]
+pub struct QuoteHop {
+    pub pool_id: String,
+    /// DEX the pool belongs to, as named by the source.
+    pub venue: String,
+    pub input_mint: String,
+    pub output_mint: String,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SourceQuote {
+    pub source: QuoteSource,
+    /// Absent when the source has no route for the pair or failed to quote.
+    pub amount_out: Option<u64>,
+    pub price_impact_bps: Option<f64>,
+    pub hops: Vec<QuoteHop>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct QuoteComparison {
+    pub input_mint: String,
+    pub output_mint: String,
+    pub amount_in: u64,
+    /// Taken out of `amount_in` before quoting, as on execution.
+    pub platform_fee: u64,
+    /// The source paying out the most.
+    pub best: Option<QuoteSource>,
+    /// Best output first, then sources without a quote.
+    pub quotes: Vec<SourceQuote>,
+}
+
+impl SourceQuote {
+    fn unavailable(source: QuoteSource, error: String) -> Self {
+        Self {
+            source,
+            amount_out: None,
+            price_impact_bps: None,
+            hops: Vec::new(),
+            error: Some(error),
+        }
+    }
+
+    fn from_route(source: QuoteSource, route: &Route) -> Self {
+        // Each hop's impact compounds on the output of the one before.
+        let retained = route.hops.iter().try_fold(1.0, |retained, hop| {
+            hop.price_impact_bps.map(|bps| retained * (1.0 - bps / 10_000.0))
+        });
+        Self {
+            source,
+            amount_out: Some(route.amount_out),
+            price_impact_bps: retained.map(|retained| (1.0 - retained) * 10_000.0),
+            hops: route.hops.iter().map(QuoteHop::from).collect(),
+            error: None,
+        }
+    }
+}
+
+impl From<&RouteHop> for QuoteHop {
+    fn from(hop: &RouteHop) -> Self {
+        Self {
+            pool_id: hop.pool_id.clone(),
+            venue: hop.dex.as_str().to_string(),
+            input_mint: hop.input_mint.clone(),
+            output_mint: hop.output_mint.clone(),
+            amount_in: hop.amount_in,
+            amount_out: hop.amount_out,
+            fee_amount: hop.fee_amount,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FeePeriod {
+    Hour,
+    Day,
+    Week,
+    Month,
+}
+
+/// Platform fees collected in one token over one period.
+#[derive(Serialize, sqlx::FromRow, ToSchema)]
+pub struct FeeSummary {
+    pub period_start: DateTime<Utc>,
+    pub mint: String,
+    pub swaps: i64,
+    /// Raw units of `mint`.
+    pub amount: i64,
+}
+
+impl FeePeriod {
+    /// Unit accepted by Postgres `date_trunc`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FeePeriod::Hour => "hour",
+            FeePeriod::Day => "day",
+            FeePeriod::Week => "week",
+            FeePeriod::Month => "month",
+        }
+    }
+}
+
+/// Quotes a validated `request` against live pool state and enforces its
+/// slippage limits and the oracle price sanity check. Returns the pool the
+/// swap goes through with the quote.
+pub async fn prepare(
+    client: &SolanaClient,
+    database: &Database,
+    oracle: &PriceOracle,
+    config: &SwapConfig,
+    request: &SwapRequest,
+) -> Result<(Pool, SwapQuote)> {
+    let slippage_bps = request.slippage_bps.unwrap_or(config.default_slippage_bps);
+    let platform_fee = platform_fee(request.amount_in, config.platform_fee_bps);
+    let swapped = request.amount_in - platform_fee;
+    let (pool, quote) = select_pool(client, database, request, swapped).await?;
+
+    let min_amount_out = min_amount_out(quote.amount_out, request.min_amount_out, slippage_bps)?;
+    check_rate(
+        client,
+        oracle,
+        &request.input_mint,
+        &quote.output_mint,
+        swapped,
+        quote.amount_out,
+    )
+    .await?;
+
+    let quote = SwapQuote {
+        pool_id: pool.id.clone(),
+        dex: pool.dex,
+        input_mint: request.input_mint.clone(),
+        output_mint: quote.output_mint,
+        amount_in: request.amount_in,
+        platform_fee,
+        expected_amount_out: quote.amount_out,
+        min_amount_out,
+        fee_amount: quote.fee_amount,
+    };
+    Ok((pool, quote))
+}
+
+/// Finds the best route for a validated `request` and enforces its slippage
+/// limits and the oracle price sanity check on the route's overall rate.
+/// Returns the route with the pools it trades through, the minimum output
+/// the last hop enforces and the platform fee, which the route's input
+/// excludes.
+pub async fn prepare_route(
+    client: &SolanaClient,
+    database: &Database,
+    route_cache: &RouteCache,
+    oracle: &PriceOracle,
+    config: &SwapConfig,
+    request: &RouteSwapRequest,
+) -> Result<(Route, Vec<Pool>, u64, u64)> {
+    let slippage_bps = request.slippage_bps.unwrap_or(config.default_slippage_bps);
+    let platform_fee = platform_fee(request.amount_in, config.platform_fee_bps);
+    let (route, pools) = route_cache
+        .find_routes(
+            client,
+            database,
+            &request.input_mint,
+            &request.output_mint,
+            request.amount_in - platform_fee,
+        )
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| ApiError::NotFound("No route trades this pair".to_string()))?;
+
+    let min_amount_out = min_amount_out(route.amount_out, request.min_amount_out, slippage_bps)?;
+    check_rate(
+        client,
+        oracle,
+        &request.input_mint,
+        &request.output_mint,
+        route.amount_in,
+        route.amount_out,
+    )
+    .await?;
+    Ok((route, pools, min_amount_out, platform_fee))
+}
+
+/// Quotes the pair through the gateway's router, the best direct pool of
+/// each DEX and, when enabled, Jupiter. A source that can't quote is listed
+/// with its error rather than failing the comparison.
+pub async fn compare_quotes(state: &AppState, input_mint: &str, output_mint: &str, amount_in: u64) -> QuoteComparison {
+    let (config, jupiter) = (&state.config.swap, &state.jupiter);
+    let platform_fee = platform_fee(amount_in, config.platform_fee_bps);
+    let swapped = amount_in - platform_fee;
+    let jupiter_quote = async {
+        if !jupiter.is_enabled() {
+            return None;
+        }
+        let quote = jupiter
+            .quote(input_mint, output_mint, swapped, config.default_slippage_bps)
+            .await;
+        Some(quote.map(|quote| SourceQuote {
+            source: QuoteSource::Jupiter,
+            amount_out: Some(quote.out_amount),
+            price_impact_bps: quote
+                .price_impact_pct
+                .and_then(|pct| pct.parse::<f64>().ok())
+                .map(|pct| pct * 10_000.0),
+            hops: quote
+                .route_plan
+                .into_iter()
+                .map(|step| QuoteHop {
+                    pool_id: step.swap_info.amm_key,
+                    venue: step.swap_info.label.unwrap_or_default(),
+                    input_mint: step.swap_info.input_mint,
+                    output_mint: step.swap_info.output_mint,
+                    amount_in: step.swap_info.in_amount,
+                    amount_out: step.swap_info.out_amount,
+                    fee_amount: step.swap_info.fee_amount,
+                })
+                .collect(),
+            error: None,
+        }))
+    };
+    let (routes, jupiter_quote) = tokio::join!(
+        state
+            .route_cache
+            .find_routes(&state.solana_client, &state.database, input_mint, output_mint, swapped),
+        jupiter_quote,
+    );
+
+    let mut quotes = Vec::new();
+    match routes {
+        Ok(routes) => {
+            quotes.push(match routes.first() {
+                Some((route, _)) => SourceQuote::from_route(QuoteSource::Router, route),
+                None => SourceQuote::unavailable(QuoteSource::Router, "No route trades this pair".to_string()),
+            });
+            // Routes are sorted best first, so the first direct one of a DEX
+            // is its best pool.
+            for dex in Dex::ALL {
+                let direct = routes
+                    .iter()
+                    .find(|(route, _)| route.hops.len() == 1 && route.hops[0].dex == dex);
+                quotes.push(match direct {
+                    Some((route, _)) => SourceQuote::from_route(dex.into(), route),
+                    None => SourceQuote::unavailable(dex.into(), "No pool of this DEX trades the pair".to_string()),
+                });
+            }
+        }
+        Err(e) => {
+            warn!("Failed to find routes from {} to {}: {}", input_mint, output_mint, e);
+            quotes.push(SourceQuote::unavailable(QuoteSource::Router, e.to_string()));
+            for dex in Dex::ALL {
+                quotes.push(SourceQuote::unavailable(dex.into(), e.to_string()));
+            }
+        }
+    }
+    match jupiter_quote {
+        Some(Ok(quote)) => quotes.push(quote),
+        Some(Err(e)) => {
+            warn!("Failed to get Jupiter quote from {} to {}: {}", input_mint, output_mint, e);
+            quotes.push(SourceQuote::unavailable(QuoteSource::Jupiter, e.to_string()));
+        }
+        None => {}
+    }
+
+    quotes.sort_by_key(|quote| std::cmp::Reverse(quote.amount_out));
+    QuoteComparison {
+        input_mint: input_mint.to_string(),
+        output_mint: output_mint.to_string(),
+        amount_in,
+        platform_fee,
+        best: quotes.first().filter(|quote| quote.amount_out.is_some()).map(|quote| quote.source),
+        quotes,
+    }
+}
+
+/// Every instruction of `owner`'s swap transaction for `quote`: SOL input
+/// wrapping, the platform fee transfer, the swap through `pool` and SOL
+/// unwrapping. Shared by client-signed and gateway-executed swaps.
+pub async fn transaction_ixs(
+    client: &SolanaClient,
+    config: &SwapConfig,
+    pool: &Pool,
+    quote: &SwapQuote,
+    owner: &Pubkey,
+) -> Result<Vec<Instruction>> {
+    let input_mint = Pubkey::from_str(&quote.input_mint)?;
+    let output_mint = Pubkey::from_str(&quote.output_mint)?;
+    let wrapping = native_sol_ixs(config, owner, &input_mint, &output_mint, quote.amount_in)?;
+
+    let mut instructions = wrapping.before;
+    instructions.extend(
+        platform_fee_ixs(client, config, owner, &input_mint, quote.platform_fee, wrapping.account).await?,
+    );
+    instructions.extend(swap_ixs(client, pool, quote, owner, wrapping.account).await?);
+    instructions.extend(wrapping.after);
+    Ok(instructions)
+}
+
+/// Creates `owner`'s output account and swaps the quoted input, less the
+/// platform fee, through `pool` with `quote.min_amount_out` as the on-chain
+/// minimum. Native SOL sides go through `wsol_account` when set. The fee
+/// transfer and SOL wrapping are not included.
+pub async fn swap_ixs(
+    client: &SolanaClient,
+    pool: &Pool,
+    quote: &SwapQuote,
+    owner: &Pubkey,
+    wsol_account: Option<Pubkey>,
+) -> Result<Vec<Instruction>> {
+    let output_mint = Pubkey::from_str(&quote.output_mint)?;
+    let params = SwapParams {
+        owner: *owner,
+        input_mint: Pubkey::from_str(&quote.input_mint)?,
+        amount_in: quote.amount_in - quote.platform_fee,
+        min_amount_out: quote.min_amount_out,
+        output_program: client.mint_program(&output_mint).await?.id(),
+        wsol_account,
+    };
+    let mut instructions = Vec::new();
+    if !params.wraps(&output_mint) {
+        instructions.push(create_associated_token_account_idempotent(
+            owner,
+            owner,
+            &output_mint,
+            &params.output_program,
+        ));
+    }
+    instructions.extend(pool.dex.adapter().build_swap_ix(client, pool, &params).await?);
+    Ok(instructions)
+}
+
+pub fn platform_fee(amount_in: u64, fee_bps: u16) -> u64 {
+    (amount_in as u128 * fee_bps as u128 / 10_000) as u64
+}
+
+/// Moves `fee` units of `mint` from the owner's associated token account,
+/// or for native SOL from `wsol_account` when set, to the treasury's,
+/// creating the latter (at the owner's expense) when it doesn't exist yet.
+/// Both accounts are under the mint's token program. Empty when no fee is
+/// due.
+pub async fn platform_fee_ixs(
+    client: &SolanaClient,
+    config: &SwapConfig,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    fee: u64,
+    wsol_account: Option<Pubkey>,
+) -> Result<Vec<Instruction>> {
+    let Some(treasury) = config.treasury.as_deref().filter(|_| fee > 0) else {
+        return Ok(Vec::new());
+    };
+    let treasury = Pubkey::from_str(treasury)?;
+    let program = client.mint_program(mint).await?.id();
+    let decimals = client
+        .resolve_mints(&[*mint])
+        .await?
+        .get(mint)
+        .map(|metadata| metadata.decimals)
+        .ok_or_else(|| ApiError::BadRequest(format!("{} is not a token mint", mint)))?;
+
+    let source = wsol_account
+        .filter(|_| wsol::is_native(mint))
+        .unwrap_or_else(|| get_associated_token_address_with_program_id(owner, mint, &program));
+    let treasury_account = get_associated_token_address_with_program_id(&treasury, mint, &program);
+    Ok(vec![
+        create_associated_token_account_idempotent(owner, &treasury, mint, &program),
+        spl_token_2022::instruction::transfer_checked(
+            &program,
+            &source,
+            mint,
+            &treasury_account,
+            owner,
+            &[],
+            fee,
+            decimals,
+        )?,
+    ])
+}
+
+/// Wrapping around a swap of `amount_in` units, platform fee included, when
+/// either side is native SOL. Nothing is wrapped when `wrap_sol` is off, and
+/// the owner's own wSOL account is used instead.
+pub fn native_sol_ixs(
+    config: &SwapConfig,
+    owner: &Pubkey,
+    input_mint: &Pubkey,
+    output_mint: &Pubkey,
+    amount_in: u64,
+) -> Result<wsol::SwapWrapping> {
+    if !config.wrap_sol {
+        return Ok(wsol::SwapWrapping::default());
+    }
+    wsol::around_swap(owner, input_mint, output_mint, amount_in)
+}
+
+/// The larger of the caller's floor and the slippage floor, rejecting
+/// quotes already below it.
+fn min_amount_out(expected: u64, floor: Option<u64>, slippage_bps: u16) -> Result<u64> {
+    let min_amount_out = floor.unwrap_or(0).max(routing::slippage_floor(expected, slippage_bps));
+    if expected < min_amount_out {
+        return Err(ApiError::BadRequest(format!(
+            "Expected output {} is below min_amount_out {}",
+            expected, min_amount_out
+        ))
+        .into());
+    }
+    Ok(min_amount_out)
+}
+
+/// Checks the decimal-adjusted rate against the oracle, when both mints
+/// resolve.
+async fn check_rate(
+    client: &SolanaClient,
+    oracle: &PriceOracle,
+    input_mint: &str,
+    output_mint: &str,
+    amount_in: u64,
+    amount_out: u64,
+) -> Result<()> {
+    let input = Pubkey::from_str(input_mint)?;
+    let output = Pubkey::from_str(output_mint)?;
+    let mints = client.resolve_mints(&[input, output]).await?;
+    if let (Some(input), Some(output)) = (mints.get(&input), mints.get(&output)) {
+        let rate = (amount_out as f64 / 10f64.powi(output.decimals as i32))
+            / (amount_in as f64 / 10f64.powi(input.decimals as i32));
+        oracle.check_rate(input_mint, output_mint, rate).await?;
+    }
+    Ok(())
+}
+
+/// The requested pool, or the best-paying known pool for the pair.
+async fn select_pool(
+    client: &SolanaClient,
+    database: &Database,
+    request: &SwapRequest,
+    amount_in: u64,
+) -> Result<(Pool, PoolQuote)> {
+    let Some(pool_id) = &request.pool_id else {
+        let output_mint = request.output_mint.as_deref().unwrap_or_default();
+        let addresses = database
+            .list_pair_pool_addresses(&request.input_mint, output_mint, MAX_PAIR_POOLS)
+            .await?
+            .iter()
+            .map(|address| Pubkey::from_str(address))
+            .collect::<Result<Vec<_>, _>>()?;
+        let pools = pools::fetch_pools(client, &addresses).await?;
+        return pools::best_quote(client, pools, &request.input_mint, amount_in)
+            .await
+            .ok_or_else(|| ApiError::NotFound("No pool trades this pair".to_string()).into());
+    };
+
+    let pool = pools::fetch_pool(client, pool_id).await?;
+    let quote = pools::quote_live(client, &pool, &request.input_mint, amount_in)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("input_mint is not traded by this pool".to_string()))?;
+    if request.output_mint.as_ref().is_some_and(|mint| *mint != quote.output_mint) {
+        return Err(ApiError::BadRequest("output_mint is not traded by this pool".to_string()).into());
+    }
+    Ok((pool, quote))
+}
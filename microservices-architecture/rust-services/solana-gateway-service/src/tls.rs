@@ -0,0 +1,61 @@
+//! HTTPS for the REST API. The certificate and key are reloaded when either
+//! file changes, so rotations (e.g. by cert-manager) need no restart.
+
+use crate::config::TlsConfig;
+use anyhow::{anyhow, Context, Result};
+use axum_server::tls_rustls::RustlsConfig;
+use std::time::{Duration, SystemTime};
+use tracing::{info, warn};
+
+fn paths(config: &TlsConfig) -> Result<(&str, &str)> {
+    match (config.cert_path.as_deref(), config.key_path.as_deref()) {
+        (Some(cert), Some(key)) => Ok((cert, key)),
+        _ => Err(anyhow!(
+            "tls.cert_path and tls.key_path are required when TLS is enabled"
+        )),
+    }
+}
+
+pub async fn load(config: &TlsConfig) -> Result<RustlsConfig> {
+    let (cert, key) = paths(config)?;
+    RustlsConfig::from_pem_file(cert, key)
+        .await
+        .with_context(|| format!("Failed to load TLS certificate {} and key {}", cert, key))
+}
+
+async fn modified(path: &str) -> Option<SystemTime> {
+    tokio::fs::metadata(path)
+        .await
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Reloads the certificate and key when their modification times change.
+/// A pair that fails to load, e.g. while only one file was replaced, keeps
+/// the current certificate and is retried on the next check.
+pub fn spawn_reload(rustls: RustlsConfig, config: TlsConfig) {
+    if config.reload_secs == 0 {
+        return;
+    }
+    tokio::spawn(async move {
+        let Ok((cert, key)) = paths(&config) else {
+            return;
+        };
+        let mut loaded = (modified(cert).await, modified(key).await);
+        let mut interval = tokio::time::interval(Duration::from_secs(config.reload_secs));
+        loop {
+            interval.tick().await;
+            let current = (modified(cert).await, modified(key).await);
+            if current == loaded {
+                continue;
+            }
+            match rustls.reload_from_pem_file(cert, key).await {
+                Ok(()) => {
+                    info!("Reloaded TLS certificate {}", cert);
+                    loaded = current;
+                }
+                Err(e) => warn!("Failed to reload TLS certificate {}: {}", cert, e),
+            }
+        }
+    });
+}
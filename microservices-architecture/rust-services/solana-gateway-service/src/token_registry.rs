@@ -0,0 +1,152 @@
+use crate::config::TokenRegistryConfig;
+use crate::database::Database;
+use crate::solana_client::TokenBalance;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+/// Off-chain description of a mint, from the configured token list.
+#[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct TokenMetadata {
+    pub symbol: String,
+    pub name: String,
+    pub logo_uri: Option<String>,
+    pub coingecko_id: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct TokenMetadataRow {
+    pub mint: String,
+    #[sqlx(flatten)]
+    pub metadata: TokenMetadata,
+}
+
+// The Solana token-list format wraps entries in `tokens`; Jupiter-style
+// lists are a bare array of the same entries.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TokenList {
+    Wrapped { tokens: Vec<TokenListEntry> },
+    Bare(Vec<TokenListEntry>),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenListEntry {
+    address: String,
+    symbol: String,
+    name: String,
+    chain_id: Option<u64>,
+    #[serde(rename = "logoURI")]
+    logo_uri: Option<String>,
+    #[serde(default)]
+    extensions: TokenListExtensions,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenListExtensions {
+    coingecko_id: Option<String>,
+}
+
+/// Token metadata merged into mint and balance responses. The list is
+/// stored in the database, so replicas and restarts serve it without
+/// refetching, and held in memory for lookups.
+pub struct TokenRegistry {
+    database: Arc<Database>,
+    http: reqwest::Client,
+    config: TokenRegistryConfig,
+    tokens: RwLock<HashMap<String, TokenMetadata>>,
+}
+
+impl TokenList {
+    fn into_entries(self) -> Vec<TokenListEntry> {
+        match self {
+            TokenList::Wrapped { tokens } => tokens,
+            TokenList::Bare(tokens) => tokens,
+        }
+    }
+}
+
+impl TokenRegistry {
+    pub fn new(database: Arc<Database>, config: &TokenRegistryConfig) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()?;
+        Ok(Self {
+            database,
+            http,
+            config: config.clone(),
+            tokens: RwLock::new(HashMap::new()),
+        })
+    }
+
+    pub fn get(&self, mint: &str) -> Option<TokenMetadata> {
+        self.tokens.read().unwrap().get(mint).cloned()
+    }
+
+    /// Attaches metadata to every balance whose mint is listed.
+    pub fn enrich(&self, balances: &mut [TokenBalance]) {
+        let tokens = self.tokens.read().unwrap();
+        for balance in balances {
+            balance.metadata = tokens.get(&balance.mint).cloned();
+        }
+    }
+
+    /// Fetches the token list into the database, when one is configured,
+    /// then reloads the in-memory copy from the database.
+    async fn refresh(&self) -> Result<()> {
+        if let Some(url) = &self.config.list_url {
+            match self.fetch(url).await {
+                Ok(tokens) => {
+                    self.database.upsert_token_metadata(&tokens).await?;
+                    info!("Loaded {} tokens from {}", tokens.len(), url);
+                }
+                Err(e) => warn!("Failed to fetch token list from {}: {}", url, e),
+            }
+        }
+        let tokens = self.database.list_token_metadata().await?;
+        *self.tokens.write().unwrap() = tokens.into_iter().map(|row| (row.mint, row.metadata)).collect();
+        Ok(())
+    }
+
+    async fn fetch(&self, url: &str) -> Result<Vec<TokenMetadataRow>> {
+        let list: TokenList = self.http.get(url).send().await?.error_for_status()?.json().await?;
+        Ok(list
+            .into_entries()
+            .into_iter()
+            .filter(|entry| match (self.config.chain_id, entry.chain_id) {
+                (Some(wanted), Some(chain_id)) => wanted == chain_id,
+                _ => true,
+            })
+            .map(|entry| TokenMetadataRow {
+                mint: entry.address,
+                metadata: TokenMetadata {
+                    symbol: entry.symbol,
+                    name: entry.name,
+                    logo_uri: entry.logo_uri,
+                    coingecko_id: entry.extensions.coingecko_id,
+                },
+            })
+            .collect())
+    }
+}
+
+pub fn spawn_refresh(registry: Arc<TokenRegistry>, config: TokenRegistryConfig) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.refresh_secs.max(60)));
+        loop {
+            interval.tick().await;
+            match registry.refresh().await {
+                // Without a list, nothing changes after the first load.
+                Ok(()) if config.list_url.is_none() => return,
+                Ok(()) => {}
+                Err(e) => warn!("Failed to refresh token registry: {}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,213 @@
+use crate::config::TokenSafetyConfig;
+use crate::error::ApiError;
+use crate::solana_client::SolanaClient;
+use crate::token_registry::TokenRegistry;
+use crate::tokens::TokenProgram;
+use anyhow::Result;
+use moka::future::Cache;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{program_option::COption, pubkey::Pubkey};
+use spl_token_2022::{
+    extension::{
+        default_account_state::DefaultAccountState, non_transferable::NonTransferable,
+        permanent_delegate::PermanentDelegate, transfer_fee::TransferFeeConfig, transfer_hook::TransferHook,
+        BaseStateWithExtensions, StateWithExtensions,
+    },
+    state::{AccountState, Mint},
+};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use utoipa::ToSchema;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskLevel {
+    Low,
+    Medium,
+    High,
+}
+
+impl RiskLevel {
+    fn score(self) -> u8 {
+        match self {
+            RiskLevel::Low => 10,
+            RiskLevel::Medium => 25,
+            RiskLevel::High => 50,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskKind {
+    /// Supply can still be inflated.
+    MintAuthority,
+    /// Holders' accounts can be frozen.
+    FreezeAuthority,
+    /// New token accounts start frozen.
+    DefaultFrozen,
+    /// Token-2022 transfer fee.
+    TransferFee,
+    /// A delegate can move or burn any holder's tokens.
+    PermanentDelegate,
+    /// Transfers run a program that can reject them.
+    TransferHook,
+    /// Tokens can't be transferred at all.
+    NonTransferable,
+    /// The largest accounts hold more of the supply than configured.
+    ConcentratedHolders,
+    /// Not in the token registry.
+    UnverifiedMetadata,
+}
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct RiskFlag {
+    pub kind: RiskKind,
+    pub level: RiskLevel,
+    pub detail: String,
+}
+
+/// Safety assessment of a mint. `score` adds up the flags' weights, capped
+/// at 100.
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct RiskReport {
+    pub level: RiskLevel,
+    pub score: u8,
+    pub flags: Vec<RiskFlag>,
+}
+
+impl RiskReport {
+    fn new(flags: Vec<RiskFlag>) -> Self {
+        let score = flags.iter().map(|flag| flag.level.score() as u32).sum::<u32>().min(100) as u8;
+        Self {
+            level: flags.iter().map(|flag| flag.level).max().unwrap_or(RiskLevel::Low),
+            score,
+            flags,
+        }
+    }
+}
+
+fn flag(kind: RiskKind, level: RiskLevel, detail: impl Into<String>) -> RiskFlag {
+    RiskFlag {
+        kind,
+        level,
+        detail: detail.into(),
+    }
+}
+
+/// Flags in a mint's own state: authorities and Token-2022 extensions
+/// that let the issuer restrict or tax holders.
+pub fn mint_flags(data: &[u8]) -> Option<Vec<RiskFlag>> {
+    let state = StateWithExtensions::<Mint>::unpack(data).ok()?;
+    let mut flags = Vec::new();
+    if let COption::Some(authority) = state.base.mint_authority {
+        flags.push(flag(RiskKind::MintAuthority, RiskLevel::Medium, format!("mint authority {}", authority)));
+    }
+    if let COption::Some(authority) = state.base.freeze_authority {
+        flags.push(flag(RiskKind::FreezeAuthority, RiskLevel::High, format!("freeze authority {}", authority)));
+    }
+    if let Ok(config) = state.get_extension::<DefaultAccountState>() {
+        if config.state == AccountState::Frozen as u8 {
+            flags.push(flag(RiskKind::DefaultFrozen, RiskLevel::High, "accounts are created frozen"));
+        }
+    }
+    if let Ok(config) = state.get_extension::<TransferFeeConfig>() {
+        let basis_points = u16::from(config.newer_transfer_fee.transfer_fee_basis_points);
+        if basis_points > 0 {
+            flags.push(flag(RiskKind::TransferFee, RiskLevel::Medium, format!("{} bps per transfer", basis_points)));
+        }
+    }
+    if let Ok(delegate) = state.get_extension::<PermanentDelegate>() {
+        if let Some(delegate) = Option::<Pubkey>::from(delegate.delegate) {
+            flags.push(flag(RiskKind::PermanentDelegate, RiskLevel::High, format!("permanent delegate {}", delegate)));
+        }
+    }
+    if let Ok(hook) = state.get_extension::<TransferHook>() {
+        if let Some(program) = Option::<Pubkey>::from(hook.program_id) {
+            flags.push(flag(RiskKind::TransferHook, RiskLevel::Medium, format!("transfer hook program {}", program)));
+        }
+    }
+    if state.get_extension::<NonTransferable>().is_ok() {
+        flags.push(flag(RiskKind::NonTransferable, RiskLevel::High, "tokens can't be transferred"));
+    }
+    Some(flags)
+}
+
+/// Risk reports for mints, cached briefly since each one reads the mint and
+/// its largest accounts.
+pub struct TokenSafety {
+    client: Arc<SolanaClient>,
+    registry: Arc<TokenRegistry>,
+    config: TokenSafetyConfig,
+    reports: Cache<Pubkey, RiskReport>,
+}
+
+impl TokenSafety {
+    pub fn new(client: Arc<SolanaClient>, registry: Arc<TokenRegistry>, config: &TokenSafetyConfig) -> Self {
+        let reports = Cache::builder()
+            .max_capacity(config.cache_capacity)
+            .time_to_live(Duration::from_secs(config.cache_ttl_secs))
+            .build();
+        Self {
+            client,
+            registry,
+            config: config.clone(),
+            reports,
+        }
+    }
+
+    pub async fn assess(&self, mint: &Pubkey) -> Result<RiskReport> {
+        if let Some(report) = self.reports.get(mint).await {
+            return Ok(report);
+        }
+
+        let account = self.client.get_raw_account(mint).await?;
+        let mut flags = TokenProgram::from_owner(&account.owner)
+            .and_then(|_| mint_flags(&account.data))
+            .ok_or_else(|| ApiError::BadRequest("Account is not a valid mint".to_string()))?;
+
+        let holders = self
+            .client
+            .get_token_holders(mint, self.config.top_holders, false)
+            .await?;
+        if holders.top_holders_percent > self.config.max_top_holders_percent {
+            flags.push(flag(
+                RiskKind::ConcentratedHolders,
+                RiskLevel::Medium,
+                format!("top {} accounts hold {:.1}% of supply", holders.holders.len(), holders.top_holders_percent),
+            ));
+        }
+        if self.registry.get(&mint.to_string()).is_none() {
+            flags.push(flag(RiskKind::UnverifiedMetadata, RiskLevel::Low, "not in the token registry"));
+        }
+
+        let report = RiskReport::new(flags);
+        self.reports.insert(*mint, report.clone()).await;
+        Ok(report)
+    }
+
+    /// In strict mode, refuses swaps paying out a high-risk token unless it
+    /// is trusted.
+    pub async fn check_swap_output(&self, mint: &str) -> Result<()> {
+        if !self.config.strict || self.config.trusted_mints.iter().any(|trusted| trusted == mint) {
+            return Ok(());
+        }
+        let report = self.assess(&Pubkey::from_str(mint)?).await?;
+        if report.level == RiskLevel::High {
+            let reasons: Vec<String> = report
+                .flags
+                .iter()
+                .filter(|flag| flag.level == RiskLevel::High)
+                .map(|flag| flag.detail.clone())
+                .collect();
+            return Err(ApiError::BadRequest(format!(
+                "Swaps into {} are blocked as high risk: {}",
+                mint,
+                reasons.join(", ")
+            ))
+            .into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use spl_token_2022::{
+    extension::{
+        interest_bearing_mint::InterestBearingConfig,
+        transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+        BaseStateWithExtensions, StateWithExtensions,
+    },
+    state::{Account, Mint},
+};
+use utoipa::ToSchema;
+
+/// Token programs whose accounts the gateway understands. Token-2022 state
+/// is a superset of the legacy layout, so both are parsed with the
+/// extension-aware unpacker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema, async_graphql::Enum)]
+pub enum TokenProgram {
+    #[serde(rename = "spl-token")]
+    Token,
+    #[serde(rename = "spl-token-2022")]
+    Token2022,
+}
+
+impl TokenProgram {
+    pub const ALL: [TokenProgram; 2] = [TokenProgram::Token, TokenProgram::Token2022];
+
+    pub fn id(self) -> Pubkey {
+        match self {
+            TokenProgram::Token => spl_token::id(),
+            TokenProgram::Token2022 => spl_token_2022::id(),
+        }
+    }
+
+    pub fn from_owner(owner: &Pubkey) -> Option<TokenProgram> {
+        TokenProgram::ALL.into_iter().find(|program| program.id() == *owner)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct TransferFee {
+    pub basis_points: u16,
+    pub maximum_fee: u64,
+}
+
+#[derive(Clone)]
+pub struct MintMetadata {
+    pub decimals: u8,
+    pub transfer_fee: Option<TransferFee>,
+    pub interest_rate_bps: Option<i16>,
+}
+
+pub struct ParsedTokenAccount {
+    pub program: TokenProgram,
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub withheld_amount: Option<u64>,
+    /// Closes the account instead of the owner when set.
+    pub close_authority: Option<Pubkey>,
+    pub frozen: bool,
+}
+
+/// Extension data surfaced alongside Token-2022 balances.
+#[derive(Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct TokenExtensions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transfer_fee: Option<TransferFee>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub withheld_amount: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interest_rate_bps: Option<i16>,
+}
+
+impl TokenExtensions {
+    pub fn new(account: &ParsedTokenAccount, mint: Option<&MintMetadata>) -> Option<Self> {
+        let extensions = Self {
+            transfer_fee: mint.and_then(|m| m.transfer_fee.clone()),
+            withheld_amount: account.withheld_amount,
+            interest_rate_bps: mint.and_then(|m| m.interest_rate_bps),
+        };
+        let empty = extensions.transfer_fee.is_none()
+            && extensions.withheld_amount.is_none()
+            && extensions.interest_rate_bps.is_none();
+        (!empty).then_some(extensions)
+    }
+}
+
+pub fn parse_token_account(program: TokenProgram, data: &[u8]) -> Option<ParsedTokenAccount> {
+    let state = StateWithExtensions::<Account>::unpack(data).ok()?;
+    let withheld_amount = state
+        .get_extension::<TransferFeeAmount>()
+        .ok()
+        .map(|fee| u64::from(fee.withheld_amount));
+
+    Some(ParsedTokenAccount {
+        program,
+        mint: state.base.mint,
+        owner: state.base.owner,
+        amount: state.base.amount,
+        withheld_amount,
+        close_authority: state.base.close_authority.into(),
+        frozen: state.base.is_frozen(),
+    })
+}
+
+pub fn parse_mint(data: &[u8]) -> Option<MintMetadata> {
+    let state = StateWithExtensions::<Mint>::unpack(data).ok()?;
+    let transfer_fee = state.get_extension::<TransferFeeConfig>().ok().map(|config| TransferFee {
+        basis_points: u16::from(config.newer_transfer_fee.transfer_fee_basis_points),
+        maximum_fee: u64::from(config.newer_transfer_fee.maximum_fee),
+    });
+    let interest_rate_bps = state
+        .get_extension::<InterestBearingConfig>()
+        .ok()
+        .map(|config| i16::from(config.current_rate));
+
+    Some(MintMetadata {
+        decimals: state.base.decimals,
+        transfer_fee,
+        interest_rate_bps,
+    })
+}
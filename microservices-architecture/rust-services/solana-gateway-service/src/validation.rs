@@ -0,0 +1,412 @@
+use crate::api_keys::{ApiKeyRateLimit, ApiKeyScopes, NewApiKey};
+use crate::auth::Role;
+use crate::compliance::NewComplianceEntry;
+use crate::config::{DcaConfig, SwapConfig};
+use crate::dca::DcaRequest;
+use crate::holders::MAX_LARGEST_ACCOUNTS;
+use crate::error::ApiError;
+use crate::handlers::admin::LogLevel;
+use crate::handlers::balance_history::{BalanceHistoryQuery, MAX_POINTS};
+use crate::handlers::holders::HoldersQuery;
+use crate::handlers::liquidity::{DepositRequest, WithdrawRequest};
+use crate::handlers::pda::PdaRequest;
+use crate::handlers::rent::RentQuery;
+use crate::handlers::signatures::VerifySignatureRequest;
+use crate::handlers::swap::{ExecuteSwapRequest, RouteQuery};
+use crate::handlers::transactions::{SignatureStatusesRequest, MAX_STATUS_SIGNATURES};
+use crate::solana_client::SignMode;
+use crate::swap::{RouteSwapRequest, SwapRequest};
+use crate::watchlist::WatchRequest;
+use crate::TransactionRequest;
+use serde::Serialize;
+use solana_sdk::{
+    pubkey::{Pubkey, MAX_SEEDS, MAX_SEED_LEN},
+    signature::Signature,
+    system_instruction::MAX_PERMITTED_DATA_LENGTH,
+};
+use std::str::FromStr;
+use tracing_subscriber::EnvFilter;
+use utoipa::ToSchema;
+
+// Largest memo that still fits in a transaction alongside a transfer.
+pub const MAX_MEMO_BYTES: usize = 566;
+const MAX_LABEL_BYTES: usize = 128;
+const MAX_DCA_INTERVAL_SECS: u64 = 365 * 24 * 3600;
+// Sign-in messages are a few lines of text.
+const MAX_SIGNED_MESSAGE_BYTES: usize = 4096;
+
+/// A single rejected request field, reported in 422 responses.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// Collects every field error in a request so clients can fix them in one
+/// round trip instead of discovering them one at a time.
+#[derive(Default)]
+pub struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub fn check(&mut self, field: &str, valid: bool, message: impl Into<String>) -> &mut Self {
+        if !valid {
+            self.errors.push(FieldError {
+                field: field.to_string(),
+                message: message.into(),
+            });
+        }
+        self
+    }
+
+    pub fn pubkey(&mut self, field: &str, value: &str) -> &mut Self {
+        self.check(field, Pubkey::from_str(value).is_ok(), "must be a base58 public key")
+    }
+
+    pub fn optional_pubkey(&mut self, field: &str, value: Option<&str>) -> &mut Self {
+        match value {
+            Some(value) => self.pubkey(field, value),
+            None => self,
+        }
+    }
+
+    pub fn positive(&mut self, field: &str, value: u64) -> &mut Self {
+        self.check(field, value > 0, "must be greater than zero")
+    }
+
+    pub fn max_bytes(&mut self, field: &str, value: Option<&str>, max: usize) -> &mut Self {
+        let len = value.map_or(0, str::len);
+        self.check(field, len <= max, format!("may not exceed {} bytes", max))
+    }
+
+    pub fn scopes(&mut self, field: &str, scopes: &[String]) -> &mut Self {
+        self.check(field, !scopes.is_empty(), "must grant at least one role").check(
+            field,
+            scopes.iter().all(|scope| Role::from_name(scope).is_some()),
+            "must only contain viewer, trader or admin",
+        )
+    }
+
+    pub fn rate_limit(&mut self, rate_limit: &ApiKeyRateLimit) -> &mut Self {
+        let in_range = |quota: Option<u32>| quota.map_or(true, |quota| quota > 0 && quota <= i32::MAX as u32);
+        self.check(
+            "read_per_minute",
+            rate_limit.read_per_minute.is_some() == rate_limit.trading_per_minute.is_some(),
+            "must be set together with trading_per_minute",
+        )
+        .check("read_per_minute", in_range(rate_limit.read_per_minute), "must be a positive 32-bit integer")
+        .check(
+            "trading_per_minute",
+            in_range(rate_limit.trading_per_minute),
+            "must be a positive 32-bit integer",
+        )
+    }
+
+    pub fn finish(&mut self) -> Result<(), ApiError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ApiError::Validation(std::mem::take(&mut self.errors)))
+        }
+    }
+}
+
+impl TransactionRequest {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        Validator::default()
+            .pubkey("from", &self.from)
+            .pubkey("to", &self.to)
+            .positive("amount", self.amount)
+            .optional_pubkey("mint", self.mint.as_deref())
+            .optional_pubkey("nonce_account", self.nonce_account.as_deref())
+            .max_bytes("memo", self.memo.as_deref(), MAX_MEMO_BYTES)
+            .check("memo", self.memo.as_deref() != Some(""), "may not be empty")
+            .check(
+                "wallet_id",
+                self.sign_mode == SignMode::Gateway || self.wallet_id.is_none(),
+                "can't be used when the client signs",
+            )
+            .max_bytes("client_reference", self.client_reference.as_deref(), MAX_LABEL_BYTES)
+            .check("client_reference", self.client_reference.as_deref() != Some(""), "may not be empty")
+            .finish()
+    }
+
+    /// Rules for a transfer proposed from the Squads vault `vault`, on top
+    /// of `validate`.
+    pub fn validate_squads(&self, vault: &str) -> Result<(), ApiError> {
+        Validator::default()
+            .check("from", self.from == vault, format!("must be the multisig vault {}", vault))
+            .check("wallet_id", self.wallet_id.is_some(), "is required to propose from the multisig")
+            .check("nonce_account", self.nonce_account.is_none(), "can't be used with a multisig")
+            .finish()
+    }
+}
+
+impl SwapRequest {
+    pub fn validate(&self, config: &SwapConfig) -> Result<(), ApiError> {
+        let slippage_bps = self.slippage_bps.unwrap_or(config.default_slippage_bps);
+        Validator::default()
+            .optional_pubkey("pool_id", self.pool_id.as_deref())
+            .pubkey("input_mint", &self.input_mint)
+            .optional_pubkey("output_mint", self.output_mint.as_deref())
+            .check(
+                "output_mint",
+                self.pool_id.is_some() || self.output_mint.is_some(),
+                "is required when pool_id is omitted",
+            )
+            .positive("amount_in", self.amount_in)
+            .check(
+                "slippage_bps",
+                slippage_bps <= config.max_slippage_bps,
+                format!("may not exceed {}", config.max_slippage_bps),
+            )
+            .finish()
+    }
+}
+
+impl DepositRequest {
+    pub fn validate(&self, config: &SwapConfig) -> Result<(), ApiError> {
+        Validator::default()
+            .pubkey("owner", &self.owner)
+            .pubkey("mint", &self.mint)
+            .positive("amount", self.amount)
+            .check(
+                "slippage_bps",
+                self.slippage_bps.unwrap_or(config.default_slippage_bps) <= config.max_slippage_bps,
+                format!("may not exceed {}", config.max_slippage_bps),
+            )
+            .finish()
+    }
+}
+
+impl WithdrawRequest {
+    pub fn validate(&self, config: &SwapConfig) -> Result<(), ApiError> {
+        Validator::default()
+            .pubkey("owner", &self.owner)
+            .positive("lp_amount", self.lp_amount)
+            .check(
+                "slippage_bps",
+                self.slippage_bps.unwrap_or(config.default_slippage_bps) <= config.max_slippage_bps,
+                format!("may not exceed {}", config.max_slippage_bps),
+            )
+            .finish()
+    }
+}
+
+impl ExecuteSwapRequest {
+    /// Checks the fields a client-signed or dry-run swap adds; the swap
+    /// itself is validated where it is built or submitted.
+    pub fn validate(&self, dry_run: bool) -> Result<(), ApiError> {
+        let client = self.sign_mode == SignMode::Client;
+        Validator::default()
+            .check("owner", !client || self.owner.is_some(), "is required when the client signs")
+            .check("owner", !dry_run || self.owner.is_some(), "is required for a dry run")
+            .optional_pubkey("owner", self.owner.as_deref())
+            .check("mev_protection", !client || !self.swap.mev_protection, "requires the gateway to sign")
+            .finish()
+    }
+}
+
+impl DcaRequest {
+    pub fn validate(&self, config: &DcaConfig, swap: &SwapConfig) -> Result<(), ApiError> {
+        let slippage_bps = self.slippage_bps.unwrap_or(swap.default_slippage_bps);
+        Validator::default()
+            .pubkey("input_mint", &self.input_mint)
+            .pubkey("output_mint", &self.output_mint)
+            .check("output_mint", self.output_mint != self.input_mint, "must differ from input_mint")
+            .positive("amount_per_execution", self.amount_per_execution)
+            .check(
+                "total_budget",
+                self.total_budget >= self.amount_per_execution,
+                "must cover at least one execution",
+            )
+            .check("total_budget", self.total_budget <= i64::MAX as u64, "is too large")
+            .check(
+                "interval_secs",
+                self.interval_secs >= config.min_interval_secs,
+                format!("must be at least {}", config.min_interval_secs),
+            )
+            .check("interval_secs", self.interval_secs <= MAX_DCA_INTERVAL_SECS, "may not exceed a year")
+            .check(
+                "slippage_bps",
+                slippage_bps <= swap.max_slippage_bps,
+                format!("may not exceed {}", swap.max_slippage_bps),
+            )
+            .finish()
+    }
+}
+
+impl RouteSwapRequest {
+    pub fn validate(&self, config: &SwapConfig) -> Result<(), ApiError> {
+        let slippage_bps = self.slippage_bps.unwrap_or(config.default_slippage_bps);
+        Validator::default()
+            .pubkey("input_mint", &self.input_mint)
+            .pubkey("output_mint", &self.output_mint)
+            .check("output_mint", self.output_mint != self.input_mint, "must differ from input_mint")
+            .positive("amount_in", self.amount_in)
+            .check(
+                "slippage_bps",
+                slippage_bps <= config.max_slippage_bps,
+                format!("may not exceed {}", config.max_slippage_bps),
+            )
+            .finish()
+    }
+}
+
+impl RouteQuery {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        Validator::default()
+            .pubkey("input_mint", &self.input_mint)
+            .pubkey("output_mint", &self.output_mint)
+            .check("output_mint", self.output_mint != self.input_mint, "must differ from input_mint")
+            .positive("amount_in", self.amount_in)
+            .finish()
+    }
+}
+
+impl VerifySignatureRequest {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        Validator::default()
+            .pubkey("pubkey", &self.pubkey)
+            .check(
+                "signature",
+                Signature::from_str(&self.signature).is_ok(),
+                "must be a base58 ed25519 signature",
+            )
+            .check("message", self.message_bytes().is_some(), "must match encoding")
+            .max_bytes("message", Some(&self.message), MAX_SIGNED_MESSAGE_BYTES)
+            .finish()
+    }
+}
+
+impl PdaRequest {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        let mut validator = Validator::default();
+        validator.pubkey("program_id", &self.program_id).check(
+            "seeds",
+            // The bump takes the last seed slot.
+            self.seeds.len() < MAX_SEEDS,
+            format!("may not exceed {} seeds", MAX_SEEDS - 1),
+        );
+        for (i, seed) in self.seeds.iter().enumerate() {
+            let field = format!("seeds[{}].value", i);
+            match seed.bytes() {
+                Some(bytes) => validator.check(
+                    &field,
+                    bytes.len() <= MAX_SEED_LEN,
+                    format!("may not exceed {} bytes", MAX_SEED_LEN),
+                ),
+                None => validator.check(&field, false, "must match the seed type"),
+            };
+        }
+        validator.finish()
+    }
+}
+
+impl HoldersQuery {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        let limit = self.limit.unwrap_or(MAX_LARGEST_ACCOUNTS);
+        Validator::default()
+            .check(
+                "limit",
+                (1..=MAX_LARGEST_ACCOUNTS).contains(&limit),
+                format!("must be between 1 and {}", MAX_LARGEST_ACCOUNTS),
+            )
+            .finish()
+    }
+}
+
+impl BalanceHistoryQuery {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        let (from, to) = self.range();
+        let points = (to - from).num_seconds() / self.interval().seconds();
+        Validator::default()
+            .check("from", from < to, "must be before to")
+            .check("from", points <= MAX_POINTS, format!("range may not exceed {} intervals", MAX_POINTS))
+            .finish()
+    }
+}
+
+impl SignatureStatusesRequest {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        Validator::default()
+            .check("signatures", !self.signatures.is_empty(), "must not be empty")
+            .check(
+                "signatures",
+                self.signatures.len() <= MAX_STATUS_SIGNATURES,
+                format!("may not contain more than {} signatures", MAX_STATUS_SIGNATURES),
+            )
+            .check(
+                "signatures",
+                self.signatures.iter().all(|signature| Signature::from_str(signature).is_ok()),
+                "must only contain base58 signatures",
+            )
+            .finish()
+    }
+}
+
+impl RentQuery {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        Validator::default()
+            .check(
+                "data_len",
+                self.data_len <= MAX_PERMITTED_DATA_LENGTH,
+                format!("may not exceed {} bytes", MAX_PERMITTED_DATA_LENGTH),
+            )
+            .finish()
+    }
+}
+
+impl WatchRequest {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        Validator::default()
+            .pubkey("address", &self.address)
+            .max_bytes("label", self.label.as_deref(), MAX_LABEL_BYTES)
+            .finish()
+    }
+}
+
+impl NewApiKey {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        Validator::default()
+            .check("name", !self.name.trim().is_empty(), "must not be empty")
+            .max_bytes("name", Some(&self.name), MAX_LABEL_BYTES)
+            .scopes("scopes", &self.scopes)
+            .rate_limit(&self.rate_limit)
+            .finish()
+    }
+}
+
+impl LogLevel {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        Validator::default()
+            .check(
+                "filter",
+                EnvFilter::try_new(&self.filter).is_ok(),
+                "must be valid log filter directives",
+            )
+            .finish()
+    }
+}
+
+impl NewComplianceEntry {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        Validator::default()
+            .pubkey("value", &self.value)
+            .max_bytes("reason", self.reason.as_deref(), MAX_LABEL_BYTES)
+            .finish()
+    }
+}
+
+impl ApiKeyScopes {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        Validator::default().scopes("scopes", &self.scopes).finish()
+    }
+}
+
+impl ApiKeyRateLimit {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        Validator::default().rate_limit(self).finish()
+    }
+}
@@ -0,0 +1,171 @@
+use crate::auth::{Principal, Role};
+use crate::client_references;
+use crate::config::WalletConfig;
+use crate::database::Database;
+use crate::error::ApiError;
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    Aes256Gcm, Key, Nonce,
+};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use solana_sdk::signature::{Keypair, Signer};
+use std::sync::Arc;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct Wallet {
+    pub id: Uuid,
+    pub label: Option<String>,
+    pub pubkey: String,
+    /// Subject of the principal that created or imported the wallet, e.g.
+    /// `api-key:<id>`. Only it and admins can see the wallet and sign with
+    /// it.
+    pub owner: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The owner whose wallets `principal` may see and sign with, or None for
+/// admins, who may use every wallet.
+pub fn scope(principal: Option<&Principal>) -> Option<&str> {
+    match principal {
+        Some(principal) if principal.role == Role::Admin => None,
+        _ => Some(client_references::owner(principal)),
+    }
+}
+
+/// A keypair encrypted with AES-256-GCM under the master key. The wallet ID
+/// is bound as associated data so ciphertexts can't be swapped between rows.
+#[derive(sqlx::FromRow)]
+pub struct EncryptedKey {
+    pub ciphertext: Vec<u8>,
+    pub nonce: Vec<u8>,
+}
+
+/// Creates, imports and unlocks signing keypairs stored encrypted at rest.
+pub struct WalletManager {
+    database: Arc<Database>,
+    cipher: Option<Aes256Gcm>,
+    allow_export: bool,
+}
+
+impl WalletManager {
+    pub fn new(database: Arc<Database>, config: &WalletConfig) -> Result<Self> {
+        let cipher = match &config.master_key {
+            Some(master_key) => {
+                let bytes = hex::decode(master_key).context("Wallet master key must be hex encoded")?;
+                if bytes.len() != 32 {
+                    anyhow::bail!("Wallet master key must be 32 bytes");
+                }
+                Some(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&bytes)))
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            database,
+            cipher,
+            allow_export: config.allow_export,
+        })
+    }
+
+    pub async fn create(&self, owner: &str, label: Option<String>) -> Result<Wallet> {
+        self.store(owner, label, Keypair::new()).await
+    }
+
+    /// Imports a secret key given as base58 or as a JSON byte array (the
+    /// format written by `solana-keygen`).
+    pub async fn import(&self, owner: &str, label: Option<String>, secret_key: &str) -> Result<Wallet> {
+        let bytes = match serde_json::from_str::<Vec<u8>>(secret_key) {
+            Ok(bytes) => bytes,
+            Err(_) => bs58::decode(secret_key.trim())
+                .into_vec()
+                .map_err(|_| ApiError::BadRequest("Secret key is not valid base58 or JSON".to_string()))?,
+        };
+        let keypair = Keypair::from_bytes(&bytes)
+            .map_err(|_| ApiError::BadRequest("Secret key is not a valid ed25519 keypair".to_string()))?;
+        self.store(owner, label, keypair).await
+    }
+
+    /// Wallets of `owner`, or every wallet when None.
+    pub async fn list(&self, owner: Option<&str>) -> Result<Vec<Wallet>> {
+        self.database.list_wallets(owner).await
+    }
+
+    /// The wallet, when it belongs to `owner` or `owner` is None. Other
+    /// owners' wallets are reported as not found.
+    pub async fn get(&self, owner: Option<&str>, id: Uuid) -> Result<Wallet> {
+        self.database
+            .get_wallet(id, owner)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Wallet not found".to_string()).into())
+    }
+
+    /// Unlocks the wallet, when it belongs to `owner` or `owner` is None.
+    pub async fn keypair(&self, owner: Option<&str>, id: Uuid) -> Result<Keypair> {
+        let cipher = self.cipher()?;
+        let key = self
+            .database
+            .get_wallet_key(id, owner)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Wallet not found".to_string()))?;
+
+        let secret = cipher
+            .decrypt(
+                Nonce::from_slice(&key.nonce),
+                Payload { msg: &key.ciphertext, aad: id.as_bytes() },
+            )
+            .map_err(|_| anyhow::anyhow!("Failed to decrypt wallet {}", id))?;
+        Ok(Keypair::from_bytes(&secret)?)
+    }
+
+    /// Unlocks the wallet of `owner` (any owner when None) that must sign
+    /// as `signer`.
+    pub async fn signer_for(&self, owner: Option<&str>, id: Uuid, signer: &str) -> Result<Keypair> {
+        let keypair = self.keypair(owner, id).await?;
+        if keypair.pubkey().to_string() != signer {
+            return Err(ApiError::BadRequest(format!("Wallet {} cannot sign for {}", id, signer)).into());
+        }
+        Ok(keypair)
+    }
+
+    pub async fn export(&self, id: Uuid) -> Result<String> {
+        if !self.allow_export {
+            return Err(ApiError::Forbidden("Wallet export is disabled".to_string()).into());
+        }
+        Ok(self.keypair(None, id).await?.to_base58_string())
+    }
+
+    async fn store(&self, owner: &str, label: Option<String>, keypair: Keypair) -> Result<Wallet> {
+        let cipher = self.cipher()?;
+        let wallet = Wallet {
+            id: Uuid::new_v4(),
+            label,
+            pubkey: keypair.pubkey().to_string(),
+            owner: owner.to_string(),
+            created_at: Utc::now(),
+        };
+
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, Payload { msg: &keypair.to_bytes(), aad: wallet.id.as_bytes() })
+            .map_err(|_| anyhow::anyhow!("Failed to encrypt wallet key"))?;
+        let key = EncryptedKey {
+            ciphertext,
+            nonce: nonce.to_vec(),
+        };
+
+        if !self.database.insert_wallet(&wallet, &key).await? {
+            return Err(ApiError::Conflict(format!("Wallet {} already exists", wallet.pubkey)).into());
+        }
+        Ok(wallet)
+    }
+
+    fn cipher(&self) -> Result<&Aes256Gcm> {
+        self.cipher.as_ref().ok_or_else(|| {
+            ApiError::ServiceUnavailable("Wallet storage is not configured".to_string()).into()
+        })
+    }
+}
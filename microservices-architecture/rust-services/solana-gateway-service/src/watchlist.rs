@@ -0,0 +1,223 @@
+use crate::audit::hex_sha256;
+use crate::config::WatchlistConfig;
+use crate::database::Database;
+use crate::events::EventPublisher;
+use crate::solana_client::SolanaClient;
+use crate::tokens::TokenProgram;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{account::Account, program_option::COption, pubkey::Pubkey};
+use spl_token_2022::{
+    extension::StateWithExtensions,
+    state::{Account as TokenAccount, Mint},
+};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, ToSchema)]
+pub struct WatchRequest {
+    pub address: String,
+    pub label: Option<String>,
+}
+
+/// An account of interest and the state the watcher last recorded for it.
+#[derive(Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct WatchedAccount {
+    pub address: String,
+    pub label: Option<String>,
+    /// Absent until the watcher has first observed the account.
+    pub lamports: Option<i64>,
+    /// Hex SHA-256 of the account data; absent while the account doesn't exist.
+    pub data_hash: Option<String>,
+    /// Authorities by name, as in `AuthorityChange::authority`; absent
+    /// while the account doesn't exist.
+    #[schema(value_type = Option<Object>)]
+    pub authorities: Option<serde_json::Value>,
+    pub observed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A balance or data change detected on a watched account. Published to
+/// Kafka and delivered to `account.changed` webhooks.
+#[derive(Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct AccountChange {
+    pub id: i64,
+    pub address: String,
+    pub previous_lamports: i64,
+    pub lamports: i64,
+    pub data_changed: bool,
+    /// Absent once the account has been closed.
+    pub data_hash: Option<String>,
+    pub detected_at: DateTime<Utc>,
+}
+
+/// A change of who controls a watched account: the owning program was
+/// reassigned, or a token account's owner, delegate or close authority or
+/// a mint's mint or freeze authority was set, changed or removed.
+/// Published to Kafka and delivered to `account.authority_changed`
+/// webhooks.
+#[derive(Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct AuthorityChange {
+    pub id: i64,
+    pub address: String,
+    /// `program_owner`, `token_owner`, `delegate`, `close_authority`,
+    /// `mint_authority` or `freeze_authority`.
+    pub authority: String,
+    /// Absent when the authority was unset.
+    pub previous_value: Option<String>,
+    /// Absent when the authority was removed.
+    pub new_value: Option<String>,
+    pub detected_at: DateTime<Utc>,
+}
+
+pub struct NewAuthorityChange {
+    pub authority: String,
+    pub previous_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+/// An account's authorities by name. Optional SPL authorities that are
+/// unset are present as None.
+pub type Authorities = BTreeMap<String, Option<String>>;
+
+/// Account state as compared between polls.
+pub struct ObservedState {
+    pub lamports: i64,
+    pub data_hash: Option<String>,
+    pub authorities: Option<Authorities>,
+}
+
+/// The owning program of an account and, for SPL token accounts and
+/// mints, the authorities that can move or reassign it.
+fn authorities(account: &Account) -> Authorities {
+    let pubkey = |key: COption<Pubkey>| match key {
+        COption::Some(key) => Some(key.to_string()),
+        COption::None => None,
+    };
+    let mut authorities = Authorities::new();
+    authorities.insert("program_owner".to_string(), Some(account.owner.to_string()));
+    if TokenProgram::from_owner(&account.owner).is_none() {
+        return authorities;
+    }
+    if let Ok(state) = StateWithExtensions::<TokenAccount>::unpack(&account.data) {
+        authorities.insert("token_owner".to_string(), Some(state.base.owner.to_string()));
+        authorities.insert("delegate".to_string(), pubkey(state.base.delegate));
+        authorities.insert("close_authority".to_string(), pubkey(state.base.close_authority));
+    } else if let Ok(state) = StateWithExtensions::<Mint>::unpack(&account.data) {
+        authorities.insert("mint_authority".to_string(), pubkey(state.base.mint_authority));
+        authorities.insert("freeze_authority".to_string(), pubkey(state.base.freeze_authority));
+    }
+    authorities
+}
+
+/// Every authority whose value differs between two observations.
+fn authority_changes(previous: &Authorities, current: &Authorities) -> Vec<NewAuthorityChange> {
+    let mut names: Vec<&String> = previous.keys().chain(current.keys()).collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let previous_value = previous.get(name).cloned().flatten();
+            let new_value = current.get(name).cloned().flatten();
+            (previous_value != new_value).then(|| NewAuthorityChange {
+                authority: name.clone(),
+                previous_value,
+                new_value,
+            })
+        })
+        .collect()
+}
+
+/// Polls watched accounts with batched `getMultipleAccounts` and records
+/// every balance, data or authority change. The first observation of an
+/// account only sets its baseline.
+pub fn spawn_watcher(
+    client: Arc<SolanaClient>,
+    database: Arc<Database>,
+    events: Arc<EventPublisher>,
+    config: WatchlistConfig,
+) {
+    if !config.enabled {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.poll_interval_secs.max(1)));
+        loop {
+            interval.tick().await;
+            if let Err(e) = poll(&client, &database, &events, config.max_accounts).await {
+                warn!("Failed to poll watchlist: {}", e);
+            }
+        }
+    });
+}
+
+async fn poll(
+    client: &SolanaClient,
+    database: &Database,
+    events: &EventPublisher,
+    max_accounts: i64,
+) -> Result<()> {
+    let watched = database.list_watched_accounts(max_accounts).await?;
+    let keys = watched
+        .iter()
+        .map(|account| Pubkey::from_str(&account.address))
+        .collect::<Result<Vec<_>, _>>()?;
+    let accounts = client.get_multiple_accounts(&keys).await?;
+
+    for (watched, account) in watched.iter().zip(accounts) {
+        let observed = ObservedState {
+            lamports: account.as_ref().map_or(0, |account| account.lamports as i64),
+            data_hash: account.as_ref().map(|account| hex_sha256(&account.data)),
+            authorities: account.as_ref().map(authorities),
+        };
+        if watched.observed_at.is_none() {
+            database.set_watch_baseline(&watched.address, &observed).await?;
+            continue;
+        }
+
+        // Authorities are compared while the account exists; its closing
+        // and reopening are balance changes. Accounts watched before
+        // authorities were tracked have none stored yet.
+        let stored: Option<Authorities> = watched
+            .authorities
+            .clone()
+            .and_then(|authorities| serde_json::from_value(authorities).ok());
+        if let (Some(previous), Some(current)) = (&stored, &observed.authorities) {
+            let changes = authority_changes(previous, current);
+            if !changes.is_empty() {
+                for change in database.record_authority_changes(&watched.address, &changes, current).await? {
+                    warn!(
+                        "Watched account {} {} changed: {} -> {}",
+                        change.address,
+                        change.authority,
+                        change.previous_value.as_deref().unwrap_or("none"),
+                        change.new_value.as_deref().unwrap_or("none")
+                    );
+                    events.watched_authority_changed(&change);
+                }
+            }
+        }
+
+        if watched.lamports == Some(observed.lamports) && watched.data_hash == observed.data_hash {
+            if stored.is_none() && observed.authorities.is_some() {
+                database.set_watch_baseline(&watched.address, &observed).await?;
+            }
+            continue;
+        }
+
+        let change = database.record_account_change(watched, &observed).await?;
+        debug!(
+            "Watched account {} changed: {} -> {} lamports",
+            change.address, change.previous_lamports, change.lamports
+        );
+        events.watched_account_changed(&change);
+    }
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use crate::config::WebhookConfig;
+use crate::database::Database;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+/// Event names that webhooks subscribe to in `webhooks.events`. A webhook
+/// with no events receives all of them.
+pub const ACCOUNT_CHANGED: &str = "account.changed";
+pub const AUTHORITY_CHANGED: &str = "account.authority_changed";
+pub const DCA_FILLED: &str = "dca.filled";
+
+const EVENT_HEADER: &str = "x-webhook-event";
+const SIGNATURE_HEADER: &str = "x-webhook-signature";
+// Deliveries attempted per poll.
+const BATCH_SIZE: i64 = 100;
+const MAX_BACKOFF_SECS: u64 = 3_600;
+
+/// A queued delivery joined with its webhook's endpoint and secret.
+#[derive(sqlx::FromRow)]
+pub struct PendingDelivery {
+    pub id: i64,
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub url: String,
+    pub secret: String,
+}
+
+/// Delivers queued `webhook_deliveries` rows, at least once, retrying
+/// failures with exponential backoff until `max_attempts` is reached.
+/// Bodies are signed with the webhook's secret as
+/// `X-Webhook-Signature: sha256=<hex HMAC-SHA256>`.
+pub fn spawn_dispatcher(database: Arc<Database>, config: WebhookConfig) {
+    if !config.enabled {
+        return;
+    }
+    let http = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(config.timeout_secs))
+        .build()
+    {
+        Ok(http) => http,
+        Err(e) => {
+            warn!("Failed to create webhook HTTP client: {}", e);
+            return;
+        }
+    };
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.poll_interval_secs.max(1)));
+        loop {
+            interval.tick().await;
+            let deliveries = match database.pending_webhook_deliveries(BATCH_SIZE).await {
+                Ok(deliveries) => deliveries,
+                Err(e) => {
+                    warn!("Failed to load webhook deliveries: {}", e);
+                    continue;
+                }
+            };
+            for delivery in deliveries {
+                deliver(&http, &database, &config, delivery).await;
+            }
+        }
+    });
+}
+
+async fn deliver(http: &reqwest::Client, database: &Database, config: &WebhookConfig, delivery: PendingDelivery) {
+    let body = delivery.payload.to_string();
+    let result = http
+        .post(&delivery.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, &delivery.event)
+        .header(SIGNATURE_HEADER, format!("sha256={}", sign(&delivery.secret, body.as_bytes())))
+        .body(body)
+        .send()
+        .await;
+
+    let status_code = result.as_ref().ok().map(|response| response.status());
+    let delivered = status_code.is_some_and(|status| status.is_success());
+    let attempts = delivery.attempts + 1;
+    if !delivered {
+        let reason = match &result {
+            Ok(response) => response.status().to_string(),
+            Err(e) => e.to_string(),
+        };
+        warn!(
+            "Webhook delivery {} to {} failed (attempt {}): {}",
+            delivery.id, delivery.url, attempts, reason
+        );
+    }
+
+    let next_attempt_at = (!delivered && attempts < config.max_attempts as i32)
+        .then(|| Utc::now() + backoff(config, attempts));
+    if let Err(e) = database
+        .record_webhook_attempt(
+            delivery.id,
+            status_code.map(|status| status.as_u16() as i32),
+            delivered,
+            next_attempt_at,
+        )
+        .await
+    {
+        warn!("Failed to record webhook delivery {}: {}", delivery.id, e);
+    }
+}
+
+fn backoff(config: &WebhookConfig, attempts: i32) -> chrono::Duration {
+    let exponent = (attempts - 1).clamp(0, 16) as u32;
+    let secs = config.retry_base_delay_secs.saturating_mul(1 << exponent).min(MAX_BACKOFF_SECS);
+    chrono::Duration::seconds(secs as i64)
+}
+
+fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
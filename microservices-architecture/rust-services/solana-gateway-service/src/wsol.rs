@@ -0,0 +1,123 @@
+//! Native SOL in swaps and transfers. The token programs only move wrapped
+//! SOL, so SOL spent or received goes through a temporary wSOL account the
+//! transaction creates at an address derived from the owner and a random
+//! seed, funds, and closes again at the end, unwrapping what it holds back
+//! to SOL. The owner's own wSOL account, and any wSOL in it, is left alone.
+//! Clients trade and send SOL without holding wSOL themselves.
+
+use anyhow::Result;
+use solana_sdk::{instruction::Instruction, program_pack::Pack, pubkey::Pubkey, rent::Rent, system_instruction};
+use spl_token::native_mint;
+
+pub fn is_native(mint: &Pubkey) -> bool {
+    *mint == native_mint::id()
+}
+
+/// A wSOL account owned by `owner` that only lives for one transaction.
+pub struct TempAccount {
+    pub address: Pubkey,
+    owner: Pubkey,
+    seed: String,
+}
+
+impl TempAccount {
+    pub fn new(owner: &Pubkey) -> Result<Self> {
+        let seed = format!("wsol-{:016x}", rand::random::<u64>());
+        Ok(Self {
+            address: Pubkey::create_with_seed(owner, &seed, &spl_token::id())?,
+            owner: *owner,
+            seed,
+        })
+    }
+
+    /// Creates the account with `lamports` on top of its rent, all paid by
+    /// the owner, and syncs its token balance to them.
+    pub fn open(&self, lamports: u64) -> Result<Vec<Instruction>> {
+        let len = spl_token::state::Account::LEN;
+        Ok(vec![
+            system_instruction::create_account_with_seed(
+                &self.owner,
+                &self.address,
+                &self.owner,
+                &self.seed,
+                Rent::default().minimum_balance(len) + lamports,
+                len as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_account3(
+                &spl_token::id(),
+                &self.address,
+                &native_mint::id(),
+                &self.owner,
+            )?,
+            spl_token::instruction::sync_native(&spl_token::id(), &self.address)?,
+        ])
+    }
+
+    /// Closes the account, returning its whole balance and its rent to the
+    /// owner as SOL.
+    pub fn close(&self) -> Result<Instruction> {
+        Ok(spl_token::instruction::close_account(
+            &spl_token::id(),
+            &self.address,
+            &self.owner,
+            &self.owner,
+            &[],
+        )?)
+    }
+}
+
+/// SOL wrapping around one swap. `account` is the temporary wSOL account
+/// standing in for the owner's on the swap's native SOL sides; `before`
+/// and `after` are empty, and `account` is None, for token pairs.
+#[derive(Default)]
+pub struct SwapWrapping {
+    pub account: Option<Pubkey>,
+    pub before: Vec<Instruction>,
+    pub after: Vec<Instruction>,
+}
+
+/// Wrapping for a swap of `amount_in` units of `input_mint` into
+/// `output_mint`: when either side is SOL, a temporary account is opened
+/// first, funded with SOL input, and closed last, which also returns input
+/// the swap didn't spend.
+pub fn around_swap(
+    owner: &Pubkey,
+    input_mint: &Pubkey,
+    output_mint: &Pubkey,
+    amount_in: u64,
+) -> Result<SwapWrapping> {
+    if !is_native(input_mint) && !is_native(output_mint) {
+        return Ok(SwapWrapping::default());
+    }
+    let account = TempAccount::new(owner)?;
+    let funding = if is_native(input_mint) { amount_in } else { 0 };
+    Ok(SwapWrapping {
+        account: Some(account.address),
+        before: account.open(funding)?,
+        after: vec![account.close()?],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spl_associated_token_account::get_associated_token_address;
+
+    #[test]
+    fn sol_swap_leaves_the_owners_wsol_account_alone() {
+        let owner = Pubkey::new_unique();
+        let wrapping = around_swap(&owner, &native_mint::id(), &Pubkey::new_unique(), 1_000).unwrap();
+        let account = wrapping.account.unwrap();
+        assert_ne!(account, get_associated_token_address(&owner, &native_mint::id()));
+        assert_eq!(wrapping.after.len(), 1);
+        assert_eq!(wrapping.after[0].accounts[0].pubkey, account);
+    }
+
+    #[test]
+    fn token_pair_swap_wraps_nothing() {
+        let owner = Pubkey::new_unique();
+        let wrapping = around_swap(&owner, &Pubkey::new_unique(), &Pubkey::new_unique(), 1_000).unwrap();
+        assert!(wrapping.account.is_none() && wrapping.before.is_empty() && wrapping.after.is_empty());
+    }
+}